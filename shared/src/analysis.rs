@@ -0,0 +1,858 @@
+// Exam analysis
+//
+// Turns the marks for a class's assessments into the performance summaries
+// in `domain`. Each component mark becomes a percentage of its maximum, and
+// a subject score is the weighted mean of the component percentages the
+// candidate has marks for, so a paper not yet marked does not drag the
+// score down. An absent candidate scores zero on that paper. Scores are
+// graded with whatever scheme the grading policy gives each subject.
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{
+    ClassPerformanceSummary, ClassSubjectSummary, ComponentSpecificSummary, Student, StudentClass,
+    StudentPerformanceSummary, Subject, SubjectComponentSummary, SubjectSpecificSummary,
+};
+use crate::grading::{GradeCount, GradingPolicy};
+use crate::marks::{Assessment, AssessmentComponent, Mark};
+use crate::statistics::{HISTOGRAM_BIN_WIDTH, Histogram, Statistics};
+
+/// How component percentages are combined into a subject score.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Weighting {
+    /// The weight set on each component of the assessment.
+    #[default]
+    Assessment,
+    /// Every component counts the same.
+    Equal,
+    /// Components count in proportion to their maximum mark, as if the raw
+    /// marks were added up.
+    RawTotal,
+    /// Weights by component code, falling back to the assessment's weight
+    /// for components not listed.
+    Custom(Vec<(String, f32)>),
+}
+
+impl Weighting {
+    pub const NAMES: &'static [&'static str] = &["Assessment", "Equal", "Raw Total"];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Assessment => "Assessment",
+            Self::Equal => "Equal",
+            Self::RawTotal => "Raw Total",
+            Self::Custom(_) => "Custom",
+        }
+    }
+
+    /// Parses one of the fixed weightings by name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim() {
+            "Assessment" => Some(Self::Assessment),
+            "Equal" => Some(Self::Equal),
+            "Raw Total" => Some(Self::RawTotal),
+            _ => None,
+        }
+    }
+
+    pub fn weight(&self, component: &AssessmentComponent) -> f32 {
+        match self {
+            Self::Assessment => component.weight(),
+            Self::Equal => 1.0,
+            Self::RawTotal => component.max_mark() as f32,
+            Self::Custom(weights) => weights
+                .iter()
+                .find(|(code, _)| code.eq_ignore_ascii_case(component.code()))
+                .map(|(_, weight)| *weight)
+                .unwrap_or_else(|| component.weight()),
+        }
+    }
+}
+
+impl std::fmt::Display for Weighting {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// An assessment and every mark entered for it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AssessmentResults {
+    assessment: Assessment,
+    marks: Vec<Mark>,
+}
+
+impl AssessmentResults {
+    pub fn new(assessment: Assessment, marks: Vec<Mark>) -> Self {
+        Self { assessment, marks }
+    }
+
+    pub fn assessment(&self) -> &Assessment {
+        &self.assessment
+    }
+
+    pub fn marks(&self) -> &[Mark] {
+        &self.marks
+    }
+
+    /// A candidate's percentage on a component, if it has been marked.
+    pub fn component_percentage(
+        &self,
+        candidate_number: &str,
+        component: &AssessmentComponent,
+    ) -> Option<f32> {
+        self.marks
+            .iter()
+            .find(|mark| {
+                mark.candidate_number() == candidate_number
+                    && mark.component_code() == component.code()
+            })
+            .map(|mark| percentage(mark.raw_score().unwrap_or(0.0), component.max_mark()))
+    }
+
+    /// A candidate's weighted subject score, if any component has been
+    /// marked.
+    pub fn subject_score(&self, candidate_number: &str, weighting: &Weighting) -> Option<f32> {
+        let (total, weights) = self
+            .assessment
+            .components()
+            .iter()
+            .filter_map(|component| {
+                let score = self.component_percentage(candidate_number, component)?;
+                Some((score, weighting.weight(component)))
+            })
+            .fold((0.0, 0.0), |(total, weights), (score, weight)| {
+                (total + score * weight, weights + weight)
+            });
+
+        (weights > 0.0).then(|| total / weights)
+    }
+
+    /// Mean percentage on a component across the candidates marked on it.
+    pub fn component_average(&self, component: &AssessmentComponent) -> Option<f32> {
+        mean(
+            self.marks
+                .iter()
+                .filter(|mark| mark.component_code() == component.code())
+                .map(|mark| percentage(mark.raw_score().unwrap_or(0.0), component.max_mark())),
+        )
+    }
+}
+
+/// Grades for one subject in a class, under the subject's scheme.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SubjectGrades {
+    subject: Subject,
+    scheme_code: String,
+    average_grade: String,
+    student_grades: Vec<(String, String)>,
+    distribution: Vec<GradeCount>,
+}
+
+impl SubjectGrades {
+    pub fn subject(&self) -> Subject {
+        self.subject
+    }
+
+    pub fn scheme_code(&self) -> &str {
+        &self.scheme_code
+    }
+
+    /// The grade the class average would earn.
+    pub fn average_grade(&self) -> &str {
+        &self.average_grade
+    }
+
+    /// (candidate number, grade) for each student with a score.
+    pub fn student_grades(&self) -> &[(String, String)] {
+        &self.student_grades
+    }
+
+    /// Highest grade first, including grades nobody got.
+    pub fn distribution(&self) -> &[GradeCount] {
+        &self.distribution
+    }
+}
+
+/// What class positions are worked out from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RankBy {
+    /// The mean of the student's subject scores.
+    #[default]
+    Average,
+    /// The sum of the student's subject scores, which puts students taking
+    /// more subjects ahead.
+    Total,
+}
+
+impl RankBy {
+    pub const NAMES: &'static [&'static str] = &["Average", "Total"];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Average => "Average",
+            Self::Total => "Total",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim() {
+            "Average" => Some(Self::Average),
+            "Total" => Some(Self::Total),
+            _ => None,
+        }
+    }
+
+    pub fn score(&self, summary: &StudentPerformanceSummary) -> f32 {
+        match self {
+            Self::Average => summary.overall_average(),
+            Self::Total => summary.total_score(),
+        }
+    }
+}
+
+/// A student's place in class. Students with equal scores share a place
+/// and the next place is skipped, so four students rank 1st, 2nd=, 2nd=,
+/// 4th.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Position {
+    place: u32,
+    is_tied: bool,
+    out_of: u32,
+}
+
+impl Position {
+    pub fn new(place: u32, is_tied: bool, out_of: u32) -> Self {
+        Self {
+            place,
+            is_tied,
+            out_of,
+        }
+    }
+
+    pub fn place(&self) -> u32 {
+        self.place
+    }
+
+    pub fn is_tied(&self) -> bool {
+        self.is_tied
+    }
+
+    /// How many students were ranked.
+    pub fn out_of(&self) -> u32 {
+        self.out_of
+    }
+}
+
+impl std::fmt::Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let suffix = match (self.place % 10, self.place % 100) {
+            (_, 11..=13) => "th",
+            (1, _) => "st",
+            (2, _) => "nd",
+            (3, _) => "rd",
+            _ => "th",
+        };
+        write!(
+            f,
+            "{}{}{}",
+            self.place,
+            suffix,
+            if self.is_tied { "=" } else { "" }
+        )
+    }
+}
+
+/// Ranks candidates by score, highest first. Scores are compared to one
+/// decimal place, the precision they are reported at, so students shown
+/// with the same score share a place.
+pub fn rank(scores: &[(String, f32)]) -> Vec<(String, Position)> {
+    let rounded = |score: f32| (score * 10.0).round() as i64;
+    let mut sorted: Vec<&(String, f32)> = scores.iter().collect();
+    sorted.sort_by_key(|(_, score)| std::cmp::Reverse(rounded(*score)));
+
+    let out_of = sorted.len() as u32;
+    sorted
+        .iter()
+        .map(|(candidate_number, score)| {
+            let score = rounded(*score);
+            let above = sorted
+                .iter()
+                .filter(|(_, other)| rounded(*other) > score)
+                .count() as u32;
+            let level = sorted
+                .iter()
+                .filter(|(_, other)| rounded(*other) == score)
+                .count();
+            (
+                candidate_number.clone(),
+                Position::new(above + 1, level > 1, out_of),
+            )
+        })
+        .collect()
+}
+
+/// The output of analysing one class.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ClassAnalysis {
+    class_summary: ClassPerformanceSummary,
+    student_summaries: Vec<StudentPerformanceSummary>,
+    subject_grades: Vec<SubjectGrades>,
+    rank_by: RankBy,
+}
+
+impl ClassAnalysis {
+    pub fn class_summary(&self) -> &ClassPerformanceSummary {
+        &self.class_summary
+    }
+
+    /// One summary per student with at least one subject score, in the
+    /// order the students were given.
+    pub fn student_summaries(&self) -> &[StudentPerformanceSummary] {
+        &self.student_summaries
+    }
+
+    /// One entry per subject that has a grading scheme.
+    pub fn subject_grades(&self) -> &[SubjectGrades] {
+        &self.subject_grades
+    }
+
+    pub fn grades_for(&self, subject: Subject) -> Option<&SubjectGrades> {
+        self.subject_grades
+            .iter()
+            .find(|grades| grades.subject == subject)
+    }
+
+    pub fn grade(&self, candidate_number: &str, subject: Subject) -> Option<&str> {
+        self.grades_for(subject)?
+            .student_grades
+            .iter()
+            .find(|(candidate, _)| candidate == candidate_number)
+            .map(|(_, grade)| grade.as_str())
+    }
+
+    /// What the class positions on the student summaries are based on.
+    pub fn rank_by(&self) -> RankBy {
+        self.rank_by
+    }
+
+    pub fn position(&self, candidate_number: &str) -> Option<Position> {
+        self.student_summaries
+            .iter()
+            .find(|summary| summary.student().candidate_number() == candidate_number)?
+            .position()
+    }
+
+    /// The student's standardised score in each subject they have a score
+    /// in, leaving out subjects where everyone scored the same.
+    pub fn z_scores(&self, candidate_number: &str) -> Vec<(Subject, f32)> {
+        self.student_summaries
+            .iter()
+            .find(|summary| summary.student().candidate_number() == candidate_number)
+            .map(|summary| {
+                summary
+                    .subject_specific_details()
+                    .iter()
+                    .filter_map(|detail| {
+                        let z_score = self
+                            .class_summary
+                            .subject_summary(detail.subject())?
+                            .z_score(detail.overall_subject_score())?;
+                        Some((detail.subject(), z_score))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn z_score(&self, candidate_number: &str, subject: Subject) -> Option<f32> {
+        self.z_scores(candidate_number)
+            .into_iter()
+            .find(|(scored, _)| *scored == subject)
+            .map(|(_, z_score)| z_score)
+    }
+
+    /// The mean of the student's z-scores, which weighs every subject the
+    /// same however harshly it was marked.
+    pub fn standardised_average(&self, candidate_number: &str) -> Option<f32> {
+        mean(
+            self.z_scores(candidate_number)
+                .into_iter()
+                .map(|(_, z_score)| z_score),
+        )
+    }
+}
+
+fn percentage(raw_score: f32, max_mark: u32) -> f32 {
+    if max_mark == 0 {
+        return 0.0;
+    }
+    raw_score / max_mark as f32 * 100.0
+}
+
+fn mean(values: impl Iterator<Item = f32>) -> Option<f32> {
+    let (total, count) = values.fold((0.0, 0), |(total, count), value| (total + value, count + 1));
+    (count > 0).then(|| total / count as f32)
+}
+
+/// Summarises one student across `results`, or `None` if they have no
+/// marks at all.
+pub fn student_summary(
+    student: &Student,
+    results: &[AssessmentResults],
+    weighting: &Weighting,
+) -> Option<StudentPerformanceSummary> {
+    let details: Vec<SubjectSpecificSummary> = results
+        .iter()
+        .filter(|results| {
+            student
+                .subjects_read()
+                .contains(&results.assessment.subject())
+        })
+        .filter_map(|results| {
+            let score = results.subject_score(student.candidate_number(), weighting)?;
+            let components = results
+                .assessment
+                .components()
+                .iter()
+                .filter_map(|component| {
+                    Some(SubjectComponentSummary::new(
+                        component.code().to_string(),
+                        results.component_percentage(student.candidate_number(), component)?,
+                        results.component_average(component).unwrap_or_default(),
+                    ))
+                })
+                .collect();
+            Some(SubjectSpecificSummary::new(
+                results.assessment.subject(),
+                score,
+                ComponentSpecificSummary::new(components),
+            ))
+        })
+        .collect();
+
+    let overall_average = mean(details.iter().map(|detail| detail.overall_subject_score()))?;
+    let by_score = |a: &&SubjectSpecificSummary, b: &&SubjectSpecificSummary| {
+        a.overall_subject_score()
+            .total_cmp(&b.overall_subject_score())
+    };
+    let best_subject = details.iter().max_by(by_score)?.subject();
+    let worst_subject = details.iter().min_by(by_score)?.subject();
+
+    Some(StudentPerformanceSummary::new(
+        student.clone(),
+        overall_average,
+        best_subject,
+        worst_subject,
+        details,
+    ))
+}
+
+/// Analyses `class` from one set of results per subject. Returns `None` if
+/// no student in the class has any marks.
+pub fn analyse_class(
+    class: StudentClass,
+    students: &[Student],
+    results: &[AssessmentResults],
+    weighting: &Weighting,
+    grading: &GradingPolicy,
+    rank_by: RankBy,
+) -> Option<ClassAnalysis> {
+    let mut student_summaries: Vec<StudentPerformanceSummary> = students
+        .iter()
+        .filter(|student| student.class() == class)
+        .filter_map(|student| student_summary(student, results, weighting))
+        .collect();
+
+    let averages: Vec<f32> = student_summaries
+        .iter()
+        .map(|summary| summary.overall_average())
+        .collect();
+    let overall_statistics = Statistics::of(&averages)?;
+
+    let mut subjects: Vec<Subject> = results
+        .iter()
+        .map(|results| results.assessment.subject())
+        .collect();
+    subjects.sort();
+    subjects.dedup();
+
+    let overall_scores: Vec<(String, f32)> = student_summaries
+        .iter()
+        .map(|summary| {
+            (
+                summary.student().candidate_number().to_string(),
+                rank_by.score(summary),
+            )
+        })
+        .collect();
+    for (candidate_number, position) in rank(&overall_scores) {
+        if let Some(summary) = student_summaries
+            .iter_mut()
+            .find(|summary| summary.student().candidate_number() == candidate_number)
+        {
+            summary.set_position(position);
+        }
+    }
+    for subject in &subjects {
+        let subject_scores: Vec<(String, f32)> = student_summaries
+            .iter()
+            .filter_map(|summary| {
+                let detail = summary
+                    .subject_specific_details()
+                    .iter()
+                    .find(|detail| detail.subject() == *subject)?;
+                Some((
+                    summary.student().candidate_number().to_string(),
+                    detail.overall_subject_score(),
+                ))
+            })
+            .collect();
+        for (candidate_number, position) in rank(&subject_scores) {
+            if let Some(detail) = student_summaries
+                .iter_mut()
+                .find(|summary| summary.student().candidate_number() == candidate_number)
+                .and_then(|summary| summary.subject_specific_detail_mut(*subject))
+            {
+                detail.set_position(position);
+            }
+        }
+    }
+
+    let subject_summaries: Vec<ClassSubjectSummary> = subjects
+        .into_iter()
+        .filter_map(|subject| {
+            let scores: Vec<f32> = student_summaries
+                .iter()
+                .flat_map(|summary| {
+                    summary
+                        .subject_specific_details()
+                        .iter()
+                        .filter(|detail| detail.subject() == subject)
+                        .map(|detail| detail.overall_subject_score())
+                })
+                .collect();
+            let pass_rate = grading
+                .scheme_for(subject)
+                .filter(|scheme| scheme.pass_mark().is_some())
+                .map(|scheme| {
+                    let passes = scores
+                        .iter()
+                        .filter(|score| scheme.is_pass(**score))
+                        .count();
+                    passes as f32 / scores.len() as f32 * 100.0
+                });
+            Some(ClassSubjectSummary::new(
+                subject,
+                Statistics::of(&scores)?,
+                pass_rate,
+                Histogram::of(&scores, HISTOGRAM_BIN_WIDTH),
+            ))
+        })
+        .collect();
+
+    let subject_grades = subject_summaries
+        .iter()
+        .filter_map(|summary| {
+            let subject = summary.subject();
+            let scheme = grading.scheme_for(subject)?;
+            let scores: Vec<(String, f32)> = student_summaries
+                .iter()
+                .flat_map(|student| {
+                    student
+                        .subject_specific_details()
+                        .iter()
+                        .filter(|detail| detail.subject() == subject)
+                        .map(|detail| {
+                            (
+                                student.student().candidate_number().to_string(),
+                                detail.overall_subject_score(),
+                            )
+                        })
+                })
+                .collect();
+            Some(SubjectGrades {
+                subject,
+                scheme_code: scheme.code().to_string(),
+                average_grade: scheme.grade(summary.subject_average())?.to_string(),
+                student_grades: scores
+                    .iter()
+                    .filter_map(|(candidate_number, score)| {
+                        Some((candidate_number.clone(), scheme.grade(*score)?.to_string()))
+                    })
+                    .collect(),
+                distribution: scheme.distribution(scores.iter().map(|(_, score)| *score)),
+            })
+        })
+        .collect();
+
+    Some(ClassAnalysis {
+        class_summary: ClassPerformanceSummary::new(
+            class,
+            overall_statistics,
+            Histogram::of(&averages, HISTOGRAM_BIN_WIDTH),
+            subject_summaries,
+        ),
+        student_summaries,
+        subject_grades,
+        rank_by,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::student;
+    use crate::marks::{AcademicPeriod, Term};
+
+    fn scores(scores: &[(&str, f32)]) -> Vec<(String, f32)> {
+        scores
+            .iter()
+            .map(|(candidate_number, score)| (candidate_number.to_string(), *score))
+            .collect()
+    }
+
+    fn places(ranked: &[(String, Position)]) -> Vec<String> {
+        ranked
+            .iter()
+            .map(|(candidate_number, position)| format!("{} {}", candidate_number, position))
+            .collect()
+    }
+
+    fn class_and_subject() -> (StudentClass, Subject) {
+        (
+            StudentClass::all().next().expect("a built-in class"),
+            Subject::all().next().expect("a built-in subject"),
+        )
+    }
+
+    /// Paper 1 out of 50 with weight 1 and paper 2 out of 100 with weight 3.
+    fn results(marks: &[(&str, &str, Option<f32>)]) -> AssessmentResults {
+        results_for(class_and_subject().1, marks)
+    }
+
+    fn results_for(subject: Subject, marks: &[(&str, &str, Option<f32>)]) -> AssessmentResults {
+        let class = class_and_subject().0;
+        AssessmentResults::new(
+            Assessment::new(
+                "End of term".to_string(),
+                class,
+                subject,
+                AcademicPeriod::new(2025, Term::First),
+                vec![
+                    AssessmentComponent::new("P1".to_string(), "Paper 1".to_string(), 50, 1.0),
+                    AssessmentComponent::new("P2".to_string(), "Paper 2".to_string(), 100, 3.0),
+                ],
+            ),
+            marks
+                .iter()
+                .map(|(candidate_number, component_code, raw_score)| {
+                    Mark::new(
+                        candidate_number.to_string(),
+                        component_code.to_string(),
+                        *raw_score,
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn tied_scores_share_a_place_and_skip_the_next() {
+        let ranked = rank(&scores(&[
+            ("A", 80.0),
+            ("B", 70.0),
+            ("C", 70.0),
+            ("D", 60.0),
+        ]));
+
+        assert_eq!(places(&ranked), vec!["A 1st", "B 2nd=", "C 2nd=", "D 4th"]);
+        assert!(ranked.iter().all(|(_, position)| position.out_of() == 4));
+    }
+
+    #[test]
+    fn scores_equal_to_one_decimal_place_are_tied() {
+        let ranked = rank(&scores(&[("A", 70.04), ("B", 69.96), ("C", 69.94)]));
+
+        assert_eq!(places(&ranked), vec!["A 1st=", "B 1st=", "C 3rd"]);
+    }
+
+    #[test]
+    fn a_nan_score_ranks_below_real_scores() {
+        let ranked = rank(&scores(&[("A", f32::NAN), ("B", 50.0)]));
+
+        assert_eq!(places(&ranked), vec!["B 1st", "A 2nd"]);
+    }
+
+    #[test]
+    fn ranking_nobody_gives_no_positions() {
+        assert!(rank(&[]).is_empty());
+    }
+
+    #[test]
+    fn subject_scores_follow_the_weighting() {
+        // 50% on paper 1 and 80% on paper 2.
+        let results = results(&[("A", "P1", Some(25.0)), ("A", "P2", Some(80.0))]);
+        let score = |weighting: &Weighting| results.subject_score("A", weighting).unwrap();
+
+        assert_eq!(score(&Weighting::Assessment), 72.5);
+        assert_eq!(score(&Weighting::Equal), 65.0);
+        assert_eq!(score(&Weighting::RawTotal), 70.0);
+        assert_eq!(
+            score(&Weighting::Custom(vec![("p1".to_string(), 3.0)])),
+            65.0
+        );
+    }
+
+    #[test]
+    fn absent_candidates_score_zero_and_unmarked_papers_are_left_out() {
+        let results = results(&[
+            ("A", "P1", None),
+            ("B", "P2", Some(80.0)),
+            ("C", "P1", None),
+            ("C", "P2", Some(80.0)),
+        ]);
+
+        assert_eq!(
+            results.subject_score("A", &Weighting::Assessment),
+            Some(0.0)
+        );
+        assert_eq!(
+            results.subject_score("B", &Weighting::Assessment),
+            Some(80.0)
+        );
+        assert_eq!(
+            results.subject_score("C", &Weighting::Assessment),
+            Some(60.0)
+        );
+        assert_eq!(results.subject_score("D", &Weighting::Assessment), None);
+    }
+
+    #[test]
+    fn a_class_with_no_marked_students_has_no_analysis() {
+        let (class, subject) = class_and_subject();
+        let results = [results(&[("A", "P1", Some(25.0))])];
+        let analyse = |students: &[Student]| {
+            analyse_class(
+                class,
+                students,
+                &results,
+                &Weighting::Assessment,
+                &GradingPolicy::seed(),
+                RankBy::Average,
+            )
+        };
+
+        assert_eq!(analyse(&[]), None);
+        assert_eq!(analyse(&[student("B", class, &[subject])]), None);
+    }
+
+    #[test]
+    fn students_with_equal_averages_share_a_class_position() {
+        let (class, subject) = class_and_subject();
+        let students: Vec<Student> = ["A", "B", "C"]
+            .into_iter()
+            .map(|candidate_number| student(candidate_number, class, &[subject]))
+            .collect();
+        let results = [results(&[
+            ("A", "P2", Some(90.0)),
+            ("B", "P2", Some(90.0)),
+            ("C", "P2", Some(40.0)),
+        ])];
+
+        let analysis = analyse_class(
+            class,
+            &students,
+            &results,
+            &Weighting::Assessment,
+            &GradingPolicy::seed(),
+            RankBy::Average,
+        )
+        .expect("the class has marks");
+
+        assert_eq!(analysis.position("A"), Some(Position::new(1, true, 3)));
+        assert_eq!(analysis.position("B"), Some(Position::new(1, true, 3)));
+        assert_eq!(analysis.position("C"), Some(Position::new(3, false, 3)));
+    }
+
+    #[test]
+    fn ranking_by_total_favours_students_taking_more_subjects() {
+        let (class, first) = class_and_subject();
+        let second = Subject::all().nth(1).expect("a second built-in subject");
+        let students = [
+            student("A", class, &[first, second]),
+            student("B", class, &[first]),
+        ];
+        let results = [
+            results_for(first, &[("A", "P2", Some(60.0)), ("B", "P2", Some(90.0))]),
+            results_for(second, &[("A", "P2", Some(60.0))]),
+        ];
+        let analyse = |rank_by| {
+            analyse_class(
+                class,
+                &students,
+                &results,
+                &Weighting::Assessment,
+                &GradingPolicy::seed(),
+                rank_by,
+            )
+            .expect("the class has marks")
+        };
+
+        let by_average = analyse(RankBy::Average);
+        assert_eq!(by_average.position("B"), Some(Position::new(1, false, 2)));
+        assert_eq!(by_average.position("A"), Some(Position::new(2, false, 2)));
+
+        let by_total = analyse(RankBy::Total);
+        assert_eq!(by_total.rank_by(), RankBy::Total);
+        assert_eq!(by_total.position("A"), Some(Position::new(1, false, 2)));
+        assert_eq!(by_total.position("B"), Some(Position::new(2, false, 2)));
+    }
+
+    #[test]
+    fn subject_positions_only_count_students_with_a_score() {
+        let (class, first) = class_and_subject();
+        let second = Subject::all().nth(1).expect("a second built-in subject");
+        let students = [
+            student("A", class, &[first, second]),
+            student("B", class, &[first]),
+        ];
+        let results = [
+            results_for(first, &[("A", "P2", Some(60.0)), ("B", "P2", Some(90.0))]),
+            results_for(second, &[("A", "P2", Some(60.0))]),
+        ];
+
+        let analysis = analyse_class(
+            class,
+            &students,
+            &results,
+            &Weighting::Assessment,
+            &GradingPolicy::seed(),
+            RankBy::Average,
+        )
+        .expect("the class has marks");
+        let subject_position = |candidate_number: &str, subject: Subject| {
+            analysis
+                .student_summaries()
+                .iter()
+                .find(|summary| summary.student().candidate_number() == candidate_number)?
+                .subject_specific_details()
+                .iter()
+                .find(|detail| detail.subject() == subject)?
+                .position()
+        };
+
+        assert_eq!(
+            subject_position("B", first),
+            Some(Position::new(1, false, 2))
+        );
+        assert_eq!(
+            subject_position("A", first),
+            Some(Position::new(2, false, 2))
+        );
+        assert_eq!(
+            subject_position("A", second),
+            Some(Position::new(1, false, 1))
+        );
+        assert_eq!(subject_position("B", second), None);
+    }
+}
@@ -0,0 +1,517 @@
+// Subject and class catalogue
+//
+// The curriculum is data rather than code: programmes, classes and subjects
+// are stored by the webapp and loaded into a `Catalogue` at runtime. The
+// built-in `domain` enums seed a fresh database; anything added since is
+// carried as `StudentClass::Added` or `Subject::Added`, so codes read from
+// the database are resolved against the loaded catalogue.
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{
+    AddedClass, AddedSubject, PaperComponent, ParseError, StudentClass, Subject, WassceSubject,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Programme {
+    code: String,
+    name: String,
+    exam_board: String,
+}
+
+impl Programme {
+    pub fn new(code: String, name: String, exam_board: String) -> Self {
+        Self {
+            code,
+            name,
+            exam_board,
+        }
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn exam_board(&self) -> &str {
+        &self.exam_board
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ClassGroup {
+    code: String,
+    name: String,
+    programme_code: String,
+    year_order: i32,
+}
+
+impl ClassGroup {
+    pub fn new(code: String, name: String, programme_code: String, year_order: i32) -> Self {
+        Self {
+            code,
+            name,
+            programme_code,
+            year_order,
+        }
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn programme_code(&self) -> &str {
+        &self.programme_code
+    }
+
+    pub fn year_order(&self) -> i32 {
+        self.year_order
+    }
+
+    /// The built-in class this group corresponds to, or the group itself
+    /// as an added class.
+    pub fn student_class(&self) -> StudentClass {
+        StudentClass::from_code(&self.code).unwrap_or_else(|| {
+            StudentClass::Added(AddedClass::new(
+                &self.code,
+                &self.name,
+                &self.programme_code,
+                self.year_order,
+            ))
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CatalogueSubject {
+    code: String,
+    name: String,
+    programme_code: String,
+    syllabus_code: Option<String>,
+    is_active: bool,
+    is_core: bool,
+    components: Vec<PaperComponent>,
+}
+
+impl CatalogueSubject {
+    pub fn new(
+        code: String,
+        name: String,
+        programme_code: String,
+        syllabus_code: Option<String>,
+        is_active: bool,
+        is_core: bool,
+        components: Vec<PaperComponent>,
+    ) -> Self {
+        Self {
+            code,
+            name,
+            programme_code,
+            syllabus_code,
+            is_active,
+            is_core,
+            components,
+        }
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn programme_code(&self) -> &str {
+        &self.programme_code
+    }
+
+    /// The exam board's own syllabus code, e.g. "0620" for IGCSE Chemistry.
+    pub fn syllabus_code(&self) -> Option<&str> {
+        self.syllabus_code.as_deref()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.is_active
+    }
+
+    /// Whether every student of the programme must pass it.
+    pub fn is_core(&self) -> bool {
+        self.is_core
+    }
+
+    pub fn components(&self) -> &[PaperComponent] {
+        &self.components
+    }
+
+    /// The built-in subject this entry corresponds to, or the entry itself
+    /// as an added subject.
+    pub fn subject(&self) -> Subject {
+        Subject::from_code(&self.code).unwrap_or_else(|| {
+            Subject::Added(AddedSubject::new(
+                &self.code,
+                &self.name,
+                &self.programme_code,
+            ))
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Catalogue {
+    programmes: Vec<Programme>,
+    class_groups: Vec<ClassGroup>,
+    subjects: Vec<CatalogueSubject>,
+}
+
+impl Catalogue {
+    pub fn new(
+        programmes: Vec<Programme>,
+        class_groups: Vec<ClassGroup>,
+        subjects: Vec<CatalogueSubject>,
+    ) -> Self {
+        Self {
+            programmes,
+            class_groups,
+            subjects,
+        }
+    }
+
+    /// The catalogue as described by the built-in enums, used to seed a new
+    /// database.
+    pub fn seed() -> Self {
+        let programmes = [
+            ("LSEC", "Lower Secondary", "Ridge School"),
+            ("IGCSE", "IGCSE", "Cambridge"),
+            ("ALEVEL", "A-Level", "Cambridge"),
+            ("WASSCE", "WASSCE", "WAEC"),
+        ]
+        .into_iter()
+        .map(|(code, name, exam_board)| {
+            Programme::new(code.to_string(), name.to_string(), exam_board.to_string())
+        })
+        .collect();
+
+        let class_groups = StudentClass::all()
+            .zip(1..)
+            .map(|(class, year_order)| {
+                ClassGroup::new(
+                    class.code().to_string(),
+                    class.to_string(),
+                    class.programme_code().to_string(),
+                    year_order,
+                )
+            })
+            .collect();
+
+        let subjects = Subject::all()
+            .map(|subject| {
+                CatalogueSubject::new(
+                    subject.code(),
+                    subject.name().to_string(),
+                    subject.programme_code().to_string(),
+                    None,
+                    true,
+                    seed_is_core(subject),
+                    seed_components(subject),
+                )
+            })
+            .collect();
+
+        Self::new(programmes, class_groups, subjects)
+    }
+
+    pub fn programmes(&self) -> &[Programme] {
+        &self.programmes
+    }
+
+    pub fn class_groups(&self) -> &[ClassGroup] {
+        &self.class_groups
+    }
+
+    pub fn subjects(&self) -> &[CatalogueSubject] {
+        &self.subjects
+    }
+
+    pub fn programme(&self, code: &str) -> Option<&Programme> {
+        self.programmes
+            .iter()
+            .find(|programme| programme.code.eq_ignore_ascii_case(code))
+    }
+
+    pub fn class_group(&self, code: &str) -> Option<&ClassGroup> {
+        self.class_groups
+            .iter()
+            .find(|class_group| class_group.code.eq_ignore_ascii_case(code))
+    }
+
+    pub fn subject(&self, code: &str) -> Option<&CatalogueSubject> {
+        self.subjects
+            .iter()
+            .find(|subject| subject.code.eq_ignore_ascii_case(code))
+    }
+
+    /// Looks up the catalogue entry for a built-in subject.
+    pub fn resolve(&self, subject: Subject) -> Option<&CatalogueSubject> {
+        self.subject(&subject.code())
+    }
+
+    /// The class stored under `code`. Built-in classes removed from the
+    /// catalogue still resolve, so older records stay readable.
+    pub fn class_for(&self, code: &str) -> Result<StudentClass, ParseError> {
+        let code = code.trim();
+        self.class_group(code)
+            .map(ClassGroup::student_class)
+            .or_else(|| StudentClass::from_code(code))
+            .ok_or_else(|| ParseError::UnknownStudentClass(code.to_string()))
+    }
+
+    /// The subject stored under `code`. Built-in subjects removed from the
+    /// catalogue still resolve, so older records stay readable.
+    pub fn subject_for(&self, code: &str) -> Result<Subject, ParseError> {
+        let code = code.trim();
+        self.subject(code)
+            .map(CatalogueSubject::subject)
+            .or_else(|| Subject::from_code(code))
+            .ok_or_else(|| ParseError::UnknownSubject(code.to_string()))
+    }
+
+    /// Every class in year order.
+    pub fn classes(&self) -> Vec<StudentClass> {
+        let mut class_groups: Vec<&ClassGroup> = self.class_groups.iter().collect();
+        class_groups.sort_by_key(|class_group| class_group.year_order);
+        class_groups
+            .into_iter()
+            .map(ClassGroup::student_class)
+            .collect()
+    }
+
+    /// Active subjects offered to a class, in catalogue order.
+    pub fn subjects_offered(&self, class: StudentClass) -> impl Iterator<Item = Subject> + '_ {
+        self.subjects
+            .iter()
+            .filter(move |subject| {
+                subject.is_active && subject.programme_code == class.programme_code()
+            })
+            .map(CatalogueSubject::subject)
+    }
+
+    /// Active subjects every student of a class must pass, in catalogue
+    /// order.
+    pub fn core_subjects(&self, class: StudentClass) -> impl Iterator<Item = Subject> + '_ {
+        self.subjects
+            .iter()
+            .filter(move |subject| {
+                subject.is_active
+                    && subject.is_core
+                    && subject.programme_code == class.programme_code()
+            })
+            .map(CatalogueSubject::subject)
+    }
+
+    /// The class students of `class` move up to at year end: the next
+    /// class of the same programme by year order, or `None` after its last
+    /// year.
+    pub fn next_year(&self, class: StudentClass) -> Option<StudentClass> {
+        let year_order = self.class_group(class.code())?.year_order;
+        self.class_groups
+            .iter()
+            .filter(|class_group| {
+                class_group.programme_code == class.programme_code()
+                    && class_group.year_order > year_order
+            })
+            .min_by_key(|class_group| class_group.year_order)
+            .map(ClassGroup::student_class)
+    }
+
+    /// Active subjects offered to a class, in catalogue order.
+    pub fn subjects_for_class<'a>(
+        &'a self,
+        class: &'a ClassGroup,
+    ) -> impl Iterator<Item = &'a CatalogueSubject> {
+        self.subjects.iter().filter(move |subject| {
+            subject.is_active && subject.programme_code == class.programme_code
+        })
+    }
+}
+
+/// English and Core Mathematics, which every WASSCE candidate must pass.
+fn seed_is_core(subject: Subject) -> bool {
+    matches!(
+        subject,
+        Subject::Wassce(WassceSubject::English | WassceSubject::CoreMathematics)
+    )
+}
+
+fn seed_components(subject: Subject) -> Vec<PaperComponent> {
+    use crate::domain::{ALevelSubject, IGCSESubject, WassceSubject};
+
+    let has_practical = matches!(
+        subject,
+        Subject::Wassce(WassceSubject::Physics | WassceSubject::Chemistry | WassceSubject::Biology)
+            | Subject::ALevel(ALevelSubject::Physics)
+            | Subject::IGCSE(IGCSESubject::Chemistry | IGCSESubject::Biology)
+    );
+
+    let mut components = vec![
+        PaperComponent::new("P1".to_string(), "Paper 1".to_string(), 60),
+        PaperComponent::new("P2".to_string(), "Paper 2".to_string(), 120),
+    ];
+    if has_practical {
+        components.push(PaperComponent::new(
+            "PR".to_string(),
+            "Practical".to_string(),
+            120,
+        ));
+    }
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_additions() -> Catalogue {
+        let mut catalogue = Catalogue::seed();
+        catalogue.programmes.push(Programme::new(
+            "IB".to_string(),
+            "IB Diploma".to_string(),
+            "IBO".to_string(),
+        ));
+        catalogue.class_groups.push(ClassGroup::new(
+            "IB1".to_string(),
+            "IB Year 1".to_string(),
+            "IB".to_string(),
+            20,
+        ));
+        catalogue.class_groups.push(ClassGroup::new(
+            "IB2".to_string(),
+            "IB Year 2".to_string(),
+            "IB".to_string(),
+            21,
+        ));
+        catalogue.subjects.push(CatalogueSubject::new(
+            "IB-TOK".to_string(),
+            "Theory of Knowledge".to_string(),
+            "IB".to_string(),
+            None,
+            true,
+            true,
+            Vec::new(),
+        ));
+        catalogue
+    }
+
+    #[test]
+    fn built_in_codes_resolve_to_built_in_variants() {
+        let catalogue = Catalogue::seed();
+        for class in StudentClass::all() {
+            assert_eq!(catalogue.class_for(class.code()), Ok(class));
+        }
+        for subject in Subject::all() {
+            assert_eq!(catalogue.subject_for(&subject.code()), Ok(subject));
+        }
+    }
+
+    #[test]
+    fn added_codes_resolve_through_the_catalogue() {
+        let catalogue = with_additions();
+
+        let class = catalogue.class_for("ib1").unwrap();
+        assert!(matches!(class, StudentClass::Added(_)));
+        assert_eq!(class.code(), "IB1");
+        assert_eq!(class.programme_code(), "IB");
+        assert_eq!(class.to_string(), "IB Year 1");
+
+        let subject = catalogue.subject_for("IB-TOK").unwrap();
+        assert!(matches!(subject, Subject::Added(_)));
+        assert_eq!(subject.code(), "IB-TOK");
+        assert_eq!(subject.name(), "Theory of Knowledge");
+        assert_eq!(
+            catalogue.subjects_offered(class).collect::<Vec<_>>(),
+            vec![subject]
+        );
+    }
+
+    #[test]
+    fn unknown_codes_are_errors() {
+        let catalogue = with_additions();
+        assert_eq!(
+            catalogue.class_for("Z9"),
+            Err(ParseError::UnknownStudentClass("Z9".to_string()))
+        );
+        assert_eq!(
+            catalogue.subject_for("IB-XYZ"),
+            Err(ParseError::UnknownSubject("IB-XYZ".to_string()))
+        );
+    }
+
+    #[test]
+    fn built_in_codes_outlive_their_catalogue_entry() {
+        let mut catalogue = Catalogue::seed();
+        catalogue.class_groups.clear();
+        catalogue.subjects.clear();
+        assert!(catalogue.class_for("W3").is_ok());
+        assert!(catalogue.subject_for("WASSCE-PHY").is_ok());
+    }
+
+    #[test]
+    fn next_year_follows_year_order_within_a_programme() {
+        let catalogue = with_additions();
+        let ib1 = catalogue.class_for("IB1").unwrap();
+        let ib2 = catalogue.class_for("IB2").unwrap();
+        assert_eq!(catalogue.next_year(ib1), Some(ib2));
+        assert_eq!(catalogue.next_year(ib2), None);
+
+        for class in StudentClass::all() {
+            assert_eq!(catalogue.next_year(class), class.next_year());
+        }
+    }
+
+    #[test]
+    fn core_subjects_are_the_active_core_subjects_of_the_programme() {
+        let mut catalogue = with_additions();
+        let wassce = catalogue.class_for("W1").unwrap();
+        assert_eq!(
+            catalogue.core_subjects(wassce).collect::<Vec<_>>(),
+            [
+                Subject::Wassce(WassceSubject::English),
+                Subject::Wassce(WassceSubject::CoreMathematics),
+            ]
+        );
+        for class in StudentClass::all().filter(|class| class.programme_code() != "WASSCE") {
+            assert_eq!(catalogue.core_subjects(class).count(), 0);
+        }
+
+        let ib1 = catalogue.class_for("IB1").unwrap();
+        let tok = catalogue.subject_for("IB-TOK").unwrap();
+        assert_eq!(catalogue.core_subjects(ib1).collect::<Vec<_>>(), [tok]);
+
+        for subject in &mut catalogue.subjects {
+            if subject.code == "WASSCE-ENG" {
+                subject.is_active = false;
+            }
+        }
+        assert_eq!(
+            catalogue.core_subjects(wassce).collect::<Vec<_>>(),
+            [Subject::Wassce(WassceSubject::CoreMathematics)]
+        );
+    }
+
+    #[test]
+    fn added_variants_survive_serialisation() {
+        let catalogue = with_additions();
+        let class = catalogue.class_for("IB2").unwrap();
+        let subject = catalogue.subject_for("IB-TOK").unwrap();
+
+        let json = serde_json::to_string(&(class, subject)).unwrap();
+        let read: (StudentClass, Subject) = serde_json::from_str(&json).unwrap();
+        assert_eq!(read, (class, subject));
+    }
+}
@@ -0,0 +1,434 @@
+// Early warnings
+//
+// After an assessment, picks out the students who may need help: those
+// whose average fell sharply since the last assessment they sat, those
+// below the pass mark in a core subject, and those with marks still to be
+// entered. Scores come from the class analysis, so every figure matches the
+// analysis page.
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::{AssessmentResults, ClassAnalysis};
+use crate::domain::{Student, Subject};
+use crate::grading::GradingPolicy;
+use crate::trends::{Sitting, Trends};
+
+/// Fall in overall average, in percentage points, that raises a warning
+/// unless another threshold is chosen.
+pub const DEFAULT_DROP_THRESHOLD: f32 = 10.0;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Concern {
+    /// The overall average fell by more than the threshold since
+    /// `previous_assessment`.
+    AverageDropped {
+        previous_assessment: String,
+        previous: f32,
+        current: f32,
+    },
+    BelowPass {
+        subject: Subject,
+        score: f32,
+        pass_mark: f32,
+    },
+    /// Components of a subject the student reads with no mark entered.
+    MissingMarks {
+        subject: Subject,
+        components: Vec<String>,
+    },
+}
+
+impl std::fmt::Display for Concern {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::AverageDropped {
+                previous_assessment,
+                previous,
+                current,
+            } => write!(
+                f,
+                "Average down {:.1} points since {} ({:.1} to {:.1})",
+                previous - current,
+                previous_assessment,
+                previous,
+                current
+            ),
+            Self::BelowPass {
+                subject,
+                score,
+                pass_mark,
+            } => write!(
+                f,
+                "Below pass in {}: {:.1} (pass mark {:.1})",
+                subject, score, pass_mark
+            ),
+            Self::MissingMarks {
+                subject,
+                components,
+            } => write!(f, "Missing marks in {}: {}", subject, components.join(", ")),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AtRiskStudent {
+    student: Student,
+    average: Option<f32>,
+    concerns: Vec<Concern>,
+}
+
+impl AtRiskStudent {
+    pub fn student(&self) -> &Student {
+        &self.student
+    }
+
+    /// `None` when the student has no marks at all.
+    pub fn average(&self) -> Option<f32> {
+        self.average
+    }
+
+    /// Never empty.
+    pub fn concerns(&self) -> &[Concern] {
+        &self.concerns
+    }
+}
+
+/// The students of `analysis`'s class with at least one concern, in the
+/// order given. `earlier` holds the assessments sat before this one, of
+/// any class, to find each student's previous average. `core_subjects` are
+/// those the class must pass, as the catalogue marks them.
+pub fn at_risk_students(
+    students: &[Student],
+    results: &[AssessmentResults],
+    analysis: &ClassAnalysis,
+    earlier: &[Sitting],
+    core_subjects: &[Subject],
+    grading: &GradingPolicy,
+    drop_threshold: f32,
+) -> Vec<AtRiskStudent> {
+    let class = analysis.class_summary().class();
+    students
+        .iter()
+        .filter(|student| student.class() == class)
+        .filter_map(|student| {
+            let candidate_number = student.candidate_number();
+            let summary = analysis
+                .student_summaries()
+                .iter()
+                .find(|summary| summary.student().candidate_number() == candidate_number);
+            let mut concerns = Vec::new();
+
+            if let Some(summary) = summary {
+                let trends = Trends::student(candidate_number, earlier);
+                if let Some(previous) = trends.overall().latest()
+                    && previous.score() - summary.overall_average() > drop_threshold
+                {
+                    concerns.push(Concern::AverageDropped {
+                        previous_assessment: previous.assessment_name().to_string(),
+                        previous: previous.score(),
+                        current: summary.overall_average(),
+                    });
+                }
+
+                for detail in summary.subject_specific_details() {
+                    if !core_subjects.contains(&detail.subject()) {
+                        continue;
+                    }
+                    let Some(pass_mark) = grading
+                        .scheme_for(detail.subject())
+                        .and_then(|scheme| scheme.pass_mark())
+                    else {
+                        continue;
+                    };
+                    if detail.overall_subject_score() < pass_mark {
+                        concerns.push(Concern::BelowPass {
+                            subject: detail.subject(),
+                            score: detail.overall_subject_score(),
+                            pass_mark,
+                        });
+                    }
+                }
+            }
+
+            for results in results {
+                let subject = results.assessment().subject();
+                if !student.subjects_read().contains(&subject) {
+                    continue;
+                }
+                let components: Vec<String> = results
+                    .assessment()
+                    .components()
+                    .iter()
+                    .filter(|component| {
+                        results
+                            .component_percentage(candidate_number, component)
+                            .is_none()
+                    })
+                    .map(|component| component.code().to_string())
+                    .collect();
+                if !components.is_empty() {
+                    concerns.push(Concern::MissingMarks {
+                        subject,
+                        components,
+                    });
+                }
+            }
+
+            (!concerns.is_empty()).then(|| AtRiskStudent {
+                student: student.clone(),
+                average: summary.map(|summary| summary.overall_average()),
+                concerns,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{RankBy, Weighting, analyse_class};
+    use crate::domain::{StudentClass, WassceSubject};
+    use crate::fixtures;
+    use crate::marks::{AcademicPeriod, Assessment, AssessmentComponent, Mark, Term};
+
+    const ENGLISH: Subject = Subject::Wassce(WassceSubject::English);
+
+    fn class() -> StudentClass {
+        StudentClass::all().next().expect("a built-in class")
+    }
+
+    fn elective() -> Subject {
+        Subject::all()
+            .find(|subject| *subject != ENGLISH)
+            .expect("a subject that is not core")
+    }
+
+    fn student(candidate_number: &str, class: StudentClass) -> Student {
+        fixtures::student(candidate_number, class, &[ENGLISH, elective()])
+    }
+
+    /// Two papers, each out of 100 and weighted equally.
+    fn results(
+        period: AcademicPeriod,
+        subject: Subject,
+        marks: &[(&str, &str, Option<f32>)],
+    ) -> AssessmentResults {
+        AssessmentResults::new(
+            Assessment::new(
+                "Exam".to_string(),
+                class(),
+                subject,
+                period,
+                vec![
+                    AssessmentComponent::new("P1".to_string(), "Paper 1".to_string(), 100, 1.0),
+                    AssessmentComponent::new("P2".to_string(), "Paper 2".to_string(), 100, 1.0),
+                ],
+            ),
+            marks
+                .iter()
+                .map(|(candidate_number, component_code, score)| {
+                    Mark::new(
+                        candidate_number.to_string(),
+                        component_code.to_string(),
+                        *score,
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    fn analysis(students: &[Student], results: &[AssessmentResults]) -> ClassAnalysis {
+        analyse_class(
+            class(),
+            students,
+            results,
+            &Weighting::Assessment,
+            &GradingPolicy::seed(),
+            RankBy::Average,
+        )
+        .expect("the class has marks")
+    }
+
+    /// English scores on both papers for each candidate.
+    fn english(period: AcademicPeriod, scores: &[(&str, f32)]) -> AssessmentResults {
+        let marks: Vec<(&str, &str, Option<f32>)> = scores
+            .iter()
+            .flat_map(|(candidate_number, score)| {
+                [
+                    (*candidate_number, "P1", Some(*score)),
+                    (*candidate_number, "P2", Some(*score)),
+                ]
+            })
+            .collect();
+        results(period, ENGLISH, &marks)
+    }
+
+    fn at_risk(
+        students: &[Student],
+        results: &[AssessmentResults],
+        earlier: &[Sitting],
+    ) -> Vec<AtRiskStudent> {
+        at_risk_students(
+            students,
+            results,
+            &analysis(students, results),
+            earlier,
+            &[ENGLISH],
+            &GradingPolicy::seed(),
+            DEFAULT_DROP_THRESHOLD,
+        )
+    }
+
+    fn candidates(at_risk: &[AtRiskStudent]) -> Vec<&str> {
+        at_risk
+            .iter()
+            .map(|student| student.student().candidate_number())
+            .collect()
+    }
+
+    #[test]
+    fn a_fall_beyond_the_threshold_is_a_concern() {
+        let previous = AcademicPeriod::new(2025, Term::First);
+        let current = AcademicPeriod::new(2025, Term::Second);
+        let students = [student("A", class()), student("B", class())];
+        let earlier_results = [english(previous, &[("A", 80.0), ("B", 80.0)])];
+        let earlier = [Sitting::new(
+            "Earlier".to_string(),
+            previous,
+            analysis(&students, &earlier_results),
+        )];
+
+        let at_risk = at_risk(
+            &students,
+            &[english(current, &[("A", 69.0), ("B", 70.0)])],
+            &earlier,
+        );
+
+        assert_eq!(candidates(&at_risk), vec!["A"]);
+        assert_eq!(
+            at_risk[0].concerns(),
+            [Concern::AverageDropped {
+                previous_assessment: "Earlier".to_string(),
+                previous: 80.0,
+                current: 69.0,
+            }]
+        );
+    }
+
+    #[test]
+    fn only_core_subjects_below_the_pass_mark_are_concerns() {
+        let period = AcademicPeriod::new(2025, Term::First);
+        let students = [student("A", class()), student("B", class())];
+        let elective_marks = [
+            ("A", "P1", Some(10.0)),
+            ("A", "P2", Some(10.0)),
+            ("B", "P1", Some(10.0)),
+            ("B", "P2", Some(10.0)),
+        ];
+
+        let at_risk = at_risk(
+            &students,
+            &[
+                english(period, &[("A", 49.0), ("B", 50.0)]),
+                results(period, elective(), &elective_marks),
+            ],
+            &[],
+        );
+
+        assert_eq!(candidates(&at_risk), vec!["A"]);
+        assert_eq!(
+            at_risk[0].concerns(),
+            [Concern::BelowPass {
+                subject: ENGLISH,
+                score: 49.0,
+                pass_mark: 50.0,
+            }]
+        );
+    }
+
+    #[test]
+    fn an_absent_candidate_scores_zero_rather_than_missing_marks() {
+        let period = AcademicPeriod::new(2025, Term::First);
+        let students = [student("A", class())];
+
+        let at_risk = at_risk(
+            &students,
+            &[results(
+                period,
+                ENGLISH,
+                &[("A", "P1", None), ("A", "P2", None)],
+            )],
+            &[],
+        );
+
+        assert_eq!(at_risk[0].average(), Some(0.0));
+        assert_eq!(
+            at_risk[0].concerns(),
+            [Concern::BelowPass {
+                subject: ENGLISH,
+                score: 0.0,
+                pass_mark: 50.0,
+            }]
+        );
+    }
+
+    #[test]
+    fn unentered_marks_are_concerns_even_without_an_average() {
+        let period = AcademicPeriod::new(2025, Term::First);
+        let students = [student("A", class()), student("B", class())];
+
+        let at_risk = at_risk(
+            &students,
+            &[results(period, ENGLISH, &[("A", "P1", Some(70.0))])],
+            &[],
+        );
+
+        assert_eq!(candidates(&at_risk), vec!["A", "B"]);
+        assert_eq!(
+            at_risk[0].concerns(),
+            [Concern::MissingMarks {
+                subject: ENGLISH,
+                components: vec!["P2".to_string()],
+            }]
+        );
+        assert_eq!(at_risk[1].average(), None);
+        assert_eq!(
+            at_risk[1].concerns(),
+            [Concern::MissingMarks {
+                subject: ENGLISH,
+                components: vec!["P1".to_string(), "P2".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn students_of_other_classes_are_not_warned_about() {
+        let period = AcademicPeriod::new(2025, Term::First);
+        let other = StudentClass::all().nth(1).expect("a second built-in class");
+        let students = [student("A", class()), student("B", other)];
+
+        let at_risk = at_risk(&students, &[english(period, &[("A", 10.0)])], &[]);
+
+        assert_eq!(candidates(&at_risk), vec!["A"]);
+    }
+
+    #[test]
+    fn nobody_is_at_risk_in_an_empty_class() {
+        let period = AcademicPeriod::new(2025, Term::First);
+        let students = [student("A", class())];
+        let results = [english(period, &[("A", 90.0)])];
+
+        assert!(at_risk(&students, &results, &[]).is_empty());
+        assert!(
+            at_risk_students(
+                &[],
+                &results,
+                &analysis(&students, &results),
+                &[],
+                &[ENGLISH],
+                &GradingPolicy::seed(),
+                DEFAULT_DROP_THRESHOLD,
+            )
+            .is_empty()
+        );
+    }
+}
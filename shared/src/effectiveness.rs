@@ -0,0 +1,506 @@
+// Teacher and subject effectiveness
+//
+// Results for one assessment grouped by subject across the classes that sat
+// it, and by teacher through the subjects they teach in the classes they
+// teach. Besides raw averages,
+// each group has a value added: how far students' scores in the subject sit
+// above or below their own average in their other subjects, so a subject
+// chosen by strong students does not look better taught than it is.
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::ClassAnalysis;
+use crate::domain::{StudentClass, StudentPerformanceSummary, Subject, Teacher};
+use crate::grading::GradingPolicy;
+use crate::statistics::Statistics;
+
+/// A student's score in `subject` less the mean of their other subject
+/// scores, or `None` if they have no score in it or no other subject.
+pub fn value_added(summary: &StudentPerformanceSummary, subject: Subject) -> Option<f32> {
+    let details = summary.subject_specific_details();
+    let score = details
+        .iter()
+        .find(|detail| detail.subject() == subject)?
+        .overall_subject_score();
+    let others: Vec<f32> = details
+        .iter()
+        .filter(|detail| detail.subject() != subject)
+        .map(|detail| detail.overall_subject_score())
+        .collect();
+    if others.is_empty() {
+        return None;
+    }
+    Some(score - others.iter().sum::<f32>() / others.len() as f32)
+}
+
+/// Scores gathered for a group, kept raw so groups can be merged.
+#[derive(Clone, Debug, Default)]
+struct Tally {
+    scores: Vec<f32>,
+    value_added: Vec<f32>,
+    passes: u32,
+    graded: u32,
+}
+
+impl Tally {
+    fn of(analysis: &ClassAnalysis, subject: Subject, grading: &GradingPolicy) -> Self {
+        let mut tally = Self::default();
+        for summary in analysis.student_summaries() {
+            let Some(detail) = summary
+                .subject_specific_details()
+                .iter()
+                .find(|detail| detail.subject() == subject)
+            else {
+                continue;
+            };
+            tally.scores.push(detail.overall_subject_score());
+            tally.value_added.extend(value_added(summary, subject));
+        }
+        if let Some(scheme) = grading
+            .scheme_for(subject)
+            .filter(|scheme| scheme.pass_mark().is_some())
+        {
+            tally.graded = tally.scores.len() as u32;
+            tally.passes = tally
+                .scores
+                .iter()
+                .filter(|score| scheme.is_pass(**score))
+                .count() as u32;
+        }
+        tally
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.scores.extend(&other.scores);
+        self.value_added.extend(&other.value_added);
+        self.passes += other.passes;
+        self.graded += other.graded;
+    }
+
+    fn pass_rate(&self) -> Option<f32> {
+        (self.graded > 0).then(|| self.passes as f32 / self.graded as f32 * 100.0)
+    }
+
+    fn value_added(&self) -> Option<f32> {
+        (!self.value_added.is_empty())
+            .then(|| self.value_added.iter().sum::<f32>() / self.value_added.len() as f32)
+    }
+}
+
+/// How a group did: spread of scores, pass rate and value added.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Effectiveness {
+    statistics: Statistics,
+    pass_rate: Option<f32>,
+    value_added: Option<f32>,
+}
+
+impl Effectiveness {
+    fn of(tally: &Tally) -> Option<Self> {
+        Some(Self {
+            statistics: Statistics::of(&tally.scores)?,
+            pass_rate: tally.pass_rate(),
+            value_added: tally.value_added(),
+        })
+    }
+
+    pub fn statistics(&self) -> &Statistics {
+        &self.statistics
+    }
+
+    /// `None` when no subject in the group has a grading scheme with a
+    /// pass grade.
+    pub fn pass_rate(&self) -> Option<f32> {
+        self.pass_rate
+    }
+
+    /// Mean value added in percentage points, or `None` when no student in
+    /// the group takes another subject.
+    pub fn value_added(&self) -> Option<f32> {
+        self.value_added
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SubjectEffectiveness {
+    subject: Subject,
+    overall: Effectiveness,
+    classes: Vec<(StudentClass, Effectiveness)>,
+}
+
+impl SubjectEffectiveness {
+    /// `subject` across the classes `include` accepts, with the merged
+    /// tally, or `None` if none of them have scores in it.
+    fn of(
+        subject: Subject,
+        tallies: &[(Subject, StudentClass, Tally)],
+        include: impl Fn(StudentClass) -> bool,
+    ) -> Option<(Self, Tally)> {
+        let mut total = Tally::default();
+        let classes = tallies
+            .iter()
+            .filter(|(tallied, class, _)| *tallied == subject && include(*class))
+            .filter_map(|(_, class, tally)| {
+                total.merge(tally);
+                Some((*class, Effectiveness::of(tally)?))
+            })
+            .collect();
+        let overall = Effectiveness::of(&total)?;
+        Some((
+            Self {
+                subject,
+                overall,
+                classes,
+            },
+            total,
+        ))
+    }
+
+    pub fn subject(&self) -> Subject {
+        self.subject
+    }
+
+    /// Every class that sat the subject, together.
+    pub fn overall(&self) -> &Effectiveness {
+        &self.overall
+    }
+
+    /// In class order.
+    pub fn classes(&self) -> &[(StudentClass, Effectiveness)] {
+        &self.classes
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TeacherEffectiveness {
+    teacher: Teacher,
+    overall: Effectiveness,
+    subjects: Vec<SubjectEffectiveness>,
+}
+
+impl TeacherEffectiveness {
+    pub fn teacher(&self) -> &Teacher {
+        &self.teacher
+    }
+
+    /// Every subject the teacher teaches in their classes, together.
+    pub fn overall(&self) -> &Effectiveness {
+        &self.overall
+    }
+
+    /// The subjects the teacher teaches that were sat, counting only the
+    /// classes they teach.
+    pub fn subjects(&self) -> &[SubjectEffectiveness] {
+        &self.subjects
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EffectivenessReport {
+    assessment_name: String,
+    subjects: Vec<SubjectEffectiveness>,
+    teachers: Vec<TeacherEffectiveness>,
+}
+
+impl EffectivenessReport {
+    /// `analyses` holds one analysis per class that sat the assessment.
+    /// `teachers` pairs each teacher with the classes they teach; a teacher
+    /// is judged only on their subjects in those classes, and teachers with
+    /// no such results are left out.
+    pub fn new(
+        assessment_name: String,
+        analyses: &[ClassAnalysis],
+        teachers: &[(Teacher, Vec<StudentClass>)],
+        grading: &GradingPolicy,
+    ) -> Self {
+        let mut tallies: Vec<(Subject, StudentClass, Tally)> = analyses
+            .iter()
+            .flat_map(|analysis| {
+                let class = analysis.class_summary().class();
+                analysis
+                    .class_summary()
+                    .class_subject_performance_summary()
+                    .iter()
+                    .map(move |summary| {
+                        (
+                            summary.subject(),
+                            class,
+                            Tally::of(analysis, summary.subject(), grading),
+                        )
+                    })
+            })
+            .collect();
+        tallies.sort_by_key(|(subject, class, _)| (*subject, *class));
+
+        let mut sat: Vec<Subject> = tallies.iter().map(|(subject, ..)| *subject).collect();
+        sat.dedup();
+
+        let subjects = sat
+            .iter()
+            .filter_map(|subject| SubjectEffectiveness::of(*subject, &tallies, |_| true))
+            .map(|(row, _)| row)
+            .collect();
+
+        let teachers = teachers
+            .iter()
+            .filter_map(|(teacher, classes)| {
+                let mut total = Tally::default();
+                let subjects = sat
+                    .iter()
+                    .filter(|subject| teacher.subjects_taught().contains(subject))
+                    .filter_map(|subject| {
+                        SubjectEffectiveness::of(*subject, &tallies, |class| {
+                            classes.contains(&class)
+                        })
+                    })
+                    .map(|(row, tally)| {
+                        total.merge(&tally);
+                        row
+                    })
+                    .collect();
+                Some(TeacherEffectiveness {
+                    teacher: teacher.clone(),
+                    overall: Effectiveness::of(&total)?,
+                    subjects,
+                })
+            })
+            .collect();
+
+        Self {
+            assessment_name,
+            subjects,
+            teachers,
+        }
+    }
+
+    pub fn assessment_name(&self) -> &str {
+        &self.assessment_name
+    }
+
+    /// In subject order.
+    pub fn subjects(&self) -> &[SubjectEffectiveness] {
+        &self.subjects
+    }
+
+    /// In the order the teachers were given.
+    pub fn teachers(&self) -> &[TeacherEffectiveness] {
+        &self.teachers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{AssessmentResults, RankBy, Weighting, analyse_class};
+    use crate::domain::{Gender, Student, TeacherPersonalInfo};
+    use crate::fixtures::{name, student};
+    use crate::marks::{AcademicPeriod, Assessment, AssessmentComponent, Mark, Term};
+
+    fn classes() -> (StudentClass, StudentClass) {
+        let mut classes = StudentClass::all();
+        (
+            classes.next().expect("a built-in class"),
+            classes.next().expect("a second built-in class"),
+        )
+    }
+
+    fn subjects() -> (Subject, Subject) {
+        let mut subjects = Subject::all();
+        (
+            subjects.next().expect("a built-in subject"),
+            subjects.next().expect("a second built-in subject"),
+        )
+    }
+
+    fn assert_close(actual: Option<f32>, expected: f32) {
+        let actual = actual.expect("a value");
+        assert!(
+            (actual - expected).abs() < 1e-3,
+            "{} is not {}",
+            actual,
+            expected
+        );
+    }
+
+    /// `marks` holds (candidate number, subject, percentage), `None` for
+    /// an absent candidate. Every candidate reads both subjects.
+    fn analysis(class: StudentClass, marks: &[(&str, Subject, Option<f32>)]) -> ClassAnalysis {
+        let (first, second) = subjects();
+        let mut candidates: Vec<&str> = marks.iter().map(|(candidate, ..)| *candidate).collect();
+        candidates.dedup();
+        let students: Vec<Student> = candidates
+            .into_iter()
+            .map(|candidate_number| student(candidate_number, class, &[first, second]))
+            .collect();
+        let results: Vec<AssessmentResults> = [first, second]
+            .into_iter()
+            .map(|subject| {
+                AssessmentResults::new(
+                    Assessment::new(
+                        "Mock".to_string(),
+                        class,
+                        subject,
+                        AcademicPeriod::new(2025, Term::Second),
+                        vec![AssessmentComponent::new(
+                            "P1".to_string(),
+                            "Paper 1".to_string(),
+                            100,
+                            1.0,
+                        )],
+                    ),
+                    marks
+                        .iter()
+                        .filter(|(_, marked, _)| *marked == subject)
+                        .map(|(candidate_number, _, score)| {
+                            Mark::new(candidate_number.to_string(), "P1".to_string(), *score)
+                        })
+                        .collect(),
+                )
+            })
+            .collect();
+        analyse_class(
+            class,
+            &students,
+            &results,
+            &Weighting::Assessment,
+            &GradingPolicy::seed(),
+            RankBy::Average,
+        )
+        .expect("the class has marks")
+    }
+
+    fn teacher(teacher_name: &str, subjects: Vec<Subject>) -> Teacher {
+        Teacher::new(
+            TeacherPersonalInfo::new(name(teacher_name), Gender::Male),
+            subjects,
+        )
+    }
+
+    fn report(teachers: &[(Teacher, Vec<StudentClass>)]) -> EffectivenessReport {
+        let (first_class, second_class) = classes();
+        let (first, second) = subjects();
+        EffectivenessReport::new(
+            "Mock".to_string(),
+            &[
+                analysis(
+                    first_class,
+                    &[
+                        ("A", first, Some(80.0)),
+                        ("A", second, Some(60.0)),
+                        ("B", first, Some(49.0)),
+                        ("B", second, Some(50.0)),
+                        ("C", first, None),
+                        ("C", second, Some(40.0)),
+                    ],
+                ),
+                analysis(
+                    second_class,
+                    &[
+                        ("D", first, Some(50.0)),
+                        ("D", second, Some(70.0)),
+                        ("E", first, Some(30.0)),
+                        ("E", second, Some(30.0)),
+                    ],
+                ),
+            ],
+            teachers,
+            &GradingPolicy::seed(),
+        )
+    }
+
+    #[test]
+    fn passes_are_counted_from_each_score() {
+        let (first, _) = subjects();
+        let pass_mark = GradingPolicy::seed()
+            .scheme_for(first)
+            .and_then(|scheme| scheme.pass_mark())
+            .expect("the subject has a pass mark");
+        assert_eq!(pass_mark, 50.0);
+
+        let report = report(&[]);
+        let row = &report.subjects()[0];
+
+        assert_eq!(row.subject(), first);
+        // A and D pass; B just misses and C was absent.
+        assert_eq!(row.overall().pass_rate(), Some(40.0));
+        assert_eq!(row.overall().statistics().count(), 5);
+        assert_close(row.classes()[0].1.pass_rate(), 100.0 / 3.0);
+        assert_close(row.classes()[1].1.pass_rate(), 50.0);
+    }
+
+    #[test]
+    fn value_added_compares_with_the_students_other_subjects() {
+        let (first, second) = subjects();
+        let report = report(&[]);
+        let row = |subject| {
+            report
+                .subjects()
+                .iter()
+                .find(|row| row.subject() == subject)
+                .expect("the subject was sat")
+        };
+
+        // (20 - 1 - 40 - 20 + 0) / 5 and the opposite for the other subject.
+        assert_close(row(first).overall().value_added(), -8.2);
+        assert_close(row(second).overall().value_added(), 8.2);
+    }
+
+    #[test]
+    fn teachers_are_judged_only_on_the_classes_they_teach() {
+        let (first_class, second_class) = classes();
+        let (first, _) = subjects();
+        let report = report(&[
+            (teacher("Kofi", vec![first]), vec![second_class]),
+            (teacher("Esi", vec![first]), vec![first_class, second_class]),
+        ]);
+
+        let [kofi, esi] = report.teachers() else {
+            panic!("both teachers have results");
+        };
+        assert_eq!(kofi.overall().statistics().count(), 2);
+        assert_eq!(kofi.overall().pass_rate(), Some(50.0));
+        assert_eq!(kofi.subjects().len(), 1);
+        assert_eq!(kofi.subjects()[0].classes().len(), 1);
+        assert_eq!(kofi.subjects()[0].classes()[0].0, second_class);
+        assert_eq!(esi.overall(), report.subjects()[0].overall());
+    }
+
+    #[test]
+    fn teachers_without_results_in_their_classes_are_left_out() {
+        let (first_class, _) = classes();
+        let (first, second) = subjects();
+        let unsat = Subject::all()
+            .find(|subject| ![first, second].contains(subject))
+            .expect("a third built-in subject");
+        let report = report(&[
+            (teacher("Ama", vec![first]), Vec::new()),
+            (teacher("Yaw", vec![unsat]), vec![first_class]),
+        ]);
+
+        assert!(report.teachers().is_empty());
+    }
+
+    #[test]
+    fn value_added_needs_another_subject() {
+        let (class, _) = classes();
+        let (first, _) = subjects();
+        let analysis = analysis(class, &[("A", first, Some(70.0))]);
+        let summary = &analysis.student_summaries()[0];
+
+        assert_eq!(value_added(summary, first), None);
+    }
+
+    #[test]
+    fn nothing_sat_gives_an_empty_report() {
+        let (class, _) = classes();
+        let (first, _) = subjects();
+        let report = EffectivenessReport::new(
+            "Mock".to_string(),
+            &[],
+            &[(teacher("Ama", vec![first]), vec![class])],
+            &GradingPolicy::seed(),
+        );
+
+        assert!(report.subjects().is_empty());
+        assert!(report.teachers().is_empty());
+    }
+}
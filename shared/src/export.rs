@@ -0,0 +1,532 @@
+// Timetable exports
+//
+// A published exam or invigilation timetable is flattened into dated,
+// timed entries, which are then written out as CSV or as an iCalendar file.
+// The webapp draws its PDFs from the same entries, so every format of an
+// export lists the same sittings.
+use std::collections::BTreeMap;
+
+use chrono::{NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{ExamDate, ExamTTObject, Student, StudentClass, Subject};
+
+/// Domain used to make calendar event UIDs globally unique.
+const UID_DOMAIN: &str = "ridge-school-kumasi";
+
+pub const CSV_HEADERS: &[&str] = &[
+    "Date", "Day", "Session", "Start", "End", "Title", "Details", "Room",
+];
+
+/// One sitting on a timetable: a paper, or an invigilation duty.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct TimetableEntry {
+    exam_date: ExamDate,
+    start: NaiveTime,
+    end: NaiveTime,
+    title: String,
+    details: String,
+    room: String,
+}
+
+impl TimetableEntry {
+    pub fn new(
+        exam_date: ExamDate,
+        start: NaiveTime,
+        end: NaiveTime,
+        title: String,
+        details: String,
+        room: String,
+    ) -> Self {
+        Self {
+            exam_date,
+            start,
+            end,
+            title,
+            details,
+            room,
+        }
+    }
+
+    /// An invigilation duty. Duties are stored by sitting and length, so
+    /// they are taken to start with the session.
+    pub fn duty(exam_date: ExamDate, room_code: &str, invigilator: &str, minutes: u32) -> Self {
+        let start = exam_date.session().default_start_time();
+        let end = start + chrono::Duration::minutes(i64::from(minutes));
+        Self::new(
+            exam_date,
+            start,
+            end,
+            format!("Invigilation, room {}", room_code),
+            invigilator.to_string(),
+            room_code.to_string(),
+        )
+    }
+
+    pub fn exam_date(&self) -> &ExamDate {
+        &self.exam_date
+    }
+
+    pub fn start(&self) -> NaiveTime {
+        self.start
+    }
+
+    pub fn end(&self) -> NaiveTime {
+        self.end
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// The classes sitting a paper, or the invigilator on a duty.
+    pub fn details(&self) -> &str {
+        &self.details
+    }
+
+    /// Empty when no room is known.
+    pub fn room(&self) -> &str {
+        &self.room
+    }
+
+    /// The entry as a row under `CSV_HEADERS`.
+    pub fn cells(&self) -> Vec<String> {
+        vec![
+            self.exam_date.date().format("%Y-%m-%d").to_string(),
+            self.exam_date.date().format("%A").to_string(),
+            self.exam_date.session().to_string(),
+            self.start.format("%H:%M").to_string(),
+            self.end.format("%H:%M").to_string(),
+            self.title.clone(),
+            self.details.clone(),
+            self.room.clone(),
+        ]
+    }
+
+    fn uid(&self) -> String {
+        let slug: String = format!("{}-{}-{}", self.title, self.details, self.room)
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        format!(
+            "{}-{}-{}@{}",
+            self.exam_date.date().format("%Y%m%d"),
+            self.start.format("%H%M"),
+            slug,
+            UID_DOMAIN
+        )
+    }
+}
+
+/// Which papers of the exam timetable to export.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExamScope {
+    School,
+    Class(StudentClass),
+    /// Only the papers the student sits: their class's papers in the
+    /// subjects they read.
+    Student(Student),
+}
+
+impl ExamScope {
+    fn includes(&self, class: StudentClass, subject: Subject) -> bool {
+        match self {
+            Self::School => true,
+            Self::Class(scope) => *scope == class,
+            Self::Student(student) => {
+                student.class() == class && student.subjects_read().contains(&subject)
+            }
+        }
+    }
+}
+
+/// A named, dated list of sittings, ready to export.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Timetable {
+    name: String,
+    entries: Vec<TimetableEntry>,
+}
+
+impl Timetable {
+    /// Entries are put in date and start time order.
+    pub fn new(name: String, mut entries: Vec<TimetableEntry>) -> Self {
+        entries.sort();
+        Self { name, entries }
+    }
+
+    /// The papers of `timetables` in `scope`. A paper sat by several
+    /// classes is one entry listing all of them.
+    pub fn exams(name: String, timetables: &[ExamTTObject], scope: &ExamScope) -> Self {
+        let mut papers: BTreeMap<(ExamDate, NaiveTime, NaiveTime, String), Vec<StudentClass>> =
+            BTreeMap::new();
+        for timetable in timetables {
+            let class = timetable.student_class();
+            for pair in timetable.exam_pairs() {
+                for paper in pair.papers() {
+                    if !scope.includes(class, paper.subject()) {
+                        continue;
+                    }
+                    let title = format!("{} {}", paper.subject(), paper.component().name());
+                    let classes = papers
+                        .entry((
+                            pair.exam_date().clone(),
+                            paper.start_time(),
+                            paper.end_time(),
+                            title,
+                        ))
+                        .or_default();
+                    if !classes.contains(&class) {
+                        classes.push(class);
+                    }
+                }
+            }
+        }
+
+        let entries = papers
+            .into_iter()
+            .map(|((exam_date, start, end, title), mut classes)| {
+                classes.sort();
+                let classes = classes
+                    .iter()
+                    .map(|class| class.code())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                TimetableEntry::new(exam_date, start, end, title, classes, String::new())
+            })
+            .collect();
+
+        Self::new(name, entries)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn entries(&self) -> &[TimetableEntry] {
+        &self.entries
+    }
+
+    /// A file name with the given extension, e.g. "Exam_timetable_W3.csv".
+    pub fn file_name(&self, extension: &str) -> String {
+        let stem: String = self
+            .name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        format!("{}.{}", stem, extension)
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = csv_line(CSV_HEADERS.iter().map(|header| header.to_string()));
+        for entry in &self.entries {
+            csv.push_str(&csv_line(entry.cells()));
+        }
+        csv
+    }
+
+    /// An iCalendar file with an event per entry. Times are written as
+    /// local time, which calendar apps show as given; Ghana keeps GMT all
+    /// year so nothing shifts. `stamp` is when the file was made, in UTC.
+    pub fn to_ics(&self, stamp: NaiveDateTime) -> String {
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            format!("PRODID:-//{}//Timetables//EN", UID_DOMAIN),
+            "CALSCALE:GREGORIAN".to_string(),
+            "METHOD:PUBLISH".to_string(),
+            format!("X-WR-CALNAME:{}", ics_text(&self.name)),
+        ];
+        for entry in &self.entries {
+            let date = entry.exam_date.date();
+            lines.push("BEGIN:VEVENT".to_string());
+            lines.push(format!("UID:{}", entry.uid()));
+            lines.push(format!("DTSTAMP:{}", stamp.format("%Y%m%dT%H%M%SZ")));
+            lines.push(format!(
+                "DTSTART:{}",
+                date.and_time(entry.start).format("%Y%m%dT%H%M%S")
+            ));
+            lines.push(format!(
+                "DTEND:{}",
+                date.and_time(entry.end).format("%Y%m%dT%H%M%S")
+            ));
+            lines.push(format!("SUMMARY:{}", ics_text(&entry.title)));
+            if !entry.details.is_empty() {
+                lines.push(format!("DESCRIPTION:{}", ics_text(&entry.details)));
+            }
+            if !entry.room.is_empty() {
+                lines.push(format!("LOCATION:{}", ics_text(&entry.room)));
+            }
+            lines.push("END:VEVENT".to_string());
+        }
+        lines.push("END:VCALENDAR".to_string());
+
+        lines.iter().map(|line| fold(line)).collect()
+    }
+}
+
+pub(crate) fn csv_line(cells: impl IntoIterator<Item = String>) -> String {
+    let mut line = cells
+        .into_iter()
+        .map(|cell| {
+            if cell.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            } else {
+                cell
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    line.push_str("\r\n");
+    line
+}
+
+/// Escapes iCalendar TEXT values.
+fn ics_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Folds a content line to 75 octets, as iCalendar requires, and ends it
+/// with CRLF.
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::domain::{ExamPair, ExamPaper, ExamSession, PaperComponent, WassceSubject};
+    use crate::fixtures;
+
+    const ENGLISH: Subject = Subject::Wassce(WassceSubject::English);
+    const PHYSICS: Subject = Subject::Wassce(WassceSubject::Physics);
+
+    fn class(code: &str) -> StudentClass {
+        StudentClass::from_code(code).expect("a built-in class")
+    }
+
+    /// Monday 4 May 2026.
+    fn morning() -> ExamDate {
+        ExamDate::new(
+            NaiveDate::from_ymd_opt(2026, 5, 4).expect("valid date"),
+            ExamSession::Morning,
+        )
+    }
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).expect("valid time")
+    }
+
+    fn paper(subject: Subject, hour: u32) -> ExamPaper {
+        ExamPaper::new(
+            subject,
+            PaperComponent::new("P1".to_string(), "Paper 1".to_string(), 90),
+            time(hour, 0),
+        )
+    }
+
+    fn timetable(code: &str, papers: Vec<ExamPaper>) -> ExamTTObject {
+        ExamTTObject::new(class(code), vec![ExamPair::new(morning(), papers)])
+    }
+
+    fn entry(title: &str, details: &str, room: &str) -> TimetableEntry {
+        TimetableEntry::new(
+            morning(),
+            time(9, 0),
+            time(10, 30),
+            title.to_string(),
+            details.to_string(),
+            room.to_string(),
+        )
+    }
+
+    fn details(timetable: &Timetable) -> Vec<(&str, &str)> {
+        timetable
+            .entries()
+            .iter()
+            .map(|entry| (entry.title(), entry.details()))
+            .collect()
+    }
+
+    #[test]
+    fn a_paper_sat_by_several_classes_is_one_entry() {
+        let timetables = [
+            timetable("W2", vec![paper(ENGLISH, 9)]),
+            timetable("W1", vec![paper(ENGLISH, 9), paper(PHYSICS, 11)]),
+        ];
+        let timetable = Timetable::exams("Exams".to_string(), &timetables, &ExamScope::School);
+
+        assert_eq!(
+            details(&timetable),
+            [
+                ("WASSCE English Paper 1", "W1, W2"),
+                ("WASSCE Physics Paper 1", "W1"),
+            ]
+        );
+        let english = &timetable.entries()[0];
+        assert_eq!((english.start(), english.end()), (time(9, 0), time(10, 30)));
+        assert_eq!(english.room(), "");
+    }
+
+    #[test]
+    fn a_class_or_student_scope_keeps_only_their_papers() {
+        let timetables = [
+            timetable("W1", vec![paper(ENGLISH, 9), paper(PHYSICS, 11)]),
+            timetable("W2", vec![paper(ENGLISH, 9)]),
+        ];
+        let for_class = Timetable::exams(
+            "W2".to_string(),
+            &timetables,
+            &ExamScope::Class(class("W2")),
+        );
+        assert_eq!(details(&for_class), [("WASSCE English Paper 1", "W2")]);
+
+        let student = fixtures::student("C01", class("W1"), &[PHYSICS]);
+        let for_student =
+            Timetable::exams("C01".to_string(), &timetables, &ExamScope::Student(student));
+        assert_eq!(details(&for_student), [("WASSCE Physics Paper 1", "W1")]);
+    }
+
+    #[test]
+    fn entries_are_put_in_date_and_time_order() {
+        let afternoon = ExamDate::new(morning().date(), ExamSession::Afternoon);
+        let later = TimetableEntry::duty(afternoon, "B2", "Ama Test", 120);
+        let earlier = entry("English", "", "");
+        let timetable = Timetable::new("Duties".to_string(), vec![later.clone(), earlier.clone()]);
+
+        assert_eq!(timetable.entries(), &[earlier, later]);
+    }
+
+    #[test]
+    fn a_duty_starts_with_its_session() {
+        let duty = TimetableEntry::duty(morning(), "B2", "Ama Test", 120);
+
+        assert_eq!(duty.title(), "Invigilation, room B2");
+        assert_eq!(duty.details(), "Ama Test");
+        assert_eq!(duty.room(), "B2");
+        assert_eq!((duty.start(), duty.end()), (time(9, 0), time(11, 0)));
+    }
+
+    #[test]
+    fn the_file_name_keeps_only_letters_digits_and_dashes() {
+        let timetable = Timetable::new("Exam timetable: W3/May-June".to_string(), Vec::new());
+
+        assert_eq!(
+            timetable.file_name("csv"),
+            "Exam_timetable__W3_May-June.csv"
+        );
+    }
+
+    #[test]
+    fn csv_has_a_header_row_and_quotes_cells_that_need_it() {
+        let timetable = Timetable::new(
+            "Exams".to_string(),
+            vec![entry("English \"Paper 1\"", "W1, W2", "Hall")],
+        );
+
+        assert_eq!(
+            timetable.to_csv(),
+            "Date,Day,Session,Start,End,Title,Details,Room\r\n\
+             2026-05-04,Monday,AM,09:00,10:30,\"English \"\"Paper 1\"\"\",\"W1, W2\",Hall\r\n"
+        );
+    }
+
+    #[test]
+    fn ics_has_an_event_per_entry() {
+        let timetable = Timetable::new(
+            "Exams".to_string(),
+            vec![
+                entry("English", "W1, W2", ""),
+                entry("Physics", "", "Lab; 2"),
+            ],
+        );
+        let stamp = NaiveDate::from_ymd_opt(2026, 4, 1)
+            .expect("valid date")
+            .and_time(time(8, 30));
+        let ics = timetable.to_ics(stamp);
+        let lines: Vec<&str> = ics.split("\r\n").collect();
+
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(lines[0], "BEGIN:VCALENDAR");
+        assert!(lines.contains(&"X-WR-CALNAME:Exams"));
+        assert_eq!(
+            lines.iter().filter(|line| **line == "BEGIN:VEVENT").count(),
+            2
+        );
+        for line in [
+            "UID:20260504-0900-english-w1--w2-@ridge-school-kumasi",
+            "DTSTAMP:20260401T083000Z",
+            "DTSTART:20260504T090000",
+            "DTEND:20260504T103000",
+            "SUMMARY:English",
+            r"DESCRIPTION:W1\, W2",
+            r"LOCATION:Lab\; 2",
+        ] {
+            assert!(lines.contains(&line), "{} missing from {:?}", line, lines);
+        }
+        // Entries without details or a room leave the property out.
+        assert_eq!(
+            lines
+                .iter()
+                .filter(|line| line.starts_with("DESCRIPTION:"))
+                .count(),
+            1
+        );
+        assert_eq!(
+            lines
+                .iter()
+                .filter(|line| line.starts_with("LOCATION:"))
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn long_ics_lines_are_folded_at_75_octets() {
+        let title = "é".repeat(50);
+        let folded = fold(&format!("SUMMARY:{}", title));
+        let lines: Vec<&str> = folded.trim_end_matches("\r\n").split("\r\n").collect();
+
+        assert!(lines.len() > 1);
+        assert!(lines.iter().all(|line| line.len() <= 75), "{:?}", lines);
+        assert!(lines[1..].iter().all(|line| line.starts_with(' ')));
+        let unfolded: String = lines
+            .iter()
+            .enumerate()
+            .map(|(i, line)| if i == 0 { *line } else { &line[1..] })
+            .collect();
+        assert_eq!(unfolded, format!("SUMMARY:{}", title));
+    }
+
+    #[test]
+    fn ics_text_escapes_backslashes_separators_and_newlines() {
+        assert_eq!(ics_text("a\\b;c,d\ne"), r"a\\b\;c\,d\ne");
+    }
+}
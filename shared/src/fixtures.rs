@@ -0,0 +1,28 @@
+// Test fixtures
+//
+// Builders shared by the unit tests here and in the webapp, which turns on
+// the `fixtures` feature for its own tests. Nothing here is compiled into a
+// release build.
+use chrono::NaiveDate;
+
+use crate::domain::{Gender, PersonalName, Student, StudentClass, StudentPersonalInfo, Subject};
+
+/// "`first_name` Test", for students and staff alike.
+pub fn name(first_name: &str) -> PersonalName {
+    PersonalName::new(first_name.to_string(), String::new(), "Test".to_string())
+}
+
+/// A student named after their candidate number, born on 1 January 2010,
+/// reading `subjects` in `class`.
+pub fn student(candidate_number: &str, class: StudentClass, subjects: &[Subject]) -> Student {
+    Student::new(
+        candidate_number.to_string(),
+        StudentPersonalInfo::new(
+            name(candidate_number),
+            NaiveDate::from_ymd_opt(2010, 1, 1).expect("valid date"),
+            Gender::Female,
+        ),
+        class,
+        subjects.to_vec(),
+    )
+}
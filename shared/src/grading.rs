@@ -0,0 +1,484 @@
+// Grade boundaries
+//
+// Each exam board turns percentages into grades differently, so grading is
+// data: a scheme is a list of boundaries, and a policy says which scheme
+// applies to each programme, with per-subject overrides. The built-in
+// schemes are only used to seed a fresh database.
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::Subject;
+
+/// The lowest percentage that earns `grade`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GradeBoundary {
+    grade: String,
+    min_percentage: f32,
+}
+
+impl GradeBoundary {
+    pub fn new(grade: String, min_percentage: f32) -> Self {
+        Self {
+            grade,
+            min_percentage,
+        }
+    }
+
+    pub fn grade(&self) -> &str {
+        &self.grade
+    }
+
+    pub fn min_percentage(&self) -> f32 {
+        self.min_percentage
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GradeScheme {
+    code: String,
+    name: String,
+    boundaries: Vec<GradeBoundary>,
+    pass_grade: String,
+}
+
+impl GradeScheme {
+    /// Boundaries may be given in any order; they are kept highest first.
+    pub fn new(
+        code: String,
+        name: String,
+        mut boundaries: Vec<GradeBoundary>,
+        pass_grade: String,
+    ) -> Self {
+        boundaries.sort_by(|a, b| b.min_percentage.total_cmp(&a.min_percentage));
+        Self {
+            code,
+            name,
+            boundaries,
+            pass_grade,
+        }
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Highest grade first.
+    pub fn boundaries(&self) -> &[GradeBoundary] {
+        &self.boundaries
+    }
+
+    /// The lowest grade that still counts as a pass.
+    pub fn pass_grade(&self) -> &str {
+        &self.pass_grade
+    }
+
+    pub fn grades(&self) -> impl Iterator<Item = &str> {
+        self.boundaries.iter().map(|boundary| boundary.grade())
+    }
+
+    /// The grade for a percentage. Anything below the lowest boundary gets
+    /// the lowest grade.
+    pub fn grade(&self, percentage: f32) -> Option<&str> {
+        self.boundaries
+            .iter()
+            .find(|boundary| percentage >= boundary.min_percentage)
+            .or(self.boundaries.last())
+            .map(|boundary| boundary.grade())
+    }
+
+    /// The percentage needed to pass, if the pass grade is one of the
+    /// scheme's grades.
+    pub fn pass_mark(&self) -> Option<f32> {
+        self.boundaries
+            .iter()
+            .find(|boundary| boundary.grade == self.pass_grade)
+            .map(|boundary| boundary.min_percentage)
+    }
+
+    pub fn is_pass(&self, percentage: f32) -> bool {
+        self.pass_mark()
+            .is_some_and(|pass_mark| percentage >= pass_mark)
+    }
+
+    /// How many of `percentages` fall in each grade, highest grade first,
+    /// including grades nobody got.
+    pub fn distribution(&self, percentages: impl IntoIterator<Item = f32>) -> Vec<GradeCount> {
+        let mut counts: Vec<GradeCount> = self
+            .grades()
+            .map(|grade| GradeCount::new(grade.to_string(), 0))
+            .collect();
+        for percentage in percentages {
+            if let Some(grade) = self.grade(percentage)
+                && let Some(count) = counts.iter_mut().find(|count| count.grade == grade)
+            {
+                count.count += 1;
+            }
+        }
+        counts
+    }
+
+    /// Problems that would make the scheme grade wrongly. An empty list
+    /// means the scheme can be saved.
+    pub fn issues(&self) -> Vec<SchemeIssue> {
+        let mut issues = Vec::new();
+        if self.boundaries.is_empty() {
+            issues.push(SchemeIssue::NoBoundaries);
+            return issues;
+        }
+
+        let mut grades = BTreeSet::new();
+        for boundary in &self.boundaries {
+            if boundary.grade.trim().is_empty() {
+                issues.push(SchemeIssue::BlankGrade);
+            } else if !grades.insert(boundary.grade.as_str()) {
+                issues.push(SchemeIssue::DuplicateGrade(boundary.grade.clone()));
+            }
+            if !(0.0..=100.0).contains(&boundary.min_percentage) {
+                issues.push(SchemeIssue::OutOfRange(
+                    boundary.grade.clone(),
+                    boundary.min_percentage,
+                ));
+            }
+        }
+        for pair in self.boundaries.windows(2) {
+            if pair[0].min_percentage == pair[1].min_percentage {
+                issues.push(SchemeIssue::SameBoundary(
+                    pair[0].grade.clone(),
+                    pair[1].grade.clone(),
+                ));
+            }
+        }
+        if self
+            .boundaries
+            .last()
+            .is_some_and(|lowest| lowest.min_percentage > 0.0)
+        {
+            issues.push(SchemeIssue::NoLowestGrade);
+        }
+        if self.pass_mark().is_none() {
+            issues.push(SchemeIssue::UnknownPassGrade(self.pass_grade.clone()));
+        }
+
+        issues
+    }
+
+    /// WAEC grades for WASSCE.
+    pub fn wassce() -> Self {
+        Self::preset(
+            "WASSCE",
+            "WASSCE (A1–F9)",
+            &[
+                ("A1", 75.0),
+                ("B2", 70.0),
+                ("B3", 65.0),
+                ("C4", 60.0),
+                ("C5", 55.0),
+                ("C6", 50.0),
+                ("D7", 45.0),
+                ("E8", 40.0),
+                ("F9", 0.0),
+            ],
+            "C6",
+        )
+    }
+
+    pub fn a_level() -> Self {
+        Self::preset(
+            "ALEVEL",
+            "A-Level (A*–E)",
+            &[
+                ("A*", 90.0),
+                ("A", 80.0),
+                ("B", 70.0),
+                ("C", 60.0),
+                ("D", 50.0),
+                ("E", 40.0),
+                ("U", 0.0),
+            ],
+            "E",
+        )
+    }
+
+    pub fn igcse_letters() -> Self {
+        Self::preset(
+            "IGCSE",
+            "IGCSE (A*–G)",
+            &[
+                ("A*", 90.0),
+                ("A", 80.0),
+                ("B", 70.0),
+                ("C", 60.0),
+                ("D", 50.0),
+                ("E", 40.0),
+                ("F", 30.0),
+                ("G", 20.0),
+                ("U", 0.0),
+            ],
+            "C",
+        )
+    }
+
+    pub fn igcse_numbers() -> Self {
+        Self::preset(
+            "IGCSE91",
+            "IGCSE (9–1)",
+            &[
+                ("9", 90.0),
+                ("8", 80.0),
+                ("7", 70.0),
+                ("6", 60.0),
+                ("5", 50.0),
+                ("4", 40.0),
+                ("3", 30.0),
+                ("2", 20.0),
+                ("1", 10.0),
+                ("U", 0.0),
+            ],
+            "4",
+        )
+    }
+
+    fn preset(code: &str, name: &str, boundaries: &[(&str, f32)], pass_grade: &str) -> Self {
+        Self::new(
+            code.to_string(),
+            name.to_string(),
+            boundaries
+                .iter()
+                .map(|(grade, min_percentage)| {
+                    GradeBoundary::new(grade.to_string(), *min_percentage)
+                })
+                .collect(),
+            pass_grade.to_string(),
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum SchemeIssue {
+    NoBoundaries,
+    BlankGrade,
+    DuplicateGrade(String),
+    OutOfRange(String, f32),
+    /// Two grades share a boundary, so the lower one can never be given.
+    SameBoundary(String, String),
+    /// The lowest boundary is above zero.
+    NoLowestGrade,
+    UnknownPassGrade(String),
+}
+
+impl std::fmt::Display for SchemeIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::NoBoundaries => write!(f, "the scheme has no grades"),
+            Self::BlankGrade => write!(f, "a boundary has no grade"),
+            Self::DuplicateGrade(grade) => write!(f, "{} appears more than once", grade),
+            Self::OutOfRange(grade, min_percentage) => write!(
+                f,
+                "{} starts at {}%, which is not between 0 and 100",
+                grade, min_percentage
+            ),
+            Self::SameBoundary(higher, lower) => {
+                write!(f, "{} and {} start at the same percentage", higher, lower)
+            }
+            Self::NoLowestGrade => write!(f, "the lowest grade must start at 0%"),
+            Self::UnknownPassGrade(grade) => {
+                write!(f, "pass grade {} is not one of the scheme's grades", grade)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GradeCount {
+    grade: String,
+    count: u32,
+}
+
+impl GradeCount {
+    pub fn new(grade: String, count: u32) -> Self {
+        Self { grade, count }
+    }
+
+    pub fn grade(&self) -> &str {
+        &self.grade
+    }
+
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+}
+
+/// Which scheme grades each subject. `target_code` is either a programme
+/// code, covering every subject in it, or a subject code, which wins over
+/// its programme.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemeAssignment {
+    target_code: String,
+    scheme_code: String,
+}
+
+impl SchemeAssignment {
+    pub fn new(target_code: String, scheme_code: String) -> Self {
+        Self {
+            target_code,
+            scheme_code,
+        }
+    }
+
+    pub fn target_code(&self) -> &str {
+        &self.target_code
+    }
+
+    pub fn scheme_code(&self) -> &str {
+        &self.scheme_code
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct GradingPolicy {
+    schemes: Vec<GradeScheme>,
+    assignments: Vec<SchemeAssignment>,
+}
+
+impl GradingPolicy {
+    pub fn new(schemes: Vec<GradeScheme>, assignments: Vec<SchemeAssignment>) -> Self {
+        Self {
+            schemes,
+            assignments,
+        }
+    }
+
+    /// The board schemes, each attached to its programme.
+    pub fn seed() -> Self {
+        let schemes = vec![
+            GradeScheme::wassce(),
+            GradeScheme::a_level(),
+            GradeScheme::igcse_letters(),
+            GradeScheme::igcse_numbers(),
+        ];
+        let assignments = [
+            ("WASSCE", "WASSCE"),
+            ("ALEVEL", "ALEVEL"),
+            ("IGCSE", "IGCSE"),
+        ]
+        .into_iter()
+        .map(|(target_code, scheme_code)| {
+            SchemeAssignment::new(target_code.to_string(), scheme_code.to_string())
+        })
+        .collect();
+
+        Self::new(schemes, assignments)
+    }
+
+    pub fn schemes(&self) -> &[GradeScheme] {
+        &self.schemes
+    }
+
+    pub fn assignments(&self) -> &[SchemeAssignment] {
+        &self.assignments
+    }
+
+    pub fn scheme(&self, code: &str) -> Option<&GradeScheme> {
+        self.schemes
+            .iter()
+            .find(|scheme| scheme.code.eq_ignore_ascii_case(code))
+    }
+
+    fn assigned(&self, target_code: &str) -> Option<&GradeScheme> {
+        self.assignments
+            .iter()
+            .find(|assignment| assignment.target_code.eq_ignore_ascii_case(target_code))
+            .and_then(|assignment| self.scheme(&assignment.scheme_code))
+    }
+
+    /// The scheme for a subject: its own if it has one, otherwise its
+    /// programme's.
+    pub fn scheme_for(&self, subject: Subject) -> Option<&GradeScheme> {
+        self.assigned(&subject.code())
+            .or_else(|| self.assigned(subject.programme_code()))
+    }
+
+    pub fn grade(&self, subject: Subject, percentage: f32) -> Option<&str> {
+        self.scheme_for(subject)?.grade(percentage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scheme(boundaries: &[(&str, f32)], pass_grade: &str) -> GradeScheme {
+        GradeScheme::preset("TEST", "Test", boundaries, pass_grade)
+    }
+
+    #[test]
+    fn preset_schemes_have_no_issues() {
+        for scheme in GradingPolicy::seed().schemes() {
+            assert_eq!(scheme.issues(), Vec::new(), "{}", scheme.code());
+        }
+    }
+
+    #[test]
+    fn a_scheme_without_boundaries_reports_only_that() {
+        assert_eq!(scheme(&[], "A").issues(), vec![SchemeIssue::NoBoundaries]);
+    }
+
+    #[test]
+    fn blank_and_repeated_grades_are_issues() {
+        let issues = scheme(&[("A", 70.0), (" ", 50.0), ("A", 0.0)], "A").issues();
+
+        assert!(issues.contains(&SchemeIssue::BlankGrade));
+        assert!(issues.contains(&SchemeIssue::DuplicateGrade("A".to_string())));
+    }
+
+    #[test]
+    fn grades_sharing_a_boundary_are_an_issue() {
+        let issues = scheme(&[("A", 50.0), ("B", 50.0), ("F", 0.0)], "B").issues();
+
+        assert_eq!(
+            issues,
+            vec![SchemeIssue::SameBoundary("A".to_string(), "B".to_string())]
+        );
+    }
+
+    #[test]
+    fn boundaries_outside_0_to_100_and_nan_are_out_of_range() {
+        let issues = scheme(&[("A", 120.0), ("B", f32::NAN), ("F", 0.0)], "F").issues();
+
+        assert!(issues.contains(&SchemeIssue::OutOfRange("A".to_string(), 120.0)));
+        assert!(issues.iter().any(|issue| matches!(
+            issue,
+            SchemeIssue::OutOfRange(grade, min_percentage)
+                if grade == "B" && min_percentage.is_nan()
+        )));
+    }
+
+    #[test]
+    fn the_lowest_grade_must_start_at_zero() {
+        let issues = scheme(&[("A", 70.0), ("B", 40.0)], "B").issues();
+
+        assert_eq!(issues, vec![SchemeIssue::NoLowestGrade]);
+    }
+
+    #[test]
+    fn the_pass_grade_must_be_one_of_the_grades() {
+        let issues = scheme(&[("A", 70.0), ("F", 0.0)], "C").issues();
+
+        assert_eq!(issues, vec![SchemeIssue::UnknownPassGrade("C".to_string())]);
+    }
+
+    #[test]
+    fn scores_on_a_boundary_earn_that_grade() {
+        let scheme = GradeScheme::wassce();
+
+        assert_eq!(scheme.grade(75.0), Some("A1"));
+        assert_eq!(scheme.grade(74.9), Some("B2"));
+        assert!(scheme.is_pass(50.0));
+        assert!(!scheme.is_pass(49.9));
+    }
+}
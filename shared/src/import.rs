@@ -0,0 +1,590 @@
+// Spreadsheet import
+//
+// The webapp reads an uploaded file (CSV or the first worksheet of an Excel
+// workbook) into a `Sheet` of text cells. Everything after that happens
+// here, so the preview shown in the browser and the check made before
+// saving are the same code.
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::marks::{Assessment, MarkEntry, MarkIssue, validate_entries};
+use crate::validation::Severity;
+
+/// Header names, in order of preference, that identify the candidate column.
+pub const CANDIDATE_HEADERS: &[&str] = &[
+    "candidate number",
+    "candidate no",
+    "candidate",
+    "index number",
+    "index no",
+    "index",
+];
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SheetRow {
+    line: usize,
+    cells: Vec<String>,
+}
+
+impl SheetRow {
+    /// `line` is the row number the user sees in their spreadsheet.
+    pub fn new(line: usize, cells: Vec<String>) -> Self {
+        Self { line, cells }
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The trimmed cell in `column`, or "" past the end of a short row.
+    pub fn cell(&self, column: usize) -> &str {
+        self.cells.get(column).map_or("", |cell| cell.trim())
+    }
+
+    pub fn is_blank(&self) -> bool {
+        self.cells.iter().all(|cell| cell.trim().is_empty())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Sheet {
+    headers: Vec<String>,
+    rows: Vec<SheetRow>,
+}
+
+impl Sheet {
+    pub fn new(headers: Vec<String>, rows: Vec<SheetRow>) -> Self {
+        Self { headers, rows }
+    }
+
+    /// Builds a sheet from raw records, numbered from line 1. The first
+    /// non-blank record is the header row; blank records are dropped.
+    pub fn from_records(records: Vec<Vec<String>>) -> Option<Self> {
+        let mut rows = records
+            .into_iter()
+            .zip(1..)
+            .map(|(cells, line)| SheetRow::new(line, cells))
+            .filter(|row| !row.is_blank());
+        let headers = rows
+            .next()?
+            .cells
+            .into_iter()
+            .map(|header| header.trim().to_string())
+            .collect();
+
+        Some(Self::new(headers, rows.collect()))
+    }
+
+    pub fn headers(&self) -> &[String] {
+        &self.headers
+    }
+
+    pub fn rows(&self) -> &[SheetRow] {
+        &self.rows
+    }
+
+    /// The first column whose header matches `name`, ignoring case.
+    pub fn column(&self, name: &str) -> Option<usize> {
+        self.headers
+            .iter()
+            .position(|header| header.trim().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+/// Which sheet column holds the candidate number and which holds each
+/// assessment component. A component without a column is left untouched.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MarksColumns {
+    candidate: Option<usize>,
+    components: Vec<(String, Option<usize>)>,
+}
+
+impl MarksColumns {
+    pub fn new(candidate: Option<usize>, components: Vec<(String, Option<usize>)>) -> Self {
+        Self {
+            candidate,
+            components,
+        }
+    }
+
+    /// Guesses the columns from the headers: a candidate header from
+    /// `CANDIDATE_HEADERS`, and each component by its code or name.
+    pub fn detect(sheet: &Sheet, assessment: &Assessment) -> Self {
+        let candidate = CANDIDATE_HEADERS
+            .iter()
+            .find_map(|header| sheet.column(header));
+        let components = assessment
+            .components()
+            .iter()
+            .map(|component| {
+                let column = sheet
+                    .column(component.code())
+                    .or_else(|| sheet.column(component.name()));
+                (component.code().to_string(), column)
+            })
+            .collect();
+
+        Self::new(candidate, components)
+    }
+
+    pub fn candidate(&self) -> Option<usize> {
+        self.candidate
+    }
+
+    pub fn components(&self) -> &[(String, Option<usize>)] {
+        &self.components
+    }
+
+    pub fn component(&self, code: &str) -> Option<usize> {
+        self.components
+            .iter()
+            .find(|(component, _)| component.eq_ignore_ascii_case(code))
+            .and_then(|(_, column)| *column)
+    }
+
+    pub fn set_candidate(&mut self, column: Option<usize>) {
+        self.candidate = column;
+    }
+
+    pub fn set_component(&mut self, code: &str, column: Option<usize>) {
+        if let Some((_, mapped)) = self
+            .components
+            .iter_mut()
+            .find(|(component, _)| component.eq_ignore_ascii_case(code))
+        {
+            *mapped = column;
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ImportIssue {
+    NoCandidateColumn,
+    NoComponentColumns,
+    /// A row has marks but no candidate number.
+    BlankCandidate {
+        line: usize,
+    },
+    DuplicateCandidate {
+        line: usize,
+        first_line: usize,
+        candidate_number: String,
+    },
+    /// A problem with the marks themselves, on the row it came from.
+    Mark {
+        line: usize,
+        issue: MarkIssue,
+    },
+}
+
+impl ImportIssue {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::Mark { issue, .. } => issue.severity(),
+            Self::NoCandidateColumn
+            | Self::NoComponentColumns
+            | Self::BlankCandidate { .. }
+            | Self::DuplicateCandidate { .. } => Severity::Error,
+        }
+    }
+
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Self::BlankCandidate { line }
+            | Self::DuplicateCandidate { line, .. }
+            | Self::Mark { line, .. } => Some(*line),
+            Self::NoCandidateColumn | Self::NoComponentColumns => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ImportIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::NoCandidateColumn => write!(f, "no column is chosen for candidate numbers"),
+            Self::NoComponentColumns => write!(f, "no column is chosen for any component"),
+            Self::BlankCandidate { line } => {
+                write!(f, "line {}: marks without a candidate number", line)
+            }
+            Self::DuplicateCandidate {
+                line,
+                first_line,
+                candidate_number,
+            } => write!(
+                f,
+                "line {}: {} already appears on line {}",
+                line, candidate_number, first_line
+            ),
+            Self::Mark { line, issue } => write!(f, "line {}: {}", line, issue),
+        }
+    }
+}
+
+/// One sheet row as it will be imported.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportRow {
+    line: usize,
+    candidate_number: String,
+    entries: Vec<MarkEntry>,
+}
+
+impl ImportRow {
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn candidate_number(&self) -> &str {
+        &self.candidate_number
+    }
+
+    /// The non-blank cells for mapped components.
+    pub fn entries(&self) -> &[MarkEntry] {
+        &self.entries
+    }
+
+    pub fn entry(&self, component_code: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|entry| entry.component_code() == component_code)
+            .map(|entry| entry.entry())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MarksImport {
+    rows: Vec<ImportRow>,
+    issues: Vec<ImportIssue>,
+}
+
+impl MarksImport {
+    pub fn rows(&self) -> &[ImportRow] {
+        &self.rows
+    }
+
+    /// Errors first.
+    pub fn issues(&self) -> &[ImportIssue] {
+        &self.issues
+    }
+
+    pub fn issues_on(&self, line: usize) -> impl Iterator<Item = &ImportIssue> {
+        self.issues
+            .iter()
+            .filter(move |issue| issue.line() == Some(line))
+    }
+
+    pub fn is_importable(&self) -> bool {
+        !self.rows.is_empty()
+            && self
+                .issues
+                .iter()
+                .all(|issue| issue.severity() != Severity::Error)
+    }
+
+    /// Every entry to save. Blank cells are left out so that importing a
+    /// partly filled sheet never clears marks already entered.
+    pub fn entries(&self) -> Vec<MarkEntry> {
+        self.rows
+            .iter()
+            .flat_map(|row| row.entries.iter().cloned())
+            .collect()
+    }
+}
+
+/// Reads marks for `assessment` out of `sheet` and checks them the same way
+/// as the marks grid. Missing marks are only reported for candidates in the
+/// sheet and components that have a column.
+pub fn read_marks(
+    assessment: &Assessment,
+    candidate_numbers: &[String],
+    sheet: &Sheet,
+    columns: &MarksColumns,
+) -> MarksImport {
+    let mut issues = Vec::new();
+    let Some(candidate_column) = columns.candidate else {
+        issues.push(ImportIssue::NoCandidateColumn);
+        return MarksImport {
+            rows: Vec::new(),
+            issues,
+        };
+    };
+    let mapped: Vec<(&str, usize)> = columns
+        .components
+        .iter()
+        .filter_map(|(code, column)| Some((code.as_str(), (*column)?)))
+        .collect();
+    if mapped.is_empty() {
+        issues.push(ImportIssue::NoComponentColumns);
+        return MarksImport {
+            rows: Vec::new(),
+            issues,
+        };
+    }
+
+    let mut rows = Vec::new();
+    let mut lines: BTreeMap<String, usize> = BTreeMap::new();
+    for row in sheet.rows() {
+        let candidate_number = row.cell(candidate_column).to_string();
+        let entries: Vec<MarkEntry> = mapped
+            .iter()
+            .filter(|(_, column)| !row.cell(*column).is_empty())
+            .map(|(code, column)| {
+                MarkEntry::new(
+                    candidate_number.clone(),
+                    code.to_string(),
+                    row.cell(*column).to_string(),
+                )
+            })
+            .collect();
+
+        if candidate_number.is_empty() {
+            if !entries.is_empty() {
+                issues.push(ImportIssue::BlankCandidate { line: row.line() });
+            }
+            continue;
+        }
+        if let Some(first_line) = lines.get(&candidate_number) {
+            issues.push(ImportIssue::DuplicateCandidate {
+                line: row.line(),
+                first_line: *first_line,
+                candidate_number,
+            });
+            continue;
+        }
+
+        lines.insert(candidate_number.clone(), row.line());
+        rows.push(ImportRow {
+            line: row.line(),
+            candidate_number,
+            entries,
+        });
+    }
+
+    let entries: Vec<MarkEntry> = rows
+        .iter()
+        .flat_map(|row| row.entries.iter().cloned())
+        .collect();
+    let (_, mark_issues) = validate_entries(assessment, candidate_numbers, &entries);
+    issues.extend(mark_issues.into_iter().filter_map(|issue| {
+        let line = *lines.get(issue.candidate_number()?)?;
+        if matches!(issue, MarkIssue::Missing { .. })
+            && issue
+                .component()
+                .is_none_or(|code| columns.component(code).is_none())
+        {
+            return None;
+        }
+        Some(ImportIssue::Mark { line, issue })
+    }));
+
+    // `validate_entries` only sees candidates with marks, so a row with an
+    // unknown candidate and nothing else would otherwise pass silently.
+    issues.extend(
+        rows.iter()
+            .filter(|row| {
+                row.entries.is_empty() && !candidate_numbers.contains(&row.candidate_number)
+            })
+            .map(|row| ImportIssue::Mark {
+                line: row.line,
+                issue: MarkIssue::UnknownCandidate {
+                    candidate_number: row.candidate_number.clone(),
+                },
+            }),
+    );
+
+    issues.sort_by_key(|issue| (std::cmp::Reverse(issue.severity()), issue.line()));
+    MarksImport { rows, issues }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{StudentClass, Subject};
+    use crate::marks::{AcademicPeriod, AssessmentComponent, Term};
+
+    /// Paper 1 out of 50 and Paper 2 out of 100.
+    fn assessment() -> Assessment {
+        Assessment::new(
+            "End of term".to_string(),
+            StudentClass::all().next().expect("a built-in class"),
+            Subject::all().next().expect("a built-in subject"),
+            AcademicPeriod::new(2026, Term::First),
+            vec![
+                AssessmentComponent::new("P1".to_string(), "Paper 1".to_string(), 50, 1.0),
+                AssessmentComponent::new("P2".to_string(), "Paper 2".to_string(), 100, 1.0),
+            ],
+        )
+    }
+
+    fn candidates() -> Vec<String> {
+        vec!["C01".to_string(), "C02".to_string(), "C03".to_string()]
+    }
+
+    fn sheet(records: &[&[&str]]) -> Sheet {
+        Sheet::from_records(
+            records
+                .iter()
+                .map(|record| record.iter().map(|cell| cell.to_string()).collect())
+                .collect(),
+        )
+        .expect("a header row")
+    }
+
+    /// Reads `records` with the columns detected from their headers.
+    fn import(records: &[&[&str]]) -> MarksImport {
+        let sheet = sheet(records);
+        let columns = MarksColumns::detect(&sheet, &assessment());
+        read_marks(&assessment(), &candidates(), &sheet, &columns)
+    }
+
+    fn errors(import: &MarksImport) -> Vec<&ImportIssue> {
+        import
+            .issues()
+            .iter()
+            .filter(|issue| issue.severity() == Severity::Error)
+            .collect()
+    }
+
+    #[test]
+    fn the_first_non_blank_record_is_the_header_row() {
+        let sheet = sheet(&[
+            &["", " "],
+            &[" Candidate ", "P1 "],
+            &["C01", "40"],
+            &["", ""],
+            &["C02", "41"],
+        ]);
+
+        assert_eq!(sheet.headers(), ["Candidate", "P1"]);
+        let lines: Vec<usize> = sheet.rows().iter().map(SheetRow::line).collect();
+        assert_eq!(lines, [3, 5]);
+        assert_eq!(sheet.rows()[0].cell(1), "40");
+        assert_eq!(sheet.rows()[0].cell(5), "");
+        assert_eq!(sheet.column("p1"), Some(1));
+
+        assert_eq!(Sheet::from_records(Vec::new()), None);
+        assert_eq!(
+            Sheet::from_records(vec![vec![String::new()], vec![" ".to_string()]]),
+            None
+        );
+    }
+
+    #[test]
+    fn columns_are_detected_from_their_headers() {
+        let labelled = sheet(&[&["Name", "Index No", "Candidate Number", "paper 2", "p1"]]);
+
+        let columns = MarksColumns::detect(&labelled, &assessment());
+
+        assert_eq!(columns.candidate(), Some(2));
+        assert_eq!(columns.component("P1"), Some(4));
+        assert_eq!(columns.component("P2"), Some(3));
+
+        let unlabelled = sheet(&[&["Student", "Paper 1"]]);
+        let columns = MarksColumns::detect(&unlabelled, &assessment());
+        assert_eq!(columns.candidate(), None);
+        assert_eq!(columns.component("P1"), Some(1));
+        assert_eq!(columns.component("P2"), None);
+    }
+
+    #[test]
+    fn marks_are_read_from_the_mapped_columns() {
+        let import = import(&[
+            &["Candidate", "Name", "P1", "P2"],
+            &["C01", "Ama", "40", "90"],
+            &["C02", "Kofi", "ABS", "abs"],
+        ]);
+
+        assert!(import.is_importable());
+        assert!(import.issues().is_empty());
+        assert_eq!(import.rows()[0].entry("P1"), Some("40"));
+        assert_eq!(import.rows()[0].entry("P2"), Some("90"));
+        assert_eq!(import.rows()[1].entry("P1"), Some("ABS"));
+        assert_eq!(import.entries().len(), 4);
+    }
+
+    #[test]
+    fn blank_cells_are_left_out_and_reported_missing_on_their_line() {
+        let import = import(&[
+            &["Candidate", "P1"],
+            &["C01", "40"],
+            &["C02", ""],
+            &["", ""],
+        ]);
+
+        assert!(import.is_importable());
+        assert!(import.rows()[1].entries().is_empty());
+        assert_eq!(
+            import.issues(),
+            [ImportIssue::Mark {
+                line: 3,
+                issue: MarkIssue::Missing {
+                    candidate_number: "C02".to_string(),
+                    component: "P1".to_string(),
+                },
+            }],
+            "P2 has no column and C03 is not in the sheet"
+        );
+    }
+
+    #[test]
+    fn bad_scores_are_errors_on_their_line() {
+        let import = import(&[
+            &["Candidate", "P1", "P2"],
+            &["C01", "51", "90"],
+            &["C02", "forty", "90"],
+        ]);
+
+        assert!(!import.is_importable());
+        let lines: Vec<Option<usize>> = errors(&import).iter().map(|issue| issue.line()).collect();
+        assert_eq!(lines, [Some(2), Some(3)]);
+        assert_eq!(
+            errors(&import)[1].to_string(),
+            "line 3: C02 has \"forty\" for P1, which is not a mark or ABS"
+        );
+    }
+
+    #[test]
+    fn rows_that_cannot_be_matched_to_a_candidate_are_errors() {
+        let import = import(&[
+            &["Candidate", "P1", "P2"],
+            &["", "40", "90"],
+            &["C01", "40", "90"],
+            &["C01", "41", "91"],
+            &["C09", "", ""],
+        ]);
+
+        assert_eq!(
+            errors(&import),
+            [
+                &ImportIssue::BlankCandidate { line: 2 },
+                &ImportIssue::DuplicateCandidate {
+                    line: 4,
+                    first_line: 3,
+                    candidate_number: "C01".to_string(),
+                },
+                &ImportIssue::Mark {
+                    line: 5,
+                    issue: MarkIssue::UnknownCandidate {
+                        candidate_number: "C09".to_string(),
+                    },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn a_sheet_without_mapped_columns_imports_nothing() {
+        let sheet = sheet(&[&["Candidate", "P1"], &["C01", "40"]]);
+        let mut columns = MarksColumns::detect(&sheet, &assessment());
+
+        columns.set_component("P1", None);
+        let import = read_marks(&assessment(), &candidates(), &sheet, &columns);
+        assert_eq!(import.issues(), [ImportIssue::NoComponentColumns]);
+        assert!(!import.is_importable());
+
+        columns.set_component("p1", Some(1));
+        columns.set_candidate(None);
+        let import = read_marks(&assessment(), &candidates(), &sheet, &columns);
+        assert_eq!(import.issues(), [ImportIssue::NoCandidateColumn]);
+    }
+}
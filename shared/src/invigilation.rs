@@ -0,0 +1,1068 @@
+// Invigilation timetable generation
+//
+// Works from the room allocations of a finalised exam timetable. Every
+// occupied room in a sitting needs enough invigilators for its candidates;
+// staff are drawn from the teachers, skipping anyone who teaches a subject
+// being sat in the room, who is already on duty in that sitting, or who is
+// unavailable then. Longer duties are handed out first, each to the eligible
+// teacher with the fewest duty minutes so far, which keeps the totals close.
+//
+// Duty limits and session preferences are either hard, and never broken, or
+// soft, and broken only when nobody else can cover a room. Every soft
+// constraint broken this way is reported as a relaxation.
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{ExamDate, ExamDay, ExamSession, PersonalName, Subject, Teacher};
+use crate::rooms::{AllocationReport, RoomAllocation};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct InvigilationRules {
+    candidates_per_invigilator: u32,
+    minimum_per_room: u32,
+}
+
+impl InvigilationRules {
+    pub fn new(candidates_per_invigilator: u32, minimum_per_room: u32) -> Self {
+        Self {
+            candidates_per_invigilator: candidates_per_invigilator.max(1),
+            minimum_per_room,
+        }
+    }
+
+    pub fn candidates_per_invigilator(&self) -> u32 {
+        self.candidates_per_invigilator
+    }
+
+    pub fn minimum_per_room(&self) -> u32 {
+        self.minimum_per_room
+    }
+
+    /// Invigilators needed for a room seating `candidates`.
+    pub fn invigilators_needed(&self, candidates: u32) -> u32 {
+        candidates
+            .div_ceil(self.candidates_per_invigilator)
+            .max(self.minimum_per_room)
+    }
+}
+
+impl Default for InvigilationRules {
+    /// One invigilator per 30 candidates, at least one in every room.
+    fn default() -> Self {
+        Self::new(30, 1)
+    }
+}
+
+/// A time a member of staff cannot invigilate. Always a hard constraint.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Unavailability {
+    /// Leave from `from` to `to` inclusive.
+    Leave { from: NaiveDate, to: NaiveDate },
+    /// A weekday not worked, for part-time staff.
+    Day(ExamDay),
+    /// A session never invigilated, on any day.
+    Session(ExamSession),
+    /// A single sitting, e.g. one clashing with a timetabled lesson.
+    Sitting(ExamDate),
+}
+
+impl Unavailability {
+    pub fn covers(&self, exam_date: &ExamDate) -> bool {
+        match self {
+            Self::Leave { from, to } => (*from..=*to).contains(&exam_date.date()),
+            Self::Day(day) => ExamDay::from_weekday(exam_date.date().weekday()) == Some(*day),
+            Self::Session(session) => exam_date.session() == *session,
+            Self::Sitting(sitting) => sitting == exam_date,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Strength {
+    Hard,
+    Soft,
+}
+
+impl Strength {
+    pub const ALL: &'static [Self] = &[Self::Hard, Self::Soft];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Hard => "Never break",
+            Self::Soft => "Break if needed",
+        }
+    }
+
+    /// Stable code for storage.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Hard => "hard",
+            Self::Soft => "soft",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|strength| strength.code() == code)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct StaffConstraints {
+    unavailable: Vec<Unavailability>,
+    max_duties_per_day: Option<(u32, Strength)>,
+    no_back_to_back: Option<Strength>,
+    preferred_sessions: Vec<ExamSession>,
+}
+
+impl StaffConstraints {
+    pub fn new(
+        unavailable: Vec<Unavailability>,
+        max_duties_per_day: Option<(u32, Strength)>,
+        no_back_to_back: Option<Strength>,
+        preferred_sessions: Vec<ExamSession>,
+    ) -> Self {
+        Self {
+            unavailable,
+            max_duties_per_day,
+            no_back_to_back,
+            preferred_sessions,
+        }
+    }
+
+    pub fn unavailable(&self) -> &[Unavailability] {
+        &self.unavailable
+    }
+
+    pub fn max_duties_per_day(&self) -> Option<(u32, Strength)> {
+        self.max_duties_per_day
+    }
+
+    /// Whether duties in consecutive sessions of the same day are ruled out.
+    pub fn no_back_to_back(&self) -> Option<Strength> {
+        self.no_back_to_back
+    }
+
+    /// Sessions the teacher would rather invigilate. Always soft; empty means
+    /// no preference.
+    pub fn preferred_sessions(&self) -> &[ExamSession] {
+        &self.preferred_sessions
+    }
+
+    pub fn is_available(&self, exam_date: &ExamDate) -> bool {
+        !self
+            .unavailable
+            .iter()
+            .any(|unavailability| unavailability.covers(exam_date))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct StaffMember {
+    teacher: Teacher,
+    constraints: StaffConstraints,
+}
+
+impl StaffMember {
+    pub fn new(teacher: Teacher, constraints: StaffConstraints) -> Self {
+        Self {
+            teacher,
+            constraints,
+        }
+    }
+
+    pub fn teacher(&self) -> &Teacher {
+        &self.teacher
+    }
+
+    pub fn constraints(&self) -> &StaffConstraints {
+        &self.constraints
+    }
+}
+
+impl From<Teacher> for StaffMember {
+    fn from(teacher: Teacher) -> Self {
+        Self::new(teacher, StaffConstraints::default())
+    }
+}
+
+/// A soft constraint that can be relaxed, in the order they are given up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Constraint {
+    PreferredSessions,
+    NoBackToBack,
+    MaxDutiesPerDay(u32),
+}
+
+impl std::fmt::Display for Constraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::PreferredSessions => write!(f, "preferred sessions"),
+            Self::NoBackToBack => write!(f, "no back-to-back duties"),
+            Self::MaxDutiesPerDay(limit) => write!(f, "max duties per day ({})", limit),
+        }
+    }
+}
+
+/// A soft constraint broken to staff a room.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Relaxation {
+    exam_date: ExamDate,
+    room_code: String,
+    staff: usize,
+    invigilator: PersonalName,
+    constraint: Constraint,
+}
+
+impl Relaxation {
+    pub fn exam_date(&self) -> &ExamDate {
+        &self.exam_date
+    }
+
+    pub fn room_code(&self) -> &str {
+        &self.room_code
+    }
+
+    /// Index of the invigilator in the staff given to [`generate`].
+    pub fn staff(&self) -> usize {
+        self.staff
+    }
+
+    pub fn invigilator(&self) -> &PersonalName {
+        &self.invigilator
+    }
+
+    pub fn constraint(&self) -> Constraint {
+        self.constraint
+    }
+}
+
+impl std::fmt::Display for Relaxation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} invigilates {} on {} despite {}",
+            self.invigilator.first_name(),
+            self.invigilator.last_name(),
+            self.room_code,
+            self.exam_date,
+            self.constraint
+        )
+    }
+}
+
+/// One invigilator's duty in one room for one sitting.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Duty {
+    exam_date: ExamDate,
+    room_code: String,
+    staff: usize,
+    invigilator: PersonalName,
+    minutes: u32,
+}
+
+impl Duty {
+    pub fn exam_date(&self) -> &ExamDate {
+        &self.exam_date
+    }
+
+    pub fn room_code(&self) -> &str {
+        &self.room_code
+    }
+
+    /// Index of the invigilator in the staff given to [`generate`]. Names
+    /// can be shared, so this is what tells invigilators apart.
+    pub fn staff(&self) -> usize {
+        self.staff
+    }
+
+    pub fn invigilator(&self) -> &PersonalName {
+        &self.invigilator
+    }
+
+    /// From the first paper starting to the last paper ending.
+    pub fn minutes(&self) -> u32 {
+        self.minutes
+    }
+}
+
+/// A room that could not be given all the invigilators it needs.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Shortfall {
+    exam_date: ExamDate,
+    room_code: String,
+    needed: u32,
+    assigned: u32,
+}
+
+impl Shortfall {
+    pub fn exam_date(&self) -> &ExamDate {
+        &self.exam_date
+    }
+
+    pub fn room_code(&self) -> &str {
+        &self.room_code
+    }
+
+    pub fn needed(&self) -> u32 {
+        self.needed
+    }
+
+    pub fn assigned(&self) -> u32 {
+        self.assigned
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InvigilationReport {
+    duties: Vec<Duty>,
+    shortfalls: Vec<Shortfall>,
+    relaxations: Vec<Relaxation>,
+    minutes: Vec<(PersonalName, u32)>,
+}
+
+impl InvigilationReport {
+    /// Duties ordered by sitting, then room.
+    pub fn duties(&self) -> &[Duty] {
+        &self.duties
+    }
+
+    pub fn shortfalls(&self) -> &[Shortfall] {
+        &self.shortfalls
+    }
+
+    pub fn is_fully_staffed(&self) -> bool {
+        self.shortfalls.is_empty()
+    }
+
+    /// Soft constraints broken, ordered by sitting, then room.
+    pub fn relaxations(&self) -> &[Relaxation] {
+        &self.relaxations
+    }
+
+    /// Total duty minutes per member of staff, in the order given.
+    pub fn minutes(&self) -> &[(PersonalName, u32)] {
+        &self.minutes
+    }
+
+    /// Duties of the member of staff at `staff` in the order given.
+    pub fn duties_for(&self, staff: usize) -> impl Iterator<Item = &Duty> {
+        self.duties.iter().filter(move |duty| duty.staff == staff)
+    }
+}
+
+/// Length of a room's sitting in minutes.
+pub fn duty_minutes(allocation: &RoomAllocation) -> u32 {
+    let papers = allocation.papers();
+    let start = papers.iter().map(|paper| paper.start_time()).min();
+    let end = papers.iter().map(|paper| paper.end_time()).max();
+    match (start, end) {
+        (Some(start), Some(end)) => (end - start).num_minutes().max(0) as u32,
+        _ => 0,
+    }
+}
+
+/// The soft constraints `constraints` would break if the teacher took a duty
+/// in `exam_date`, or `None` if a hard one would be broken.
+fn broken_constraints(
+    constraints: &StaffConstraints,
+    exam_date: &ExamDate,
+    duties: &[&ExamDate],
+) -> Option<Vec<Constraint>> {
+    if !constraints.is_available(exam_date) {
+        return None;
+    }
+
+    let mut broken = Vec::new();
+
+    if let Some((limit, strength)) = constraints.max_duties_per_day {
+        let that_day = duties
+            .iter()
+            .filter(|other| other.date() == exam_date.date())
+            .count();
+        if that_day as u32 >= limit {
+            match strength {
+                Strength::Hard => return None,
+                Strength::Soft => broken.push(Constraint::MaxDutiesPerDay(limit)),
+            }
+        }
+    }
+
+    if let Some(strength) = constraints.no_back_to_back {
+        let position = |session: ExamSession| ExamSession::ALL.iter().position(|s| *s == session);
+        let back_to_back = duties.iter().any(|other| {
+            other.date() == exam_date.date()
+                && matches!(
+                    (position(other.session()), position(exam_date.session())),
+                    (Some(a), Some(b)) if a.abs_diff(b) == 1
+                )
+        });
+        if back_to_back {
+            match strength {
+                Strength::Hard => return None,
+                Strength::Soft => broken.push(Constraint::NoBackToBack),
+            }
+        }
+    }
+
+    if !constraints.preferred_sessions.is_empty()
+        && !constraints
+            .preferred_sessions
+            .contains(&exam_date.session())
+    {
+        broken.push(Constraint::PreferredSessions);
+    }
+
+    broken.sort_by(|a, b| b.cmp(a));
+    Some(broken)
+}
+
+pub fn generate(
+    allocations: &AllocationReport,
+    staff: &[StaffMember],
+    rules: &InvigilationRules,
+) -> InvigilationReport {
+    let mut rooms: Vec<&RoomAllocation> = allocations.allocations().iter().collect();
+    rooms.sort_by_key(|allocation| {
+        (
+            std::cmp::Reverse(duty_minutes(allocation)),
+            allocation.exam_date().clone(),
+            allocation.room().code().to_string(),
+        )
+    });
+
+    let mut minutes = vec![0u32; staff.len()];
+    let mut on_duty: Vec<(ExamDate, usize)> = Vec::new();
+    let mut report = InvigilationReport::default();
+
+    for allocation in rooms {
+        let exam_date = allocation.exam_date();
+        let length = duty_minutes(allocation);
+        let subjects: Vec<Subject> = allocation
+            .papers()
+            .iter()
+            .map(|paper| paper.subject())
+            .collect();
+        let needed = rules.invigilators_needed(allocation.seats().len() as u32);
+
+        let mut assigned = 0;
+        while assigned < needed {
+            let next = (0..staff.len())
+                .filter(|index| {
+                    !staff[*index]
+                        .teacher
+                        .subjects_taught()
+                        .iter()
+                        .any(|subject| subjects.contains(subject))
+                })
+                .filter_map(|index| {
+                    let duties: Vec<&ExamDate> = on_duty
+                        .iter()
+                        .filter(|(_, other)| *other == index)
+                        .map(|(date, _)| date)
+                        .collect();
+                    if duties.contains(&exam_date) {
+                        return None;
+                    }
+                    broken_constraints(&staff[index].constraints, exam_date, &duties)
+                        .map(|broken| (index, broken))
+                })
+                .min_by(|(a, a_broken), (b, b_broken)| {
+                    (a_broken, minutes[*a], *a).cmp(&(b_broken, minutes[*b], *b))
+                });
+
+            let Some((index, broken)) = next else {
+                break;
+            };
+
+            let invigilator = staff[index].teacher.personal_info().name().clone();
+            minutes[index] += length;
+            on_duty.push((exam_date.clone(), index));
+            report
+                .relaxations
+                .extend(broken.into_iter().map(|constraint| Relaxation {
+                    exam_date: exam_date.clone(),
+                    room_code: allocation.room().code().to_string(),
+                    staff: index,
+                    invigilator: invigilator.clone(),
+                    constraint,
+                }));
+            report.duties.push(Duty {
+                exam_date: exam_date.clone(),
+                room_code: allocation.room().code().to_string(),
+                staff: index,
+                invigilator,
+                minutes: length,
+            });
+            assigned += 1;
+        }
+
+        if assigned < needed {
+            report.shortfalls.push(Shortfall {
+                exam_date: exam_date.clone(),
+                room_code: allocation.room().code().to_string(),
+                needed,
+                assigned,
+            });
+        }
+    }
+
+    report
+        .duties
+        .sort_by(|a, b| (&a.exam_date, &a.room_code).cmp(&(&b.exam_date, &b.room_code)));
+    report
+        .shortfalls
+        .sort_by(|a, b| (&a.exam_date, &a.room_code).cmp(&(&b.exam_date, &b.room_code)));
+    report
+        .relaxations
+        .sort_by(|a, b| (&a.exam_date, &a.room_code).cmp(&(&b.exam_date, &b.room_code)));
+    report.minutes = staff
+        .iter()
+        .zip(minutes)
+        .map(|(member, total)| (member.teacher.personal_info().name().clone(), total))
+        .collect();
+
+    report
+}
+
+/// Where a duty swap or cover request is in the approval workflow. The
+/// counterpart accepts or declines a pending request, then an exams officer
+/// approves or rejects an accepted one. The requester can cancel until then.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum SwapStatus {
+    Pending,
+    Accepted,
+    Declined,
+    Approved,
+    Rejected,
+    Cancelled,
+}
+
+impl SwapStatus {
+    pub const ALL: &'static [Self] = &[
+        Self::Pending,
+        Self::Accepted,
+        Self::Declined,
+        Self::Approved,
+        Self::Rejected,
+        Self::Cancelled,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "Pending",
+            Self::Accepted => "Accepted",
+            Self::Declined => "Declined",
+            Self::Approved => "Approved",
+            Self::Rejected => "Rejected",
+            Self::Cancelled => "Cancelled",
+        }
+    }
+
+    /// Stable code for storage.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Accepted => "accepted",
+            Self::Declined => "declined",
+            Self::Approved => "approved",
+            Self::Rejected => "rejected",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|status| status.code() == code)
+    }
+
+    /// Whether the request still holds the duty open.
+    pub fn is_open(&self) -> bool {
+        matches!(self, Self::Pending | Self::Accepted)
+    }
+
+    pub fn can_become(&self, next: Self) -> bool {
+        matches!(
+            (self, next),
+            (
+                Self::Pending,
+                Self::Accepted | Self::Declined | Self::Cancelled
+            ) | (
+                Self::Accepted,
+                Self::Approved | Self::Rejected | Self::Cancelled
+            )
+        )
+    }
+}
+
+impl std::fmt::Display for SwapStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveTime;
+
+    use super::*;
+    use crate::domain::{ExamPaper, Gender, PaperComponent, TeacherPersonalInfo};
+    use crate::fixtures;
+    use crate::rooms::{Room, Seat};
+
+    /// Day 1 is Monday 4 May 2026.
+    fn sitting(day: u32, session: ExamSession) -> ExamDate {
+        ExamDate::new(
+            NaiveDate::from_ymd_opt(2026, 5, 3 + day).expect("valid date"),
+            session,
+        )
+    }
+
+    fn subject(index: usize) -> Subject {
+        Subject::all().nth(index).expect("a built-in subject")
+    }
+
+    /// `code` seating `candidates` for one `minutes`-long paper in `subject`.
+    fn room(
+        exam_date: ExamDate,
+        code: &str,
+        subject: Subject,
+        candidates: u32,
+        minutes: u32,
+    ) -> RoomAllocation {
+        let paper = ExamPaper::new(
+            subject,
+            PaperComponent::new("P1".to_string(), "Paper 1".to_string(), minutes),
+            NaiveTime::from_hms_opt(9, 0, 0).expect("valid time"),
+        );
+        let seats = (1..=candidates)
+            .map(|desk| {
+                Seat::new(
+                    desk,
+                    format!("C{:03}", desk),
+                    fixtures::name(&format!("Student{}", desk)),
+                    paper.clone(),
+                )
+            })
+            .collect();
+        RoomAllocation::new(
+            exam_date,
+            Room::new(code.to_string(), code.to_string(), 200, true, true),
+            seats,
+        )
+    }
+
+    fn teacher(first_name: &str, subjects: &[Subject]) -> StaffMember {
+        Teacher::new(
+            TeacherPersonalInfo::new(fixtures::name(first_name), Gender::Female),
+            subjects.to_vec(),
+        )
+        .into()
+    }
+
+    fn staffed(rooms: Vec<RoomAllocation>, staff: &[StaffMember]) -> InvigilationReport {
+        generate(
+            &AllocationReport::new(rooms, Vec::new()),
+            staff,
+            &InvigilationRules::default(),
+        )
+    }
+
+    #[test]
+    fn a_room_gets_an_invigilator_per_started_block_of_candidates() {
+        let rules = InvigilationRules::new(30, 1);
+        assert_eq!(rules.invigilators_needed(0), 1);
+        assert_eq!(rules.invigilators_needed(30), 1);
+        assert_eq!(rules.invigilators_needed(31), 2);
+        assert_eq!(InvigilationRules::new(30, 3).invigilators_needed(31), 3);
+
+        let staff: Vec<StaffMember> = ["Ama", "Kofi", "Esi", "Yaw"]
+            .into_iter()
+            .map(|first_name| teacher(first_name, &[]))
+            .collect();
+        let report = staffed(
+            vec![
+                room(sitting(1, ExamSession::Morning), "HALL", subject(0), 61, 90),
+                room(sitting(1, ExamSession::Morning), "C1", subject(0), 12, 90),
+            ],
+            &staff,
+        );
+
+        let in_room = |code: &str| {
+            report
+                .duties()
+                .iter()
+                .filter(|duty| duty.room_code() == code)
+                .count()
+        };
+        assert_eq!(in_room("HALL"), 3);
+        assert_eq!(in_room("C1"), 1);
+        assert!(report.is_fully_staffed());
+    }
+
+    #[test]
+    fn a_room_short_of_staff_is_reported() {
+        let report = staffed(
+            vec![room(
+                sitting(1, ExamSession::Morning),
+                "HALL",
+                subject(0),
+                61,
+                90,
+            )],
+            &[teacher("Ama", &[])],
+        );
+
+        assert_eq!(report.duties().len(), 1);
+        assert_eq!(report.shortfalls().len(), 1);
+        assert_eq!(report.shortfalls()[0].needed(), 3);
+        assert_eq!(report.shortfalls()[0].assigned(), 1);
+    }
+
+    #[test]
+    fn teachers_do_not_invigilate_their_own_subject() {
+        let staff = [
+            teacher("Ama", &[subject(0)]),
+            teacher("Kofi", &[subject(1)]),
+        ];
+        let report = staffed(
+            vec![
+                room(sitting(1, ExamSession::Morning), "HALL", subject(0), 20, 90),
+                room(sitting(2, ExamSession::Morning), "HALL", subject(1), 20, 90),
+            ],
+            &staff,
+        );
+
+        let own_subject = |staff: usize, day: u32| {
+            report
+                .duties_for(staff)
+                .any(|duty| duty.exam_date().date() == sitting(day, ExamSession::Morning).date())
+        };
+        assert!(!own_subject(0, 1));
+        assert!(own_subject(0, 2));
+        assert!(own_subject(1, 1));
+        assert!(!own_subject(1, 2));
+
+        let report = staffed(
+            vec![room(
+                sitting(1, ExamSession::Morning),
+                "HALL",
+                subject(0),
+                20,
+                90,
+            )],
+            &staff[..1],
+        );
+        assert!(report.duties().is_empty());
+        assert!(!report.is_fully_staffed());
+    }
+
+    #[test]
+    fn duty_minutes_are_balanced_across_staff() {
+        let staff = [teacher("Ama", &[]), teacher("Kofi", &[])];
+        let report = staffed(
+            vec![
+                room(sitting(1, ExamSession::Morning), "HALL", subject(0), 20, 60),
+                room(
+                    sitting(2, ExamSession::Morning),
+                    "HALL",
+                    subject(0),
+                    20,
+                    180,
+                ),
+                room(sitting(3, ExamSession::Morning), "HALL", subject(0), 20, 60),
+                room(sitting(4, ExamSession::Morning), "HALL", subject(0), 20, 60),
+            ],
+            &staff,
+        );
+
+        assert_eq!(
+            report.minutes(),
+            [(fixtures::name("Ama"), 180), (fixtures::name("Kofi"), 180)]
+        );
+        assert_eq!(report.duties_for(0).count(), 1);
+        assert_eq!(report.duties_for(1).count(), 3);
+    }
+
+    #[test]
+    fn staff_sharing_a_name_keep_their_own_duties() {
+        let staff = [teacher("Ama", &[]), teacher("Ama", &[])];
+        let report = staffed(
+            vec![
+                room(sitting(1, ExamSession::Morning), "HALL", subject(0), 20, 90),
+                room(sitting(2, ExamSession::Morning), "HALL", subject(0), 20, 90),
+            ],
+            &staff,
+        );
+
+        let days = |staff: usize| -> Vec<NaiveDate> {
+            report
+                .duties_for(staff)
+                .map(|duty| duty.exam_date().date())
+                .collect()
+        };
+        assert_eq!(days(0), [sitting(1, ExamSession::Morning).date()]);
+        assert_eq!(days(1), [sitting(2, ExamSession::Morning).date()]);
+    }
+
+    fn constrained(first_name: &str, constraints: StaffConstraints) -> StaffMember {
+        let member = teacher(first_name, &[]);
+        StaffMember::new(member.teacher().clone(), constraints)
+    }
+
+    fn unavailable(unavailability: Unavailability) -> StaffConstraints {
+        StaffConstraints::new(vec![unavailability], None, None, Vec::new())
+    }
+
+    /// The morning and afternoon of day 1, one room each.
+    fn one_day() -> Vec<RoomAllocation> {
+        vec![
+            room(sitting(1, ExamSession::Morning), "HALL", subject(0), 20, 90),
+            room(
+                sitting(1, ExamSession::Afternoon),
+                "HALL",
+                subject(1),
+                20,
+                90,
+            ),
+        ]
+    }
+
+    #[test]
+    fn leave_rules_out_every_sitting_it_covers() {
+        let leave = unavailable(Unavailability::Leave {
+            from: sitting(2, ExamSession::Morning).date(),
+            to: sitting(3, ExamSession::Morning).date(),
+        });
+
+        assert!(broken_constraints(&leave, &sitting(1, ExamSession::Evening), &[]).is_some());
+        assert!(broken_constraints(&leave, &sitting(2, ExamSession::Morning), &[]).is_none());
+        assert!(broken_constraints(&leave, &sitting(3, ExamSession::Evening), &[]).is_none());
+        assert!(broken_constraints(&leave, &sitting(4, ExamSession::Morning), &[]).is_some());
+
+        let report = staffed(
+            vec![room(
+                sitting(2, ExamSession::Morning),
+                "HALL",
+                subject(0),
+                20,
+                90,
+            )],
+            &[constrained("Ama", leave), teacher("Kofi", &[])],
+        );
+        assert_eq!(report.duties_for(0).count(), 0);
+        assert_eq!(report.duties_for(1).count(), 1);
+    }
+
+    #[test]
+    fn an_unavailable_day_rules_out_that_weekday_only() {
+        let tuesdays = unavailable(Unavailability::Day(ExamDay::Tuesday));
+
+        assert!(broken_constraints(&tuesdays, &sitting(1, ExamSession::Morning), &[]).is_some());
+        assert!(broken_constraints(&tuesdays, &sitting(2, ExamSession::Morning), &[]).is_none());
+        assert!(broken_constraints(&tuesdays, &sitting(9, ExamSession::Afternoon), &[]).is_none());
+
+        let report = staffed(
+            vec![room(
+                sitting(2, ExamSession::Morning),
+                "HALL",
+                subject(0),
+                20,
+                90,
+            )],
+            &[constrained("Ama", tuesdays)],
+        );
+        assert!(report.duties().is_empty());
+        assert!(report.relaxations().is_empty());
+    }
+
+    #[test]
+    fn an_unavailable_session_rules_out_that_session_every_day() {
+        let afternoons = unavailable(Unavailability::Session(ExamSession::Afternoon));
+
+        assert!(broken_constraints(&afternoons, &sitting(1, ExamSession::Morning), &[]).is_some());
+        for day in 1..=5 {
+            assert!(
+                broken_constraints(&afternoons, &sitting(day, ExamSession::Afternoon), &[])
+                    .is_none()
+            );
+        }
+
+        let report = staffed(one_day(), &[constrained("Ama", afternoons)]);
+        assert_eq!(report.duties().len(), 1);
+        assert_eq!(
+            report.duties()[0].exam_date(),
+            &sitting(1, ExamSession::Morning)
+        );
+    }
+
+    #[test]
+    fn a_soft_daily_limit_is_relaxed_only_when_nobody_else_is_free() {
+        let limited =
+            StaffConstraints::new(Vec::new(), Some((1, Strength::Soft)), None, Vec::new());
+        let morning = sitting(1, ExamSession::Morning);
+        assert_eq!(
+            broken_constraints(&limited, &sitting(1, ExamSession::Evening), &[&morning]),
+            Some(vec![Constraint::MaxDutiesPerDay(1)])
+        );
+        assert_eq!(
+            broken_constraints(&limited, &sitting(2, ExamSession::Morning), &[&morning]),
+            Some(Vec::new())
+        );
+
+        let report = staffed(
+            one_day(),
+            &[constrained("Ama", limited.clone()), teacher("Kofi", &[])],
+        );
+        assert_eq!(report.duties_for(0).count(), 1);
+        assert_eq!(report.duties_for(1).count(), 1);
+        assert!(report.relaxations().is_empty());
+
+        let report = staffed(one_day(), &[constrained("Ama", limited)]);
+        assert_eq!(report.duties_for(0).count(), 2);
+        assert!(report.is_fully_staffed());
+        assert_eq!(report.relaxations().len(), 1);
+    }
+
+    #[test]
+    fn a_soft_back_to_back_limit_is_relaxed_only_when_nobody_else_is_free() {
+        let spaced = StaffConstraints::new(Vec::new(), None, Some(Strength::Soft), Vec::new());
+        let morning = sitting(1, ExamSession::Morning);
+        assert_eq!(
+            broken_constraints(&spaced, &sitting(1, ExamSession::Afternoon), &[&morning]),
+            Some(vec![Constraint::NoBackToBack])
+        );
+        assert_eq!(
+            broken_constraints(&spaced, &sitting(1, ExamSession::Evening), &[&morning]),
+            Some(Vec::new())
+        );
+
+        let report = staffed(
+            one_day(),
+            &[constrained("Ama", spaced.clone()), teacher("Kofi", &[])],
+        );
+        assert_eq!(report.duties_for(0).count(), 1);
+        assert!(report.relaxations().is_empty());
+
+        let report = staffed(one_day(), &[constrained("Ama", spaced)]);
+        assert_eq!(report.duties_for(0).count(), 2);
+        assert_eq!(report.relaxations().len(), 1);
+    }
+
+    #[test]
+    fn hard_limits_are_never_relaxed() {
+        for constraints in [
+            StaffConstraints::new(Vec::new(), Some((1, Strength::Hard)), None, Vec::new()),
+            StaffConstraints::new(Vec::new(), None, Some(Strength::Hard), Vec::new()),
+        ] {
+            let morning = sitting(1, ExamSession::Morning);
+            assert_eq!(
+                broken_constraints(
+                    &constraints,
+                    &sitting(1, ExamSession::Afternoon),
+                    &[&morning]
+                ),
+                None
+            );
+
+            let report = staffed(one_day(), &[constrained("Ama", constraints)]);
+            assert_eq!(report.duties().len(), 1);
+            assert_eq!(report.shortfalls().len(), 1);
+            assert!(report.relaxations().is_empty());
+        }
+    }
+
+    #[test]
+    fn each_relaxation_is_reported_against_its_duty() {
+        let constraints = StaffConstraints::new(
+            Vec::new(),
+            Some((1, Strength::Soft)),
+            Some(Strength::Soft),
+            vec![ExamSession::Morning],
+        );
+        let report = staffed(one_day(), &[constrained("Ama", constraints)]);
+
+        let afternoon = sitting(1, ExamSession::Afternoon);
+        let relaxed: Vec<(&ExamDate, &str, usize, Constraint)> = report
+            .relaxations()
+            .iter()
+            .map(|relaxation| {
+                (
+                    relaxation.exam_date(),
+                    relaxation.room_code(),
+                    relaxation.staff(),
+                    relaxation.constraint(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            relaxed,
+            [
+                (&afternoon, "HALL", 0, Constraint::MaxDutiesPerDay(1)),
+                (&afternoon, "HALL", 0, Constraint::NoBackToBack),
+                (&afternoon, "HALL", 0, Constraint::PreferredSessions),
+            ]
+        );
+        assert_eq!(
+            report.relaxations()[0].to_string(),
+            format!(
+                "Ama Test invigilates HALL on {} despite max duties per day (1)",
+                afternoon
+            )
+        );
+    }
+
+    #[test]
+    fn swap_requests_move_only_through_the_workflow() {
+        use SwapStatus::*;
+
+        let allowed = [
+            (Pending, Accepted),
+            (Pending, Declined),
+            (Pending, Cancelled),
+            (Accepted, Approved),
+            (Accepted, Rejected),
+            (Accepted, Cancelled),
+        ];
+        for from in SwapStatus::ALL {
+            for to in SwapStatus::ALL {
+                assert_eq!(
+                    from.can_become(*to),
+                    allowed.contains(&(*from, *to)),
+                    "{} to {}",
+                    from,
+                    to
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn only_pending_and_accepted_requests_are_open() {
+        let open: Vec<SwapStatus> = SwapStatus::ALL
+            .iter()
+            .copied()
+            .filter(SwapStatus::is_open)
+            .collect();
+        assert_eq!(open, [SwapStatus::Pending, SwapStatus::Accepted]);
+
+        for status in SwapStatus::ALL {
+            assert_eq!(
+                status.is_open(),
+                SwapStatus::ALL.iter().any(|next| status.can_become(*next))
+            );
+        }
+    }
+
+    #[test]
+    fn swap_statuses_round_trip_through_their_codes() {
+        for status in SwapStatus::ALL {
+            assert_eq!(SwapStatus::from_code(status.code()), Some(*status));
+        }
+        assert_eq!(SwapStatus::from_code("Pending"), None);
+    }
+}
@@ -1,55 +1,586 @@
-mod domain {
+pub mod analysis;
+pub mod catalogue;
+pub mod early_warning;
+pub mod effectiveness;
+pub mod export;
+#[cfg(any(test, feature = "fixtures"))]
+pub mod fixtures;
+pub mod grading;
+pub mod import;
+pub mod invigilation;
+pub mod marks;
+pub mod registration;
+pub mod report_card;
+pub mod rooms;
+pub mod roster;
+pub mod statistics;
+pub mod timetable;
+pub mod trends;
+pub mod validation;
+
+pub mod domain {
+    use chrono::{Datelike, NaiveDate, NaiveTime, Weekday};
+    use serde::{Deserialize, Serialize};
+
+    use crate::analysis::Position;
+    use crate::statistics::{Histogram, Statistics};
+
     // DOMAIN MODELS
     // Exam and invigilation timetables
-    struct PersonalName {
+    #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    pub struct PersonalName {
         first_name: String,
         other_names: String,
         last_name: String,
     }
 
-    struct Teacher {
-        teacher: TeacherPersonalInfo,
+    impl PersonalName {
+        pub fn new(first_name: String, other_names: String, last_name: String) -> Self {
+            Self {
+                first_name,
+                other_names,
+                last_name,
+            }
+        }
+
+        pub fn first_name(&self) -> &str {
+            &self.first_name
+        }
+
+        pub fn other_names(&self) -> &str {
+            &self.other_names
+        }
+
+        pub fn last_name(&self) -> &str {
+            &self.last_name
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    pub struct Teacher {
+        personal_info: TeacherPersonalInfo,
         subjects_taught: Vec<Subject>,
     }
 
-    struct TeacherPersonalInfo {
+    impl Teacher {
+        pub fn new(personal_info: TeacherPersonalInfo, subjects_taught: Vec<Subject>) -> Self {
+            Self {
+                personal_info,
+                subjects_taught,
+            }
+        }
+
+        pub fn personal_info(&self) -> &TeacherPersonalInfo {
+            &self.personal_info
+        }
+
+        pub fn subjects_taught(&self) -> &[Subject] {
+            &self.subjects_taught
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    pub struct TeacherPersonalInfo {
         name: PersonalName,
         gender: Gender,
     }
 
+    impl TeacherPersonalInfo {
+        pub fn new(name: PersonalName, gender: Gender) -> Self {
+            Self { name, gender }
+        }
+
+        pub fn name(&self) -> &PersonalName {
+            &self.name
+        }
+
+        pub fn gender(&self) -> Gender {
+            self.gender
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
     pub struct Invigilator {
         name: PersonalName,
         level: StudentClass,
     }
 
-    struct ExamDate {
-        day: ExamDay,
-        date: String,
+    impl Invigilator {
+        pub fn new(name: PersonalName, level: StudentClass) -> Self {
+            Self { name, level }
+        }
+
+        pub fn name(&self) -> &PersonalName {
+            &self.name
+        }
+
+        pub fn level(&self) -> StudentClass {
+            self.level
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+    pub struct ExamDate {
+        date: NaiveDate,
+        session: ExamSession,
+    }
+
+    impl ExamDate {
+        pub fn new(date: NaiveDate, session: ExamSession) -> Self {
+            Self { date, session }
+        }
+
+        pub fn date(&self) -> NaiveDate {
+            self.date
+        }
+
+        pub fn weekday(&self) -> Weekday {
+            self.date.weekday()
+        }
+
+        pub fn session(&self) -> ExamSession {
+            self.session
+        }
+    }
+
+    impl std::fmt::Display for ExamDate {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "{} ({})", self.date.format("%A %-d %B %Y"), self.session)
+        }
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+    pub enum ExamSession {
+        Morning,
+        Afternoon,
+        Evening,
     }
 
-    struct Student {
-        student: StudentPersonalInfo,
+    impl ExamSession {
+        pub const ALL: &'static [Self] = &[Self::Morning, Self::Afternoon, Self::Evening];
+
+        pub fn as_str(&self) -> &'static str {
+            match self {
+                Self::Morning => "AM",
+                Self::Afternoon => "PM",
+                Self::Evening => "Evening",
+            }
+        }
+
+        pub fn from_code(code: &str) -> Option<Self> {
+            Self::ALL
+                .iter()
+                .copied()
+                .find(|session| session.as_str().eq_ignore_ascii_case(code.trim()))
+        }
+
+        /// When papers in this session start unless set otherwise.
+        pub fn default_start_time(&self) -> NaiveTime {
+            match self {
+                Self::Morning => NaiveTime::from_hms_opt(9, 0, 0),
+                Self::Afternoon => NaiveTime::from_hms_opt(13, 0, 0),
+                Self::Evening => NaiveTime::from_hms_opt(17, 0, 0),
+            }
+            .expect("valid session start time")
+        }
+    }
+
+    impl std::fmt::Display for ExamSession {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "{}", self.as_str())
+        }
+    }
+
+    /// A separately sat part of a subject, e.g. "Paper 2" or "Practical".
+    #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+    pub struct PaperComponent {
+        code: String,
+        name: String,
+        duration_minutes: u32,
+    }
+
+    impl PaperComponent {
+        pub fn new(code: String, name: String, duration_minutes: u32) -> Self {
+            Self {
+                code,
+                name,
+                duration_minutes,
+            }
+        }
+
+        pub fn code(&self) -> &str {
+            &self.code
+        }
+
+        pub fn name(&self) -> &str {
+            &self.name
+        }
+
+        pub fn duration_minutes(&self) -> u32 {
+            self.duration_minutes
+        }
+    }
+
+    /// One component of a subject scheduled to start at a given time.
+    #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+    pub struct ExamPaper {
+        subject: Subject,
+        component: PaperComponent,
+        start_time: NaiveTime,
+    }
+
+    impl ExamPaper {
+        pub fn new(subject: Subject, component: PaperComponent, start_time: NaiveTime) -> Self {
+            Self {
+                subject,
+                component,
+                start_time,
+            }
+        }
+
+        pub fn subject(&self) -> Subject {
+            self.subject
+        }
+
+        pub fn component(&self) -> &PaperComponent {
+            &self.component
+        }
+
+        pub fn start_time(&self) -> NaiveTime {
+            self.start_time
+        }
+
+        pub fn end_time(&self) -> NaiveTime {
+            self.start_time + chrono::Duration::minutes(i64::from(self.component.duration_minutes))
+        }
+    }
+
+    impl std::fmt::Display for ExamPaper {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(
+                f,
+                "{} {}, {}–{}",
+                self.subject,
+                self.component.name,
+                self.start_time.format("%-I:%M%P"),
+                self.end_time().format("%-I:%M%P")
+            )
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    pub struct Student {
+        candidate_number: String,
+        personal_info: StudentPersonalInfo,
         class: StudentClass,
         subjects_read: Vec<Subject>,
     }
 
-    struct StudentPersonalInfo {
+    impl Student {
+        pub fn new(
+            candidate_number: String,
+            personal_info: StudentPersonalInfo,
+            class: StudentClass,
+            subjects_read: Vec<Subject>,
+        ) -> Self {
+            Self {
+                candidate_number,
+                personal_info,
+                class,
+                subjects_read,
+            }
+        }
+
+        /// The school's index number for the student, also used as the exam
+        /// candidate number.
+        pub fn candidate_number(&self) -> &str {
+            &self.candidate_number
+        }
+
+        pub fn personal_info(&self) -> &StudentPersonalInfo {
+            &self.personal_info
+        }
+
+        pub fn class(&self) -> StudentClass {
+            self.class
+        }
+
+        pub fn subjects_read(&self) -> &[Subject] {
+            &self.subjects_read
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    pub struct StudentPersonalInfo {
         name: PersonalName,
-        age: i32,
+        date_of_birth: NaiveDate,
         gender: Gender,
     }
 
-    enum Gender {
+    impl StudentPersonalInfo {
+        pub fn new(name: PersonalName, date_of_birth: NaiveDate, gender: Gender) -> Self {
+            Self {
+                name,
+                date_of_birth,
+                gender,
+            }
+        }
+
+        pub fn name(&self) -> &PersonalName {
+            &self.name
+        }
+
+        pub fn date_of_birth(&self) -> NaiveDate {
+            self.date_of_birth
+        }
+
+        /// Whole years of age on `date`, such as an exam board's cut-off.
+        pub fn age_on(&self, date: NaiveDate) -> i32 {
+            let birthday_passed = (date.month(), date.day())
+                >= (self.date_of_birth.month(), self.date_of_birth.day());
+            date.year() - self.date_of_birth.year() - if birthday_passed { 0 } else { 1 }
+        }
+
+        pub fn gender(&self) -> Gender {
+            self.gender
+        }
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    pub enum Gender {
         Male,
         Female,
     }
 
-    enum StudentClass {
-        LowerSecondaryStudentClass,
-        UpperSecondaryStudentClass,
+    impl Gender {
+        pub const ALL: &'static [Self] = &[Self::Male, Self::Female];
+
+        pub fn as_str(&self) -> &'static str {
+            match self {
+                Self::Male => "Male",
+                Self::Female => "Female",
+            }
+        }
+
+        pub fn from_code(code: &str) -> Option<Self> {
+            Self::ALL
+                .iter()
+                .copied()
+                .find(|gender| gender.as_str().eq_ignore_ascii_case(code.trim()))
+        }
+    }
+
+    impl std::fmt::Display for Gender {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "{}", self.as_str())
+        }
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+    pub enum StudentClass {
+        LowerSecondary(LowerSecondaryStudentClass),
+        UpperSecondary(UpperSecondaryStudentClass),
+        /// A class added through the catalogue rather than built in.
+        Added(AddedClass),
+    }
+
+    impl StudentClass {
+        /// Every class in the school, lower secondary first, in year order.
+        pub fn all() -> impl Iterator<Item = Self> {
+            LowerSecondaryStudentClass::ALL
+                .iter()
+                .copied()
+                .map(Self::LowerSecondary)
+                .chain(
+                    UpperSecondaryStudentClass::ALL
+                        .iter()
+                        .copied()
+                        .map(Self::UpperSecondary),
+                )
+        }
+
+        /// Code of the catalogue programme this class follows.
+        pub fn programme_code(&self) -> &'static str {
+            match self {
+                Self::Added(class) => class.programme_code,
+                Self::LowerSecondary(_) => "LSEC",
+                Self::UpperSecondary(
+                    UpperSecondaryStudentClass::IG1 | UpperSecondaryStudentClass::IG2,
+                ) => "IGCSE",
+                Self::UpperSecondary(
+                    UpperSecondaryStudentClass::ASLevel | UpperSecondaryStudentClass::ALevel,
+                ) => "ALEVEL",
+                Self::UpperSecondary(_) => "WASSCE",
+            }
+        }
+
+        pub fn code(&self) -> &'static str {
+            match self {
+                Self::LowerSecondary(class) => class.code(),
+                Self::UpperSecondary(class) => class.code(),
+                Self::Added(class) => class.code,
+            }
+        }
+
+        /// The built-in class with `code`. Classes added through the
+        /// catalogue are resolved with `Catalogue::class_for`.
+        pub fn from_code(code: &str) -> Option<Self> {
+            Self::all().find(|class| class.code().eq_ignore_ascii_case(code.trim()))
+        }
+
+        /// Ages a student of the class can plausibly be on the age cut-off
+        /// of the academic year: from a year young for the class to three
+        /// years old for it, allowing for late starts and repeated years.
+        /// Classes added through the catalogue have no usual age, so any
+        /// secondary school age is accepted.
+        pub fn plausible_ages(&self) -> std::ops::RangeInclusive<i32> {
+            use LowerSecondaryStudentClass as Lower;
+            use UpperSecondaryStudentClass as Upper;
+
+            let usual = match self {
+                Self::Added(_) => return 10..=21,
+                Self::LowerSecondary(Lower::Year8) => 12,
+                Self::LowerSecondary(Lower::Year9) => 13,
+                Self::UpperSecondary(Upper::IG1) => 14,
+                Self::UpperSecondary(Upper::IG2 | Upper::WASSCE1) => 15,
+                Self::UpperSecondary(Upper::ASLevel | Upper::WASSCE2) => 16,
+                Self::UpperSecondary(Upper::ALevel | Upper::WASSCE3) => 17,
+            };
+            usual - 1..=usual + 3
+        }
+
+        /// The class students move up to at year end, or `None` after the
+        /// last year of a programme, when they leave or the school decides
+        /// which programme they join. Only covers the built-in classes; use
+        /// `Catalogue::next_year` to include added ones.
+        pub fn next_year(&self) -> Option<Self> {
+            use LowerSecondaryStudentClass as Lower;
+            use UpperSecondaryStudentClass as Upper;
+
+            match self {
+                Self::LowerSecondary(Lower::Year8) => Some(Self::LowerSecondary(Lower::Year9)),
+                Self::UpperSecondary(Upper::IG1) => Some(Self::UpperSecondary(Upper::IG2)),
+                Self::UpperSecondary(Upper::ASLevel) => Some(Self::UpperSecondary(Upper::ALevel)),
+                Self::UpperSecondary(Upper::WASSCE1) => Some(Self::UpperSecondary(Upper::WASSCE2)),
+                Self::UpperSecondary(Upper::WASSCE2) => Some(Self::UpperSecondary(Upper::WASSCE3)),
+                Self::LowerSecondary(Lower::Year9)
+                | Self::UpperSecondary(Upper::IG2 | Upper::ALevel | Upper::WASSCE3)
+                | Self::Added(_) => None,
+            }
+        }
+    }
+
+    impl std::fmt::Display for StudentClass {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            match self {
+                Self::LowerSecondary(class) => write!(f, "{}", class),
+                Self::UpperSecondary(class) => write!(f, "{}", class),
+                Self::Added(class) => write!(f, "{}", class.name),
+            }
+        }
+    }
+
+    impl std::str::FromStr for StudentClass {
+        type Err = ParseError;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let s = s.trim();
+            Self::all()
+                .find(|class| class.to_string().eq_ignore_ascii_case(s))
+                .ok_or_else(|| ParseError::UnknownStudentClass(s.to_string()))
+        }
+    }
+
+    /// A class from the catalogue with no built-in counterpart. Ordered by
+    /// year so added classes sort like the built-in ones.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct AddedClass {
+        year_order: i32,
+        code: &'static str,
+        name: &'static str,
+        programme_code: &'static str,
+    }
+
+    impl AddedClass {
+        pub fn new(code: &str, name: &str, programme_code: &str, year_order: i32) -> Self {
+            Self {
+                year_order,
+                code: intern(code),
+                name: intern(name),
+                programme_code: intern(programme_code),
+            }
+        }
+
+        pub fn year_order(&self) -> i32 {
+            self.year_order
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct AddedClassData {
+        code: String,
+        name: String,
+        programme_code: String,
+        year_order: i32,
+    }
+
+    impl Serialize for AddedClass {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            AddedClassData::from(*self).serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for AddedClass {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            AddedClassData::deserialize(deserializer).map(Self::from)
+        }
+    }
+
+    impl From<AddedClassData> for AddedClass {
+        fn from(data: AddedClassData) -> Self {
+            Self::new(
+                &data.code,
+                &data.name,
+                &data.programme_code,
+                data.year_order,
+            )
+        }
+    }
+
+    impl From<AddedClass> for AddedClassData {
+        fn from(class: AddedClass) -> Self {
+            Self {
+                code: class.code.to_string(),
+                name: class.name.to_string(),
+                programme_code: class.programme_code.to_string(),
+                year_order: class.year_order,
+            }
+        }
+    }
+
+    /// Keeps a string for the life of the process, so classes and subjects
+    /// added through the catalogue can be `Copy` like the built-in ones.
+    /// Each distinct string is kept once, and the catalogue is small.
+    fn intern(s: &str) -> &'static str {
+        use std::collections::BTreeSet;
+        use std::sync::{Mutex, OnceLock};
+
+        static INTERNED: OnceLock<Mutex<BTreeSet<&'static str>>> = OnceLock::new();
+
+        let mut interned = INTERNED
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(existing) = interned.get(s) {
+            return existing;
+        }
+        let leaked: &'static str = Box::leak(s.to_string().into_boxed_str());
+        interned.insert(leaked);
+        leaked
     }
 
-    enum ExamDay {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+    pub enum ExamDay {
         Monday,
         Tuesday,
         Wednesday,
@@ -57,7 +588,46 @@ mod domain {
         Friday,
     }
 
-    enum UpperSecondaryStudentClass {
+    impl ExamDay {
+        pub const ALL: &'static [Self] = &[
+            Self::Monday,
+            Self::Tuesday,
+            Self::Wednesday,
+            Self::Thursday,
+            Self::Friday,
+        ];
+
+        pub fn as_str(&self) -> &'static str {
+            match self {
+                Self::Monday => "Monday",
+                Self::Tuesday => "Tuesday",
+                Self::Wednesday => "Wednesday",
+                Self::Thursday => "Thursday",
+                Self::Friday => "Friday",
+            }
+        }
+
+        pub fn from_code(code: &str) -> Option<Self> {
+            Self::ALL
+                .iter()
+                .copied()
+                .find(|day| day.as_str().eq_ignore_ascii_case(code.trim()))
+        }
+
+        pub fn from_weekday(weekday: Weekday) -> Option<Self> {
+            match weekday {
+                Weekday::Mon => Some(Self::Monday),
+                Weekday::Tue => Some(Self::Tuesday),
+                Weekday::Wed => Some(Self::Wednesday),
+                Weekday::Thu => Some(Self::Thursday),
+                Weekday::Fri => Some(Self::Friday),
+                Weekday::Sat | Weekday::Sun => None,
+            }
+        }
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+    pub enum UpperSecondaryStudentClass {
         IG1,
         IG2,
         ASLevel,
@@ -67,54 +637,491 @@ mod domain {
         WASSCE3,
     }
 
-    enum LowerSecondaryStudentClass {
+    impl UpperSecondaryStudentClass {
+        pub const ALL: &'static [Self] = &[
+            Self::IG1,
+            Self::IG2,
+            Self::ASLevel,
+            Self::ALevel,
+            Self::WASSCE1,
+            Self::WASSCE2,
+            Self::WASSCE3,
+        ];
+
+        pub fn as_str(&self) -> &'static str {
+            match self {
+                Self::IG1 => "IG1",
+                Self::IG2 => "IG2",
+                Self::ASLevel => "AS Level",
+                Self::ALevel => "A Level",
+                Self::WASSCE1 => "WASSCE 1",
+                Self::WASSCE2 => "WASSCE 2",
+                Self::WASSCE3 => "WASSCE 3",
+            }
+        }
+
+        /// Short stable code used as the catalogue key.
+        pub fn code(&self) -> &'static str {
+            match self {
+                Self::IG1 => "IG1",
+                Self::IG2 => "IG2",
+                Self::ASLevel => "AS",
+                Self::ALevel => "AL",
+                Self::WASSCE1 => "W1",
+                Self::WASSCE2 => "W2",
+                Self::WASSCE3 => "W3",
+            }
+        }
+    }
+
+    impl std::fmt::Display for UpperSecondaryStudentClass {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "{}", self.as_str())
+        }
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+    pub enum LowerSecondaryStudentClass {
         Year8,
         Year9,
     }
 
+    impl LowerSecondaryStudentClass {
+        pub const ALL: &'static [Self] = &[Self::Year8, Self::Year9];
+
+        pub fn as_str(&self) -> &'static str {
+            match self {
+                Self::Year8 => "Year 8",
+                Self::Year9 => "Year 9",
+            }
+        }
+
+        /// Short stable code used as the catalogue key.
+        pub fn code(&self) -> &'static str {
+            match self {
+                Self::Year8 => "Y8",
+                Self::Year9 => "Y9",
+            }
+        }
+    }
+
+    impl std::fmt::Display for LowerSecondaryStudentClass {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "{}", self.as_str())
+        }
+    }
+
     // Analysis of results
 
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
     pub struct StudentPerformanceSummary {
         student: Student,
         overall_average: f32,
         best_subject: Subject,
         worst_subject: Subject,
         subject_specific_details: Vec<SubjectSpecificSummary>,
+        position: Option<Position>,
+    }
+
+    impl StudentPerformanceSummary {
+        /// The class position is left unset until the whole class has been
+        /// summarised.
+        pub fn new(
+            student: Student,
+            overall_average: f32,
+            best_subject: Subject,
+            worst_subject: Subject,
+            subject_specific_details: Vec<SubjectSpecificSummary>,
+        ) -> Self {
+            Self {
+                student,
+                overall_average,
+                best_subject,
+                worst_subject,
+                subject_specific_details,
+                position: None,
+            }
+        }
+
+        pub fn student(&self) -> &Student {
+            &self.student
+        }
+
+        pub fn overall_average(&self) -> f32 {
+            self.overall_average
+        }
+
+        pub fn best_subject(&self) -> Subject {
+            self.best_subject
+        }
+
+        pub fn worst_subject(&self) -> Subject {
+            self.worst_subject
+        }
+
+        pub fn subject_specific_details(&self) -> &[SubjectSpecificSummary] {
+            &self.subject_specific_details
+        }
+
+        pub fn subject_specific_detail_mut(
+            &mut self,
+            subject: Subject,
+        ) -> Option<&mut SubjectSpecificSummary> {
+            self.subject_specific_details
+                .iter_mut()
+                .find(|detail| detail.subject == subject)
+        }
+
+        /// The sum of the student's subject scores.
+        pub fn total_score(&self) -> f32 {
+            self.subject_specific_details
+                .iter()
+                .map(|detail| detail.overall_subject_score)
+                .sum()
+        }
+
+        /// Position in class, by average or total as the analysis chose.
+        pub fn position(&self) -> Option<Position> {
+            self.position
+        }
+
+        pub fn set_position(&mut self, position: Position) {
+            self.position = Some(position);
+        }
     }
 
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
     pub struct ClassPerformanceSummary {
         class: StudentClass,
-        class_overall_average: f32,
+        overall_statistics: Statistics,
+        overall_histogram: Histogram,
         class_subject_performance_summary: Vec<ClassSubjectSummary>,
     }
 
-    struct SubjectSpecificSummary {
+    impl ClassPerformanceSummary {
+        /// `overall_statistics` and `overall_histogram` describe the
+        /// students' overall averages.
+        pub fn new(
+            class: StudentClass,
+            overall_statistics: Statistics,
+            overall_histogram: Histogram,
+            class_subject_performance_summary: Vec<ClassSubjectSummary>,
+        ) -> Self {
+            Self {
+                class,
+                overall_statistics,
+                overall_histogram,
+                class_subject_performance_summary,
+            }
+        }
+
+        pub fn class(&self) -> StudentClass {
+            self.class
+        }
+
+        pub fn class_overall_average(&self) -> f32 {
+            self.overall_statistics.mean()
+        }
+
+        pub fn overall_statistics(&self) -> &Statistics {
+            &self.overall_statistics
+        }
+
+        pub fn overall_histogram(&self) -> &Histogram {
+            &self.overall_histogram
+        }
+
+        pub fn class_subject_performance_summary(&self) -> &[ClassSubjectSummary] {
+            &self.class_subject_performance_summary
+        }
+
+        pub fn subject_summary(&self, subject: Subject) -> Option<&ClassSubjectSummary> {
+            self.class_subject_performance_summary
+                .iter()
+                .find(|summary| summary.subject == subject)
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    pub struct SubjectSpecificSummary {
         subject: Subject,
         overall_subject_score: f32,
         component_specific_details: ComponentSpecificSummary,
+        position: Option<Position>,
     }
 
-    struct ComponentSpecificSummary {
+    impl SubjectSpecificSummary {
+        pub fn new(
+            subject: Subject,
+            overall_subject_score: f32,
+            component_specific_details: ComponentSpecificSummary,
+        ) -> Self {
+            Self {
+                subject,
+                overall_subject_score,
+                component_specific_details,
+                position: None,
+            }
+        }
+
+        pub fn subject(&self) -> Subject {
+            self.subject
+        }
+
+        pub fn overall_subject_score(&self) -> f32 {
+            self.overall_subject_score
+        }
+
+        pub fn component_specific_details(&self) -> &ComponentSpecificSummary {
+            &self.component_specific_details
+        }
+
+        /// Position in the subject among classmates taking it.
+        pub fn position(&self) -> Option<Position> {
+            self.position
+        }
+
+        pub fn set_position(&mut self, position: Position) {
+            self.position = Some(position);
+        }
+    }
+
+    #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+    pub struct ComponentSpecificSummary {
         component_list: Vec<SubjectComponentSummary>,
     }
 
-    struct SubjectComponentSummary {
+    impl ComponentSpecificSummary {
+        pub fn new(component_list: Vec<SubjectComponentSummary>) -> Self {
+            Self { component_list }
+        }
+
+        pub fn component_list(&self) -> &[SubjectComponentSummary] {
+            &self.component_list
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    pub struct SubjectComponentSummary {
         component_identifier: String,
         specific_score: f32,
         class_average: f32,
     }
-    struct ClassSubjectSummary {
+
+    impl SubjectComponentSummary {
+        pub fn new(component_identifier: String, specific_score: f32, class_average: f32) -> Self {
+            Self {
+                component_identifier,
+                specific_score,
+                class_average,
+            }
+        }
+
+        pub fn component_identifier(&self) -> &str {
+            &self.component_identifier
+        }
+
+        pub fn specific_score(&self) -> f32 {
+            self.specific_score
+        }
+
+        pub fn class_average(&self) -> f32 {
+            self.class_average
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    pub struct ClassSubjectSummary {
         subject: Subject,
-        subject_average: f32,
+        statistics: Statistics,
+        pass_rate: Option<f32>,
+        histogram: Histogram,
     }
 
-    enum Subject {
-        WassceSubject,
-        ALevelSubject,
-        IGCSESubject,
+    impl ClassSubjectSummary {
+        pub fn new(
+            subject: Subject,
+            statistics: Statistics,
+            pass_rate: Option<f32>,
+            histogram: Histogram,
+        ) -> Self {
+            Self {
+                subject,
+                statistics,
+                pass_rate,
+                histogram,
+            }
+        }
+
+        pub fn subject(&self) -> Subject {
+            self.subject
+        }
+
+        pub fn subject_average(&self) -> f32 {
+            self.statistics.mean()
+        }
+
+        pub fn statistics(&self) -> &Statistics {
+            &self.statistics
+        }
+
+        /// Percentage of students at or above the pass mark, or `None` when
+        /// the subject has no grading scheme with a pass grade.
+        pub fn pass_rate(&self) -> Option<f32> {
+            self.pass_rate
+        }
+
+        pub fn histogram(&self) -> &Histogram {
+            &self.histogram
+        }
+
+        /// How `score` compares with the class, in standard deviations, so
+        /// scores in subjects marked differently can be compared.
+        pub fn z_score(&self, score: f32) -> Option<f32> {
+            self.statistics.z_score(score)
+        }
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+    pub enum Subject {
+        Wassce(WassceSubject),
+        ALevel(ALevelSubject),
+        IGCSE(IGCSESubject),
+        /// A subject added through the catalogue rather than built in.
+        Added(AddedSubject),
     }
 
-    enum WassceSubject {
+    impl Subject {
+        /// Every subject offered, grouped by exam board.
+        pub fn all() -> impl Iterator<Item = Self> {
+            WassceSubject::ALL
+                .iter()
+                .copied()
+                .map(Self::Wassce)
+                .chain(ALevelSubject::ALL.iter().copied().map(Self::ALevel))
+                .chain(IGCSESubject::ALL.iter().copied().map(Self::IGCSE))
+        }
+
+        /// The exam board prefix used in the canonical name, e.g. "WASSCE".
+        pub fn board(&self) -> &'static str {
+            match self {
+                Self::Wassce(_) => "WASSCE",
+                Self::ALevel(_) => "A-Level",
+                Self::IGCSE(_) => "IGCSE",
+                Self::Added(subject) => subject.programme_code,
+            }
+        }
+
+        /// The subject name without the exam board prefix, e.g. "Physics".
+        pub fn name(&self) -> &'static str {
+            match self {
+                Self::Wassce(subject) => subject.as_str(),
+                Self::ALevel(subject) => subject.as_str(),
+                Self::IGCSE(subject) => subject.as_str(),
+                Self::Added(subject) => subject.name,
+            }
+        }
+
+        /// Code of the catalogue programme this subject belongs to.
+        pub fn programme_code(&self) -> &'static str {
+            match self {
+                Self::Wassce(_) => "WASSCE",
+                Self::ALevel(_) => "ALEVEL",
+                Self::IGCSE(_) => "IGCSE",
+                Self::Added(subject) => subject.programme_code,
+            }
+        }
+
+        /// Catalogue code, e.g. "WASSCE-PHY".
+        pub fn code(&self) -> String {
+            let subject_code = match self {
+                Self::Wassce(subject) => subject.code(),
+                Self::ALevel(subject) => subject.code(),
+                Self::IGCSE(subject) => subject.code(),
+                Self::Added(subject) => return subject.code.to_string(),
+            };
+            format!("{}-{}", self.programme_code(), subject_code)
+        }
+
+        /// The built-in subject with `code`. Subjects added through the
+        /// catalogue are resolved with `Catalogue::subject_for`.
+        pub fn from_code(code: &str) -> Option<Self> {
+            Self::all().find(|subject| subject.code().eq_ignore_ascii_case(code.trim()))
+        }
+    }
+
+    impl std::fmt::Display for Subject {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "{} {}", self.board(), self.name())
+        }
+    }
+
+    impl std::str::FromStr for Subject {
+        type Err = ParseError;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let s = s.trim();
+            Self::all()
+                .find(|subject| subject.to_string().eq_ignore_ascii_case(s))
+                .ok_or_else(|| ParseError::UnknownSubject(s.to_string()))
+        }
+    }
+
+    /// A subject from the catalogue with no built-in counterpart.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct AddedSubject {
+        code: &'static str,
+        name: &'static str,
+        programme_code: &'static str,
+    }
+
+    impl AddedSubject {
+        pub fn new(code: &str, name: &str, programme_code: &str) -> Self {
+            Self {
+                code: intern(code),
+                name: intern(name),
+                programme_code: intern(programme_code),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct AddedSubjectData {
+        code: String,
+        name: String,
+        programme_code: String,
+    }
+
+    impl Serialize for AddedSubject {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            AddedSubjectData::from(*self).serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for AddedSubject {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            AddedSubjectData::deserialize(deserializer).map(Self::from)
+        }
+    }
+
+    impl From<AddedSubjectData> for AddedSubject {
+        fn from(data: AddedSubjectData) -> Self {
+            Self::new(&data.code, &data.name, &data.programme_code)
+        }
+    }
+
+    impl From<AddedSubject> for AddedSubjectData {
+        fn from(subject: AddedSubject) -> Self {
+            Self {
+                code: subject.code.to_string(),
+                name: subject.name.to_string(),
+                programme_code: subject.programme_code.to_string(),
+            }
+        }
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+    pub enum WassceSubject {
         English,
         CoreMathematics,
         IntegratedScience,
@@ -128,7 +1135,63 @@ mod domain {
         Literature,
     }
 
-    enum ALevelSubject {
+    impl WassceSubject {
+        pub const ALL: &'static [Self] = &[
+            Self::English,
+            Self::CoreMathematics,
+            Self::IntegratedScience,
+            Self::SocialStudies,
+            Self::ElectiveMathematics,
+            Self::Physics,
+            Self::Chemistry,
+            Self::Biology,
+            Self::Government,
+            Self::Economics,
+            Self::Literature,
+        ];
+
+        pub fn as_str(&self) -> &'static str {
+            match self {
+                Self::English => "English",
+                Self::CoreMathematics => "Core Mathematics",
+                Self::IntegratedScience => "Integrated Science",
+                Self::SocialStudies => "Social Studies",
+                Self::ElectiveMathematics => "Elective Mathematics",
+                Self::Physics => "Physics",
+                Self::Chemistry => "Chemistry",
+                Self::Biology => "Biology",
+                Self::Government => "Government",
+                Self::Economics => "Economics",
+                Self::Literature => "Literature",
+            }
+        }
+
+        /// Short stable code used as the catalogue key.
+        pub fn code(&self) -> &'static str {
+            match self {
+                Self::English => "ENG",
+                Self::CoreMathematics => "CMAT",
+                Self::IntegratedScience => "ISCI",
+                Self::SocialStudies => "SOC",
+                Self::ElectiveMathematics => "EMAT",
+                Self::Physics => "PHY",
+                Self::Chemistry => "CHEM",
+                Self::Biology => "BIO",
+                Self::Government => "GOV",
+                Self::Economics => "ECON",
+                Self::Literature => "LIT",
+            }
+        }
+    }
+
+    impl std::fmt::Display for WassceSubject {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "{}", self.as_str())
+        }
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+    pub enum ALevelSubject {
         Physics,
         ProbabilityStatistics,
         PureMathematics,
@@ -138,7 +1201,51 @@ mod domain {
         IT,
     }
 
-    enum IGCSESubject {
+    impl ALevelSubject {
+        pub const ALL: &'static [Self] = &[
+            Self::Physics,
+            Self::ProbabilityStatistics,
+            Self::PureMathematics,
+            Self::Mechanics,
+            Self::Economics,
+            Self::French,
+            Self::IT,
+        ];
+
+        pub fn as_str(&self) -> &'static str {
+            match self {
+                Self::Physics => "Physics",
+                Self::ProbabilityStatistics => "Probability & Statistics",
+                Self::PureMathematics => "Pure Mathematics",
+                Self::Mechanics => "Mechanics",
+                Self::Economics => "Economics",
+                Self::French => "French",
+                Self::IT => "Information Technology",
+            }
+        }
+
+        /// Short stable code used as the catalogue key.
+        pub fn code(&self) -> &'static str {
+            match self {
+                Self::Physics => "PHY",
+                Self::ProbabilityStatistics => "PSTAT",
+                Self::PureMathematics => "PMAT",
+                Self::Mechanics => "MECH",
+                Self::Economics => "ECON",
+                Self::French => "FRE",
+                Self::IT => "IT",
+            }
+        }
+    }
+
+    impl std::fmt::Display for ALevelSubject {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "{}", self.as_str())
+        }
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+    pub enum IGCSESubject {
         BusinessStudies,
         AdditionalMathematics,
         ExtendedMathematics,
@@ -148,13 +1255,298 @@ mod domain {
         Geography,
     }
 
-    struct ExamPair {
+    impl IGCSESubject {
+        pub const ALL: &'static [Self] = &[
+            Self::BusinessStudies,
+            Self::AdditionalMathematics,
+            Self::ExtendedMathematics,
+            Self::French,
+            Self::Chemistry,
+            Self::Biology,
+            Self::Geography,
+        ];
+
+        pub fn as_str(&self) -> &'static str {
+            match self {
+                Self::BusinessStudies => "Business Studies",
+                Self::AdditionalMathematics => "Additional Mathematics",
+                Self::ExtendedMathematics => "Extended Mathematics",
+                Self::French => "French",
+                Self::Chemistry => "Chemistry",
+                Self::Biology => "Biology",
+                Self::Geography => "Geography",
+            }
+        }
+
+        /// Short stable code used as the catalogue key.
+        pub fn code(&self) -> &'static str {
+            match self {
+                Self::BusinessStudies => "BUS",
+                Self::AdditionalMathematics => "AMAT",
+                Self::ExtendedMathematics => "XMAT",
+                Self::French => "FRE",
+                Self::Chemistry => "CHEM",
+                Self::Biology => "BIO",
+                Self::Geography => "GEO",
+            }
+        }
+    }
+
+    impl std::fmt::Display for IGCSESubject {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "{}", self.as_str())
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    pub struct ExamPair {
         exam_date: ExamDate,
-        subject_list: Vec<Subject>,
+        papers: Vec<ExamPaper>,
     }
 
+    impl ExamPair {
+        pub fn new(exam_date: ExamDate, papers: Vec<ExamPaper>) -> Self {
+            Self { exam_date, papers }
+        }
+
+        pub fn exam_date(&self) -> &ExamDate {
+            &self.exam_date
+        }
+
+        pub fn papers(&self) -> &[ExamPaper] {
+            &self.papers
+        }
+
+        /// The distinct subjects sat in this session.
+        pub fn subject_list(&self) -> Vec<Subject> {
+            let mut subjects: Vec<Subject> =
+                self.papers.iter().map(|paper| paper.subject).collect();
+            subjects.sort();
+            subjects.dedup();
+            subjects
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
     pub struct ExamTTObject {
         student_class: StudentClass,
-        exam_pair: ExamPair,
+        exam_pairs: Vec<ExamPair>,
+    }
+
+    impl ExamTTObject {
+        pub fn new(student_class: StudentClass, exam_pairs: Vec<ExamPair>) -> Self {
+            Self {
+                student_class,
+                exam_pairs,
+            }
+        }
+
+        pub fn student_class(&self) -> StudentClass {
+            self.student_class
+        }
+
+        /// The class's sittings in chronological order.
+        pub fn exam_pairs(&self) -> &[ExamPair] {
+            &self.exam_pairs
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub enum ParseError {
+        UnknownStudentClass(String),
+        UnknownSubject(String),
+    }
+
+    impl std::fmt::Display for ParseError {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            match self {
+                Self::UnknownStudentClass(class) => write!(f, "Unknown student class: {}", class),
+                Self::UnknownSubject(subject) => write!(f, "Unknown subject: {}", subject),
+            }
+        }
+    }
+
+    impl std::error::Error for ParseError {}
+    #[cfg(test)]
+    mod tests {
+        use chrono::{NaiveDate, NaiveTime};
+        use serde::de::DeserializeOwned;
+
+        use super::*;
+        use crate::fixtures::{name, student};
+
+        fn round_trip<T: Serialize + DeserializeOwned>(value: &T) -> T {
+            let json = serde_json::to_string(value).expect("serializes");
+            serde_json::from_str(&json).expect("deserializes")
+        }
+
+        #[test]
+        fn students_and_teachers_survive_serialization() {
+            let class = StudentClass::all().next().expect("a built-in class");
+            let subjects: Vec<Subject> = Subject::all().take(2).collect();
+            let student = student("C001", class, &subjects);
+            let teacher = Teacher::new(
+                TeacherPersonalInfo::new(name("Kofi"), Gender::Male),
+                subjects,
+            );
+
+            assert_eq!(round_trip(&student), student);
+            assert_eq!(round_trip(&teacher), teacher);
+            assert_eq!(
+                round_trip(&Invigilator::new(name("Esi"), class)),
+                Invigilator::new(name("Esi"), class)
+            );
+        }
+
+        #[test]
+        fn a_timetable_survives_serialization() {
+            let class = StudentClass::all().next().expect("a built-in class");
+            let subject = Subject::all().next().expect("a built-in subject");
+            let timetable = ExamTTObject::new(
+                class,
+                vec![ExamPair::new(
+                    ExamDate::new(
+                        NaiveDate::from_ymd_opt(2026, 5, 4).expect("valid date"),
+                        ExamSession::Morning,
+                    ),
+                    vec![ExamPaper::new(
+                        subject,
+                        PaperComponent::new("P1".to_string(), "Paper 1".to_string(), 90),
+                        NaiveTime::from_hms_opt(9, 0, 0).expect("valid time"),
+                    )],
+                )],
+            );
+
+            assert_eq!(round_trip(&timetable), timetable);
+        }
+
+        #[test]
+        fn every_class_parses_back_from_its_name_and_code() {
+            let classes: Vec<StudentClass> = StudentClass::all().collect();
+            assert_eq!(
+                classes.len(),
+                LowerSecondaryStudentClass::ALL.len() + UpperSecondaryStudentClass::ALL.len()
+            );
+
+            for class in classes {
+                assert_eq!(class.to_string().parse(), Ok(class));
+                assert_eq!(StudentClass::from_code(class.code()), Some(class));
+                assert_eq!(round_trip(&class), class);
+            }
+        }
+
+        #[test]
+        fn every_subject_parses_back_from_its_name_and_code() {
+            let subjects: Vec<Subject> = Subject::all().collect();
+            assert_eq!(
+                subjects.len(),
+                WassceSubject::ALL.len() + ALevelSubject::ALL.len() + IGCSESubject::ALL.len()
+            );
+
+            for subject in subjects {
+                assert_eq!(subject.to_string().parse(), Ok(subject));
+                assert_eq!(Subject::from_code(&subject.code()), Some(subject));
+                assert_eq!(round_trip(&subject), subject);
+            }
+        }
+
+        #[test]
+        fn parsing_ignores_case_and_surrounding_spaces() {
+            let class = StudentClass::UpperSecondary(UpperSecondaryStudentClass::IG2);
+            let subject = Subject::Wassce(WassceSubject::English);
+
+            assert_eq!(
+                format!("  {}  ", class.to_string().to_lowercase()).parse(),
+                Ok(class)
+            );
+            assert_eq!(subject.to_string().to_uppercase().parse(), Ok(subject));
+            assert_eq!(
+                StudentClass::from_code(&format!(" {} ", class.code().to_lowercase())),
+                Some(class)
+            );
+        }
+
+        #[test]
+        fn unknown_names_are_parse_errors() {
+            assert_eq!(
+                " Year 13 ".parse::<StudentClass>(),
+                Err(ParseError::UnknownStudentClass("Year 13".to_string()))
+            );
+            assert_eq!(
+                "WASSCE Latin".parse::<Subject>(),
+                Err(ParseError::UnknownSubject("WASSCE Latin".to_string()))
+            );
+        }
+
+        #[test]
+        fn added_classes_and_subjects_survive_serialization() {
+            let class = StudentClass::Added(AddedClass::new("F1", "Form 1", "JHS", 7));
+            let subject = Subject::Added(AddedSubject::new("JHS-SCI", "Science", "JHS"));
+
+            assert_eq!(round_trip(&class), class);
+            assert_eq!(round_trip(&subject), subject);
+            assert_eq!(subject.code(), "JHS-SCI");
+            assert_eq!(subject.to_string(), "JHS Science");
+        }
+
+        fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+            NaiveDate::from_ymd_opt(year, month, day).expect("valid date")
+        }
+
+        fn born(year: i32, month: u32, day: u32) -> StudentPersonalInfo {
+            StudentPersonalInfo::new(name("Ama"), date(year, month, day), Gender::Female)
+        }
+
+        #[test]
+        fn a_birthday_on_the_cut_off_counts() {
+            let info = born(2010, 9, 1);
+
+            assert_eq!(info.age_on(date(2026, 8, 31)), 15);
+            assert_eq!(info.age_on(date(2026, 9, 1)), 16);
+            assert_eq!(info.age_on(date(2010, 9, 1)), 0);
+        }
+
+        #[test]
+        fn a_29_february_birthday_falls_on_1_march_outside_leap_years() {
+            let info = born(2008, 2, 29);
+
+            assert_eq!(info.age_on(date(2026, 2, 28)), 17);
+            assert_eq!(info.age_on(date(2026, 3, 1)), 18);
+            assert_eq!(info.age_on(date(2028, 2, 28)), 19);
+            assert_eq!(info.age_on(date(2028, 2, 29)), 20);
+        }
+
+        #[test]
+        fn an_estimated_date_of_birth_gives_back_its_age_all_year() {
+            // Students saved with only an age of 15 in 2026 were given
+            // 1 January 2011.
+            let info = born(2026 - 15, 1, 1);
+
+            assert_eq!(info.age_on(date(2026, 1, 1)), 15);
+            assert_eq!(info.age_on(date(2026, 12, 31)), 15);
+            assert_eq!(info.age_on(date(2025, 12, 31)), 14);
+        }
+
+        #[test]
+        fn plausible_ages_run_from_a_year_under_to_three_over_the_usual_age() {
+            let w3 = StudentClass::from_code("W3").expect("a built-in class");
+            assert_eq!(w3.plausible_ages(), 16..=20);
+
+            for class in StudentClass::all() {
+                let ages = class.plausible_ages();
+                assert_eq!(ages.end() - ages.start(), 4, "{}", class);
+                if let Some(next) = class.next_year() {
+                    assert_eq!(
+                        *next.plausible_ages().start(),
+                        ages.start() + 1,
+                        "{}",
+                        class
+                    );
+                }
+            }
+
+            let added = StudentClass::Added(AddedClass::new("F1", "Form 1", "JHS", 7));
+            assert_eq!(added.plausible_ages(), 10..=21);
+        }
     }
 }
@@ -0,0 +1,693 @@
+// Marks entry
+//
+// An assessment is one class sitting one subject (an end-of-term exam, a
+// mock) in a term of the academic year, made up of weighted components each
+// marked out of its own maximum.
+// Marks are entered as text from a grid and checked here before they are
+// stored, so the webapp and any importer agree on what a valid score is.
+use std::collections::BTreeSet;
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{StudentClass, Subject};
+use crate::validation::Severity;
+
+/// What teachers type for a candidate who missed a paper.
+pub const ABSENT: &str = "ABS";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AssessmentComponent {
+    code: String,
+    name: String,
+    max_mark: u32,
+    weight: f32,
+}
+
+impl AssessmentComponent {
+    pub fn new(code: String, name: String, max_mark: u32, weight: f32) -> Self {
+        Self {
+            code,
+            name,
+            max_mark,
+            weight,
+        }
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn max_mark(&self) -> u32 {
+        self.max_mark
+    }
+
+    /// Share of the subject score, relative to the other components.
+    pub fn weight(&self) -> f32 {
+        self.weight
+    }
+}
+
+/// The three terms of the school year, which runs from September to July.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Term {
+    First,
+    Second,
+    Third,
+}
+
+impl Term {
+    pub const ALL: [Self; 3] = [Self::First, Self::Second, Self::Third];
+
+    /// 1, 2 or 3.
+    pub fn number(&self) -> u8 {
+        match self {
+            Self::First => 1,
+            Self::Second => 2,
+            Self::Third => 3,
+        }
+    }
+
+    pub fn from_number(number: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|term| term.number() == number)
+    }
+}
+
+impl std::fmt::Display for Term {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Term {}", self.number())
+    }
+}
+
+/// A term of an academic year. Periods order chronologically.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct AcademicPeriod {
+    year: i32,
+    term: Term,
+}
+
+impl AcademicPeriod {
+    /// `year` is the calendar year the academic year starts in, so 2025
+    /// for 2025/26.
+    pub fn new(year: i32, term: Term) -> Self {
+        Self { year, term }
+    }
+
+    /// The term `date` falls in: September to December is the first term,
+    /// January to April the second and May to August the third.
+    pub fn containing(date: NaiveDate) -> Self {
+        match date.month() {
+            9..=12 => Self::new(date.year(), Term::First),
+            1..=4 => Self::new(date.year() - 1, Term::Second),
+            _ => Self::new(date.year() - 1, Term::Third),
+        }
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn term(&self) -> Term {
+        self.term
+    }
+
+    /// The date ages are worked out on for the academic year, unless an
+    /// exam board sets its own: 1 September, when the year starts.
+    pub fn age_cut_off(&self) -> NaiveDate {
+        NaiveDate::from_ymd_opt(self.year, 9, 1).expect("1 September is a valid date")
+    }
+
+    /// e.g. "2025/26".
+    pub fn year_label(&self) -> String {
+        format!("{}/{:02}", self.year, (self.year + 1) % 100)
+    }
+}
+
+impl std::fmt::Display for AcademicPeriod {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} {}", self.year_label(), self.term)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Assessment {
+    name: String,
+    class: StudentClass,
+    subject: Subject,
+    period: AcademicPeriod,
+    components: Vec<AssessmentComponent>,
+}
+
+impl Assessment {
+    pub fn new(
+        name: String,
+        class: StudentClass,
+        subject: Subject,
+        period: AcademicPeriod,
+        components: Vec<AssessmentComponent>,
+    ) -> Self {
+        Self {
+            name,
+            class,
+            subject,
+            period,
+            components,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn class(&self) -> StudentClass {
+        self.class
+    }
+
+    /// The term the assessment was sat in.
+    pub fn period(&self) -> AcademicPeriod {
+        self.period
+    }
+
+    pub fn subject(&self) -> Subject {
+        self.subject
+    }
+
+    pub fn components(&self) -> &[AssessmentComponent] {
+        &self.components
+    }
+
+    pub fn component(&self, code: &str) -> Option<&AssessmentComponent> {
+        self.components
+            .iter()
+            .find(|component| component.code.eq_ignore_ascii_case(code))
+    }
+}
+
+/// A validated score for one candidate on one component. `None` means the
+/// candidate was absent.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Mark {
+    candidate_number: String,
+    component_code: String,
+    raw_score: Option<f32>,
+}
+
+impl Mark {
+    pub fn new(candidate_number: String, component_code: String, raw_score: Option<f32>) -> Self {
+        Self {
+            candidate_number,
+            component_code,
+            raw_score,
+        }
+    }
+
+    pub fn candidate_number(&self) -> &str {
+        &self.candidate_number
+    }
+
+    pub fn component_code(&self) -> &str {
+        &self.component_code
+    }
+
+    pub fn raw_score(&self) -> Option<f32> {
+        self.raw_score
+    }
+
+    pub fn is_absent(&self) -> bool {
+        self.raw_score.is_none()
+    }
+}
+
+/// A grid cell as typed.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MarkEntry {
+    candidate_number: String,
+    component_code: String,
+    entry: String,
+}
+
+impl MarkEntry {
+    pub fn new(candidate_number: String, component_code: String, entry: String) -> Self {
+        Self {
+            candidate_number,
+            component_code,
+            entry,
+        }
+    }
+
+    pub fn candidate_number(&self) -> &str {
+        &self.candidate_number
+    }
+
+    pub fn component_code(&self) -> &str {
+        &self.component_code
+    }
+
+    pub fn entry(&self) -> &str {
+        &self.entry
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum MarkIssue {
+    /// No score has been entered yet.
+    Missing {
+        candidate_number: String,
+        component: String,
+    },
+    OutOfRange {
+        candidate_number: String,
+        component: String,
+        score: f32,
+        max_mark: u32,
+    },
+    NotAScore {
+        candidate_number: String,
+        component: String,
+        entry: String,
+    },
+    /// More than one entry for the same candidate and component.
+    Duplicate {
+        candidate_number: String,
+        component: String,
+    },
+    /// The candidate is not on the class list for the assessment.
+    UnknownCandidate {
+        candidate_number: String,
+    },
+    UnknownComponent {
+        component: String,
+    },
+}
+
+impl MarkIssue {
+    /// Missing scores are expected while entry is under way; anything else
+    /// stops the marks from being saved.
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::Missing { .. } => Severity::Warning,
+            Self::OutOfRange { .. }
+            | Self::NotAScore { .. }
+            | Self::Duplicate { .. }
+            | Self::UnknownCandidate { .. }
+            | Self::UnknownComponent { .. } => Severity::Error,
+        }
+    }
+
+    pub fn candidate_number(&self) -> Option<&str> {
+        match self {
+            Self::Missing {
+                candidate_number, ..
+            }
+            | Self::OutOfRange {
+                candidate_number, ..
+            }
+            | Self::NotAScore {
+                candidate_number, ..
+            }
+            | Self::Duplicate {
+                candidate_number, ..
+            }
+            | Self::UnknownCandidate { candidate_number } => Some(candidate_number),
+            Self::UnknownComponent { .. } => None,
+        }
+    }
+
+    pub fn component(&self) -> Option<&str> {
+        match self {
+            Self::Missing { component, .. }
+            | Self::OutOfRange { component, .. }
+            | Self::NotAScore { component, .. }
+            | Self::Duplicate { component, .. }
+            | Self::UnknownComponent { component } => Some(component),
+            Self::UnknownCandidate { .. } => None,
+        }
+    }
+}
+
+impl std::fmt::Display for MarkIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Missing {
+                candidate_number,
+                component,
+            } => write!(f, "{} has no mark for {}", candidate_number, component),
+            Self::OutOfRange {
+                candidate_number,
+                component,
+                score,
+                max_mark,
+            } => write!(
+                f,
+                "{} scored {} on {}, which is out of {}",
+                candidate_number, score, component, max_mark
+            ),
+            Self::NotAScore {
+                candidate_number,
+                component,
+                entry,
+            } => write!(
+                f,
+                "{} has \"{}\" for {}, which is not a mark or {}",
+                candidate_number, entry, component, ABSENT
+            ),
+            Self::Duplicate {
+                candidate_number,
+                component,
+            } => write!(
+                f,
+                "{} has more than one entry for {}",
+                candidate_number, component
+            ),
+            Self::UnknownCandidate { candidate_number } => {
+                write!(f, "{} is not on the class list", candidate_number)
+            }
+            Self::UnknownComponent { component } => {
+                write!(f, "{} is not a component of this assessment", component)
+            }
+        }
+    }
+}
+
+/// Checks grid entries against the assessment and its class list. Blank
+/// entries are left out of the marks; every (candidate, component) pair
+/// without a mark is reported as missing, and one entered more than once as
+/// a duplicate. Errors come first.
+pub fn validate_entries(
+    assessment: &Assessment,
+    candidate_numbers: &[String],
+    entries: &[MarkEntry],
+) -> (Vec<Mark>, Vec<MarkIssue>) {
+    let mut marks = Vec::new();
+    let mut issues = Vec::new();
+    let mut unknown_candidates = BTreeSet::new();
+    let mut unknown_components = BTreeSet::new();
+    let mut entered = BTreeSet::new();
+    let mut duplicates = BTreeSet::new();
+
+    for entry in entries {
+        let text = entry.entry.trim();
+        if text.is_empty() {
+            continue;
+        }
+        if !candidate_numbers.contains(&entry.candidate_number) {
+            unknown_candidates.insert(entry.candidate_number.clone());
+            continue;
+        }
+        let Some(component) = assessment.component(&entry.component_code) else {
+            unknown_components.insert(entry.component_code.clone());
+            continue;
+        };
+        let pair = (entry.candidate_number.clone(), component.code.clone());
+        if !entered.insert(pair.clone()) {
+            duplicates.insert(pair);
+            continue;
+        }
+
+        if text.eq_ignore_ascii_case(ABSENT) {
+            marks.push(Mark::new(
+                entry.candidate_number.clone(),
+                component.code.clone(),
+                None,
+            ));
+            continue;
+        }
+
+        match text.parse::<f32>() {
+            Ok(score)
+                if score.is_finite() && (0.0..=component.max_mark as f32).contains(&score) =>
+            {
+                marks.push(Mark::new(
+                    entry.candidate_number.clone(),
+                    component.code.clone(),
+                    Some(score),
+                ));
+            }
+            Ok(score) if score.is_finite() => issues.push(MarkIssue::OutOfRange {
+                candidate_number: entry.candidate_number.clone(),
+                component: component.code.clone(),
+                score,
+                max_mark: component.max_mark,
+            }),
+            _ => issues.push(MarkIssue::NotAScore {
+                candidate_number: entry.candidate_number.clone(),
+                component: component.code.clone(),
+                entry: text.to_string(),
+            }),
+        }
+    }
+
+    issues.extend(duplicates.into_iter().map(|(candidate_number, component)| {
+        MarkIssue::Duplicate {
+            candidate_number,
+            component,
+        }
+    }));
+    issues.extend(
+        unknown_candidates
+            .into_iter()
+            .map(|candidate_number| MarkIssue::UnknownCandidate { candidate_number }),
+    );
+    issues.extend(
+        unknown_components
+            .into_iter()
+            .map(|component| MarkIssue::UnknownComponent { component }),
+    );
+
+    for candidate_number in candidate_numbers {
+        for component in &assessment.components {
+            let is_marked = marks.iter().any(|mark| {
+                mark.candidate_number == *candidate_number && mark.component_code == component.code
+            });
+            let is_invalid = issues.iter().any(|issue| {
+                issue.candidate_number() == Some(candidate_number.as_str())
+                    && issue.component() == Some(component.code.as_str())
+            });
+            if !is_marked && !is_invalid {
+                issues.push(MarkIssue::Missing {
+                    candidate_number: candidate_number.clone(),
+                    component: component.code.clone(),
+                });
+            }
+        }
+    }
+
+    issues.sort_by_key(|issue| std::cmp::Reverse(issue.severity()));
+    (marks, issues)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Paper 1 out of 50 and Paper 2 out of 100.
+    fn assessment() -> Assessment {
+        Assessment::new(
+            "End of term".to_string(),
+            StudentClass::all().next().expect("a built-in class"),
+            Subject::all().next().expect("a built-in subject"),
+            AcademicPeriod::new(2026, Term::First),
+            vec![
+                AssessmentComponent::new("P1".to_string(), "Paper 1".to_string(), 50, 1.0),
+                AssessmentComponent::new("P2".to_string(), "Paper 2".to_string(), 100, 1.0),
+            ],
+        )
+    }
+
+    fn candidates() -> Vec<String> {
+        vec!["C01".to_string(), "C02".to_string()]
+    }
+
+    fn entry(candidate_number: &str, component_code: &str, text: &str) -> MarkEntry {
+        MarkEntry::new(
+            candidate_number.to_string(),
+            component_code.to_string(),
+            text.to_string(),
+        )
+    }
+
+    fn errors(issues: &[MarkIssue]) -> Vec<&MarkIssue> {
+        issues
+            .iter()
+            .filter(|issue| issue.severity() == Severity::Error)
+            .collect()
+    }
+
+    #[test]
+    fn valid_scores_become_marks() {
+        let (marks, issues) = validate_entries(
+            &assessment(),
+            &candidates(),
+            &[
+                entry("C01", "P1", "0"),
+                entry("C01", "P2", "100"),
+                entry("C02", "P1", " 49.5 "),
+                entry("C02", "P2", "72"),
+            ],
+        );
+
+        let scores: Vec<(&str, &str, Option<f32>)> = marks
+            .iter()
+            .map(|mark| {
+                (
+                    mark.candidate_number(),
+                    mark.component_code(),
+                    mark.raw_score(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            scores,
+            [
+                ("C01", "P1", Some(0.0)),
+                ("C01", "P2", Some(100.0)),
+                ("C02", "P1", Some(49.5)),
+                ("C02", "P2", Some(72.0)),
+            ]
+        );
+        assert!(issues.is_empty());
+    }
+
+    #[test]
+    fn scores_outside_the_component_maximum_are_rejected() {
+        let (marks, issues) = validate_entries(
+            &assessment(),
+            &candidates(),
+            &[
+                entry("C01", "P1", "51"),
+                entry("C01", "P2", "-1"),
+                entry("C02", "P1", "NaN"),
+                entry("C02", "P2", "seventy"),
+            ],
+        );
+
+        assert!(marks.is_empty());
+        assert_eq!(
+            errors(&issues),
+            [
+                &MarkIssue::OutOfRange {
+                    candidate_number: "C01".to_string(),
+                    component: "P1".to_string(),
+                    score: 51.0,
+                    max_mark: 50,
+                },
+                &MarkIssue::OutOfRange {
+                    candidate_number: "C01".to_string(),
+                    component: "P2".to_string(),
+                    score: -1.0,
+                    max_mark: 100,
+                },
+                &MarkIssue::NotAScore {
+                    candidate_number: "C02".to_string(),
+                    component: "P1".to_string(),
+                    entry: "NaN".to_string(),
+                },
+                &MarkIssue::NotAScore {
+                    candidate_number: "C02".to_string(),
+                    component: "P2".to_string(),
+                    entry: "seventy".to_string(),
+                },
+            ]
+        );
+        assert_eq!(issues.len(), 4, "invalid entries are not also missing");
+    }
+
+    #[test]
+    fn absent_candidates_are_marked_without_a_score() {
+        let (marks, issues) = validate_entries(
+            &assessment(),
+            &candidates(),
+            &[entry("C01", "P1", "ABS"), entry("C01", "P2", "abs")],
+        );
+
+        assert_eq!(marks.len(), 2);
+        assert!(marks.iter().all(Mark::is_absent));
+        assert!(errors(&issues).is_empty());
+        assert_eq!(
+            issues,
+            [
+                MarkIssue::Missing {
+                    candidate_number: "C02".to_string(),
+                    component: "P1".to_string(),
+                },
+                MarkIssue::Missing {
+                    candidate_number: "C02".to_string(),
+                    component: "P2".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn blank_entries_are_left_out_and_reported_missing() {
+        let (marks, issues) = validate_entries(
+            &assessment(),
+            &candidates(),
+            &[
+                entry("C01", "P1", "40"),
+                entry("C01", "P2", "  "),
+                entry("C09", "P1", ""),
+            ],
+        );
+
+        assert_eq!(marks.len(), 1);
+        assert!(errors(&issues).is_empty());
+        assert_eq!(issues.len(), 3);
+    }
+
+    #[test]
+    fn a_candidate_and_component_entered_twice_are_rejected() {
+        let (_, issues) = validate_entries(
+            &assessment(),
+            &candidates(),
+            &[
+                entry("C01", "P1", "40"),
+                entry("C01", "P1", "45"),
+                entry("C01", "P1", "ABS"),
+                entry("C02", "P1", "40"),
+            ],
+        );
+
+        assert_eq!(
+            errors(&issues),
+            [&MarkIssue::Duplicate {
+                candidate_number: "C01".to_string(),
+                component: "P1".to_string(),
+            }]
+        );
+        assert_eq!(
+            errors(&issues)[0].to_string(),
+            "C01 has more than one entry for P1"
+        );
+    }
+
+    #[test]
+    fn unknown_candidates_and_components_are_rejected_once_each() {
+        let (marks, issues) = validate_entries(
+            &assessment(),
+            &candidates(),
+            &[
+                entry("C09", "P1", "40"),
+                entry("C09", "P2", "40"),
+                entry("C01", "P3", "40"),
+            ],
+        );
+
+        assert!(marks.is_empty());
+        assert_eq!(
+            errors(&issues),
+            [
+                &MarkIssue::UnknownCandidate {
+                    candidate_number: "C09".to_string(),
+                },
+                &MarkIssue::UnknownComponent {
+                    component: "P3".to_string(),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,333 @@
+// Exam board registrations
+//
+// Before each exam series the school registers its candidates with the
+// board. This module lists the candidates of a class with their dates of
+// birth and the syllabus codes of the subjects they read, and writes the
+// list as CSV for the board's registration portal. Ages are worked out on
+// the board's cut-off date rather than stored, so they are right whichever
+// year the list is made.
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::catalogue::Catalogue;
+use crate::domain::{Gender, Student, StudentClass};
+use crate::export::csv_line;
+
+pub const REGISTRATION_HEADERS: &[&str] = &[
+    "Candidate Number",
+    "Surname",
+    "First Name",
+    "Other Names",
+    "Gender",
+    "Date of Birth",
+    "Age at Cut-off",
+    "Subjects",
+];
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegistrationEntry {
+    candidate_number: String,
+    last_name: String,
+    first_name: String,
+    other_names: String,
+    gender: Gender,
+    date_of_birth: NaiveDate,
+    age: i32,
+    syllabus_codes: Vec<String>,
+}
+
+impl RegistrationEntry {
+    pub fn candidate_number(&self) -> &str {
+        &self.candidate_number
+    }
+
+    pub fn last_name(&self) -> &str {
+        &self.last_name
+    }
+
+    pub fn first_name(&self) -> &str {
+        &self.first_name
+    }
+
+    pub fn other_names(&self) -> &str {
+        &self.other_names
+    }
+
+    pub fn gender(&self) -> Gender {
+        self.gender
+    }
+
+    pub fn date_of_birth(&self) -> NaiveDate {
+        self.date_of_birth
+    }
+
+    /// Whole years of age on the registration's cut-off.
+    pub fn age(&self) -> i32 {
+        self.age
+    }
+
+    /// The board's syllabus code for each subject, or the school's own
+    /// code where the catalogue has none.
+    pub fn syllabus_codes(&self) -> &[String] {
+        &self.syllabus_codes
+    }
+
+    /// The entry as a row under `REGISTRATION_HEADERS`. Dates are written
+    /// day first, as the boards' portals expect.
+    pub fn cells(&self) -> Vec<String> {
+        vec![
+            self.candidate_number.clone(),
+            self.last_name.clone(),
+            self.first_name.clone(),
+            self.other_names.clone(),
+            self.gender.as_str().to_string(),
+            self.date_of_birth.format("%d/%m/%Y").to_string(),
+            self.age.to_string(),
+            self.syllabus_codes.join(" "),
+        ]
+    }
+}
+
+/// The candidates of one class to register with their exam board.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Registration {
+    class: StudentClass,
+    cut_off: NaiveDate,
+    entries: Vec<RegistrationEntry>,
+}
+
+impl Registration {
+    /// The students of `class` among `students`, by candidate number, with
+    /// their ages on `cut_off`.
+    pub fn new(
+        class: StudentClass,
+        cut_off: NaiveDate,
+        students: &[Student],
+        catalogue: &Catalogue,
+    ) -> Self {
+        let mut entries: Vec<RegistrationEntry> = students
+            .iter()
+            .filter(|student| student.class() == class)
+            .map(|student| {
+                let info = student.personal_info();
+                RegistrationEntry {
+                    candidate_number: student.candidate_number().to_string(),
+                    last_name: info.name().last_name().to_string(),
+                    first_name: info.name().first_name().to_string(),
+                    other_names: info.name().other_names().to_string(),
+                    gender: info.gender(),
+                    date_of_birth: info.date_of_birth(),
+                    age: info.age_on(cut_off),
+                    syllabus_codes: student
+                        .subjects_read()
+                        .iter()
+                        .map(|subject| {
+                            catalogue
+                                .resolve(*subject)
+                                .and_then(|entry| entry.syllabus_code())
+                                .map(str::to_string)
+                                .unwrap_or_else(|| subject.code())
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+        entries.sort_by(|a, b| a.candidate_number.cmp(&b.candidate_number));
+        Self {
+            class,
+            cut_off,
+            entries,
+        }
+    }
+
+    pub fn class(&self) -> StudentClass {
+        self.class
+    }
+
+    pub fn cut_off(&self) -> NaiveDate {
+        self.cut_off
+    }
+
+    pub fn entries(&self) -> &[RegistrationEntry] {
+        &self.entries
+    }
+
+    /// Entries whose age on the cut-off is not plausible for the class.
+    pub fn implausible_ages(&self) -> impl Iterator<Item = &RegistrationEntry> {
+        let ages = self.class.plausible_ages();
+        self.entries
+            .iter()
+            .filter(move |entry| !ages.contains(&entry.age))
+    }
+
+    /// e.g. "Registration_W3_2026-09-01.csv".
+    pub fn file_name(&self) -> String {
+        format!(
+            "Registration_{}_{}.csv",
+            self.class.code(),
+            self.cut_off.format("%Y-%m-%d")
+        )
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = csv_line(REGISTRATION_HEADERS.iter().map(|header| header.to_string()));
+        for entry in &self.entries {
+            csv.push_str(&csv_line(entry.cells()));
+        }
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalogue::CatalogueSubject;
+    use crate::domain::{StudentPersonalInfo, Subject, WassceSubject};
+    use crate::fixtures;
+
+    const ENGLISH: Subject = Subject::Wassce(WassceSubject::English);
+    const PHYSICS: Subject = Subject::Wassce(WassceSubject::Physics);
+
+    fn w3() -> StudentClass {
+        StudentClass::from_code("W3").expect("a built-in class")
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).expect("valid date")
+    }
+
+    fn cut_off() -> NaiveDate {
+        date(2026, 9, 1)
+    }
+
+    fn student(candidate_number: &str, class: StudentClass, date_of_birth: NaiveDate) -> Student {
+        Student::new(
+            candidate_number.to_string(),
+            StudentPersonalInfo::new(
+                fixtures::name(candidate_number),
+                date_of_birth,
+                Gender::Male,
+            ),
+            class,
+            vec![ENGLISH, PHYSICS],
+        )
+    }
+
+    /// The seed catalogue, with WAEC's syllabus code for English only.
+    fn catalogue() -> Catalogue {
+        let seed = Catalogue::seed();
+        let subjects = seed
+            .subjects()
+            .iter()
+            .map(|entry| {
+                if entry.subject() != ENGLISH {
+                    return entry.clone();
+                }
+                CatalogueSubject::new(
+                    entry.code().to_string(),
+                    entry.name().to_string(),
+                    entry.programme_code().to_string(),
+                    Some("301".to_string()),
+                    entry.is_active(),
+                    entry.is_core(),
+                    entry.components().to_vec(),
+                )
+            })
+            .collect();
+        Catalogue::new(
+            seed.programmes().to_vec(),
+            seed.class_groups().to_vec(),
+            subjects,
+        )
+    }
+
+    fn registration(students: &[Student]) -> Registration {
+        Registration::new(w3(), cut_off(), students, &catalogue())
+    }
+
+    fn ages(registration: &Registration) -> Vec<(&str, i32)> {
+        registration
+            .entries()
+            .iter()
+            .map(|entry| (entry.candidate_number(), entry.age()))
+            .collect()
+    }
+
+    #[test]
+    fn the_class_is_listed_by_candidate_number_with_ages_on_the_cut_off() {
+        let other = StudentClass::from_code("W2").expect("a built-in class");
+        let registration = registration(&[
+            student("C03", w3(), date(2008, 9, 2)),
+            student("C01", w3(), date(2008, 9, 1)),
+            student("C02", other, date(2009, 9, 1)),
+        ]);
+
+        assert_eq!(ages(&registration), [("C01", 18), ("C03", 17)]);
+    }
+
+    #[test]
+    fn a_29_february_birthday_is_counted_from_1_march() {
+        let registration = Registration::new(
+            w3(),
+            date(2026, 2, 28),
+            &[student("C01", w3(), date(2008, 2, 29))],
+            &catalogue(),
+        );
+        assert_eq!(ages(&registration), [("C01", 17)]);
+
+        let registration = Registration::new(
+            w3(),
+            date(2026, 3, 1),
+            &[student("C01", w3(), date(2008, 2, 29))],
+            &catalogue(),
+        );
+        assert_eq!(ages(&registration), [("C01", 18)]);
+    }
+
+    #[test]
+    fn ages_outside_the_usual_range_for_the_class_are_picked_out() {
+        // An age of 17 entered in 2026 was stored as 1 January 2009.
+        let estimated = date(2026 - 17, 1, 1);
+        let registration = registration(&[
+            student("C01", w3(), date(2011, 5, 1)),
+            student("C02", w3(), estimated),
+            student("C03", w3(), date(2005, 9, 2)),
+            student("C04", w3(), date(2005, 9, 1)),
+        ]);
+
+        assert_eq!(
+            ages(&registration),
+            [("C01", 15), ("C02", 17), ("C03", 20), ("C04", 21)]
+        );
+        let implausible: Vec<&str> = registration
+            .implausible_ages()
+            .map(RegistrationEntry::candidate_number)
+            .collect();
+        assert_eq!(implausible, ["C01", "C04"]);
+    }
+
+    #[test]
+    fn subjects_use_the_board_syllabus_code_where_there_is_one() {
+        let registration = registration(&[student("C01", w3(), date(2008, 9, 1))]);
+
+        assert_eq!(
+            registration.entries()[0].syllabus_codes(),
+            ["301".to_string(), PHYSICS.code()]
+        );
+    }
+
+    #[test]
+    fn the_csv_has_a_header_and_a_day_first_row_per_candidate() {
+        let registration = registration(&[student("C01", w3(), date(2008, 2, 29))]);
+
+        assert_eq!(registration.file_name(), "Registration_W3_2026-09-01.csv");
+        assert_eq!(
+            registration.to_csv(),
+            format!(
+                "Candidate Number,Surname,First Name,Other Names,Gender,Date of Birth,Age at Cut-off,Subjects\r\n\
+                 C01,Test,C01,,Male,29/02/2008,18,301 {}\r\n",
+                PHYSICS.code()
+            )
+        );
+    }
+}
@@ -0,0 +1,176 @@
+// Report cards
+//
+// Everything printed on a student's report card for one assessment, taken
+// from the class analysis so the card always agrees with the analysis
+// page. Rendering to PDF is left to the webapp.
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::{ClassAnalysis, Position, RankBy};
+use crate::domain::{Student, StudentPerformanceSummary, Subject};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReportCardLine {
+    subject: Subject,
+    score: f32,
+    grade: Option<String>,
+    position: Option<Position>,
+    class_average: Option<f32>,
+}
+
+impl ReportCardLine {
+    pub fn subject(&self) -> Subject {
+        self.subject
+    }
+
+    pub fn score(&self) -> f32 {
+        self.score
+    }
+
+    /// `None` when the subject has no grading scheme.
+    pub fn grade(&self) -> Option<&str> {
+        self.grade.as_deref()
+    }
+
+    /// Position in the subject.
+    pub fn position(&self) -> Option<Position> {
+        self.position
+    }
+
+    pub fn class_average(&self) -> Option<f32> {
+        self.class_average
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReportCard {
+    assessment_name: String,
+    student: Student,
+    lines: Vec<ReportCardLine>,
+    overall_average: f32,
+    total_score: f32,
+    class_average: f32,
+    position: Option<Position>,
+    rank_by: RankBy,
+    remarks: String,
+}
+
+impl ReportCard {
+    /// The card for one summarised student of `analysis`.
+    pub fn new(
+        analysis: &ClassAnalysis,
+        assessment_name: &str,
+        summary: &StudentPerformanceSummary,
+        remarks: String,
+    ) -> Self {
+        let candidate_number = summary.student().candidate_number();
+        let lines = summary
+            .subject_specific_details()
+            .iter()
+            .map(|detail| ReportCardLine {
+                subject: detail.subject(),
+                score: detail.overall_subject_score(),
+                grade: analysis
+                    .grade(candidate_number, detail.subject())
+                    .map(str::to_string),
+                position: detail.position(),
+                class_average: analysis
+                    .class_summary()
+                    .subject_summary(detail.subject())
+                    .map(|subject| subject.subject_average()),
+            })
+            .collect();
+
+        Self {
+            assessment_name: assessment_name.to_string(),
+            student: summary.student().clone(),
+            lines,
+            overall_average: summary.overall_average(),
+            total_score: summary.total_score(),
+            class_average: analysis.class_summary().class_overall_average(),
+            position: summary.position(),
+            rank_by: analysis.rank_by(),
+            remarks,
+        }
+    }
+
+    /// A card for every summarised student in the class, in class-list
+    /// order. `remarks` pairs candidate numbers with their teacher's
+    /// remarks.
+    pub fn for_class(
+        analysis: &ClassAnalysis,
+        assessment_name: &str,
+        remarks: &[(String, String)],
+    ) -> Vec<Self> {
+        analysis
+            .student_summaries()
+            .iter()
+            .map(|summary| {
+                let remark = remarks
+                    .iter()
+                    .find(|(candidate_number, _)| {
+                        candidate_number == summary.student().candidate_number()
+                    })
+                    .map(|(_, remark)| remark.clone())
+                    .unwrap_or_default();
+                Self::new(analysis, assessment_name, summary, remark)
+            })
+            .collect()
+    }
+
+    pub fn assessment_name(&self) -> &str {
+        &self.assessment_name
+    }
+
+    pub fn student(&self) -> &Student {
+        &self.student
+    }
+
+    pub fn lines(&self) -> &[ReportCardLine] {
+        &self.lines
+    }
+
+    pub fn overall_average(&self) -> f32 {
+        self.overall_average
+    }
+
+    pub fn total_score(&self) -> f32 {
+        self.total_score
+    }
+
+    pub fn class_average(&self) -> f32 {
+        self.class_average
+    }
+
+    pub fn position(&self) -> Option<Position> {
+        self.position
+    }
+
+    /// Whether `position` is by average or by total.
+    pub fn rank_by(&self) -> RankBy {
+        self.rank_by
+    }
+
+    pub fn remarks(&self) -> &str {
+        &self.remarks
+    }
+
+    /// A file name for the card, e.g. "W3-Mock-0012.pdf".
+    pub fn file_name(&self) -> String {
+        let stem: String = format!(
+            "{}-{}-{}",
+            self.student.class().code(),
+            self.assessment_name,
+            self.student.candidate_number()
+        )
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+        format!("{}.pdf", stem)
+    }
+}
@@ -0,0 +1,610 @@
+// Exam room and seating allocation
+//
+// Each sitting in the timetable is split across the exam-suitable rooms.
+// Candidates for the same paper are kept together: a paper goes into the
+// tightest room that can take all of its candidates, and is only split
+// across rooms when no single room is big enough. A paper with candidates
+// who need an accessible room is placed first, in an accessible room; when
+// it has to be split, those candidates keep the accessible desks and the
+// rest fill the same rooms before any others.
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{ExamDate, ExamPaper, ExamTTObject, PersonalName, Student};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Room {
+    code: String,
+    name: String,
+    capacity: u32,
+    is_accessible: bool,
+    is_exam_suitable: bool,
+}
+
+impl Room {
+    pub fn new(
+        code: String,
+        name: String,
+        capacity: u32,
+        is_accessible: bool,
+        is_exam_suitable: bool,
+    ) -> Self {
+        Self {
+            code,
+            name,
+            capacity,
+            is_accessible,
+            is_exam_suitable,
+        }
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Number of exam desks.
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /// Step-free access for candidates with mobility needs.
+    pub fn is_accessible(&self) -> bool {
+        self.is_accessible
+    }
+
+    pub fn is_exam_suitable(&self) -> bool {
+        self.is_exam_suitable
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Seat {
+    desk: u32,
+    candidate_number: String,
+    name: PersonalName,
+    paper: ExamPaper,
+}
+
+impl Seat {
+    pub fn new(desk: u32, candidate_number: String, name: PersonalName, paper: ExamPaper) -> Self {
+        Self {
+            desk,
+            candidate_number,
+            name,
+            paper,
+        }
+    }
+
+    /// 1-based desk number within the room.
+    pub fn desk(&self) -> u32 {
+        self.desk
+    }
+
+    pub fn candidate_number(&self) -> &str {
+        &self.candidate_number
+    }
+
+    pub fn name(&self) -> &PersonalName {
+        &self.name
+    }
+
+    pub fn paper(&self) -> &ExamPaper {
+        &self.paper
+    }
+}
+
+/// The seat plan for one room in one sitting.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RoomAllocation {
+    exam_date: ExamDate,
+    room: Room,
+    seats: Vec<Seat>,
+}
+
+impl RoomAllocation {
+    /// A stored seat plan. `seats` are put in desk order.
+    pub fn new(exam_date: ExamDate, room: Room, mut seats: Vec<Seat>) -> Self {
+        seats.sort_by_key(|seat| seat.desk);
+        Self {
+            exam_date,
+            room,
+            seats,
+        }
+    }
+
+    pub fn exam_date(&self) -> &ExamDate {
+        &self.exam_date
+    }
+
+    pub fn room(&self) -> &Room {
+        &self.room
+    }
+
+    /// Seats in desk order.
+    pub fn seats(&self) -> &[Seat] {
+        &self.seats
+    }
+
+    /// The distinct papers sat in the room.
+    pub fn papers(&self) -> Vec<ExamPaper> {
+        self.seats
+            .iter()
+            .map(|seat| &seat.paper)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+}
+
+/// A candidate who could not be given a desk.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Unseated {
+    exam_date: ExamDate,
+    paper: ExamPaper,
+    candidate_number: String,
+    name: PersonalName,
+    needs_accessible_room: bool,
+}
+
+impl Unseated {
+    pub fn new(
+        exam_date: ExamDate,
+        paper: ExamPaper,
+        candidate_number: String,
+        name: PersonalName,
+        needs_accessible_room: bool,
+    ) -> Self {
+        Self {
+            exam_date,
+            paper,
+            candidate_number,
+            name,
+            needs_accessible_room,
+        }
+    }
+
+    pub fn exam_date(&self) -> &ExamDate {
+        &self.exam_date
+    }
+
+    pub fn paper(&self) -> &ExamPaper {
+        &self.paper
+    }
+
+    pub fn candidate_number(&self) -> &str {
+        &self.candidate_number
+    }
+
+    pub fn name(&self) -> &PersonalName {
+        &self.name
+    }
+
+    pub fn needs_accessible_room(&self) -> bool {
+        self.needs_accessible_room
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AllocationReport {
+    allocations: Vec<RoomAllocation>,
+    unseated: Vec<Unseated>,
+}
+
+impl AllocationReport {
+    /// A stored report. Seat plans are put in sitting then room order.
+    pub fn new(mut allocations: Vec<RoomAllocation>, unseated: Vec<Unseated>) -> Self {
+        allocations
+            .sort_by(|a, b| (&a.exam_date, a.room.code()).cmp(&(&b.exam_date, b.room.code())));
+        Self {
+            allocations,
+            unseated,
+        }
+    }
+
+    /// Seat plans ordered by sitting, then room.
+    pub fn allocations(&self) -> &[RoomAllocation] {
+        &self.allocations
+    }
+
+    pub fn unseated(&self) -> &[Unseated] {
+        &self.unseated
+    }
+
+    pub fn allocations_for(&self, exam_date: &ExamDate) -> impl Iterator<Item = &RoomAllocation> {
+        self.allocations
+            .iter()
+            .filter(move |allocation| allocation.exam_date == *exam_date)
+    }
+}
+
+struct RoomState<'a> {
+    room: &'a Room,
+    seats: Vec<Seat>,
+}
+
+impl RoomState<'_> {
+    fn remaining(&self) -> usize {
+        (self.room.capacity as usize).saturating_sub(self.seats.len())
+    }
+
+    fn holds(&self, paper: &ExamPaper) -> bool {
+        self.seats.iter().any(|seat| seat.paper == *paper)
+    }
+
+    fn seat(&mut self, student: &Student, paper: &ExamPaper) {
+        self.seats.push(Seat {
+            desk: self.seats.len() as u32 + 1,
+            candidate_number: student.candidate_number().to_string(),
+            name: student.personal_info().name().clone(),
+            paper: paper.clone(),
+        });
+    }
+}
+
+/// Allocates rooms and desks for every sitting in `timetables`.
+/// `needs_accessible_room` holds candidate numbers.
+pub fn allocate(
+    timetables: &[ExamTTObject],
+    students: &[Student],
+    rooms: &[Room],
+    needs_accessible_room: &BTreeSet<String>,
+) -> AllocationReport {
+    let mut sittings: BTreeMap<&ExamDate, BTreeMap<&ExamPaper, Vec<&Student>>> = BTreeMap::new();
+    for timetable in timetables {
+        for exam_pair in timetable.exam_pairs() {
+            for paper in exam_pair.papers() {
+                let candidates = sittings
+                    .entry(exam_pair.exam_date())
+                    .or_default()
+                    .entry(paper)
+                    .or_default();
+                candidates.extend(students.iter().filter(|student| {
+                    student.class() == timetable.student_class()
+                        && student.subjects_read().contains(&paper.subject())
+                }));
+            }
+        }
+    }
+
+    let mut report = AllocationReport::default();
+
+    for (exam_date, papers) in sittings {
+        let mut room_states: Vec<RoomState> = rooms
+            .iter()
+            .filter(|room| room.is_exam_suitable && room.capacity > 0)
+            .map(|room| RoomState {
+                room,
+                seats: Vec::new(),
+            })
+            .collect();
+
+        let mut groups = Vec::new();
+        for (paper, mut candidates) in papers {
+            candidates.sort_by(|a, b| a.candidate_number().cmp(b.candidate_number()));
+            candidates.dedup_by(|a, b| a.candidate_number() == b.candidate_number());
+            let (access, regular): (Vec<&Student>, Vec<&Student>) = candidates
+                .into_iter()
+                .partition(|student| needs_accessible_room.contains(student.candidate_number()));
+            groups.push((paper, access, regular));
+        }
+        groups.sort_by_key(|(_, access, regular)| {
+            (
+                access.is_empty(),
+                std::cmp::Reverse(access.len() + regular.len()),
+            )
+        });
+
+        for (paper, access, regular) in groups {
+            place_group(
+                &mut room_states,
+                exam_date,
+                paper,
+                &access,
+                &regular,
+                &mut report.unseated,
+            );
+        }
+
+        report.allocations.extend(
+            room_states
+                .into_iter()
+                .filter(|state| !state.seats.is_empty())
+                .map(|state| RoomAllocation {
+                    exam_date: exam_date.clone(),
+                    room: state.room.clone(),
+                    seats: state.seats,
+                }),
+        );
+    }
+
+    report
+}
+
+/// Seats one paper's candidates, `access` being those who need an
+/// accessible room.
+fn place_group(
+    room_states: &mut [RoomState],
+    exam_date: &ExamDate,
+    paper: &ExamPaper,
+    access: &[&Student],
+    regular: &[&Student],
+    unseated: &mut Vec<Unseated>,
+) {
+    let size = access.len() + regular.len();
+    let best_fit = room_states
+        .iter_mut()
+        .filter(|state| {
+            (access.is_empty() || state.room.is_accessible) && state.remaining() >= size
+        })
+        .min_by_key(|state| state.remaining());
+
+    if let Some(state) = best_fit {
+        for student in access.iter().chain(regular) {
+            state.seat(student, paper);
+        }
+        return;
+    }
+
+    for (candidates, accessible_only) in [(access, true), (regular, false)] {
+        let left_over = spread(room_states, paper, candidates, accessible_only);
+        unseated.extend(left_over.iter().map(|student| Unseated {
+            exam_date: exam_date.clone(),
+            paper: paper.clone(),
+            candidate_number: student.candidate_number().to_string(),
+            name: student.personal_info().name().clone(),
+            needs_accessible_room: accessible_only,
+        }));
+    }
+}
+
+/// Seats `candidates` across the rooms, filling rooms that already hold
+/// `paper` first and then the emptiest. Returns those left without a desk.
+fn spread<'a>(
+    room_states: &mut [RoomState],
+    paper: &ExamPaper,
+    candidates: &[&'a Student],
+    accessible_only: bool,
+) -> Vec<&'a Student> {
+    let mut order: Vec<usize> = (0..room_states.len())
+        .filter(|index| !accessible_only || room_states[*index].room.is_accessible)
+        .collect();
+    order.sort_by_key(|index| {
+        let state = &room_states[*index];
+        (!state.holds(paper), std::cmp::Reverse(state.remaining()))
+    });
+
+    let mut remaining = candidates.iter();
+    for index in order {
+        let state = &mut room_states[index];
+        for student in remaining.by_ref().take(state.remaining()) {
+            state.seat(student, paper);
+        }
+    }
+    remaining.copied().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveTime};
+
+    use super::*;
+    use crate::domain::{ExamPair, ExamSession, PaperComponent, StudentClass, Subject};
+    use crate::fixtures;
+
+    fn morning() -> ExamDate {
+        ExamDate::new(
+            NaiveDate::from_ymd_opt(2026, 5, 4).expect("valid date"),
+            ExamSession::Morning,
+        )
+    }
+
+    fn paper(subject: Subject) -> ExamPaper {
+        ExamPaper::new(
+            subject,
+            PaperComponent::new("P1".to_string(), "Paper 1".to_string(), 90),
+            NaiveTime::from_hms_opt(9, 0, 0).expect("valid time"),
+        )
+    }
+
+    fn room(code: &str, capacity: u32, is_accessible: bool) -> Room {
+        Room::new(
+            code.to_string(),
+            code.to_string(),
+            capacity,
+            is_accessible,
+            true,
+        )
+    }
+
+    /// One class sitting one paper per subject in the same morning, with
+    /// `sizes[i]` candidates for the `i`th subject, numbered from 1 so
+    /// candidate numbers are unique across subjects.
+    fn sitting(sizes: &[usize]) -> (Vec<ExamTTObject>, Vec<Student>, Vec<ExamPaper>) {
+        let class = StudentClass::all().next().expect("a built-in class");
+        let papers: Vec<ExamPaper> = Subject::all().take(sizes.len()).map(paper).collect();
+        let mut students = Vec::new();
+        for (paper, size) in papers.iter().zip(sizes) {
+            for _ in 0..*size {
+                let candidate_number = format!("C{:02}", students.len() + 1);
+                students.push(fixtures::student(
+                    &candidate_number,
+                    class,
+                    &[paper.subject()],
+                ));
+            }
+        }
+        let timetables = vec![ExamTTObject::new(
+            class,
+            vec![ExamPair::new(morning(), papers.clone())],
+        )];
+        (timetables, students, papers)
+    }
+
+    fn seated_in<'a>(report: &'a AllocationReport, code: &str) -> Vec<&'a str> {
+        report
+            .allocations()
+            .iter()
+            .filter(|allocation| allocation.room().code() == code)
+            .flat_map(|allocation| allocation.seats())
+            .map(|seat| seat.candidate_number())
+            .collect()
+    }
+
+    fn needing_access(candidate_numbers: &[&str]) -> BTreeSet<String> {
+        candidate_numbers
+            .iter()
+            .map(|number| number.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn papers_are_listed_once_when_their_seats_are_not_adjacent() {
+        let papers: Vec<ExamPaper> = Subject::all()
+            .take(2)
+            .map(|subject| {
+                ExamPaper::new(
+                    subject,
+                    PaperComponent::new("P1".to_string(), "Paper 1".to_string(), 90),
+                    NaiveTime::from_hms_opt(9, 0, 0).expect("valid time"),
+                )
+            })
+            .collect();
+        let seats = [&papers[0], &papers[1], &papers[0]]
+            .into_iter()
+            .enumerate()
+            .map(|(index, paper)| {
+                Seat::new(
+                    index as u32 + 1,
+                    format!("C{}", index),
+                    fixtures::name(&format!("Student{}", index)),
+                    paper.clone(),
+                )
+            })
+            .collect();
+        let allocation = RoomAllocation::new(
+            ExamDate::new(
+                NaiveDate::from_ymd_opt(2026, 5, 4).expect("valid date"),
+                ExamSession::Morning,
+            ),
+            Room::new("H1".to_string(), "Hall".to_string(), 10, true, true),
+            seats,
+        );
+
+        let mut expected = papers.clone();
+        expected.sort();
+        assert_eq!(allocation.papers(), expected);
+    }
+    #[test]
+    fn a_paper_goes_into_the_tightest_room_that_holds_it() {
+        let (timetables, students, _) = sitting(&[8]);
+        let rooms = [
+            room("HALL", 30, false),
+            room("C1", 10, false),
+            room("C2", 5, false),
+        ];
+
+        let report = allocate(&timetables, &students, &rooms, &BTreeSet::new());
+
+        assert_eq!(seated_in(&report, "C1").len(), 8);
+        assert_eq!(report.allocations().len(), 1);
+        assert!(report.unseated().is_empty());
+    }
+
+    #[test]
+    fn no_room_is_given_more_candidates_than_desks() {
+        let (timetables, students, papers) = sitting(&[6, 4]);
+        let rooms = [room("C1", 7, false), room("C2", 5, false)];
+
+        let report = allocate(&timetables, &students, &rooms, &BTreeSet::new());
+
+        for allocation in report.allocations() {
+            assert!(allocation.seats().len() as u32 <= allocation.room().capacity());
+            let desks: Vec<u32> = allocation.seats().iter().map(|seat| seat.desk()).collect();
+            assert_eq!(desks, (1..=desks.len() as u32).collect::<Vec<_>>());
+        }
+        // Each paper fits a room of its own, so neither is split.
+        assert_eq!(report.allocations()[0].papers(), vec![papers[0].clone()]);
+        assert_eq!(report.allocations()[1].papers(), vec![papers[1].clone()]);
+    }
+
+    #[test]
+    fn a_paper_too_big_for_any_room_is_split_and_the_rest_left_unseated() {
+        let (timetables, students, _) = sitting(&[10]);
+        let rooms = [room("C1", 3, false), room("C2", 5, false)];
+
+        let report = allocate(&timetables, &students, &rooms, &BTreeSet::new());
+
+        assert_eq!(
+            seated_in(&report, "C2"),
+            vec!["C01", "C02", "C03", "C04", "C05"]
+        );
+        assert_eq!(seated_in(&report, "C1"), vec!["C06", "C07", "C08"]);
+        let unseated: Vec<&str> = report
+            .unseated()
+            .iter()
+            .map(|unseated| unseated.candidate_number())
+            .collect();
+        assert_eq!(unseated, vec!["C09", "C10"]);
+    }
+
+    #[test]
+    fn candidates_needing_access_sit_with_the_rest_of_their_paper() {
+        let (timetables, students, _) = sitting(&[6]);
+        // The classroom is the tightest fit for the paper, but the
+        // candidate who needs access can only sit in the lab.
+        let rooms = [room("LAB", 20, true), room("C1", 6, false)];
+
+        let report = allocate(&timetables, &students, &rooms, &needing_access(&["C03"]));
+
+        assert_eq!(seated_in(&report, "LAB").len(), 6);
+        assert!(seated_in(&report, "C1").is_empty());
+        assert_eq!(seated_in(&report, "LAB")[0], "C03");
+    }
+
+    #[test]
+    fn a_split_paper_fills_the_accessible_room_before_others() {
+        let (timetables, students, _) = sitting(&[8]);
+        let rooms = [room("LAB", 3, true), room("HALL", 10, false)];
+
+        let report = allocate(&timetables, &students, &rooms, &needing_access(&["C05"]));
+
+        assert_eq!(seated_in(&report, "LAB"), vec!["C05", "C01", "C02"]);
+        assert_eq!(seated_in(&report, "HALL").len(), 5);
+        assert!(report.unseated().is_empty());
+    }
+
+    #[test]
+    fn candidates_needing_access_are_never_seated_in_other_rooms() {
+        let (timetables, students, _) = sitting(&[4]);
+        let rooms = [room("HALL", 10, false)];
+
+        let report = allocate(&timetables, &students, &rooms, &needing_access(&["C02"]));
+
+        assert_eq!(seated_in(&report, "HALL"), vec!["C01", "C03", "C04"]);
+        let [unseated] = report.unseated() else {
+            panic!("only the candidate needing access is unseated");
+        };
+        assert_eq!(unseated.candidate_number(), "C02");
+        assert!(unseated.needs_accessible_room());
+    }
+
+    #[test]
+    fn rooms_not_suitable_for_exams_are_not_used() {
+        let (timetables, students, _) = sitting(&[2]);
+        let rooms = [
+            Room::new("GYM".to_string(), "Gym".to_string(), 50, true, false),
+            room("C1", 5, false),
+        ];
+
+        let report = allocate(&timetables, &students, &rooms, &BTreeSet::new());
+
+        assert!(seated_in(&report, "GYM").is_empty());
+        assert_eq!(seated_in(&report, "C1").len(), 2);
+    }
+}
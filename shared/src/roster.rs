@@ -0,0 +1,1195 @@
+// Roster import
+//
+// Class lists and staff lists arrive as spreadsheets at the start of each
+// year. The webapp reads the file into a `Sheet`; this module maps its
+// columns, parses each row into a student or teacher and looks for
+// duplicates, both within the sheet and against the people already
+// registered. The preview and the import itself run the same code.
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::catalogue::{Catalogue, CatalogueSubject};
+use crate::domain::{
+    Gender, PersonalName, Student, StudentClass, StudentPersonalInfo, Subject, Teacher,
+    TeacherPersonalInfo,
+};
+use crate::import::{CANDIDATE_HEADERS, Sheet, SheetRow};
+use crate::validation::Severity;
+
+/// Date layouts accepted for dates of birth typed as text.
+pub const DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%d/%m/%Y", "%d-%m-%Y", "%d.%m.%Y"];
+
+/// Characters that separate subjects within one cell.
+const SUBJECT_SEPARATORS: &[char] = &[',', ';', '/', '\n'];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RosterKind {
+    Students,
+    Teachers,
+}
+
+impl RosterKind {
+    pub const ALL: &'static [Self] = &[Self::Students, Self::Teachers];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Students => "Students",
+            Self::Teachers => "Teachers",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|kind| kind.as_str().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// The columns a roster of this kind is read from.
+    pub fn fields(&self) -> &'static [RosterField] {
+        match self {
+            Self::Students => &[
+                RosterField::CandidateNumber,
+                RosterField::FirstName,
+                RosterField::OtherNames,
+                RosterField::LastName,
+                RosterField::Gender,
+                RosterField::DateOfBirth,
+                RosterField::Class,
+                RosterField::Subjects,
+            ],
+            Self::Teachers => &[
+                RosterField::FirstName,
+                RosterField::OtherNames,
+                RosterField::LastName,
+                RosterField::Gender,
+                RosterField::Subjects,
+            ],
+        }
+    }
+}
+
+impl std::fmt::Display for RosterKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RosterField {
+    CandidateNumber,
+    FirstName,
+    OtherNames,
+    LastName,
+    Gender,
+    DateOfBirth,
+    Class,
+    Subjects,
+}
+
+impl RosterField {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::CandidateNumber => "Candidate number",
+            Self::FirstName => "First name",
+            Self::OtherNames => "Other names",
+            Self::LastName => "Last name",
+            Self::Gender => "Gender",
+            Self::DateOfBirth => "Date of birth",
+            Self::Class => "Class",
+            Self::Subjects => "Subjects",
+        }
+    }
+
+    /// Header names, in order of preference, that identify the column.
+    pub fn headers(&self) -> &'static [&'static str] {
+        match self {
+            Self::CandidateNumber => CANDIDATE_HEADERS,
+            Self::FirstName => &["first name", "firstname", "forename", "given name"],
+            Self::OtherNames => &["other names", "other name", "middle name", "middle names"],
+            Self::LastName => &["last name", "lastname", "surname", "family name"],
+            Self::Gender => &["gender", "sex"],
+            Self::DateOfBirth => &["date of birth", "dob", "birth date", "birthday"],
+            Self::Class => &["class", "form", "class code"],
+            Self::Subjects => &["subjects", "subjects taught", "subject"],
+        }
+    }
+
+    /// Other names and subjects may be left blank.
+    pub fn is_required(&self) -> bool {
+        !matches!(self, Self::OtherNames | Self::Subjects)
+    }
+}
+
+impl std::fmt::Display for RosterField {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Which sheet column each field of a roster is read from.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RosterColumns {
+    kind: RosterKind,
+    columns: Vec<(RosterField, Option<usize>)>,
+}
+
+impl RosterColumns {
+    /// Guesses the columns from the headers.
+    pub fn detect(sheet: &Sheet, kind: RosterKind) -> Self {
+        let columns = kind
+            .fields()
+            .iter()
+            .map(|field| {
+                let column = field
+                    .headers()
+                    .iter()
+                    .find_map(|header| sheet.column(header));
+                (*field, column)
+            })
+            .collect();
+
+        Self { kind, columns }
+    }
+
+    pub fn kind(&self) -> RosterKind {
+        self.kind
+    }
+
+    pub fn columns(&self) -> &[(RosterField, Option<usize>)] {
+        &self.columns
+    }
+
+    pub fn column(&self, field: RosterField) -> Option<usize> {
+        self.columns
+            .iter()
+            .find(|(mapped, _)| *mapped == field)
+            .and_then(|(_, column)| *column)
+    }
+
+    pub fn set_column(&mut self, field: RosterField, column: Option<usize>) {
+        if let Some((_, mapped)) = self.columns.iter_mut().find(|(mapped, _)| *mapped == field) {
+            *mapped = column;
+        }
+    }
+
+    fn cell<'a>(&self, row: &'a SheetRow, field: RosterField) -> &'a str {
+        self.column(field).map_or("", |column| row.cell(column))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum RosterIssue {
+    MissingColumn {
+        field: RosterField,
+    },
+    Blank {
+        line: usize,
+        field: RosterField,
+    },
+    UnknownGender {
+        line: usize,
+        value: String,
+    },
+    InvalidDate {
+        line: usize,
+        value: String,
+    },
+    UnknownClass {
+        line: usize,
+        value: String,
+    },
+    UnknownSubject {
+        line: usize,
+        value: String,
+    },
+    /// A subject from another programme than the student's class.
+    WrongProgramme {
+        line: usize,
+        subject: Subject,
+        class: StudentClass,
+    },
+    /// The same person appears twice in the sheet.
+    Duplicate {
+        line: usize,
+        first_line: usize,
+        key: String,
+    },
+    /// The person is registered already, so the row is skipped.
+    AlreadyRegistered {
+        line: usize,
+        key: String,
+    },
+    /// Someone with the same name is registered or listed, under another
+    /// candidate number. The row is still imported.
+    SameName {
+        line: usize,
+        name: String,
+        other: String,
+    },
+    /// The student's age on the cut-off is outside what is plausible for
+    /// their class, which usually means a mistyped date of birth.
+    ImplausibleAge {
+        line: usize,
+        age: i32,
+        class: StudentClass,
+        cut_off: NaiveDate,
+    },
+}
+
+impl RosterIssue {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::AlreadyRegistered { .. } | Self::SameName { .. } => Severity::Warning,
+            Self::MissingColumn { .. }
+            | Self::Blank { .. }
+            | Self::UnknownGender { .. }
+            | Self::InvalidDate { .. }
+            | Self::UnknownClass { .. }
+            | Self::UnknownSubject { .. }
+            | Self::WrongProgramme { .. }
+            | Self::Duplicate { .. }
+            | Self::ImplausibleAge { .. } => Severity::Error,
+        }
+    }
+
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Self::MissingColumn { .. } => None,
+            Self::Blank { line, .. }
+            | Self::UnknownGender { line, .. }
+            | Self::InvalidDate { line, .. }
+            | Self::UnknownClass { line, .. }
+            | Self::UnknownSubject { line, .. }
+            | Self::WrongProgramme { line, .. }
+            | Self::Duplicate { line, .. }
+            | Self::AlreadyRegistered { line, .. }
+            | Self::SameName { line, .. }
+            | Self::ImplausibleAge { line, .. } => Some(*line),
+        }
+    }
+}
+
+impl std::fmt::Display for RosterIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::MissingColumn { field } => write!(f, "no column is chosen for {}", field),
+            Self::Blank { line, field } => write!(f, "line {}: {} is blank", line, field),
+            Self::UnknownGender { line, value } => {
+                write!(f, "line {}: unknown gender \"{}\"", line, value)
+            }
+            Self::InvalidDate { line, value } => {
+                write!(f, "line {}: \"{}\" is not a date", line, value)
+            }
+            Self::UnknownClass { line, value } => {
+                write!(f, "line {}: unknown class \"{}\"", line, value)
+            }
+            Self::UnknownSubject { line, value } => {
+                write!(f, "line {}: unknown subject \"{}\"", line, value)
+            }
+            Self::WrongProgramme {
+                line,
+                subject,
+                class,
+            } => write!(f, "line {}: {} is not taught in {}", line, subject, class),
+            Self::Duplicate {
+                line,
+                first_line,
+                key,
+            } => write!(
+                f,
+                "line {}: {} already appears on line {}",
+                line, key, first_line
+            ),
+            Self::AlreadyRegistered { line, key } => {
+                write!(
+                    f,
+                    "line {}: {} is already registered and will be skipped",
+                    line, key
+                )
+            }
+            Self::SameName { line, name, other } => write!(
+                f,
+                "line {}: {} has the same name as {}; check they are not the same person",
+                line, name, other
+            ),
+            Self::ImplausibleAge {
+                line,
+                age,
+                class,
+                cut_off,
+            } => {
+                let ages = class.plausible_ages();
+                write!(
+                    f,
+                    "line {}: would be {} on {}, outside {} to {} for {}; check the date of birth",
+                    line,
+                    age,
+                    cut_off.format("%-d %b %Y"),
+                    ages.start(),
+                    ages.end(),
+                    class
+                )
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RosterStudent {
+    candidate_number: String,
+    personal_info: StudentPersonalInfo,
+    class: StudentClass,
+    subjects: Vec<Subject>,
+}
+
+impl RosterStudent {
+    pub fn candidate_number(&self) -> &str {
+        &self.candidate_number
+    }
+
+    pub fn personal_info(&self) -> &StudentPersonalInfo {
+        &self.personal_info
+    }
+
+    pub fn class(&self) -> StudentClass {
+        self.class
+    }
+
+    pub fn subjects(&self) -> &[Subject] {
+        &self.subjects
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum RosterEntry {
+    Student(RosterStudent),
+    Teacher(Teacher),
+}
+
+/// One sheet row as it will be imported.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RosterRow {
+    line: usize,
+    entry: RosterEntry,
+}
+
+impl RosterRow {
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn entry(&self) -> &RosterEntry {
+        &self.entry
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RosterImport {
+    kind: RosterKind,
+    rows: Vec<RosterRow>,
+    issues: Vec<RosterIssue>,
+}
+
+impl RosterImport {
+    pub fn kind(&self) -> RosterKind {
+        self.kind
+    }
+
+    /// The rows to import. Rows already registered, and rows missing
+    /// something needed to read them, are left out.
+    pub fn rows(&self) -> &[RosterRow] {
+        &self.rows
+    }
+
+    /// Errors first.
+    pub fn issues(&self) -> &[RosterIssue] {
+        &self.issues
+    }
+
+    pub fn is_importable(&self) -> bool {
+        !self.rows.is_empty()
+            && self
+                .issues
+                .iter()
+                .all(|issue| issue.severity() != Severity::Error)
+    }
+
+    pub fn students(&self) -> impl Iterator<Item = &RosterStudent> {
+        self.rows.iter().filter_map(|row| match &row.entry {
+            RosterEntry::Student(student) => Some(student),
+            RosterEntry::Teacher(_) => None,
+        })
+    }
+
+    pub fn teachers(&self) -> impl Iterator<Item = &Teacher> {
+        self.rows.iter().filter_map(|row| match &row.entry {
+            RosterEntry::Teacher(teacher) => Some(teacher),
+            RosterEntry::Student(_) => None,
+        })
+    }
+}
+
+/// Why an import batch cannot be rolled back.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RollbackRefusal {
+    /// This many of the batch's students have marks entered.
+    StudentsWithMarks(i64),
+    /// This many of the batch's teachers have a login or classes.
+    TeachersInUse(i64),
+}
+
+impl std::fmt::Display for RollbackRefusal {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::StudentsWithMarks(count) => write!(
+                f,
+                "{} students in this batch already have marks; archive them instead",
+                count
+            ),
+            Self::TeachersInUse(count) => write!(
+                f,
+                "{} teachers in this batch have a login or classes; archive them instead",
+                count
+            ),
+        }
+    }
+}
+
+/// A batch can be rolled back until its people are in use: once a student
+/// has marks or a teacher has a login or classes, they are archived instead.
+pub fn check_rollback(
+    students_with_marks: i64,
+    teachers_in_use: i64,
+) -> Result<(), RollbackRefusal> {
+    if students_with_marks > 0 {
+        return Err(RollbackRefusal::StudentsWithMarks(students_with_marks));
+    }
+    if teachers_in_use > 0 {
+        return Err(RollbackRefusal::TeachersInUse(teachers_in_use));
+    }
+
+    Ok(())
+}
+
+/// First and last name, lower case, for spotting the same person twice.
+fn name_key(name: &PersonalName) -> String {
+    format!("{} {}", name.first_name(), name.last_name())
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn display_name(name: &PersonalName) -> String {
+    format!("{} {}", name.first_name(), name.last_name())
+}
+
+pub fn parse_date(value: &str) -> Option<NaiveDate> {
+    DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value.trim(), format).ok())
+}
+
+/// Subjects in a cell, matched by catalogue code, full name ("WASSCE
+/// Physics") or plain name. A plain name matches the subject of that name
+/// in `programme_code`, or in every programme when there is none.
+fn parse_subjects(
+    cell: &str,
+    programme_code: Option<&str>,
+    catalogue: &Catalogue,
+) -> Result<Vec<Subject>, String> {
+    let mut subjects = Vec::new();
+    for value in cell.split(SUBJECT_SEPARATORS).map(str::trim) {
+        if value.is_empty() {
+            continue;
+        }
+        let matched: Vec<Subject> = match catalogue
+            .subject_for(value)
+            .ok()
+            .or_else(|| value.parse().ok())
+        {
+            Some(subject) => vec![subject],
+            None => catalogue
+                .subjects()
+                .iter()
+                .map(CatalogueSubject::subject)
+                .filter(|subject| subject.name().eq_ignore_ascii_case(value))
+                .filter(|subject| {
+                    programme_code.is_none_or(|code| subject.programme_code() == code)
+                })
+                .collect(),
+        };
+        if matched.is_empty() {
+            return Err(value.to_string());
+        }
+        for subject in matched {
+            if !subjects.contains(&subject) {
+                subjects.push(subject);
+            }
+        }
+    }
+
+    Ok(subjects)
+}
+
+fn missing_columns(columns: &RosterColumns) -> Vec<RosterIssue> {
+    columns
+        .kind
+        .fields()
+        .iter()
+        .filter(|field| field.is_required() && columns.column(**field).is_none())
+        .map(|field| RosterIssue::MissingColumn { field: *field })
+        .collect()
+}
+
+/// The name and gender on a row, or the issues that stop it being read.
+fn read_person(
+    row: &SheetRow,
+    columns: &RosterColumns,
+    issues: &mut Vec<RosterIssue>,
+) -> Option<(PersonalName, Gender)> {
+    let line = row.line();
+    let first_name = columns.cell(row, RosterField::FirstName);
+    let last_name = columns.cell(row, RosterField::LastName);
+    let gender = columns.cell(row, RosterField::Gender);
+    let mut is_valid = true;
+    for (field, value) in [
+        (RosterField::FirstName, first_name),
+        (RosterField::LastName, last_name),
+        (RosterField::Gender, gender),
+    ] {
+        if value.is_empty() {
+            issues.push(RosterIssue::Blank { line, field });
+            is_valid = false;
+        }
+    }
+    let parsed_gender =
+        Gender::from_code(gender).or_else(|| match gender.to_lowercase().as_str() {
+            "m" => Some(Gender::Male),
+            "f" => Some(Gender::Female),
+            _ => None,
+        });
+    if parsed_gender.is_none() && !gender.is_empty() {
+        issues.push(RosterIssue::UnknownGender {
+            line,
+            value: gender.to_string(),
+        });
+    }
+    if !is_valid {
+        return None;
+    }
+
+    Some((
+        PersonalName::new(
+            first_name.to_string(),
+            columns.cell(row, RosterField::OtherNames).to_string(),
+            last_name.to_string(),
+        ),
+        parsed_gender?,
+    ))
+}
+
+fn finish(kind: RosterKind, rows: Vec<RosterRow>, mut issues: Vec<RosterIssue>) -> RosterImport {
+    issues.sort_by_key(|issue| (std::cmp::Reverse(issue.severity()), issue.line()));
+    RosterImport { kind, rows, issues }
+}
+
+/// Reads a class list. Students are matched on candidate number: one
+/// already registered, archived or not, is skipped rather than changed.
+/// Ages are checked against each class on `cut_off`, and classes and
+/// subjects are looked up in `catalogue`.
+pub fn read_students(
+    sheet: &Sheet,
+    columns: &RosterColumns,
+    registered: &[Student],
+    cut_off: NaiveDate,
+    catalogue: &Catalogue,
+) -> RosterImport {
+    let mut issues = missing_columns(columns);
+    if !issues.is_empty() {
+        return finish(RosterKind::Students, Vec::new(), issues);
+    }
+
+    let mut rows = Vec::new();
+    let mut lines: BTreeMap<String, usize> = BTreeMap::new();
+    let mut names: BTreeMap<String, String> = BTreeMap::new();
+    for row in sheet.rows() {
+        let line = row.line();
+        let candidate_number = columns.cell(row, RosterField::CandidateNumber);
+        let person = read_person(row, columns, &mut issues);
+        if candidate_number.is_empty() {
+            issues.push(RosterIssue::Blank {
+                line,
+                field: RosterField::CandidateNumber,
+            });
+        }
+
+        let date_of_birth = columns.cell(row, RosterField::DateOfBirth);
+        let parsed_date = parse_date(date_of_birth);
+        if date_of_birth.is_empty() {
+            issues.push(RosterIssue::Blank {
+                line,
+                field: RosterField::DateOfBirth,
+            });
+        } else if parsed_date.is_none() {
+            issues.push(RosterIssue::InvalidDate {
+                line,
+                value: date_of_birth.to_string(),
+            });
+        }
+
+        let class_code = columns.cell(row, RosterField::Class);
+        let class = catalogue
+            .class_for(class_code)
+            .ok()
+            .or_else(|| class_code.parse().ok());
+        if class_code.is_empty() {
+            issues.push(RosterIssue::Blank {
+                line,
+                field: RosterField::Class,
+            });
+        } else if class.is_none() {
+            issues.push(RosterIssue::UnknownClass {
+                line,
+                value: class_code.to_string(),
+            });
+        }
+
+        let subjects = match parse_subjects(
+            columns.cell(row, RosterField::Subjects),
+            class.map(|class| class.programme_code()),
+            catalogue,
+        ) {
+            Ok(subjects) => subjects,
+            Err(value) => {
+                issues.push(RosterIssue::UnknownSubject { line, value });
+                Vec::new()
+            }
+        };
+        if let Some(class) = class {
+            issues.extend(
+                subjects
+                    .iter()
+                    .filter(|subject| subject.programme_code() != class.programme_code())
+                    .map(|subject| RosterIssue::WrongProgramme {
+                        line,
+                        subject: *subject,
+                        class,
+                    }),
+            );
+        }
+
+        if candidate_number.is_empty() {
+            continue;
+        }
+        if let Some(first_line) = lines.get(candidate_number) {
+            issues.push(RosterIssue::Duplicate {
+                line,
+                first_line: *first_line,
+                key: candidate_number.to_string(),
+            });
+            continue;
+        }
+        lines.insert(candidate_number.to_string(), line);
+        if registered.iter().any(|student| {
+            student
+                .candidate_number()
+                .eq_ignore_ascii_case(candidate_number)
+        }) {
+            issues.push(RosterIssue::AlreadyRegistered {
+                line,
+                key: candidate_number.to_string(),
+            });
+            continue;
+        }
+
+        let (Some((name, gender)), Some(date_of_birth), Some(class)) = (person, parsed_date, class)
+        else {
+            continue;
+        };
+        let key = name_key(&name);
+        let other = names.get(&key).cloned().or_else(|| {
+            registered
+                .iter()
+                .find(|student| name_key(student.personal_info().name()) == key)
+                .map(|student| student.candidate_number().to_string())
+        });
+        if let Some(other) = other {
+            issues.push(RosterIssue::SameName {
+                line,
+                name: display_name(&name),
+                other,
+            });
+        }
+        names.insert(key, candidate_number.to_string());
+
+        let personal_info = StudentPersonalInfo::new(name, date_of_birth, gender);
+        let age = personal_info.age_on(cut_off);
+        if !class.plausible_ages().contains(&age) {
+            issues.push(RosterIssue::ImplausibleAge {
+                line,
+                age,
+                class,
+                cut_off,
+            });
+        }
+
+        rows.push(RosterRow {
+            line,
+            entry: RosterEntry::Student(RosterStudent {
+                candidate_number: candidate_number.to_string(),
+                personal_info,
+                class,
+                subjects,
+            }),
+        });
+    }
+
+    finish(RosterKind::Students, rows, issues)
+}
+
+/// Reads a staff list. Teachers have no number of their own, so they are
+/// matched on first and last name; one already registered is skipped.
+pub fn read_teachers(
+    sheet: &Sheet,
+    columns: &RosterColumns,
+    registered: &[Teacher],
+    catalogue: &Catalogue,
+) -> RosterImport {
+    let mut issues = missing_columns(columns);
+    if !issues.is_empty() {
+        return finish(RosterKind::Teachers, Vec::new(), issues);
+    }
+
+    let mut rows = Vec::new();
+    let mut lines: BTreeMap<String, usize> = BTreeMap::new();
+    for row in sheet.rows() {
+        let line = row.line();
+        let person = read_person(row, columns, &mut issues);
+        let subjects =
+            match parse_subjects(columns.cell(row, RosterField::Subjects), None, catalogue) {
+                Ok(subjects) => subjects,
+                Err(value) => {
+                    issues.push(RosterIssue::UnknownSubject { line, value });
+                    Vec::new()
+                }
+            };
+
+        let Some((name, gender)) = person else {
+            continue;
+        };
+        let key = name_key(&name);
+        if let Some(first_line) = lines.get(&key) {
+            issues.push(RosterIssue::Duplicate {
+                line,
+                first_line: *first_line,
+                key: display_name(&name),
+            });
+            continue;
+        }
+        lines.insert(key.clone(), line);
+        if registered
+            .iter()
+            .any(|teacher| name_key(teacher.personal_info().name()) == key)
+        {
+            issues.push(RosterIssue::AlreadyRegistered {
+                line,
+                key: display_name(&name),
+            });
+            continue;
+        }
+
+        rows.push(RosterRow {
+            line,
+            entry: RosterEntry::Teacher(Teacher::new(
+                TeacherPersonalInfo::new(name, gender),
+                subjects,
+            )),
+        });
+    }
+
+    finish(RosterKind::Teachers, rows, issues)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{ALevelSubject, WassceSubject};
+    use crate::fixtures;
+
+    const ENGLISH: Subject = Subject::Wassce(WassceSubject::English);
+    const PHYSICS: Subject = Subject::Wassce(WassceSubject::Physics);
+
+    const STUDENT_HEADERS: &[&str] = &[
+        "Candidate No",
+        "First Name",
+        "Middle Name",
+        "Surname",
+        "Sex",
+        "DOB",
+        "Form",
+        "Subjects",
+    ];
+
+    fn sheet(records: &[&[&str]]) -> Sheet {
+        Sheet::from_records(
+            records
+                .iter()
+                .map(|record| record.iter().map(|cell| cell.to_string()).collect())
+                .collect(),
+        )
+        .expect("a header row")
+    }
+
+    fn w3() -> StudentClass {
+        StudentClass::from_code("W3").expect("a built-in class")
+    }
+
+    fn cut_off() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 9, 1).expect("valid date")
+    }
+
+    /// Reads students listed under the usual headers.
+    fn students(rows: &[&[&str]], registered: &[Student]) -> RosterImport {
+        let records: Vec<&[&str]> = std::iter::once(STUDENT_HEADERS)
+            .chain(rows.iter().copied())
+            .collect();
+        let sheet = sheet(&records);
+        let columns = RosterColumns::detect(&sheet, RosterKind::Students);
+        read_students(&sheet, &columns, registered, cut_off(), &Catalogue::seed())
+    }
+
+    fn teachers(rows: &[&[&str]], registered: &[Teacher]) -> RosterImport {
+        let records: Vec<&[&str]> =
+            std::iter::once(&["First Name", "Last Name", "Gender", "Subjects taught"][..])
+                .chain(rows.iter().copied())
+                .collect();
+        let sheet = sheet(&records);
+        let columns = RosterColumns::detect(&sheet, RosterKind::Teachers);
+        read_teachers(&sheet, &columns, registered, &Catalogue::seed())
+    }
+
+    #[test]
+    fn columns_are_detected_from_their_usual_headers() {
+        let sheet = sheet(&[STUDENT_HEADERS]);
+        let columns = RosterColumns::detect(&sheet, RosterKind::Students);
+
+        for (column, field) in RosterKind::Students.fields().iter().enumerate() {
+            assert_eq!(columns.column(*field), Some(column), "{}", field);
+        }
+    }
+
+    #[test]
+    fn a_student_row_is_read_into_a_student() {
+        let import = students(
+            &[&[
+                "C01",
+                "Ama",
+                "Serwaa",
+                "Mensah",
+                "F",
+                "14/03/2008",
+                "W3",
+                "English; Physics",
+            ]],
+            &[],
+        );
+
+        assert!(import.is_importable(), "{:?}", import.issues());
+        assert_eq!(import.rows()[0].line(), 2);
+        let student = import.students().next().expect("one student");
+        assert_eq!(student.candidate_number(), "C01");
+        assert_eq!(student.class(), w3());
+        assert_eq!(student.subjects(), &[ENGLISH, PHYSICS]);
+        let info = student.personal_info();
+        assert_eq!(info.name().other_names(), "Serwaa");
+        assert_eq!(info.gender(), Gender::Female);
+        assert_eq!(
+            info.date_of_birth(),
+            NaiveDate::from_ymd_opt(2008, 3, 14).unwrap()
+        );
+    }
+
+    #[test]
+    fn dates_are_read_in_each_accepted_layout() {
+        let expected = NaiveDate::from_ymd_opt(2008, 3, 14);
+        for value in ["2008-03-14", "14/03/2008", "14-03-2008", " 14.03.2008 "] {
+            assert_eq!(parse_date(value), expected, "{}", value);
+        }
+        assert_eq!(parse_date("03/14/2008"), None);
+    }
+
+    #[test]
+    fn a_missing_required_column_stops_the_import() {
+        let sheet = sheet(&[
+            &["Candidate No", "First Name", "Surname"],
+            &["C01", "Ama", "Mensah"],
+        ]);
+        let columns = RosterColumns::detect(&sheet, RosterKind::Students);
+        let import = read_students(&sheet, &columns, &[], cut_off(), &Catalogue::seed());
+
+        assert!(import.rows().is_empty());
+        assert!(!import.is_importable());
+        assert_eq!(
+            import.issues(),
+            &[
+                RosterIssue::MissingColumn {
+                    field: RosterField::Gender
+                },
+                RosterIssue::MissingColumn {
+                    field: RosterField::DateOfBirth
+                },
+                RosterIssue::MissingColumn {
+                    field: RosterField::Class
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn bad_cells_are_reported_against_their_line() {
+        let import = students(
+            &[
+                &["C01", "", "", "Mensah", "X", "30/02/2008", "W9", "Latin"],
+                &["C02", "Kofi", "", "Boateng", "M", "", "", ""],
+            ],
+            &[],
+        );
+
+        assert!(!import.is_importable());
+        assert!(import.rows().is_empty());
+        let issues = import.issues();
+        for issue in [
+            RosterIssue::Blank {
+                line: 2,
+                field: RosterField::FirstName,
+            },
+            RosterIssue::UnknownGender {
+                line: 2,
+                value: "X".to_string(),
+            },
+            RosterIssue::InvalidDate {
+                line: 2,
+                value: "30/02/2008".to_string(),
+            },
+            RosterIssue::UnknownClass {
+                line: 2,
+                value: "W9".to_string(),
+            },
+            RosterIssue::UnknownSubject {
+                line: 2,
+                value: "Latin".to_string(),
+            },
+            RosterIssue::Blank {
+                line: 3,
+                field: RosterField::DateOfBirth,
+            },
+            RosterIssue::Blank {
+                line: 3,
+                field: RosterField::Class,
+            },
+        ] {
+            assert!(
+                issues.contains(&issue),
+                "{} missing from {:?}",
+                issue,
+                issues
+            );
+        }
+    }
+
+    #[test]
+    fn a_plain_subject_name_is_matched_in_the_class_programme() {
+        let import = teachers(&[&["Kwame", "Asante", "Male", "Physics"]], &[]);
+        let teacher = import.teachers().next().expect("one teacher");
+        assert_eq!(
+            teacher.subjects_taught(),
+            &[PHYSICS, Subject::ALevel(ALevelSubject::Physics)]
+        );
+
+        let import = students(
+            &[&[
+                "C01",
+                "Ama",
+                "",
+                "Mensah",
+                "F",
+                "2008-03-14",
+                "W3",
+                "Physics, PHYSICS",
+            ]],
+            &[],
+        );
+        let student = import.students().next().expect("one student");
+        assert_eq!(student.subjects(), &[PHYSICS]);
+    }
+
+    #[test]
+    fn a_subject_from_another_programme_is_an_error() {
+        let import = students(
+            &[&[
+                "C01",
+                "Ama",
+                "",
+                "Mensah",
+                "F",
+                "2008-03-14",
+                "W3",
+                "ALEVEL-PHY",
+            ]],
+            &[],
+        );
+
+        assert!(!import.is_importable());
+        assert_eq!(
+            import.issues(),
+            &[RosterIssue::WrongProgramme {
+                line: 2,
+                subject: Subject::ALevel(ALevelSubject::Physics),
+                class: w3(),
+            }]
+        );
+    }
+
+    #[test]
+    fn a_candidate_number_listed_twice_is_an_error() {
+        let import = students(
+            &[
+                &["C01", "Ama", "", "Mensah", "F", "2008-03-14", "W3", ""],
+                &["C01", "Kofi", "", "Boateng", "M", "2008-05-01", "W3", ""],
+            ],
+            &[],
+        );
+
+        assert!(!import.is_importable());
+        assert_eq!(import.rows().len(), 1);
+        assert_eq!(
+            import.issues(),
+            &[RosterIssue::Duplicate {
+                line: 3,
+                first_line: 2,
+                key: "C01".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn registered_students_are_skipped_and_shared_names_flagged() {
+        let registered = [fixtures::student("C01", w3(), &[])];
+        let import = students(
+            &[
+                &["c01", "Ama", "", "Mensah", "F", "2008-03-14", "W3", ""],
+                &["C02", "C01", "", "Test", "F", "2008-03-14", "W3", ""],
+                &["C03", "Kofi", "", "Boateng", "M", "2008-05-01", "W3", ""],
+                &["C04", " kofi ", "", "BOATENG", "M", "2008-06-01", "W3", ""],
+            ],
+            &registered,
+        );
+
+        assert!(import.is_importable(), "{:?}", import.issues());
+        let numbers: Vec<&str> = import.students().map(|s| s.candidate_number()).collect();
+        assert_eq!(numbers, ["C02", "C03", "C04"]);
+        assert_eq!(
+            import.issues(),
+            &[
+                RosterIssue::AlreadyRegistered {
+                    line: 2,
+                    key: "c01".to_string()
+                },
+                RosterIssue::SameName {
+                    line: 3,
+                    name: "C01 Test".to_string(),
+                    other: "C01".to_string(),
+                },
+                RosterIssue::SameName {
+                    line: 5,
+                    name: "kofi BOATENG".to_string(),
+                    other: "C03".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn an_implausible_age_is_an_error() {
+        let import = students(
+            &[&["C01", "Ama", "", "Mensah", "F", "2018-03-14", "W3", ""]],
+            &[],
+        );
+
+        assert!(!import.is_importable());
+        let issue = &import.issues()[0];
+        assert_eq!(
+            issue,
+            &RosterIssue::ImplausibleAge {
+                line: 2,
+                age: 8,
+                class: w3(),
+                cut_off: cut_off(),
+            }
+        );
+        assert!(issue.to_string().contains("outside 16 to 20"), "{}", issue);
+    }
+
+    #[test]
+    fn registered_teachers_and_repeats_are_matched_on_name() {
+        let registered = [Teacher::new(
+            TeacherPersonalInfo::new(fixtures::name("Esi"), Gender::Female),
+            vec![ENGLISH],
+        )];
+        let import = teachers(
+            &[
+                &["Esi", "Test", "F", "English"],
+                &["Kwame", "Asante", "M", ""],
+                &["KWAME", "asante", "M", ""],
+            ],
+            &registered,
+        );
+
+        assert_eq!(import.kind(), RosterKind::Teachers);
+        assert_eq!(import.teachers().count(), 1);
+        assert_eq!(
+            import.issues(),
+            &[
+                RosterIssue::Duplicate {
+                    line: 4,
+                    first_line: 3,
+                    key: "KWAME asante".to_string(),
+                },
+                RosterIssue::AlreadyRegistered {
+                    line: 2,
+                    key: "Esi Test".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn an_import_with_nothing_left_to_add_is_not_importable() {
+        let registered = [fixtures::student("C01", w3(), &[])];
+        let import = students(
+            &[&["C01", "Ama", "", "Mensah", "F", "2008-03-14", "W3", ""]],
+            &registered,
+        );
+
+        assert!(import.rows().is_empty());
+        assert!(!import.is_importable());
+    }
+
+    #[test]
+    fn a_batch_can_be_rolled_back_until_its_people_are_in_use() {
+        assert_eq!(check_rollback(0, 0), Ok(()));
+        assert_eq!(
+            check_rollback(2, 1),
+            Err(RollbackRefusal::StudentsWithMarks(2))
+        );
+        assert_eq!(check_rollback(0, 1), Err(RollbackRefusal::TeachersInUse(1)));
+        assert_eq!(
+            RollbackRefusal::TeachersInUse(1).to_string(),
+            "1 teachers in this batch have a login or classes; archive them instead"
+        );
+    }
+}
@@ -0,0 +1,257 @@
+// Descriptive statistics
+//
+// Summary statistics over a set of percentage scores. A class is the whole
+// population being described, not a sample of one, so the standard
+// deviation divides by the number of scores. Quartiles interpolate between
+// the two nearest scores, as spreadsheets do.
+use serde::{Deserialize, Serialize};
+
+/// Width of each histogram bar, in percentage points.
+pub const HISTOGRAM_BIN_WIDTH: f32 = 10.0;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Statistics {
+    count: u32,
+    mean: f32,
+    median: f32,
+    standard_deviation: f32,
+    lower_quartile: f32,
+    upper_quartile: f32,
+    minimum: f32,
+    maximum: f32,
+}
+
+impl Statistics {
+    /// `None` when there are no scores. Scores that are not a number are
+    /// left out.
+    pub fn of(scores: &[f32]) -> Option<Self> {
+        let mut sorted: Vec<f32> = scores
+            .iter()
+            .copied()
+            .filter(|score| !score.is_nan())
+            .collect();
+        if sorted.is_empty() {
+            return None;
+        }
+        sorted.sort_by(f32::total_cmp);
+
+        let count = sorted.len() as f32;
+        let mean = sorted.iter().sum::<f32>() / count;
+        let variance = sorted
+            .iter()
+            .map(|score| (score - mean).powi(2))
+            .sum::<f32>()
+            / count;
+
+        Some(Self {
+            count: sorted.len() as u32,
+            mean,
+            median: quantile(&sorted, 0.5),
+            standard_deviation: variance.sqrt(),
+            lower_quartile: quantile(&sorted, 0.25),
+            upper_quartile: quantile(&sorted, 0.75),
+            minimum: sorted[0],
+            maximum: sorted[sorted.len() - 1],
+        })
+    }
+
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    pub fn mean(&self) -> f32 {
+        self.mean
+    }
+
+    pub fn median(&self) -> f32 {
+        self.median
+    }
+
+    pub fn standard_deviation(&self) -> f32 {
+        self.standard_deviation
+    }
+
+    pub fn lower_quartile(&self) -> f32 {
+        self.lower_quartile
+    }
+
+    pub fn upper_quartile(&self) -> f32 {
+        self.upper_quartile
+    }
+
+    pub fn interquartile_range(&self) -> f32 {
+        self.upper_quartile - self.lower_quartile
+    }
+
+    pub fn minimum(&self) -> f32 {
+        self.minimum
+    }
+
+    pub fn maximum(&self) -> f32 {
+        self.maximum
+    }
+
+    /// How many standard deviations `score` is above the mean, or `None`
+    /// when every score is the same.
+    pub fn z_score(&self, score: f32) -> Option<f32> {
+        (self.standard_deviation > f32::EPSILON)
+            .then(|| (score - self.mean) / self.standard_deviation)
+    }
+}
+
+/// The value a fraction `q` of the way through `sorted`.
+fn quantile(sorted: &[f32], q: f32) -> f32 {
+    let position = q * (sorted.len() - 1) as f32;
+    let below = position.floor() as usize;
+    let above = position.ceil() as usize;
+    sorted[below] + (sorted[above] - sorted[below]) * (position - below as f32)
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HistogramBin {
+    lower: f32,
+    upper: f32,
+    count: u32,
+}
+
+impl HistogramBin {
+    pub fn lower(&self) -> f32 {
+        self.lower
+    }
+
+    /// Exclusive, except for the last bin which includes 100.
+    pub fn upper(&self) -> f32 {
+        self.upper
+    }
+
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// e.g. "40-49", or "90-100" for the last bin.
+    pub fn label(&self) -> String {
+        if self.upper >= 100.0 {
+            format!("{}-100", self.lower)
+        } else {
+            format!("{}-{}", self.lower, self.upper - 1.0)
+        }
+    }
+}
+
+/// How many percentage scores fall in each band from 0 to 100, including
+/// empty bands so histograms of different subjects line up. Scores that are
+/// not a number are not counted.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Histogram {
+    bins: Vec<HistogramBin>,
+}
+
+impl Histogram {
+    pub fn of(scores: &[f32], bin_width: f32) -> Self {
+        let bin_count = (100.0 / bin_width).ceil() as usize;
+        let mut bins: Vec<HistogramBin> = (0..bin_count)
+            .map(|index| HistogramBin {
+                lower: index as f32 * bin_width,
+                upper: ((index + 1) as f32 * bin_width).min(100.0),
+                count: 0,
+            })
+            .collect();
+        for score in scores.iter().filter(|score| !score.is_nan()) {
+            let index = ((score.clamp(0.0, 100.0) / bin_width) as usize).min(bin_count - 1);
+            bins[index].count += 1;
+        }
+
+        Self { bins }
+    }
+
+    pub fn bins(&self) -> &[HistogramBin] {
+        &self.bins
+    }
+
+    /// The count in the fullest bin, for scaling bars.
+    pub fn peak(&self) -> u32 {
+        self.bins.iter().map(|bin| bin.count).max().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(histogram: &Histogram) -> Vec<u32> {
+        histogram.bins().iter().map(|bin| bin.count()).collect()
+    }
+
+    #[test]
+    fn quartiles_interpolate_between_scores() {
+        let statistics = Statistics::of(&[40.0, 10.0, 30.0, 20.0]).unwrap();
+
+        assert_eq!(statistics.count(), 4);
+        assert_eq!(statistics.mean(), 25.0);
+        assert_eq!(statistics.median(), 25.0);
+        assert_eq!(statistics.lower_quartile(), 17.5);
+        assert_eq!(statistics.upper_quartile(), 32.5);
+        assert_eq!(statistics.interquartile_range(), 15.0);
+        assert_eq!(statistics.minimum(), 10.0);
+        assert_eq!(statistics.maximum(), 40.0);
+    }
+
+    #[test]
+    fn one_score_is_every_quartile() {
+        let statistics = Statistics::of(&[55.0]).unwrap();
+
+        assert_eq!(statistics.lower_quartile(), 55.0);
+        assert_eq!(statistics.median(), 55.0);
+        assert_eq!(statistics.upper_quartile(), 55.0);
+        assert_eq!(statistics.standard_deviation(), 0.0);
+        assert_eq!(statistics.z_score(55.0), None);
+    }
+
+    #[test]
+    fn the_standard_deviation_is_of_the_whole_class() {
+        let statistics = Statistics::of(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]).unwrap();
+
+        assert_eq!(statistics.standard_deviation(), 2.0);
+        assert_eq!(statistics.z_score(9.0), Some(2.0));
+    }
+
+    #[test]
+    fn no_scores_have_no_statistics() {
+        assert_eq!(Statistics::of(&[]), None);
+        assert_eq!(Statistics::of(&[f32::NAN]), None);
+    }
+
+    #[test]
+    fn nan_scores_are_left_out_of_statistics() {
+        let statistics = Statistics::of(&[f32::NAN, 60.0, 80.0]).unwrap();
+
+        assert_eq!(statistics.count(), 2);
+        assert_eq!(statistics.mean(), 70.0);
+        assert_eq!(statistics.maximum(), 80.0);
+    }
+
+    #[test]
+    fn histogram_bins_include_their_lower_bound_and_100_is_in_the_last() {
+        let histogram = Histogram::of(&[0.0, 9.9, 10.0, 89.9, 90.0, 100.0], HISTOGRAM_BIN_WIDTH);
+
+        assert_eq!(counts(&histogram), vec![2, 1, 0, 0, 0, 0, 0, 0, 1, 2]);
+        assert_eq!(histogram.bins()[0].label(), "0-9");
+        assert_eq!(histogram.bins()[9].label(), "90-100");
+        assert_eq!(histogram.peak(), 2);
+    }
+
+    #[test]
+    fn out_of_range_scores_go_in_the_end_bins_and_nan_is_not_counted() {
+        let histogram = Histogram::of(&[-5.0, 120.0, f32::NAN], HISTOGRAM_BIN_WIDTH);
+
+        assert_eq!(counts(&histogram), vec![1, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn an_empty_histogram_still_has_every_bin() {
+        let histogram = Histogram::of(&[], HISTOGRAM_BIN_WIDTH);
+
+        assert_eq!(histogram.bins().len(), 10);
+        assert_eq!(histogram.peak(), 0);
+    }
+}
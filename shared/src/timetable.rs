@@ -0,0 +1,496 @@
+// Exam timetable generation
+//
+// Papers are scheduled school-wide, so every candidate for a subject sits
+// each of its components in the same session. Two papers conflict when at
+// least one student sits both, and the generator colours that conflict
+// graph with the sessions of the exam window (DSatur ordering, preferring
+// the session that adds the fewest papers to any affected student's day).
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::catalogue::Catalogue;
+use crate::domain::{
+    ExamDate, ExamDay, ExamPair, ExamPaper, ExamSession, ExamTTObject, PaperComponent,
+    PersonalName, Student, StudentClass, Subject,
+};
+
+/// Length of the single paper assumed for a subject the catalogue has no
+/// components for.
+pub const DEFAULT_PAPER_MINUTES: u32 = 120;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ExamWindow {
+    dates: Vec<NaiveDate>,
+    sessions: Vec<ExamSession>,
+}
+
+impl ExamWindow {
+    pub fn new(dates: Vec<NaiveDate>, sessions: Vec<ExamSession>) -> Self {
+        Self { dates, sessions }
+    }
+
+    /// Every date from `start` to `end` inclusive that falls on one of
+    /// `days`.
+    pub fn from_range(
+        start: NaiveDate,
+        end: NaiveDate,
+        days: &[ExamDay],
+        sessions: Vec<ExamSession>,
+    ) -> Self {
+        let dates = start
+            .iter_days()
+            .take_while(|date| *date <= end)
+            .filter(|date| {
+                ExamDay::from_weekday(chrono::Datelike::weekday(date))
+                    .is_some_and(|day| days.contains(&day))
+            })
+            .collect();
+
+        Self::new(dates, sessions)
+    }
+
+    pub fn dates(&self) -> &[NaiveDate] {
+        &self.dates
+    }
+
+    pub fn sessions(&self) -> &[ExamSession] {
+        &self.sessions
+    }
+
+    /// Every session in the window in chronological order.
+    pub fn slots(&self) -> Vec<ExamDate> {
+        let mut slots: Vec<ExamDate> = self
+            .dates
+            .iter()
+            .flat_map(|date| {
+                self.sessions
+                    .iter()
+                    .map(move |session| ExamDate::new(*date, *session))
+            })
+            .collect();
+        slots.sort();
+        slots.dedup();
+        slots
+    }
+}
+
+/// A paper that could not be given a session free of clashes. It is still
+/// placed in the session that affects the fewest students.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Clash {
+    paper: ExamPaper,
+    exam_date: ExamDate,
+    clashes_with: Vec<ExamPaper>,
+    students: Vec<PersonalName>,
+}
+
+impl Clash {
+    pub fn paper(&self) -> &ExamPaper {
+        &self.paper
+    }
+
+    pub fn exam_date(&self) -> &ExamDate {
+        &self.exam_date
+    }
+
+    pub fn clashes_with(&self) -> &[ExamPaper] {
+        &self.clashes_with
+    }
+
+    /// Students who have two or more papers in the session.
+    pub fn students(&self) -> &[PersonalName] {
+        &self.students
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimetableReport {
+    timetables: Vec<ExamTTObject>,
+    clashes: Vec<Clash>,
+}
+
+impl TimetableReport {
+    /// One timetable per class, in class order.
+    pub fn timetables(&self) -> &[ExamTTObject] {
+        &self.timetables
+    }
+
+    pub fn clashes(&self) -> &[Clash] {
+        &self.clashes
+    }
+
+    pub fn is_clash_free(&self) -> bool {
+        self.clashes.is_empty()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    EmptyWindow,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let error_msg = match self {
+            Self::EmptyWindow => "The exam window has no sessions to schedule into.",
+        };
+
+        write!(f, "{}", error_msg)
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// The components a subject is sat in, from the catalogue when it has any.
+pub fn paper_components(catalogue: &Catalogue, subject: Subject) -> Vec<PaperComponent> {
+    catalogue
+        .resolve(subject)
+        .map(|entry| entry.components().to_vec())
+        .filter(|components| !components.is_empty())
+        .unwrap_or_else(|| {
+            vec![PaperComponent::new(
+                "P1".to_string(),
+                "Paper 1".to_string(),
+                DEFAULT_PAPER_MINUTES,
+            )]
+        })
+}
+
+pub fn generate(
+    students: &[Student],
+    window: &ExamWindow,
+    catalogue: &Catalogue,
+) -> Result<TimetableReport, Error> {
+    let slots = window.slots();
+    if slots.is_empty() {
+        return Err(Error::EmptyWindow);
+    }
+
+    let mut subject_candidates: BTreeMap<Subject, BTreeSet<usize>> = BTreeMap::new();
+    for (index, student) in students.iter().enumerate() {
+        for subject in student.subjects_read() {
+            subject_candidates
+                .entry(*subject)
+                .or_default()
+                .insert(index);
+        }
+    }
+
+    let mut candidates: BTreeMap<(Subject, PaperComponent), &BTreeSet<usize>> = BTreeMap::new();
+    for (subject, subject_students) in &subject_candidates {
+        for component in paper_components(catalogue, *subject) {
+            candidates.insert((*subject, component), subject_students);
+        }
+    }
+
+    let conflicts: BTreeMap<&(Subject, PaperComponent), BTreeSet<&(Subject, PaperComponent)>> =
+        candidates
+            .iter()
+            .map(|(paper, paper_students)| {
+                let neighbours = candidates
+                    .iter()
+                    .filter(|(other, other_students)| {
+                        *other != paper && !paper_students.is_disjoint(other_students)
+                    })
+                    .map(|(other, _)| other)
+                    .collect();
+                (paper, neighbours)
+            })
+            .collect();
+
+    let mut assignment: BTreeMap<&(Subject, PaperComponent), usize> = BTreeMap::new();
+    let mut clashes = Vec::new();
+
+    while let Some(paper) = next_paper(&candidates, &conflicts, &assignment) {
+        let paper_students = candidates[paper];
+
+        let students_in_slot = |slot: usize| -> BTreeSet<usize> {
+            assignment
+                .iter()
+                .filter(|(_, assigned)| **assigned == slot)
+                .flat_map(|(other, _)| candidates[*other].intersection(paper_students))
+                .copied()
+                .collect()
+        };
+
+        let papers_that_day = |slot: usize| -> usize {
+            let date = slots[slot].date();
+            assignment
+                .iter()
+                .filter(|(_, assigned)| slots[**assigned].date() == date)
+                .map(|(other, _)| candidates[*other].intersection(paper_students).count())
+                .sum()
+        };
+
+        let free_slot = (0..slots.len())
+            .filter(|slot| students_in_slot(*slot).is_empty())
+            .min_by_key(|slot| (papers_that_day(*slot), *slot));
+
+        let slot = match free_slot {
+            Some(slot) => slot,
+            None => {
+                let slot = (0..slots.len())
+                    .min_by_key(|slot| (students_in_slot(*slot).len(), *slot))
+                    .expect("window has at least one slot");
+                let clashes_with = assignment
+                    .iter()
+                    .filter(|(other, assigned)| {
+                        **assigned == slot && conflicts[paper].contains(*other)
+                    })
+                    .map(|(other, _)| scheduled_paper(other, &slots[slot]))
+                    .collect();
+                let students = students_in_slot(slot)
+                    .into_iter()
+                    .map(|index| students[index].personal_info().name().clone())
+                    .collect();
+                clashes.push(Clash {
+                    paper: scheduled_paper(paper, &slots[slot]),
+                    exam_date: slots[slot].clone(),
+                    clashes_with,
+                    students,
+                });
+                slot
+            }
+        };
+
+        assignment.insert(paper, slot);
+    }
+
+    let mut class_subjects: BTreeMap<StudentClass, BTreeSet<Subject>> = BTreeMap::new();
+    for student in students {
+        class_subjects
+            .entry(student.class())
+            .or_default()
+            .extend(student.subjects_read().iter().copied());
+    }
+
+    let timetables = class_subjects
+        .into_iter()
+        .map(|(class, subjects)| {
+            let exam_pairs = slots
+                .iter()
+                .enumerate()
+                .filter_map(|(slot, exam_date)| {
+                    let papers: Vec<ExamPaper> = assignment
+                        .iter()
+                        .filter(|((subject, _), assigned)| {
+                            **assigned == slot && subjects.contains(subject)
+                        })
+                        .map(|(paper, _)| scheduled_paper(paper, exam_date))
+                        .collect();
+                    (!papers.is_empty()).then(|| ExamPair::new(exam_date.clone(), papers))
+                })
+                .collect();
+            ExamTTObject::new(class, exam_pairs)
+        })
+        .collect();
+
+    Ok(TimetableReport {
+        timetables,
+        clashes,
+    })
+}
+
+fn scheduled_paper(
+    (subject, component): &(Subject, PaperComponent),
+    exam_date: &ExamDate,
+) -> ExamPaper {
+    ExamPaper::new(
+        *subject,
+        component.clone(),
+        exam_date.session().default_start_time(),
+    )
+}
+
+/// DSatur ordering: the unscheduled paper whose conflicting papers already
+/// occupy the most distinct sessions, then the most conflicts, then the most
+/// candidates.
+fn next_paper<'a>(
+    candidates: &'a BTreeMap<(Subject, PaperComponent), &BTreeSet<usize>>,
+    conflicts: &BTreeMap<&(Subject, PaperComponent), BTreeSet<&(Subject, PaperComponent)>>,
+    assignment: &BTreeMap<&(Subject, PaperComponent), usize>,
+) -> Option<&'a (Subject, PaperComponent)> {
+    candidates
+        .keys()
+        .filter(|paper| !assignment.contains_key(paper))
+        .max_by_key(|paper| {
+            let neighbours = &conflicts[paper];
+            let saturation = neighbours
+                .iter()
+                .filter_map(|other| assignment.get(other))
+                .collect::<BTreeSet<_>>()
+                .len();
+            (
+                saturation,
+                neighbours.len(),
+                candidates[*paper].len(),
+                std::cmp::Reverse(*paper),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::student;
+
+    /// Two classes from the seed catalogue, each with the first `count`
+    /// subjects it offers.
+    fn classes(catalogue: &Catalogue, count: usize) -> Vec<(StudentClass, Vec<Subject>)> {
+        catalogue
+            .classes()
+            .into_iter()
+            .map(|class| {
+                (
+                    class,
+                    catalogue.subjects_offered(class).take(count).collect(),
+                )
+            })
+            .filter(|(_, subjects): &(_, Vec<Subject>)| subjects.len() == count)
+            .take(2)
+            .collect()
+    }
+
+    fn students(classes: &[(StudentClass, Vec<Subject>)]) -> Vec<Student> {
+        classes
+            .iter()
+            .enumerate()
+            .flat_map(|(class_index, (class, subjects))| {
+                (0..3).map(move |index| {
+                    student(&format!("C{}{}", class_index, index), *class, subjects)
+                })
+            })
+            .collect()
+    }
+
+    fn window(days: u32, sessions: Vec<ExamSession>) -> ExamWindow {
+        let start = NaiveDate::from_ymd_opt(2026, 5, 4).expect("valid date");
+        ExamWindow::new(start.iter_days().take(days as usize).collect(), sessions)
+    }
+
+    fn paper_count(catalogue: &Catalogue, subjects: &[Subject]) -> usize {
+        subjects
+            .iter()
+            .map(|subject| paper_components(catalogue, *subject).len())
+            .sum()
+    }
+
+    #[test]
+    fn a_roomy_window_gives_every_student_one_paper_per_session() {
+        let catalogue = Catalogue::seed();
+        let classes = classes(&catalogue, 3);
+        let students = students(&classes);
+
+        let report = generate(
+            &students,
+            &window(10, vec![ExamSession::Morning, ExamSession::Afternoon]),
+            &catalogue,
+        )
+        .expect("the window has sessions");
+
+        assert!(report.is_clash_free());
+        for (timetable, (class, subjects)) in report.timetables().iter().zip(&classes) {
+            assert_eq!(timetable.student_class(), *class);
+            // Every student in the class reads every one of its subjects,
+            // so two papers in one session would be a clash.
+            assert!(
+                timetable
+                    .exam_pairs()
+                    .iter()
+                    .all(|pair| pair.papers().len() == 1)
+            );
+            assert_eq!(
+                timetable.exam_pairs().len(),
+                paper_count(&catalogue, subjects)
+            );
+        }
+    }
+
+    #[test]
+    fn a_window_too_small_reports_the_clashes() {
+        let catalogue = Catalogue::seed();
+        let classes = classes(&catalogue, 2);
+        let students = students(&classes[..1]);
+
+        let report = generate(
+            &students,
+            &window(1, vec![ExamSession::Morning]),
+            &catalogue,
+        )
+        .expect("the window has a session");
+
+        assert!(!report.is_clash_free());
+        let clash = &report.clashes()[0];
+        assert!(!clash.clashes_with().is_empty());
+        assert_eq!(clash.students().len(), students.len());
+        // Clashing papers are still placed, all in the one session.
+        let [timetable] = report.timetables() else {
+            panic!("one class has students");
+        };
+        assert_eq!(timetable.exam_pairs().len(), 1);
+        assert_eq!(
+            timetable.exam_pairs()[0].papers().len(),
+            paper_count(&catalogue, &classes[0].1)
+        );
+    }
+
+    #[test]
+    fn a_window_without_sessions_is_an_error() {
+        let catalogue = Catalogue::seed();
+        let students = students(&classes(&catalogue, 1));
+
+        assert_eq!(
+            generate(
+                &students,
+                &window(0, vec![ExamSession::Morning]),
+                &catalogue
+            ),
+            Err(Error::EmptyWindow)
+        );
+        assert_eq!(
+            generate(&students, &window(5, Vec::new()), &catalogue),
+            Err(Error::EmptyWindow)
+        );
+    }
+
+    #[test]
+    fn each_class_timetable_holds_only_its_own_subjects() {
+        let catalogue = Catalogue::seed();
+        let classes = classes(&catalogue, 2);
+        assert_eq!(classes.len(), 2, "two classes with two subjects each");
+        let students = students(&classes);
+
+        let report = generate(
+            &students,
+            &window(10, vec![ExamSession::Morning, ExamSession::Afternoon]),
+            &catalogue,
+        )
+        .expect("the window has sessions");
+
+        assert_eq!(report.timetables().len(), 2);
+        for (timetable, (class, subjects)) in report.timetables().iter().zip(&classes) {
+            assert_eq!(timetable.student_class(), *class);
+            for pair in timetable.exam_pairs() {
+                assert!(
+                    pair.subject_list()
+                        .iter()
+                        .all(|subject| subjects.contains(subject)),
+                    "{} sits a subject it does not read",
+                    class
+                );
+            }
+            let dates: Vec<&ExamDate> = timetable
+                .exam_pairs()
+                .iter()
+                .map(|pair| pair.exam_date())
+                .collect();
+            assert!(
+                dates.windows(2).all(|pair| pair[0] < pair[1]),
+                "{}'s sittings are in date order",
+                class
+            );
+        }
+    }
+}
@@ -0,0 +1,417 @@
+// Performance trends
+//
+// How a class or a student has done over a run of assessments (mocks,
+// end-of-term exams, one year against the next), overall and per subject.
+// Each sitting is analysed on its own first, so every point on a trend is
+// the figure the analysis page gave for that sitting.
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::{ClassAnalysis, Position};
+use crate::domain::Subject;
+use crate::marks::AcademicPeriod;
+
+/// Changes smaller than this many percentage points count as steady.
+pub const STEADY_MARGIN: f32 = 2.0;
+
+/// One class's analysis for one assessment, placed in the school calendar.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Sitting {
+    assessment_name: String,
+    period: AcademicPeriod,
+    analysis: ClassAnalysis,
+}
+
+impl Sitting {
+    pub fn new(assessment_name: String, period: AcademicPeriod, analysis: ClassAnalysis) -> Self {
+        Self {
+            assessment_name,
+            period,
+            analysis,
+        }
+    }
+
+    pub fn assessment_name(&self) -> &str {
+        &self.assessment_name
+    }
+
+    pub fn period(&self) -> AcademicPeriod {
+        self.period
+    }
+
+    pub fn analysis(&self) -> &ClassAnalysis {
+        &self.analysis
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Direction {
+    Improving,
+    Steady,
+    Declining,
+}
+
+impl Direction {
+    /// The direction of a change in score, in percentage points.
+    pub fn of(change: f32) -> Self {
+        if change >= STEADY_MARGIN {
+            Self::Improving
+        } else if change <= -STEADY_MARGIN {
+            Self::Declining
+        } else {
+            Self::Steady
+        }
+    }
+}
+
+impl std::fmt::Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Improving => write!(f, "Improving"),
+            Self::Steady => write!(f, "Steady"),
+            Self::Declining => write!(f, "Declining"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TrendPoint {
+    assessment_name: String,
+    period: AcademicPeriod,
+    score: f32,
+    position: Option<Position>,
+}
+
+impl TrendPoint {
+    pub fn assessment_name(&self) -> &str {
+        &self.assessment_name
+    }
+
+    pub fn period(&self) -> AcademicPeriod {
+        self.period
+    }
+
+    /// A student's score or a class average, as a percentage.
+    pub fn score(&self) -> f32 {
+        self.score
+    }
+
+    /// The student's place in class. Always `None` on class trends.
+    pub fn position(&self) -> Option<Position> {
+        self.position
+    }
+}
+
+/// Scores over time for one subject, or overall.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TrendSeries {
+    subject: Option<Subject>,
+    points: Vec<TrendPoint>,
+}
+
+impl TrendSeries {
+    /// `None` for the overall average.
+    pub fn subject(&self) -> Option<Subject> {
+        self.subject
+    }
+
+    /// e.g. "Overall" or "WASSCE Physics".
+    pub fn label(&self) -> String {
+        self.subject
+            .map(|subject| subject.to_string())
+            .unwrap_or_else(|| "Overall".to_string())
+    }
+
+    /// Oldest first.
+    pub fn points(&self) -> &[TrendPoint] {
+        &self.points
+    }
+
+    pub fn latest(&self) -> Option<&TrendPoint> {
+        self.points.last()
+    }
+
+    /// Change from the previous sitting to the latest, in percentage
+    /// points.
+    pub fn change(&self) -> Option<f32> {
+        let [.., previous, latest] = self.points.as_slice() else {
+            return None;
+        };
+        Some(latest.score - previous.score)
+    }
+
+    /// Change from the first sitting to the latest.
+    pub fn overall_change(&self) -> Option<f32> {
+        let (first, latest) = (self.points.first()?, self.points.last()?);
+        (self.points.len() > 1).then_some(latest.score - first.score)
+    }
+
+    /// Whether the latest sitting was better or worse than the one before.
+    pub fn direction(&self) -> Option<Direction> {
+        self.change().map(Direction::of)
+    }
+}
+
+/// The overall score and position, then each subject's, from one sitting.
+type SittingScores = (
+    (f32, Option<Position>),
+    Vec<(Subject, f32, Option<Position>)>,
+);
+
+/// Overall and per-subject series for a class or a student.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Trends {
+    overall: TrendSeries,
+    subjects: Vec<TrendSeries>,
+}
+
+impl Trends {
+    /// The class average across `sittings`, which are put in calendar
+    /// order, keeping the given order within a term. For year-on-year
+    /// comparisons, pass the same sitting from each year.
+    pub fn class(sittings: &[Sitting]) -> Self {
+        Self::collect(sittings, |sitting| {
+            let summary = sitting.analysis.class_summary();
+            let subjects = summary
+                .class_subject_performance_summary()
+                .iter()
+                .map(|subject| (subject.subject(), subject.subject_average(), None))
+                .collect();
+            Some(((summary.class_overall_average(), None), subjects))
+        })
+    }
+
+    /// One student's scores and positions across `sittings`, leaving out
+    /// any they have no marks in. The sittings may be of different classes,
+    /// so a student's record follows them up the school.
+    pub fn student(candidate_number: &str, sittings: &[Sitting]) -> Self {
+        Self::collect(sittings, |sitting| {
+            let summary = sitting
+                .analysis
+                .student_summaries()
+                .iter()
+                .find(|summary| summary.student().candidate_number() == candidate_number)?;
+            let subjects = summary
+                .subject_specific_details()
+                .iter()
+                .map(|detail| {
+                    (
+                        detail.subject(),
+                        detail.overall_subject_score(),
+                        detail.position(),
+                    )
+                })
+                .collect();
+            Some(((summary.overall_average(), summary.position()), subjects))
+        })
+    }
+
+    fn collect(sittings: &[Sitting], scores: impl Fn(&Sitting) -> Option<SittingScores>) -> Self {
+        let mut sittings: Vec<&Sitting> = sittings.iter().collect();
+        sittings.sort_by_key(|sitting| sitting.period);
+
+        let mut overall = TrendSeries {
+            subject: None,
+            points: Vec::new(),
+        };
+        let mut subjects: Vec<TrendSeries> = Vec::new();
+        for sitting in sittings {
+            let Some(((score, position), subject_scores)) = scores(sitting) else {
+                continue;
+            };
+            let point = |score, position| TrendPoint {
+                assessment_name: sitting.assessment_name.clone(),
+                period: sitting.period,
+                score,
+                position,
+            };
+            overall.points.push(point(score, position));
+            for (subject, score, position) in subject_scores {
+                let index = match subjects
+                    .iter()
+                    .position(|series| series.subject == Some(subject))
+                {
+                    Some(index) => index,
+                    None => {
+                        subjects.push(TrendSeries {
+                            subject: Some(subject),
+                            points: Vec::new(),
+                        });
+                        subjects.len() - 1
+                    }
+                };
+                subjects[index].points.push(point(score, position));
+            }
+        }
+        subjects.sort_by_key(|series| series.subject);
+
+        Self { overall, subjects }
+    }
+
+    pub fn overall(&self) -> &TrendSeries {
+        &self.overall
+    }
+
+    /// One series per subject with a score in any sitting, in subject
+    /// order. A subject missing from a sitting has no point for it.
+    pub fn subjects(&self) -> &[TrendSeries] {
+        &self.subjects
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.overall.points.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{AssessmentResults, RankBy, Weighting, analyse_class};
+    use crate::domain::{Student, StudentClass};
+    use crate::fixtures::student;
+    use crate::grading::GradingPolicy;
+    use crate::marks::{Assessment, AssessmentComponent, Mark, Term};
+
+    /// A sitting where each candidate scored the given percentage in one
+    /// subject.
+    fn sitting(name: &str, period: AcademicPeriod, scores: &[(&str, f32)]) -> Sitting {
+        let class = StudentClass::all().next().expect("a built-in class");
+        let subject = Subject::all().next().expect("a built-in subject");
+        let students: Vec<Student> = ["A", "B"]
+            .into_iter()
+            .map(|candidate_number| student(candidate_number, class, &[subject]))
+            .collect();
+        let results = AssessmentResults::new(
+            Assessment::new(
+                name.to_string(),
+                class,
+                subject,
+                period,
+                vec![AssessmentComponent::new(
+                    "P1".to_string(),
+                    "Paper 1".to_string(),
+                    100,
+                    1.0,
+                )],
+            ),
+            scores
+                .iter()
+                .map(|(candidate_number, score)| {
+                    Mark::new(candidate_number.to_string(), "P1".to_string(), Some(*score))
+                })
+                .collect(),
+        );
+        let analysis = analyse_class(
+            class,
+            &students,
+            &[results],
+            &Weighting::Assessment,
+            &GradingPolicy::seed(),
+            RankBy::Average,
+        )
+        .expect("the sitting has marks");
+        Sitting::new(name.to_string(), period, analysis)
+    }
+
+    fn scores(series: &TrendSeries) -> Vec<f32> {
+        series.points().iter().map(|point| point.score()).collect()
+    }
+
+    #[test]
+    fn changes_within_the_margin_are_steady() {
+        assert_eq!(Direction::of(STEADY_MARGIN), Direction::Improving);
+        assert_eq!(Direction::of(1.9), Direction::Steady);
+        assert_eq!(Direction::of(0.0), Direction::Steady);
+        assert_eq!(Direction::of(-1.9), Direction::Steady);
+        assert_eq!(Direction::of(-STEADY_MARGIN), Direction::Declining);
+    }
+
+    #[test]
+    fn an_unknown_change_is_steady() {
+        assert_eq!(Direction::of(f32::NAN), Direction::Steady);
+    }
+
+    #[test]
+    fn class_trends_are_in_calendar_order() {
+        let sittings = [
+            sitting(
+                "Mock",
+                AcademicPeriod::new(2025, Term::Second),
+                &[("A", 50.0), ("B", 70.0)],
+            ),
+            sitting(
+                "End of term",
+                AcademicPeriod::new(2025, Term::First),
+                &[("A", 70.0), ("B", 90.0)],
+            ),
+        ];
+
+        let trends = Trends::class(&sittings);
+
+        assert_eq!(scores(trends.overall()), vec![80.0, 60.0]);
+        assert_eq!(trends.overall().change(), Some(-20.0));
+        assert_eq!(trends.overall().direction(), Some(Direction::Declining));
+        assert_eq!(trends.subjects().len(), 1);
+        assert_eq!(trends.subjects()[0].direction(), Some(Direction::Declining));
+        assert_eq!(trends.overall().latest().unwrap().assessment_name(), "Mock");
+    }
+
+    #[test]
+    fn student_trends_skip_sittings_they_have_no_marks_in() {
+        let sittings = [
+            sitting(
+                "First",
+                AcademicPeriod::new(2024, Term::Third),
+                &[("A", 40.0), ("B", 60.0)],
+            ),
+            sitting(
+                "Second",
+                AcademicPeriod::new(2025, Term::First),
+                &[("B", 60.0)],
+            ),
+            sitting(
+                "Third",
+                AcademicPeriod::new(2025, Term::Second),
+                &[("A", 65.0), ("B", 61.0)],
+            ),
+        ];
+
+        let a = Trends::student("A", &sittings);
+        assert_eq!(scores(a.overall()), vec![40.0, 65.0]);
+        assert_eq!(a.overall().direction(), Some(Direction::Improving));
+        assert_eq!(
+            a.overall().latest().unwrap().position(),
+            Some(Position::new(1, false, 2))
+        );
+
+        let b = Trends::student("B", &sittings);
+        assert!((b.overall().change().unwrap() - 1.0).abs() < 1e-3);
+        assert_eq!(b.overall().overall_change(), b.overall().change());
+        assert_eq!(b.overall().direction(), Some(Direction::Steady));
+    }
+
+    #[test]
+    fn one_sitting_has_no_direction() {
+        let sittings = [sitting(
+            "Only",
+            AcademicPeriod::new(2025, Term::First),
+            &[("A", 50.0)],
+        )];
+
+        let trends = Trends::class(&sittings);
+        assert_eq!(trends.overall().change(), None);
+        assert_eq!(trends.overall().overall_change(), None);
+        assert_eq!(trends.overall().direction(), None);
+    }
+
+    #[test]
+    fn no_sittings_make_an_empty_trend() {
+        assert!(Trends::class(&[]).is_empty());
+        assert!(Trends::student("A", &[]).is_empty());
+        let sittings = [sitting(
+            "Only",
+            AcademicPeriod::new(2025, Term::First),
+            &[("B", 50.0)],
+        )];
+        assert!(Trends::student("A", &sittings).is_empty());
+    }
+}
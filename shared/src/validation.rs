@@ -0,0 +1,472 @@
+// Exam timetable validation
+//
+// Checks a (possibly hand-edited) set of class timetables against student
+// enrolments and the exam window, returning one diagnostic per problem so
+// the caller can list them and highlight the offending sittings.
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use chrono::NaiveDate;
+
+use crate::domain::{
+    ExamDate, ExamPaper, ExamTTObject, PersonalName, Student, StudentClass, Subject,
+};
+use crate::timetable::ExamWindow;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Diagnostic {
+    /// A student has more than one paper in the same session.
+    DoubleBooked {
+        student: PersonalName,
+        class: StudentClass,
+        exam_date: ExamDate,
+        papers: Vec<ExamPaper>,
+    },
+    /// A student has more papers in one day than the configured limit.
+    TooManyPapersInDay {
+        student: PersonalName,
+        class: StudentClass,
+        date: NaiveDate,
+        papers: usize,
+        limit: usize,
+    },
+    /// A sitting falls outside the exam window.
+    NotAnExamDay {
+        class: StudentClass,
+        exam_date: ExamDate,
+        papers: Vec<ExamPaper>,
+    },
+    /// A subject read in a class has no sitting in that class's timetable.
+    Unscheduled {
+        class: StudentClass,
+        subject: Subject,
+    },
+}
+
+impl Diagnostic {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::TooManyPapersInDay { .. } => Severity::Warning,
+            Self::DoubleBooked { .. } | Self::NotAnExamDay { .. } | Self::Unscheduled { .. } => {
+                Severity::Error
+            }
+        }
+    }
+
+    pub fn class(&self) -> StudentClass {
+        match self {
+            Self::DoubleBooked { class, .. }
+            | Self::TooManyPapersInDay { class, .. }
+            | Self::NotAnExamDay { class, .. }
+            | Self::Unscheduled { class, .. } => *class,
+        }
+    }
+
+    /// The sitting to highlight, when the problem belongs to one session.
+    pub fn exam_date(&self) -> Option<&ExamDate> {
+        match self {
+            Self::DoubleBooked { exam_date, .. } | Self::NotAnExamDay { exam_date, .. } => {
+                Some(exam_date)
+            }
+            Self::TooManyPapersInDay { .. } | Self::Unscheduled { .. } => None,
+        }
+    }
+
+    /// The subjects to highlight.
+    pub fn subjects(&self) -> Vec<Subject> {
+        match self {
+            Self::DoubleBooked { papers, .. } | Self::NotAnExamDay { papers, .. } => papers
+                .iter()
+                .map(|paper| paper.subject())
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect(),
+            Self::Unscheduled { subject, .. } => vec![*subject],
+            Self::TooManyPapersInDay { .. } => Vec::new(),
+        }
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::DoubleBooked {
+                student,
+                class,
+                exam_date,
+                papers,
+            } => write!(
+                f,
+                "{} {} ({}) has {} papers on {}: {}",
+                student.first_name(),
+                student.last_name(),
+                class,
+                papers.len(),
+                exam_date,
+                join_papers(papers)
+            ),
+            Self::TooManyPapersInDay {
+                student,
+                class,
+                date,
+                papers,
+                limit,
+            } => write!(
+                f,
+                "{} {} ({}) has {} papers on {}, more than the limit of {}",
+                student.first_name(),
+                student.last_name(),
+                class,
+                papers,
+                date.format("%A %-d %B %Y"),
+                limit
+            ),
+            Self::NotAnExamDay {
+                class,
+                exam_date,
+                papers,
+            } => write!(
+                f,
+                "{} has {} scheduled on {}, which is outside the exam window",
+                class,
+                join_papers(papers),
+                exam_date
+            ),
+            Self::Unscheduled { class, subject } => {
+                write!(f, "{} has no sitting for {}", class, subject)
+            }
+        }
+    }
+}
+
+fn join_papers(papers: &[ExamPaper]) -> String {
+    papers
+        .iter()
+        .map(|paper| format!("{} {}", paper.subject(), paper.component().name()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Validates class timetables, errors first.
+pub fn validate(
+    timetables: &[ExamTTObject],
+    students: &[Student],
+    window: &ExamWindow,
+    max_papers_per_day: usize,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    let window_slots: BTreeSet<ExamDate> = window.slots().into_iter().collect();
+    for timetable in timetables {
+        for exam_pair in timetable.exam_pairs() {
+            if !window_slots.contains(exam_pair.exam_date()) {
+                diagnostics.push(Diagnostic::NotAnExamDay {
+                    class: timetable.student_class(),
+                    exam_date: exam_pair.exam_date().clone(),
+                    papers: exam_pair.papers().to_vec(),
+                });
+            }
+        }
+    }
+
+    let mut unscheduled: BTreeSet<(StudentClass, Subject)> = BTreeSet::new();
+    for student in students {
+        let class = student.class();
+        let exam_pairs = timetables
+            .iter()
+            .filter(|timetable| timetable.student_class() == class)
+            .flat_map(|timetable| timetable.exam_pairs());
+
+        let mut sittings: BTreeMap<&ExamDate, Vec<ExamPaper>> = BTreeMap::new();
+        for exam_pair in exam_pairs {
+            for paper in exam_pair.papers() {
+                if student.subjects_read().contains(&paper.subject()) {
+                    sittings
+                        .entry(exam_pair.exam_date())
+                        .or_default()
+                        .push(paper.clone());
+                }
+            }
+        }
+
+        for subject in student.subjects_read() {
+            let is_scheduled = sittings
+                .values()
+                .flatten()
+                .any(|paper| paper.subject() == *subject);
+            if !is_scheduled {
+                unscheduled.insert((class, *subject));
+            }
+        }
+
+        let mut papers_per_day: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+        for (exam_date, papers) in &sittings {
+            *papers_per_day.entry(exam_date.date()).or_default() += papers.len();
+            if papers.len() > 1 {
+                diagnostics.push(Diagnostic::DoubleBooked {
+                    student: student.personal_info().name().clone(),
+                    class,
+                    exam_date: (*exam_date).clone(),
+                    papers: papers.clone(),
+                });
+            }
+        }
+
+        for (date, papers) in papers_per_day {
+            if papers > max_papers_per_day {
+                diagnostics.push(Diagnostic::TooManyPapersInDay {
+                    student: student.personal_info().name().clone(),
+                    class,
+                    date,
+                    papers,
+                    limit: max_papers_per_day,
+                });
+            }
+        }
+    }
+
+    diagnostics.extend(
+        unscheduled
+            .into_iter()
+            .map(|(class, subject)| Diagnostic::Unscheduled { class, subject }),
+    );
+
+    diagnostics.sort_by_key(|diagnostic| std::cmp::Reverse(diagnostic.severity()));
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveTime;
+
+    use super::*;
+    use crate::domain::{ExamDay, ExamPair, ExamSession, PaperComponent};
+    use crate::fixtures::student;
+
+    fn paper(subject: Subject, component: &str) -> ExamPaper {
+        ExamPaper::new(
+            subject,
+            PaperComponent::new(component.to_string(), component.to_string(), 90),
+            NaiveTime::from_hms_opt(9, 0, 0).expect("valid time"),
+        )
+    }
+
+    fn class() -> StudentClass {
+        StudentClass::all().next().expect("a built-in class")
+    }
+
+    fn subjects() -> (Subject, Subject) {
+        let mut subjects = Subject::all();
+        (
+            subjects.next().expect("a built-in subject"),
+            subjects.next().expect("a second built-in subject"),
+        )
+    }
+
+    /// A session on day `day` of the window, where day 1 is Monday 4 May
+    /// 2026.
+    fn sitting(day: u32, session: ExamSession) -> ExamDate {
+        ExamDate::new(
+            NaiveDate::from_ymd_opt(2026, 5, 3 + day).expect("valid date"),
+            session,
+        )
+    }
+
+    /// Monday to Friday of the week of 4 May 2026, mornings and afternoons.
+    fn window() -> ExamWindow {
+        ExamWindow::from_range(
+            NaiveDate::from_ymd_opt(2026, 5, 4).expect("valid date"),
+            NaiveDate::from_ymd_opt(2026, 5, 8).expect("valid date"),
+            ExamDay::ALL,
+            vec![ExamSession::Morning, ExamSession::Afternoon],
+        )
+    }
+
+    fn timetable(sittings: Vec<(ExamDate, Vec<ExamPaper>)>) -> Vec<ExamTTObject> {
+        vec![ExamTTObject::new(
+            class(),
+            sittings
+                .into_iter()
+                .map(|(exam_date, papers)| ExamPair::new(exam_date, papers))
+                .collect(),
+        )]
+    }
+
+    /// Diagnostics for one student reading both subjects, with at most two
+    /// papers a day.
+    fn diagnostics(sittings: Vec<(ExamDate, Vec<ExamPaper>)>) -> Vec<Diagnostic> {
+        let (first, second) = subjects();
+        validate(
+            &timetable(sittings),
+            &[student("A", class(), &[first, second])],
+            &window(),
+            2,
+        )
+    }
+
+    #[test]
+    fn a_timetable_within_the_rules_has_no_diagnostics() {
+        let (first, second) = subjects();
+
+        assert_eq!(
+            diagnostics(vec![
+                (sitting(1, ExamSession::Morning), vec![paper(first, "P1")]),
+                (
+                    sitting(1, ExamSession::Afternoon),
+                    vec![paper(second, "P1")]
+                ),
+            ]),
+            Vec::new()
+        );
+    }
+
+    #[test]
+    fn two_papers_in_one_session_double_book_the_student() {
+        let (first, second) = subjects();
+        let papers = vec![paper(first, "P1"), paper(second, "P1")];
+
+        let diagnostics = diagnostics(vec![(sitting(1, ExamSession::Morning), papers.clone())]);
+
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::DoubleBooked {
+                student: student("A", class(), &[]).personal_info().name().clone(),
+                class: class(),
+                exam_date: sitting(1, ExamSession::Morning),
+                papers,
+            }]
+        );
+        assert_eq!(diagnostics[0].severity(), Severity::Error);
+    }
+
+    #[test]
+    fn more_papers_in_a_day_than_the_limit_is_a_warning() {
+        let (first, second) = subjects();
+
+        let diagnostics = diagnostics(vec![
+            (sitting(1, ExamSession::Morning), vec![paper(first, "P1")]),
+            (
+                sitting(1, ExamSession::Afternoon),
+                vec![paper(second, "P1")],
+            ),
+            (sitting(1, ExamSession::Evening), vec![paper(first, "P2")]),
+        ]);
+
+        let too_many = diagnostics
+            .iter()
+            .find(|diagnostic| matches!(diagnostic, Diagnostic::TooManyPapersInDay { .. }))
+            .expect("three papers is over the limit of two");
+        assert_eq!(
+            too_many,
+            &Diagnostic::TooManyPapersInDay {
+                student: student("A", class(), &[]).personal_info().name().clone(),
+                class: class(),
+                date: sitting(1, ExamSession::Morning).date(),
+                papers: 3,
+                limit: 2,
+            }
+        );
+        assert_eq!(too_many.severity(), Severity::Warning);
+    }
+
+    #[test]
+    fn sittings_outside_the_window_are_not_exam_days() {
+        let (first, second) = subjects();
+        let saturday = sitting(6, ExamSession::Morning);
+
+        let diagnostics = diagnostics(vec![
+            (saturday.clone(), vec![paper(first, "P1")]),
+            (sitting(1, ExamSession::Evening), vec![paper(second, "P1")]),
+        ]);
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::NotAnExamDay {
+                    class: class(),
+                    exam_date: saturday,
+                    papers: vec![paper(first, "P1")],
+                },
+                Diagnostic::NotAnExamDay {
+                    class: class(),
+                    exam_date: sitting(1, ExamSession::Evening),
+                    papers: vec![paper(second, "P1")],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn a_subject_without_a_sitting_is_unscheduled() {
+        let (first, second) = subjects();
+
+        let diagnostics = diagnostics(vec![(
+            sitting(1, ExamSession::Morning),
+            vec![paper(first, "P1")],
+        )]);
+
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::Unscheduled {
+                class: class(),
+                subject: second,
+            }]
+        );
+        assert_eq!(diagnostics[0].subjects(), vec![second]);
+    }
+
+    #[test]
+    fn errors_are_listed_before_warnings() {
+        let (first, second) = subjects();
+
+        let diagnostics = diagnostics(vec![
+            (sitting(1, ExamSession::Morning), vec![paper(first, "P1")]),
+            (sitting(1, ExamSession::Afternoon), vec![paper(first, "P2")]),
+            (sitting(1, ExamSession::Evening), vec![paper(first, "P3")]),
+        ]);
+
+        let severities: Vec<Severity> = diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.severity())
+            .collect();
+        // The evening sitting is outside the window and the second subject
+        // is unscheduled, both errors; three papers in a day is a warning.
+        assert_eq!(
+            severities,
+            vec![Severity::Error, Severity::Error, Severity::Warning]
+        );
+        assert!(diagnostics.iter().any(|diagnostic| *diagnostic
+            == Diagnostic::Unscheduled {
+                class: class(),
+                subject: second,
+            }));
+    }
+
+    #[test]
+    fn subjects_are_listed_once_when_their_papers_are_not_adjacent() {
+        let subjects: Vec<Subject> = Subject::all().take(2).collect();
+        let diagnostic = Diagnostic::NotAnExamDay {
+            class: StudentClass::all().next().expect("a built-in class"),
+            exam_date: ExamDate::new(
+                NaiveDate::from_ymd_opt(2026, 5, 2).expect("valid date"),
+                ExamSession::Morning,
+            ),
+            papers: vec![
+                paper(subjects[0], "P1"),
+                paper(subjects[1], "P1"),
+                paper(subjects[0], "P2"),
+            ],
+        };
+
+        let mut expected = subjects.clone();
+        expected.sort();
+        assert_eq!(diagnostic.subjects(), expected);
+    }
+}
@@ -0,0 +1,487 @@
+use leptos::prelude::*;
+use shared::analysis::{ClassAnalysis, RankBy, Weighting};
+use shared::marks::{AcademicPeriod, Term};
+use shared::statistics::{Histogram, Statistics};
+
+use crate::app::{ErrorDisplay, LoadingSpinner};
+use crate::catalogue::{ClassOptions, get_catalogue};
+use crate::marks::get_assessments;
+
+#[server(GetClassAnalysis, "/api/analysis")]
+pub async fn get_class_analysis(
+    class_code: String,
+    assessment_name: String,
+    academic_year: i32,
+    term: u8,
+    weighting: String,
+    rank_by: String,
+) -> Result<Option<ClassAnalysis>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(crate::marks::MARKS_ROLES).await?;
+        let period = crate::marks::requested_period(academic_year, term)?;
+        let Some(weighting) = Weighting::from_name(&weighting) else {
+            return Err(ServerFnError::ServerError(format!("Unknown weighting: {}", weighting)));
+        };
+        let Some(rank_by) = RankBy::from_name(&rank_by) else {
+            return Err(ServerFnError::ServerError(format!("Unknown ranking: {}", rank_by)));
+        };
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+        let class = crate::catalogue::requested_class(&pool, &class_code).await?;
+
+        db::server::analyse_class(&pool, class, &assessment_name, period, &weighting, rank_by)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+fn score(value: f32) -> String {
+    format!("{:.1}", value)
+}
+
+fn z_score(value: f32) -> String {
+    format!("{:+.2}", value)
+}
+
+/// The `<option>` value for an assessment sat in `period`.
+pub(crate) fn sitting_value(name: &str, period: AcademicPeriod) -> String {
+    format!("{}:{}:{}", period.year(), period.term().number(), name)
+}
+
+/// The assessment name and period an `<option>` value from
+/// `sitting_value` stands for.
+pub(crate) fn parse_sitting_value(value: &str) -> Option<(String, AcademicPeriod)> {
+    let mut parts = value.splitn(3, ':');
+    let year = parts.next()?.parse().ok()?;
+    let term = Term::from_number(parts.next()?.parse().ok()?)?;
+    Some((parts.next()?.to_string(), AcademicPeriod::new(year, term)))
+}
+
+/// Class and assessment selects. Only the chosen class's assessments are
+/// offered, newest term first, and changing the class clears the
+/// assessment. An assessment name reused in another term is a separate
+/// choice.
+#[component]
+pub fn ClassAssessmentPicker(
+    class_code: RwSignal<String>,
+    assessment: RwSignal<Option<(String, AcademicPeriod)>>,
+) -> impl IntoView {
+    let assessments = Resource::new(|| (), |_| async move { get_assessments().await });
+    let catalogue = Resource::new(|| (), |_| async move { get_catalogue().await });
+
+    view! {
+        <select
+            class="px-3 py-2 border rounded"
+            prop:value=move || class_code.get()
+            on:change=move |ev| {
+                class_code.set(event_target_value(&ev));
+                assessment.set(None);
+            }
+        >
+            <option value="">"Class..."</option>
+            <ClassOptions catalogue=catalogue />
+        </select>
+        <select
+            class="px-3 py-2 border rounded"
+            prop:value=move || {
+                assessment
+                    .get()
+                    .map(|(name, period)| sitting_value(&name, period))
+                    .unwrap_or_default()
+            }
+            on:change=move |ev| assessment.set(parse_sitting_value(&event_target_value(&ev)))
+        >
+            <option value="">"Assessment..."</option>
+            <Suspense fallback=|| ()>
+                {move || assessments.get().and_then(Result::ok).map(|assessments| {
+                    let class_code = class_code.get();
+                    let mut sittings: Vec<(AcademicPeriod, String)> = assessments
+                        .into_iter()
+                        .filter(|record| record.assessment.class().code() == class_code)
+                        .map(|record| {
+                            (record.assessment.period(), record.assessment.name().to_string())
+                        })
+                        .collect();
+                    sittings.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+                    sittings.dedup();
+                    sittings.into_iter().map(|(period, name)| view! {
+                        <option value=sitting_value(&name, period)>{format!("{} ({})", name, period)}</option>
+                    }).collect_view()
+                })}
+            </Suspense>
+        </select>
+    }
+}
+
+#[component]
+pub fn AnalysisView() -> impl IntoView {
+    let title = use_context::<RwSignal<String>>()
+        .expect("title context should be provided");
+
+    title.set("Exam Analysis".to_string());
+
+    let class_code = RwSignal::new(String::new());
+    let assessment = RwSignal::new(None::<(String, AcademicPeriod)>);
+    let weighting = RwSignal::new(Weighting::default().as_str().to_string());
+    let rank_by = RwSignal::new(RankBy::default().as_str().to_string());
+
+    let analysis = Resource::new(
+        move || (class_code.get(), assessment.get(), weighting.get(), rank_by.get()),
+        |(class_code, assessment, weighting, rank_by)| async move {
+            let Some((assessment_name, period)) = assessment.filter(|_| !class_code.is_empty())
+            else {
+                return Ok(None);
+            };
+            get_class_analysis(
+                class_code,
+                assessment_name,
+                period.year(),
+                period.term().number(),
+                weighting,
+                rank_by,
+            )
+            .await
+        },
+    );
+
+    view! {
+        <div class="p-4 space-y-6">
+            <div class="flex gap-2 items-center">
+                <ClassAssessmentPicker class_code=class_code assessment=assessment />
+                <label class="text-sm">"Weighting"</label>
+                <select
+                    class="px-3 py-2 border rounded"
+                    prop:value=move || weighting.get()
+                    on:change=move |ev| weighting.set(event_target_value(&ev))
+                >
+                    {Weighting::NAMES.iter().map(|name| view! {
+                        <option value=*name>{*name}</option>
+                    }).collect_view()}
+                </select>
+                <label class="text-sm">"Rank by"</label>
+                <select
+                    class="px-3 py-2 border rounded"
+                    prop:value=move || rank_by.get()
+                    on:change=move |ev| rank_by.set(event_target_value(&ev))
+                >
+                    {RankBy::NAMES.iter().map(|name| view! {
+                        <option value=*name>{*name}</option>
+                    }).collect_view()}
+                </select>
+            </div>
+            <Suspense fallback=move || view! { <LoadingSpinner/> }>
+                {move || analysis.get().map(|result| match result {
+                    Ok(Some(analysis)) => view! { <ClassAnalysisView analysis=analysis /> }.into_any(),
+                    Ok(None) => view! {
+                        <p class="text-gray-500">"Choose a class and assessment with marks entered."</p>
+                    }.into_any(),
+                    Err(e) => view! { <ErrorDisplay error=e.to_string() /> }.into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
+
+/// Score distributions for one class and assessment, for the dashboard.
+#[component]
+pub fn ResultsDashboard() -> impl IntoView {
+    let class_code = RwSignal::new(String::new());
+    let assessment = RwSignal::new(None::<(String, AcademicPeriod)>);
+
+    let analysis = Resource::new(
+        move || (class_code.get(), assessment.get()),
+        |(class_code, assessment)| async move {
+            let Some((assessment_name, period)) = assessment.filter(|_| !class_code.is_empty())
+            else {
+                return Ok(None);
+            };
+            get_class_analysis(
+                class_code,
+                assessment_name,
+                period.year(),
+                period.term().number(),
+                Weighting::default().as_str().to_string(),
+                RankBy::default().as_str().to_string(),
+            )
+            .await
+        },
+    );
+
+    view! {
+        <section class="space-y-4">
+            <div class="flex gap-2 items-center">
+                <h2 class="text-lg font-semibold mr-4">"Results"</h2>
+                <ClassAssessmentPicker class_code=class_code assessment=assessment />
+            </div>
+            <Suspense fallback=move || view! { <LoadingSpinner/> }>
+                {move || analysis.get().map(|result| match result {
+                    Ok(Some(analysis)) => {
+                        let class_summary = analysis.class_summary();
+                        view! {
+                            <div class="grid grid-cols-1 md:grid-cols-2 xl:grid-cols-3 gap-4">
+                                <StatisticsCard
+                                    title="Overall average".to_string()
+                                    statistics=class_summary.overall_statistics().clone()
+                                    pass_rate=None
+                                    histogram=class_summary.overall_histogram().clone()
+                                />
+                                {class_summary.class_subject_performance_summary().iter().map(|summary| view! {
+                                    <StatisticsCard
+                                        title=summary.subject().to_string()
+                                        statistics=summary.statistics().clone()
+                                        pass_rate=summary.pass_rate()
+                                        histogram=summary.histogram().clone()
+                                    />
+                                }).collect_view()}
+                            </div>
+                        }.into_any()
+                    }
+                    Ok(None) => view! {
+                        <p class="text-gray-500">"Choose a class and assessment with marks entered."</p>
+                    }.into_any(),
+                    Err(e) => view! { <ErrorDisplay error=e.to_string() /> }.into_any(),
+                })}
+            </Suspense>
+        </section>
+    }
+}
+
+#[component]
+fn StatisticsCard(
+    title: String,
+    statistics: Statistics,
+    pass_rate: Option<f32>,
+    histogram: Histogram,
+) -> impl IntoView {
+    let peak = histogram.peak().max(1);
+
+    view! {
+        <div class="bg-white rounded-lg shadow p-4">
+            <h3 class="font-semibold mb-2">{title}</h3>
+            <dl class="grid grid-cols-4 gap-2 text-sm mb-4">
+                <div><dt class="text-gray-500">"Mean"</dt><dd>{score(statistics.mean())}</dd></div>
+                <div><dt class="text-gray-500">"Median"</dt><dd>{score(statistics.median())}</dd></div>
+                <div><dt class="text-gray-500">"Std Dev"</dt><dd>{score(statistics.standard_deviation())}</dd></div>
+                <div>
+                    <dt class="text-gray-500">"Pass Rate"</dt>
+                    <dd>{pass_rate.map(|pass_rate| format!("{}%", score(pass_rate))).unwrap_or_else(|| "—".to_string())}</dd>
+                </div>
+                <div><dt class="text-gray-500">"Q1"</dt><dd>{score(statistics.lower_quartile())}</dd></div>
+                <div><dt class="text-gray-500">"Q3"</dt><dd>{score(statistics.upper_quartile())}</dd></div>
+                <div><dt class="text-gray-500">"Range"</dt><dd>{format!("{}–{}", score(statistics.minimum()), score(statistics.maximum()))}</dd></div>
+                <div><dt class="text-gray-500">"Students"</dt><dd>{statistics.count()}</dd></div>
+            </dl>
+            <div class="flex items-end gap-1 h-32">
+                {histogram.bins().iter().map(|bin| {
+                    let height = bin.count() as f32 / peak as f32 * 100.0;
+                    view! {
+                        <div class="flex-1 flex flex-col justify-end h-full" title=format!("{}: {}", bin.label(), bin.count())>
+                            <div class="bg-blue-500 rounded-t" style=format!("height: {:.0}%", height)></div>
+                        </div>
+                    }
+                }).collect_view()}
+            </div>
+            <div class="flex gap-1 text-xs text-gray-500 mt-1">
+                {histogram.bins().iter().map(|bin| view! {
+                    <div class="flex-1 text-center">{bin.lower()}</div>
+                }).collect_view()}
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn ClassAnalysisView(analysis: ClassAnalysis) -> impl IntoView {
+    let show_z_scores = RwSignal::new(false);
+    let class_summary = analysis.class_summary().clone();
+    let subjects: Vec<_> = class_summary
+        .class_subject_performance_summary()
+        .iter()
+        .map(|summary| summary.subject())
+        .collect();
+
+    view! {
+        <section class="bg-white rounded-lg shadow p-4">
+            <h2 class="text-lg font-semibold mb-4">
+                {format!("{} — class average {}%", class_summary.class(), score(class_summary.class_overall_average()))}
+            </h2>
+            <table class="min-w-full divide-y divide-gray-200">
+                <thead class="bg-gray-50">
+                    <tr>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Subject"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Average %"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Median"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Std Dev"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Quartiles"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Pass Rate"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Grade"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Distribution"</th>
+                    </tr>
+                </thead>
+                <tbody class="divide-y divide-gray-200">
+                    {class_summary.class_subject_performance_summary().iter().map(|summary| {
+                        let grades = analysis.grades_for(summary.subject());
+                        let statistics = summary.statistics();
+                        view! {
+                            <tr class="hover:bg-gray-50">
+                                <td class="px-4 py-2 text-sm">{summary.subject().to_string()}</td>
+                                <td class="px-4 py-2 text-sm">{score(summary.subject_average())}</td>
+                                <td class="px-4 py-2 text-sm">{score(statistics.median())}</td>
+                                <td class="px-4 py-2 text-sm">{score(statistics.standard_deviation())}</td>
+                                <td class="px-4 py-2 text-sm">
+                                    {format!("{} / {}", score(statistics.lower_quartile()), score(statistics.upper_quartile()))}
+                                </td>
+                                <td class="px-4 py-2 text-sm">
+                                    {summary.pass_rate().map(|pass_rate| format!("{}%", score(pass_rate))).unwrap_or_else(|| "—".to_string())}
+                                </td>
+                                <td class="px-4 py-2 text-sm font-semibold">
+                                    {grades.map(|grades| grades.average_grade().to_string())}
+                                </td>
+                                <td class="px-4 py-2 text-sm">
+                                    {grades.map(|grades| grades.distribution().iter().map(|count| view! {
+                                        <span class="inline-block mr-3">
+                                            <span class="font-medium">{count.grade().to_string()}</span>
+                                            " "
+                                            {count.count()}
+                                        </span>
+                                    }).collect_view())}
+                                </td>
+                            </tr>
+                        }
+                    }).collect_view()}
+                </tbody>
+            </table>
+        </section>
+        <section class="bg-white rounded-lg shadow p-4 overflow-auto-x">
+            <div class="flex justify-between items-center mb-4">
+                <h2 class="text-lg font-semibold">"Students"</h2>
+                <label class="text-sm">
+                    <input
+                        type="checkbox"
+                        class="mr-2"
+                        prop:checked=move || show_z_scores.get()
+                        on:change=move |ev| show_z_scores.set(event_target_checked(&ev))
+                    />
+                    "Show z-scores"
+                </label>
+            </div>
+            <table class="min-w-full divide-y divide-gray-200">
+                <thead class="bg-gray-50">
+                    <tr>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Candidate"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Name"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Average %"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">
+                            {if analysis.rank_by() == RankBy::Total { "Position (Total)" } else { "Position" }}
+                        </th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase" title="Mean of the student's z-scores">"Standardised"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Best"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Worst"</th>
+                        {subjects.iter().map(|subject| view! {
+                            <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">{subject.name()}</th>
+                        }).collect_view()}
+                    </tr>
+                </thead>
+                <tbody class="divide-y divide-gray-200">
+                    {analysis.student_summaries().iter().map(|summary| {
+                        let name = summary.student().personal_info().name();
+                        view! {
+                            <tr class="hover:bg-gray-50">
+                                <td class="px-4 py-2 text-sm">{summary.student().candidate_number().to_string()}</td>
+                                <td class="px-4 py-2 text-sm">{format!("{} {}", name.first_name(), name.last_name())}</td>
+                                <td class="px-4 py-2 text-sm font-semibold">{score(summary.overall_average())}</td>
+                                <td class="px-4 py-2 text-sm">
+                                    {summary.position().map(|position| position.to_string())}
+                                </td>
+                                <td class="px-4 py-2 text-sm">
+                                    {analysis.standardised_average(summary.student().candidate_number()).map(z_score).unwrap_or_else(|| "—".to_string())}
+                                </td>
+                                <td class="px-4 py-2 text-sm">{summary.best_subject().name()}</td>
+                                <td class="px-4 py-2 text-sm">{summary.worst_subject().name()}</td>
+                                {subjects.iter().map(|subject| {
+                                    let detail = summary
+                                        .subject_specific_details()
+                                        .iter()
+                                        .find(|detail| detail.subject() == *subject);
+                                    let title = detail.map(|detail| {
+                                        detail
+                                            .component_specific_details()
+                                            .component_list()
+                                            .iter()
+                                            .map(|component| format!(
+                                                "{}: {} (class {})",
+                                                component.component_identifier(),
+                                                score(component.specific_score()),
+                                                score(component.class_average())
+                                            ))
+                                            .collect::<Vec<_>>()
+                                            .join("\n")
+                                    });
+                                    let candidate_number = summary.student().candidate_number();
+                                    let score = detail.map(|detail| {
+                                        let score = score(detail.overall_subject_score());
+                                        let score = match analysis.grade(candidate_number, *subject) {
+                                            Some(grade) => format!("{} ({})", score, grade),
+                                            None => score,
+                                        };
+                                        match detail.position() {
+                                            Some(position) => format!("{}, {}", score, position),
+                                            None => score,
+                                        }
+                                    }).unwrap_or_else(|| "—".to_string());
+                                    let z = analysis
+                                        .z_score(candidate_number, *subject)
+                                        .map(z_score)
+                                        .unwrap_or_else(|| "—".to_string());
+                                    view! {
+                                        <td class="px-4 py-2 text-sm" title=title>
+                                            {move || if show_z_scores.get() { z.clone() } else { score.clone() }}
+                                        </td>
+                                    }
+                                }).collect_view()}
+                            </tr>
+                        }
+                    }).collect_view()}
+                </tbody>
+            </table>
+        </section>
+    }
+}
+
+pub mod db {
+    #[cfg(feature = "ssr")]
+    pub mod server {
+        use crate::app::db::Error;
+        use shared::analysis::{ClassAnalysis, RankBy, Weighting};
+        use shared::domain::StudentClass;
+        use shared::marks::AcademicPeriod;
+        use sqlx::PgPool;
+
+        pub async fn analyse_class(
+            pool: &PgPool,
+            class: StudentClass,
+            assessment_name: &str,
+            period: AcademicPeriod,
+            weighting: &Weighting,
+            rank_by: RankBy,
+        ) -> Result<Option<ClassAnalysis>, Error> {
+            let students = crate::students::db::server::load_class_list(pool, class, None).await?;
+            let results =
+                crate::marks::db::server::load_results(pool, class, assessment_name, period)
+                    .await?;
+            let grading = crate::grading::db::server::load_grading_policy(pool).await?;
+
+            Ok(shared::analysis::analyse_class(
+                class, &students, &results, weighting, &grading, rank_by,
+            ))
+        }
+    }
+}
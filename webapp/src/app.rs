@@ -9,6 +9,21 @@ use leptos_router::{
 use reactive_stores::{Store};
 use reactive_graph::traits::{Get as ReactiveGet, Set as ReactiveSet, Read, Update};
 
+use crate::analysis::{AnalysisView, ResultsDashboard};
+use crate::catalogue::CatalogueView;
+use crate::early_warning::AtRiskWidget;
+use crate::effectiveness::EffectivenessView;
+use crate::grading::GradingView;
+use crate::invigilation::InvigilationView;
+use crate::marks::MarksView;
+use crate::reports::ReportCardsView;
+use crate::rooms::RoomsView;
+use crate::rosters::RostersView;
+use crate::students::StudentsView;
+use crate::teachers::{MyClassesView, TeachersView};
+use crate::timetables::TimetablesView;
+use crate::trends::PerformanceTrends;
+
 #[server(GetUsers, "/api")]
 pub async fn get_users() -> Result<Vec<db::User>, ServerFnError> {
     #[cfg(feature = "ssr")]
@@ -208,6 +223,19 @@ pub fn App() -> impl IntoView {
                     <Route path=StaticSegment("") view=DashboardView />
                     <Route path=StaticSegment(Page::Users.path()) view=UserManagementView />
                     <Route path=StaticSegment(Page::Roles.path()) view=RoleManagementView />
+                    <Route path=StaticSegment(Page::MyClasses.path()) view=MyClassesView />
+                    <Route path=StaticSegment(Page::Catalogue.path()) view=CatalogueView />
+                    <Route path=StaticSegment(Page::Students.path()) view=StudentsView />
+                    <Route path=StaticSegment(Page::Teachers.path()) view=TeachersView />
+                    <Route path=StaticSegment(Page::Rosters.path()) view=RostersView />
+                    <Route path=StaticSegment(Page::Rooms.path()) view=RoomsView />
+                    <Route path=StaticSegment(Page::Invigilation.path()) view=InvigilationView />
+                    <Route path=StaticSegment(Page::Timetables.path()) view=TimetablesView />
+                    <Route path=StaticSegment(Page::Marks.path()) view=MarksView />
+                    <Route path=StaticSegment(Page::Analysis.path()) view=AnalysisView />
+                    <Route path=StaticSegment(Page::Grading.path()) view=GradingView />
+                    <Route path=StaticSegment(Page::Reports.path()) view=ReportCardsView />
+                    <Route path=StaticSegment(Page::Effectiveness.path()) view=EffectivenessView />
                     <Route path=StaticSegment(Page::Settings.path()) view=SettingsView />
                 </ParentRoute>
                 // <ProtectedParentRoute 
@@ -481,12 +509,51 @@ fn Sidebar() -> impl IntoView {
                 <a href="/admin" class="block px-3 py-4 hover:bg-gray-700">
                     "Dashboard"
                 </a>
+                <a href="/admin/my-classes" class="block px-3 py-4 hover:bg-gray-700">
+                    "My Classes"
+                </a>
                 <a href="/admin/users" class="block px-3 py-4 hover:bg-gray-700">
                     "Users"
                 </a>
                 <a href="/admin/roles" class="block px-3 py-4 hover:bg-gray-700">
                     "Roles"
                 </a>
+                <a href="/admin/catalogue" class="block px-3 py-4 hover:bg-gray-700">
+                    "Catalogue"
+                </a>
+                <a href="/admin/students" class="block px-3 py-4 hover:bg-gray-700">
+                    "Students"
+                </a>
+                <a href="/admin/teachers" class="block px-3 py-4 hover:bg-gray-700">
+                    "Teachers"
+                </a>
+                <a href="/admin/rosters" class="block px-3 py-4 hover:bg-gray-700">
+                    "Roster Import"
+                </a>
+                <a href="/admin/rooms" class="block px-3 py-4 hover:bg-gray-700">
+                    "Rooms"
+                </a>
+                <a href="/admin/invigilation" class="block px-3 py-4 hover:bg-gray-700">
+                    "Invigilation"
+                </a>
+                <a href="/admin/timetables" class="block px-3 py-4 hover:bg-gray-700">
+                    "Timetables"
+                </a>
+                <a href="/admin/marks" class="block px-3 py-4 hover:bg-gray-700">
+                    "Marks"
+                </a>
+                <a href="/admin/analysis" class="block px-3 py-4 hover:bg-gray-700">
+                    "Exam Analysis"
+                </a>
+                <a href="/admin/grading" class="block px-3 py-4 hover:bg-gray-700">
+                    "Grade Schemes"
+                </a>
+                <a href="/admin/reports" class="block px-3 py-4 hover:bg-gray-700">
+                    "Report Cards"
+                </a>
+                <a href="/admin/effectiveness" class="block px-3 py-4 hover:bg-gray-700">
+                    "Effectiveness"
+                </a>
                 <a href="#" class="block px-3 py-4 hover:bg-gray-700">
                     "Audits"
                 </a>
@@ -557,7 +624,7 @@ fn ActionBar() -> impl IntoView {
 }
 
 #[component]
-fn LoadingSpinner() -> impl IntoView {
+pub fn LoadingSpinner() -> impl IntoView {
     view! {
         <div class="bg-white rounded-lg shadow px-6 py-12 animate-fade-in">
             <div class="flex flex-col items-center space-y-4">
@@ -574,7 +641,13 @@ fn DashboardView() -> impl IntoView {
 
     title.set("Dashboard".to_string());
 
-    view! { <p>"Dashboard view"</p> }
+    view! {
+        <div class="p-4 space-y-6">
+            <ResultsDashboard />
+            <AtRiskWidget />
+            <PerformanceTrends />
+        </div>
+    }
 }
 
 #[component]
@@ -690,6 +763,19 @@ pub fn ErrorDisplay(error: String) -> impl IntoView {
     }
 }
 
+/// Shows the error from the last run of a server action, if it failed.
+#[component]
+pub fn ActionError<T: Clone + Send + Sync + 'static>(
+    result: RwSignal<Option<Result<T, ServerFnError>>>,
+) -> impl IntoView {
+    view! {
+        {move || match result.get() {
+            Some(Err(e)) => Some(view! { <p class="mt-2 text-sm text-red-700">{e.to_string()}</p> }),
+            _ => None,
+        }}
+    }
+}
+
 fn RoleManagementView() -> impl IntoView {
     let title = use_context::<RwSignal<String>>()
         .expect("title context should be provided");
@@ -718,8 +804,21 @@ fn Footer() -> impl IntoView {
 pub enum Page {
     Login,
     AdminPanel,
+    MyClasses,
     Users,
     Roles,
+    Catalogue,
+    Students,
+    Teachers,
+    Rosters,
+    Rooms,
+    Invigilation,
+    Timetables,
+    Marks,
+    Analysis,
+    Grading,
+    Reports,
+    Effectiveness,
     Settings,
 }
 
@@ -728,8 +827,21 @@ impl Page {
         match self {
             Self::Login => "/",
             Self::AdminPanel => "/admin",
+            Self::MyClasses => "my-classes",
             Self::Users => "users",
             Self::Roles => "roles",
+            Self::Catalogue => "catalogue",
+            Self::Students => "students",
+            Self::Teachers => "teachers",
+            Self::Rosters => "rosters",
+            Self::Rooms => "rooms",
+            Self::Invigilation => "invigilation",
+            Self::Timetables => "timetables",
+            Self::Marks => "marks",
+            Self::Analysis => "analysis",
+            Self::Grading => "grading",
+            Self::Reports => "reports",
+            Self::Effectiveness => "effectiveness",
             Self::Settings => "settings",
         }
     }
@@ -777,6 +889,9 @@ pub mod db {
         RoleNotFound,
         SeedUserFailed,
         DatabaseQueryFailed,
+        NotAuthenticated,
+        NotAuthorized,
+        UnknownCatalogueCode,
     }
 
     #[cfg(feature = "ssr")]
@@ -801,6 +916,11 @@ pub mod db {
                 Self::RoleNotFound => "Failed to retrieve role",
                 Self::SeedUserFailed => "Failed to seed user",
                 Self::DatabaseQueryFailed => "Failed to make database query",
+                Self::NotAuthenticated => "You must be logged in to do that.",
+                Self::NotAuthorized => "You do not have permission to do that.",
+                Self::UnknownCatalogueCode => {
+                    "A stored record refers to a class or subject that is not in the catalogue."
+                }
             };
 
             write!(f, "{}", error_msg)
@@ -866,7 +986,9 @@ pub mod db {
 
             for (name, description) in [
                 ("admin", "Full system access"),
-                ("teacher", "Can view students and enter grades")
+                ("teacher", "Can view students and enter grades"),
+                ("exams_officer", "Manages exam timetables and approves duty changes"),
+                ("hod", "Head of department, sees teacher and subject effectiveness"),
             ] {
                 sqlx::query(
                     "INSERT INTO roles (name, description)
@@ -1150,6 +1272,55 @@ pub mod db {
             Ok(Some(user_session))
         }
 
+        /// Returns the logged in user if their role is one of `roles`.
+        pub async fn require_role(roles: &[&str]) -> Result<UserSession, ServerFnError> {
+            let Some(user_session) = verify_session_impl().await? else {
+                return Err(ServerFnError::ServerError(Error::NotAuthenticated.to_string()));
+            };
+            let has_role = user_session
+                .role_name
+                .as_deref()
+                .is_some_and(|role_name| roles.contains(&role_name));
+            if !has_role {
+                log::warn!("User {} denied access, requires one of {:?}", user_session.username, roles);
+                return Err(ServerFnError::ServerError(Error::NotAuthorized.to_string()));
+            }
+            Ok(user_session)
+        }
+
+        /// `require_role` for plain actix handlers, such as file downloads,
+        /// that are not server functions.
+        pub async fn require_session_role(
+            pool: &PgPool,
+            session: &Session,
+            roles: &[&str],
+        ) -> Result<UserSession, Error> {
+            let Some(user_session) = session.get::<UserSession>("user_session").ok().flatten() else {
+                return Err(Error::NotAuthenticated);
+            };
+            let session_exists = sqlx::query_scalar::<_, bool>(
+                "SELECT EXISTS(SELECT 1 FROM user_sessions WHERE session_id = $1 AND expires_at > NOW())")
+                .bind(&user_session.session_id)
+                .fetch_one(pool)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to check session: {}", e);
+                    Error::DatabaseQueryFailed
+                })?;
+            if !session_exists {
+                return Err(Error::NotAuthenticated);
+            }
+            let has_role = user_session
+                .role_name
+                .as_deref()
+                .is_some_and(|role_name| roles.contains(&role_name));
+            if !has_role {
+                log::warn!("User {} denied access, requires one of {:?}", user_session.username, roles);
+                return Err(Error::NotAuthorized);
+            }
+            Ok(user_session)
+        }
+
         pub async fn logout(pool: &PgPool, session: Session) -> Result<(), ServerFnError> {
             if let Ok(Some(user_session)) = session
                 .get::<UserSession>("user_session")
@@ -0,0 +1,1175 @@
+use leptos::prelude::*;
+use shared::catalogue::Catalogue;
+
+use crate::app::{ActionError, ErrorDisplay, LoadingSpinner};
+
+#[server(GetCatalogue, "/api/catalogue")]
+pub async fn get_catalogue() -> Result<Catalogue, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        db::server::load_catalogue(&pool)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+#[server(SaveProgramme, "/api/catalogue")]
+pub async fn save_programme(
+    code: String,
+    name: String,
+    exam_board: String,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(&["admin"]).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        db::server::upsert_programme(&pool, code.trim(), name.trim(), exam_board.trim())
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+#[server(DeleteProgramme, "/api/catalogue")]
+pub async fn delete_programme(code: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(&["admin"]).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        db::server::delete_programme(&pool, &code)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+#[server(SaveClassGroup, "/api/catalogue")]
+pub async fn save_class_group(
+    code: String,
+    name: String,
+    programme_code: String,
+    year_order: i32,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(&["admin"]).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        db::server::upsert_class_group(&pool, code.trim(), name.trim(), &programme_code, year_order)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+#[server(DeleteClassGroup, "/api/catalogue")]
+pub async fn delete_class_group(code: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use leptos::server_fn::error::NoCustomError;
+
+        crate::app::db::server::require_role(&["admin"]).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        let is_in_use = db::server::class_group_in_use(&pool, &code)
+            .await
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+        if is_in_use {
+            return Err(ServerFnError::ServerError(format!(
+                "{} is still used by students, teachers, assessments or the timetable",
+                code
+            )));
+        }
+
+        db::server::delete_class_group(&pool, &code)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+#[server(SaveSubject, "/api/catalogue")]
+pub async fn save_subject(
+    code: String,
+    name: String,
+    programme_code: String,
+    syllabus_code: String,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(&["admin"]).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        let syllabus_code = Some(syllabus_code.trim()).filter(|code| !code.is_empty());
+        db::server::upsert_subject(&pool, code.trim(), name.trim(), &programme_code, syllabus_code)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+#[server(SetSubjectActive, "/api/catalogue")]
+pub async fn set_subject_active(code: String, is_active: bool) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(&["admin"]).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        db::server::set_subject_active(&pool, &code, is_active)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+/// Marks a subject as one every student of its programme must pass, or not.
+#[server(SetSubjectCore, "/api/catalogue")]
+pub async fn set_subject_core(code: String, is_core: bool) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(&["admin"]).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        db::server::set_subject_core(&pool, &code, is_core)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+#[server(DeleteSubject, "/api/catalogue")]
+pub async fn delete_subject(code: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use leptos::server_fn::error::NoCustomError;
+
+        crate::app::db::server::require_role(&["admin"]).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        let is_in_use = db::server::subject_in_use(&pool, &code)
+            .await
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+        if is_in_use {
+            return Err(ServerFnError::ServerError(format!(
+                "{} is still used by students, teachers, assessments or the timetable",
+                code
+            )));
+        }
+
+        db::server::delete_subject(&pool, &code)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+#[server(SaveSubjectComponent, "/api/catalogue")]
+pub async fn save_subject_component(
+    subject_code: String,
+    code: String,
+    name: String,
+    duration_minutes: i32,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(&["admin"]).await?;
+        if duration_minutes <= 0 {
+            return Err(ServerFnError::ServerError(
+                "Paper duration must be a positive number of minutes".into(),
+            ));
+        }
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        db::server::upsert_subject_component(&pool, &subject_code, code.trim(), name.trim(), duration_minutes)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+#[server(DeleteSubjectComponent, "/api/catalogue")]
+pub async fn delete_subject_component(
+    subject_code: String,
+    code: String,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(&["admin"]).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        db::server::delete_subject_component(&pool, &subject_code, &code)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+/// The class a request names, looked up in the catalogue.
+#[cfg(feature = "ssr")]
+pub async fn requested_class(
+    pool: &sqlx::PgPool,
+    code: &str,
+) -> Result<shared::domain::StudentClass, ServerFnError> {
+    let catalogue = db::server::load_catalogue(pool)
+        .await
+        .map_err(|e| ServerFnError::<leptos::server_fn::error::NoCustomError>::ServerError(e.to_string()))?;
+    catalogue
+        .class_for(code)
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// The `<option>`s of a class picker: every class in the catalogue, in year
+/// order.
+#[component]
+pub fn ClassOptions(catalogue: Resource<Result<Catalogue, ServerFnError>>) -> impl IntoView {
+    view! {
+        <Suspense fallback=|| ()>
+            {move || catalogue.get().and_then(Result::ok).map(|catalogue| {
+                catalogue.classes().into_iter().map(|class| view! {
+                    <option value=class.code()>{class.to_string()}</option>
+                }).collect_view()
+            })}
+        </Suspense>
+    }
+}
+
+#[component]
+pub fn CatalogueView() -> impl IntoView {
+    let title = use_context::<RwSignal<String>>()
+        .expect("title context should be provided");
+
+    title.set("Subject Catalogue".to_string());
+
+    let save_programme = ServerAction::<SaveProgramme>::new();
+    let delete_programme = ServerAction::<DeleteProgramme>::new();
+    let save_class_group = ServerAction::<SaveClassGroup>::new();
+    let delete_class_group = ServerAction::<DeleteClassGroup>::new();
+    let save_subject = ServerAction::<SaveSubject>::new();
+    let set_subject_active = ServerAction::<SetSubjectActive>::new();
+    let set_subject_core = ServerAction::<SetSubjectCore>::new();
+    let delete_subject = ServerAction::<DeleteSubject>::new();
+    let save_component = ServerAction::<SaveSubjectComponent>::new();
+    let delete_component = ServerAction::<DeleteSubjectComponent>::new();
+
+    let catalogue = Resource::new(
+        move || {
+            (
+                save_programme.version().get(),
+                delete_programme.version().get(),
+                save_class_group.version().get(),
+                delete_class_group.version().get(),
+                save_subject.version().get(),
+                set_subject_active.version().get(),
+                set_subject_core.version().get(),
+                delete_subject.version().get(),
+                save_component.version().get(),
+                delete_component.version().get(),
+            )
+        },
+        |_| async move { get_catalogue().await },
+    );
+
+    view! {
+        <div class="p-4 space-y-8">
+            <Suspense fallback=move || view! { <LoadingSpinner/> }>
+                {move || catalogue.get().map(|result| match result {
+                    Ok(catalogue) => view! {
+                        <ProgrammeSection
+                            catalogue=catalogue.clone()
+                            save_programme=save_programme
+                            delete_programme=delete_programme
+                        />
+                        <ClassGroupSection
+                            catalogue=catalogue.clone()
+                            save_class_group=save_class_group
+                            delete_class_group=delete_class_group
+                        />
+                        <SubjectSection
+                            catalogue=catalogue
+                            save_subject=save_subject
+                            set_subject_active=set_subject_active
+                            set_subject_core=set_subject_core
+                            delete_subject=delete_subject
+                            save_component=save_component
+                            delete_component=delete_component
+                        />
+                    }.into_any(),
+                    Err(e) => view! { <ErrorDisplay error=e.to_string() /> }.into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn ProgrammeSection(
+    catalogue: Catalogue,
+    save_programme: ServerAction<SaveProgramme>,
+    delete_programme: ServerAction<DeleteProgramme>,
+) -> impl IntoView {
+    let code = RwSignal::new(String::new());
+    let name = RwSignal::new(String::new());
+    let exam_board = RwSignal::new(String::new());
+
+    view! {
+        <section class="bg-white rounded-lg shadow p-4">
+            <h2 class="text-lg font-semibold mb-4">"Programmes"</h2>
+            <table class="min-w-full divide-y divide-gray-200 mb-4">
+                <thead class="bg-gray-50">
+                    <tr>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Code"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Name"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Exam Board"</th>
+                        <th class="px-4 py-2"></th>
+                    </tr>
+                </thead>
+                <tbody class="divide-y divide-gray-200">
+                    {catalogue.programmes().iter().map(|programme| {
+                        let edit_programme = programme.clone();
+                        let programme_code = programme.code().to_string();
+                        view! {
+                            <tr class="hover:bg-gray-50">
+                                <td class="px-4 py-2 text-sm">{programme.code().to_string()}</td>
+                                <td class="px-4 py-2 text-sm">{programme.name().to_string()}</td>
+                                <td class="px-4 py-2 text-sm">{programme.exam_board().to_string()}</td>
+                                <td class="px-4 py-2 text-sm text-right space-x-2">
+                                    <button
+                                        class="text-blue-600"
+                                        on:click=move |_| {
+                                            code.set(edit_programme.code().to_string());
+                                            name.set(edit_programme.name().to_string());
+                                            exam_board.set(edit_programme.exam_board().to_string());
+                                        }
+                                    >
+                                        "Edit"
+                                    </button>
+                                    <button
+                                        class="text-red-600"
+                                        on:click=move |_| {
+                                            delete_programme.dispatch(DeleteProgramme { code: programme_code.clone() });
+                                        }
+                                    >
+                                        "Delete"
+                                    </button>
+                                </td>
+                            </tr>
+                        }
+                    }).collect_view()}
+                </tbody>
+            </table>
+            <ActionForm attr:class="flex gap-2 items-end" action=save_programme>
+                <input type="text" name="code" placeholder="Code" required class="px-3 py-2 border rounded w-32" bind:value=code />
+                <input type="text" name="name" placeholder="Name" required class="px-3 py-2 border rounded" bind:value=name />
+                <input type="text" name="exam_board" placeholder="Exam board" required class="px-3 py-2 border rounded" bind:value=exam_board />
+                <button type="submit" class="bg-gray-700 text-white px-4 py-2 rounded">"Save Programme"</button>
+            </ActionForm>
+            <ActionError result=save_programme.value() />
+            <ActionError result=delete_programme.value() />
+        </section>
+    }
+}
+
+#[component]
+fn ClassGroupSection(
+    catalogue: Catalogue,
+    save_class_group: ServerAction<SaveClassGroup>,
+    delete_class_group: ServerAction<DeleteClassGroup>,
+) -> impl IntoView {
+    let code = RwSignal::new(String::new());
+    let name = RwSignal::new(String::new());
+    let programme_code = RwSignal::new(String::new());
+    let year_order = RwSignal::new(String::new());
+
+    let mut class_groups = catalogue.class_groups().to_vec();
+    class_groups.sort_by_key(|class_group| class_group.year_order());
+
+    view! {
+        <section class="bg-white rounded-lg shadow p-4">
+            <h2 class="text-lg font-semibold mb-4">"Classes"</h2>
+            <table class="min-w-full divide-y divide-gray-200 mb-4">
+                <thead class="bg-gray-50">
+                    <tr>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Code"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Name"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Programme"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Year Order"</th>
+                        <th class="px-4 py-2"></th>
+                    </tr>
+                </thead>
+                <tbody class="divide-y divide-gray-200">
+                    {class_groups.into_iter().map(|class_group| {
+                        let edit_class_group = class_group.clone();
+                        let class_code = class_group.code().to_string();
+                        view! {
+                            <tr class="hover:bg-gray-50">
+                                <td class="px-4 py-2 text-sm">{class_group.code().to_string()}</td>
+                                <td class="px-4 py-2 text-sm">{class_group.name().to_string()}</td>
+                                <td class="px-4 py-2 text-sm">{class_group.programme_code().to_string()}</td>
+                                <td class="px-4 py-2 text-sm">{class_group.year_order()}</td>
+                                <td class="px-4 py-2 text-sm text-right space-x-2">
+                                    <button
+                                        class="text-blue-600"
+                                        on:click=move |_| {
+                                            code.set(edit_class_group.code().to_string());
+                                            name.set(edit_class_group.name().to_string());
+                                            programme_code.set(edit_class_group.programme_code().to_string());
+                                            year_order.set(edit_class_group.year_order().to_string());
+                                        }
+                                    >
+                                        "Edit"
+                                    </button>
+                                    <button
+                                        class="text-red-600"
+                                        on:click=move |_| {
+                                            delete_class_group.dispatch(DeleteClassGroup { code: class_code.clone() });
+                                        }
+                                    >
+                                        "Delete"
+                                    </button>
+                                </td>
+                            </tr>
+                        }
+                    }).collect_view()}
+                </tbody>
+            </table>
+            <ActionForm attr:class="flex gap-2 items-end" action=save_class_group>
+                <input type="text" name="code" placeholder="Code" required class="px-3 py-2 border rounded w-32" bind:value=code />
+                <input type="text" name="name" placeholder="Name" required class="px-3 py-2 border rounded" bind:value=name />
+                <ProgrammeSelect catalogue=catalogue.clone() selected=programme_code />
+                <input type="number" name="year_order" placeholder="Year order" required class="px-3 py-2 border rounded w-32" bind:value=year_order />
+                <button type="submit" class="bg-gray-700 text-white px-4 py-2 rounded">"Save Class"</button>
+            </ActionForm>
+            <ActionError result=save_class_group.value() />
+            <ActionError result=delete_class_group.value() />
+        </section>
+    }
+}
+
+#[component]
+fn SubjectSection(
+    catalogue: Catalogue,
+    save_subject: ServerAction<SaveSubject>,
+    set_subject_active: ServerAction<SetSubjectActive>,
+    set_subject_core: ServerAction<SetSubjectCore>,
+    delete_subject: ServerAction<DeleteSubject>,
+    save_component: ServerAction<SaveSubjectComponent>,
+    delete_component: ServerAction<DeleteSubjectComponent>,
+) -> impl IntoView {
+    let code = RwSignal::new(String::new());
+    let name = RwSignal::new(String::new());
+    let programme_code = RwSignal::new(String::new());
+    let syllabus_code = RwSignal::new(String::new());
+
+    let component_subject_code = RwSignal::new(String::new());
+    let component_code = RwSignal::new(String::new());
+    let component_name = RwSignal::new(String::new());
+    let component_duration = RwSignal::new(String::new());
+
+    view! {
+        <section class="bg-white rounded-lg shadow p-4">
+            <h2 class="text-lg font-semibold mb-4">"Subjects"</h2>
+            <table class="min-w-full divide-y divide-gray-200 mb-4">
+                <thead class="bg-gray-50">
+                    <tr>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Code"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Name"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Programme"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Syllabus Code"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Components"</th>
+                        <th class="px-4 py-2"></th>
+                    </tr>
+                </thead>
+                <tbody class="divide-y divide-gray-200">
+                    {catalogue.subjects().iter().map(|subject| {
+                        let edit_subject = subject.clone();
+                        let subject_code = subject.code().to_string();
+                        let toggle_code = subject_code.clone();
+                        let core_code = subject_code.clone();
+                        let component_target = subject_code.clone();
+                        let is_active = subject.is_active();
+                        let is_core = subject.is_core();
+                        view! {
+                            <tr class="hover:bg-gray-50" class:text-gray-400=!is_active>
+                                <td class="px-4 py-2 text-sm">{subject.code().to_string()}</td>
+                                <td class="px-4 py-2 text-sm">
+                                    {subject.name().to_string()}
+                                    <Show when=move || is_core>
+                                        <span class="ml-2 px-2 py-1 text-xs rounded bg-blue-100 text-blue-800">"Core"</span>
+                                    </Show>
+                                </td>
+                                <td class="px-4 py-2 text-sm">{subject.programme_code().to_string()}</td>
+                                <td class="px-4 py-2 text-sm">{subject.syllabus_code().unwrap_or("-").to_string()}</td>
+                                <td class="px-4 py-2 text-sm space-x-1">
+                                    {subject.components().iter().map(|component| {
+                                        let subject_code = subject_code.clone();
+                                        let code = component.code().to_string();
+                                        view! {
+                                            <span class="px-2 py-1 inline-flex text-xs rounded border border-gray-300">
+                                                {format!("{} ({} min)", component.name(), component.duration_minutes())}
+                                                <button
+                                                    class="ml-1 text-red-600"
+                                                    on:click=move |_| {
+                                                        delete_component.dispatch(DeleteSubjectComponent {
+                                                            subject_code: subject_code.clone(),
+                                                            code: code.clone(),
+                                                        });
+                                                    }
+                                                >
+                                                    "×"
+                                                </button>
+                                            </span>
+                                        }
+                                    }).collect_view()}
+                                    <button
+                                        class="text-blue-600 text-xs"
+                                        on:click=move |_| component_subject_code.set(component_target.clone())
+                                    >
+                                        "+ Component"
+                                    </button>
+                                </td>
+                                <td class="px-4 py-2 text-sm text-right space-x-2">
+                                    <button
+                                        class="text-blue-600"
+                                        on:click=move |_| {
+                                            code.set(edit_subject.code().to_string());
+                                            name.set(edit_subject.name().to_string());
+                                            programme_code.set(edit_subject.programme_code().to_string());
+                                            syllabus_code.set(edit_subject.syllabus_code().unwrap_or_default().to_string());
+                                        }
+                                    >
+                                        "Edit"
+                                    </button>
+                                    <button
+                                        class="text-gray-600"
+                                        on:click=move |_| {
+                                            set_subject_active.dispatch(SetSubjectActive {
+                                                code: toggle_code.clone(),
+                                                is_active: !is_active,
+                                            });
+                                        }
+                                    >
+                                        {if is_active { "Deactivate" } else { "Activate" }}
+                                    </button>
+                                    <button
+                                        class="text-gray-600"
+                                        on:click=move |_| {
+                                            set_subject_core.dispatch(SetSubjectCore {
+                                                code: core_code.clone(),
+                                                is_core: !is_core,
+                                            });
+                                        }
+                                    >
+                                        {if is_core { "Not core" } else { "Make core" }}
+                                    </button>
+                                    <button
+                                        class="text-red-600"
+                                        on:click=move |_| {
+                                            delete_subject.dispatch(DeleteSubject { code: subject_code.clone() });
+                                        }
+                                    >
+                                        "Delete"
+                                    </button>
+                                </td>
+                            </tr>
+                        }
+                    }).collect_view()}
+                </tbody>
+            </table>
+            <ActionForm attr:class="flex gap-2 items-end mb-4" action=save_subject>
+                <input type="text" name="code" placeholder="Code" required class="px-3 py-2 border rounded w-40" bind:value=code />
+                <input type="text" name="name" placeholder="Name" required class="px-3 py-2 border rounded" bind:value=name />
+                <ProgrammeSelect catalogue=catalogue.clone() selected=programme_code />
+                <input type="text" name="syllabus_code" placeholder="Syllabus code" class="px-3 py-2 border rounded w-32" bind:value=syllabus_code />
+                <button type="submit" class="bg-gray-700 text-white px-4 py-2 rounded">"Save Subject"</button>
+            </ActionForm>
+            <Show when=move || !component_subject_code.get().is_empty()>
+                <ActionForm attr:class="flex gap-2 items-end" action=save_component>
+                    <input type="hidden" name="subject_code" prop:value=move || component_subject_code.get() />
+                    <span class="text-sm py-2">{move || format!("Component for {}", component_subject_code.get())}</span>
+                    <input type="text" name="code" placeholder="Code (e.g. P3)" required class="px-3 py-2 border rounded w-32" bind:value=component_code />
+                    <input type="text" name="name" placeholder="Name (e.g. Paper 3)" required class="px-3 py-2 border rounded" bind:value=component_name />
+                    <input type="number" name="duration_minutes" placeholder="Minutes" required min="1" class="px-3 py-2 border rounded w-28" bind:value=component_duration />
+                    <button type="submit" class="bg-gray-700 text-white px-4 py-2 rounded">"Save Component"</button>
+                </ActionForm>
+            </Show>
+            <ActionError result=save_subject.value() />
+            <ActionError result=set_subject_active.value() />
+            <ActionError result=set_subject_core.value() />
+            <ActionError result=delete_subject.value() />
+            <ActionError result=save_component.value() />
+            <ActionError result=delete_component.value() />
+        </section>
+    }
+}
+
+#[component]
+fn ProgrammeSelect(catalogue: Catalogue, selected: RwSignal<String>) -> impl IntoView {
+    view! {
+        <select
+            name="programme_code"
+            required
+            class="px-3 py-2 border rounded"
+            prop:value=move || selected.get()
+            on:change=move |ev| selected.set(event_target_value(&ev))
+        >
+            <option value="">"Programme..."</option>
+            {catalogue.programmes().iter().map(|programme| {
+                view! {
+                    <option value=programme.code().to_string()>{programme.name().to_string()}</option>
+                }
+            }).collect_view()}
+        </select>
+    }
+}
+
+pub mod db {
+    #[cfg(feature = "ssr")]
+    pub mod server {
+        use crate::app::db::Error;
+        use shared::catalogue::{Catalogue, CatalogueSubject, ClassGroup, Programme};
+        use shared::domain::{PaperComponent, StudentClass, Subject};
+        use sqlx::PgPool;
+
+        pub async fn create_catalogue_tables(pool: &PgPool) -> Result<(), Error> {
+            for query in [
+                "CREATE TABLE IF NOT EXISTS programmes (
+                    id SERIAL PRIMARY KEY,
+                    code VARCHAR(20) NOT NULL UNIQUE,
+                    name VARCHAR(100) NOT NULL,
+                    exam_board VARCHAR(100) NOT NULL
+                )",
+                "CREATE TABLE IF NOT EXISTS class_groups (
+                    id SERIAL PRIMARY KEY,
+                    code VARCHAR(20) NOT NULL UNIQUE,
+                    name VARCHAR(100) NOT NULL,
+                    programme_id INTEGER NOT NULL REFERENCES programmes(id),
+                    year_order INTEGER NOT NULL
+                )",
+                "CREATE TABLE IF NOT EXISTS subjects (
+                    id SERIAL PRIMARY KEY,
+                    code VARCHAR(30) NOT NULL UNIQUE,
+                    name VARCHAR(100) NOT NULL,
+                    programme_id INTEGER NOT NULL REFERENCES programmes(id),
+                    syllabus_code VARCHAR(20),
+                    is_active BOOLEAN NOT NULL DEFAULT TRUE
+                )",
+                "CREATE TABLE IF NOT EXISTS subject_components (
+                    id SERIAL PRIMARY KEY,
+                    subject_id INTEGER NOT NULL REFERENCES subjects(id) ON DELETE CASCADE,
+                    code VARCHAR(20) NOT NULL,
+                    name VARCHAR(100) NOT NULL,
+                    UNIQUE (subject_id, code)
+                )",
+                "ALTER TABLE subject_components
+                    ADD COLUMN IF NOT EXISTS duration_minutes INTEGER NOT NULL DEFAULT 120",
+                // Core subjects were fixed in code before the catalogue held
+                // them, so subjects from then keep the same two.
+                "ALTER TABLE subjects ADD COLUMN IF NOT EXISTS is_core BOOLEAN",
+                "UPDATE subjects SET is_core = code IN ('WASSCE-ENG', 'WASSCE-CMAT')
+                WHERE is_core IS NULL",
+                "ALTER TABLE subjects
+                    ALTER COLUMN is_core SET DEFAULT FALSE,
+                    ALTER COLUMN is_core SET NOT NULL",
+            ] {
+                sqlx::query(query).execute(pool).await.map_err(|e| {
+                    log::error!("Failed to create catalogue table: {}", e);
+                    Error::TableNotCreated
+                })?;
+            }
+
+            Ok(())
+        }
+
+        /// Fills an empty catalogue from the built-in enums. A catalogue that
+        /// already has programmes is left alone so admin edits survive restarts.
+        pub async fn seed_catalogue(pool: &PgPool) -> Result<(), Error> {
+            let is_seeded = sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM programmes)")
+                .fetch_one(pool)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to check catalogue: {}", e);
+                    Error::DatabaseQueryFailed
+                })?;
+
+            if is_seeded {
+                log::info!("Catalogue already seeded, skipping seed");
+                return Ok(());
+            }
+
+            let seed = Catalogue::seed();
+            for programme in seed.programmes() {
+                upsert_programme(pool, programme.code(), programme.name(), programme.exam_board()).await?;
+            }
+            for class_group in seed.class_groups() {
+                upsert_class_group(
+                    pool,
+                    class_group.code(),
+                    class_group.name(),
+                    class_group.programme_code(),
+                    class_group.year_order(),
+                )
+                .await?;
+            }
+            for subject in seed.subjects() {
+                upsert_subject(
+                    pool,
+                    subject.code(),
+                    subject.name(),
+                    subject.programme_code(),
+                    subject.syllabus_code(),
+                )
+                .await?;
+                if subject.is_core() {
+                    set_subject_core(pool, subject.code(), true).await?;
+                }
+                for component in subject.components() {
+                    upsert_subject_component(
+                        pool,
+                        subject.code(),
+                        component.code(),
+                        component.name(),
+                        component.duration_minutes() as i32,
+                    )
+                    .await?;
+                }
+            }
+
+            Ok(())
+        }
+
+        pub async fn load_catalogue(pool: &PgPool) -> Result<Catalogue, Error> {
+            let programmes = sqlx::query_as::<_, (String, String, String)>(
+                "SELECT code, name, exam_board FROM programmes ORDER BY id",
+            )
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to retrieve programmes: {}", e);
+                Error::DatabaseQueryFailed
+            })?
+            .into_iter()
+            .map(|(code, name, exam_board)| Programme::new(code, name, exam_board))
+            .collect();
+
+            let class_groups = sqlx::query_as::<_, (String, String, String, i32)>(
+                r#"
+                SELECT c.code, c.name, p.code, c.year_order
+                FROM class_groups c
+                JOIN programmes p ON c.programme_id = p.id
+                ORDER BY c.year_order, c.code
+                "#,
+            )
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to retrieve class groups: {}", e);
+                Error::DatabaseQueryFailed
+            })?
+            .into_iter()
+            .map(|(code, name, programme_code, year_order)| {
+                ClassGroup::new(code, name, programme_code, year_order)
+            })
+            .collect();
+
+            let components = sqlx::query_as::<_, (String, String, String, i32)>(
+                r#"
+                SELECT s.code, sc.code, sc.name, sc.duration_minutes
+                FROM subject_components sc
+                JOIN subjects s ON sc.subject_id = s.id
+                ORDER BY sc.code
+                "#,
+            )
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to retrieve subject components: {}", e);
+                Error::DatabaseQueryFailed
+            })?;
+
+            let subjects = sqlx::query_as::<_, (String, String, String, Option<String>, bool, bool)>(
+                r#"
+                SELECT s.code, s.name, p.code, s.syllabus_code, s.is_active, s.is_core
+                FROM subjects s
+                JOIN programmes p ON s.programme_id = p.id
+                ORDER BY p.id, s.id
+                "#,
+            )
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to retrieve subjects: {}", e);
+                Error::DatabaseQueryFailed
+            })?
+            .into_iter()
+            .map(|(code, name, programme_code, syllabus_code, is_active, is_core)| {
+                let subject_components = components
+                    .iter()
+                    .filter(|(subject_code, _, _, _)| *subject_code == code)
+                    .map(|(_, code, name, duration_minutes)| {
+                        PaperComponent::new(code.clone(), name.clone(), *duration_minutes as u32)
+                    })
+                    .collect();
+                CatalogueSubject::new(
+                    code,
+                    name,
+                    programme_code,
+                    syllabus_code,
+                    is_active,
+                    is_core,
+                    subject_components,
+                )
+            })
+            .collect();
+
+            Ok(Catalogue::new(programmes, class_groups, subjects))
+        }
+
+        /// The class stored under `code`, for loaders reading it back from
+        /// the database.
+        pub fn stored_class(catalogue: &Catalogue, code: &str) -> Result<StudentClass, Error> {
+            catalogue.class_for(code).map_err(|e| {
+                log::error!("Failed to resolve stored class: {}", e);
+                Error::UnknownCatalogueCode
+            })
+        }
+
+        /// The subject stored under `code`, for loaders reading it back from
+        /// the database.
+        pub fn stored_subject(catalogue: &Catalogue, code: &str) -> Result<Subject, Error> {
+            catalogue.subject_for(code).map_err(|e| {
+                log::error!("Failed to resolve stored subject: {}", e);
+                Error::UnknownCatalogueCode
+            })
+        }
+
+        pub fn stored_subjects(catalogue: &Catalogue, codes: &[String]) -> Result<Vec<Subject>, Error> {
+            codes.iter().map(|code| stored_subject(catalogue, code)).collect()
+        }
+
+        pub async fn upsert_programme(
+            pool: &PgPool,
+            code: &str,
+            name: &str,
+            exam_board: &str,
+        ) -> Result<(), Error> {
+            sqlx::query(
+                r#"
+                INSERT INTO programmes (code, name, exam_board)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (code) DO UPDATE SET name = EXCLUDED.name, exam_board = EXCLUDED.exam_board
+                "#,
+            )
+            .bind(code)
+            .bind(name)
+            .bind(exam_board)
+            .execute(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to save programme {}: {}", code, e);
+                Error::DatabaseQueryFailed
+            })?;
+
+            Ok(())
+        }
+
+        pub async fn delete_programme(pool: &PgPool, code: &str) -> Result<(), Error> {
+            sqlx::query("DELETE FROM programmes WHERE code = $1")
+                .bind(code)
+                .execute(pool)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to delete programme {}: {}", code, e);
+                    Error::DatabaseQueryFailed
+                })?;
+
+            Ok(())
+        }
+
+        pub async fn upsert_class_group(
+            pool: &PgPool,
+            code: &str,
+            name: &str,
+            programme_code: &str,
+            year_order: i32,
+        ) -> Result<(), Error> {
+            sqlx::query(
+                r#"
+                INSERT INTO class_groups (code, name, programme_id, year_order)
+                SELECT $1, $2, p.id, $4 FROM programmes p WHERE p.code = $3
+                ON CONFLICT (code) DO UPDATE
+                SET name = EXCLUDED.name, programme_id = EXCLUDED.programme_id, year_order = EXCLUDED.year_order
+                "#,
+            )
+            .bind(code)
+            .bind(name)
+            .bind(programme_code)
+            .bind(year_order)
+            .execute(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to save class group {}: {}", code, e);
+                Error::DatabaseQueryFailed
+            })?;
+
+            Ok(())
+        }
+
+        /// Whether any student, teacher, assessment or timetable sitting is
+        /// in the class.
+        pub async fn class_group_in_use(pool: &PgPool, code: &str) -> Result<bool, Error> {
+            sqlx::query_scalar::<_, bool>(
+                r#"
+                SELECT EXISTS (SELECT 1 FROM students WHERE class_code = $1)
+                    OR EXISTS (SELECT 1 FROM teacher_classes WHERE class_code = $1)
+                    OR EXISTS (SELECT 1 FROM assessments WHERE class_code = $1)
+                    OR EXISTS (SELECT 1 FROM exam_timetable WHERE class_code = $1)
+                "#,
+            )
+            .bind(code)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to check use of class group {}: {}", code, e);
+                Error::DatabaseQueryFailed
+            })
+        }
+
+        pub async fn delete_class_group(pool: &PgPool, code: &str) -> Result<(), Error> {
+            sqlx::query("DELETE FROM class_groups WHERE code = $1")
+                .bind(code)
+                .execute(pool)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to delete class group {}: {}", code, e);
+                    Error::DatabaseQueryFailed
+                })?;
+
+            Ok(())
+        }
+
+        pub async fn upsert_subject(
+            pool: &PgPool,
+            code: &str,
+            name: &str,
+            programme_code: &str,
+            syllabus_code: Option<&str>,
+        ) -> Result<(), Error> {
+            sqlx::query(
+                r#"
+                INSERT INTO subjects (code, name, programme_id, syllabus_code)
+                SELECT $1, $2, p.id, $4 FROM programmes p WHERE p.code = $3
+                ON CONFLICT (code) DO UPDATE
+                SET name = EXCLUDED.name, programme_id = EXCLUDED.programme_id, syllabus_code = EXCLUDED.syllabus_code
+                "#,
+            )
+            .bind(code)
+            .bind(name)
+            .bind(programme_code)
+            .bind(syllabus_code)
+            .execute(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to save subject {}: {}", code, e);
+                Error::DatabaseQueryFailed
+            })?;
+
+            Ok(())
+        }
+
+        pub async fn set_subject_active(pool: &PgPool, code: &str, is_active: bool) -> Result<(), Error> {
+            sqlx::query("UPDATE subjects SET is_active = $2 WHERE code = $1")
+                .bind(code)
+                .bind(is_active)
+                .execute(pool)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to update subject {}: {}", code, e);
+                    Error::DatabaseQueryFailed
+                })?;
+
+            Ok(())
+        }
+
+        pub async fn set_subject_core(pool: &PgPool, code: &str, is_core: bool) -> Result<(), Error> {
+            sqlx::query("UPDATE subjects SET is_core = $2 WHERE code = $1")
+                .bind(code)
+                .bind(is_core)
+                .execute(pool)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to update subject {}: {}", code, e);
+                    Error::DatabaseQueryFailed
+                })?;
+
+            Ok(())
+        }
+
+        /// Whether any student, teacher, assessment or timetable sitting has
+        /// the subject.
+        pub async fn subject_in_use(pool: &PgPool, code: &str) -> Result<bool, Error> {
+            sqlx::query_scalar::<_, bool>(
+                r#"
+                SELECT EXISTS (SELECT 1 FROM student_subjects WHERE subject_code = $1)
+                    OR EXISTS (SELECT 1 FROM teacher_subjects WHERE subject_code = $1)
+                    OR EXISTS (SELECT 1 FROM assessments WHERE subject_code = $1)
+                    OR EXISTS (SELECT 1 FROM exam_timetable WHERE subject_code = $1)
+                "#,
+            )
+            .bind(code)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to check use of subject {}: {}", code, e);
+                Error::DatabaseQueryFailed
+            })
+        }
+
+        pub async fn delete_subject(pool: &PgPool, code: &str) -> Result<(), Error> {
+            sqlx::query("DELETE FROM subjects WHERE code = $1")
+                .bind(code)
+                .execute(pool)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to delete subject {}: {}", code, e);
+                    Error::DatabaseQueryFailed
+                })?;
+
+            Ok(())
+        }
+
+        pub async fn upsert_subject_component(
+            pool: &PgPool,
+            subject_code: &str,
+            code: &str,
+            name: &str,
+            duration_minutes: i32,
+        ) -> Result<(), Error> {
+            sqlx::query(
+                r#"
+                INSERT INTO subject_components (subject_id, code, name, duration_minutes)
+                SELECT s.id, $2, $3, $4 FROM subjects s WHERE s.code = $1
+                ON CONFLICT (subject_id, code)
+                DO UPDATE SET name = EXCLUDED.name, duration_minutes = EXCLUDED.duration_minutes
+                "#,
+            )
+            .bind(subject_code)
+            .bind(code)
+            .bind(name)
+            .bind(duration_minutes)
+            .execute(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to save component {} for {}: {}", code, subject_code, e);
+                Error::DatabaseQueryFailed
+            })?;
+
+            Ok(())
+        }
+
+        pub async fn delete_subject_component(
+            pool: &PgPool,
+            subject_code: &str,
+            code: &str,
+        ) -> Result<(), Error> {
+            sqlx::query(
+                r#"
+                DELETE FROM subject_components sc
+                USING subjects s
+                WHERE sc.subject_id = s.id AND s.code = $1 AND sc.code = $2
+                "#,
+            )
+            .bind(subject_code)
+            .bind(code)
+            .execute(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to delete component {} for {}: {}", code, subject_code, e);
+                Error::DatabaseQueryFailed
+            })?;
+
+            Ok(())
+        }
+    }
+}
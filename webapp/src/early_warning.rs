@@ -0,0 +1,295 @@
+use leptos::prelude::*;
+use shared::early_warning::{AtRiskStudent, Concern, DEFAULT_DROP_THRESHOLD};
+use shared::marks::AcademicPeriod;
+
+use crate::analysis::ClassAssessmentPicker;
+use crate::app::{ErrorDisplay, LoadingSpinner};
+use crate::trends::{TrendCard, get_student_trends};
+
+/// Students needing attention after an assessment, or `None` when nobody in
+/// the class has marks for it.
+#[server(GetAtRiskStudents, "/api/early-warning")]
+pub async fn get_at_risk_students(
+    class_code: String,
+    assessment_name: String,
+    academic_year: i32,
+    term: u8,
+    drop_threshold: f32,
+) -> Result<Option<Vec<AtRiskStudent>>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(crate::marks::MARKS_ROLES).await?;
+        let period = crate::marks::requested_period(academic_year, term)?;
+        if !(drop_threshold.is_finite() && drop_threshold >= 0.0) {
+            return Err(ServerFnError::ServerError(
+                "Drop threshold must be zero or more".into(),
+            ));
+        }
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+        let class = crate::catalogue::requested_class(&pool, &class_code).await?;
+
+        db::server::at_risk_students(&pool, class, &assessment_name, period, drop_threshold)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+fn concern_class(concern: &Concern) -> &'static str {
+    match concern {
+        Concern::AverageDropped { .. } => "bg-orange-100 text-orange-800",
+        Concern::BelowPass { .. } => "bg-red-100 text-red-800",
+        Concern::MissingMarks { .. } => "bg-gray-100 text-gray-800",
+    }
+}
+
+fn concern_label(concern: &Concern) -> &'static str {
+    match concern {
+        Concern::AverageDropped { .. } => "Dropped",
+        Concern::BelowPass { .. } => "Below pass",
+        Concern::MissingMarks { .. } => "Missing marks",
+    }
+}
+
+/// Early warnings for one class and assessment, for the dashboard.
+#[component]
+pub fn AtRiskWidget() -> impl IntoView {
+    let class_code = RwSignal::new(String::new());
+    let assessment = RwSignal::new(None::<(String, AcademicPeriod)>);
+    let drop_threshold = RwSignal::new(DEFAULT_DROP_THRESHOLD);
+    let selected = RwSignal::new(None::<String>);
+
+    let students = Resource::new(
+        move || (class_code.get(), assessment.get(), drop_threshold.get()),
+        |(class_code, assessment, drop_threshold)| async move {
+            let Some((assessment_name, period)) = assessment.filter(|_| !class_code.is_empty())
+            else {
+                return Ok(None);
+            };
+            get_at_risk_students(
+                class_code,
+                assessment_name,
+                period.year(),
+                period.term().number(),
+                drop_threshold,
+            )
+            .await
+        },
+    );
+
+    view! {
+        <section class="space-y-4">
+            <div class="flex gap-2 items-center">
+                <h2 class="text-lg font-semibold mr-4">"At Risk"</h2>
+                <ClassAssessmentPicker class_code=class_code assessment=assessment />
+                <label class="text-sm">"Drop of more than"</label>
+                <input
+                    type="number"
+                    min="0"
+                    step="1"
+                    class="w-20 px-3 py-2 border rounded"
+                    prop:value=move || drop_threshold.get().to_string()
+                    on:change=move |ev| {
+                        if let Ok(value) = event_target_value(&ev).parse::<f32>() {
+                            drop_threshold.set(value);
+                        }
+                    }
+                />
+                <span class="text-sm">"points"</span>
+            </div>
+            <Suspense fallback=move || view! { <LoadingSpinner/> }>
+                {move || students.get().map(|result| match result {
+                    Ok(Some(students)) if students.is_empty() => view! {
+                        <p class="text-gray-500">"No students need attention."</p>
+                    }.into_any(),
+                    Ok(Some(students)) => view! {
+                        <div class="bg-white rounded-lg shadow p-4 overflow-auto-x">
+                            <table class="min-w-full divide-y divide-gray-200">
+                                <thead class="bg-gray-50">
+                                    <tr>
+                                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Candidate"</th>
+                                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Name"</th>
+                                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Average %"</th>
+                                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Concerns"</th>
+                                    </tr>
+                                </thead>
+                                <tbody class="divide-y divide-gray-200">
+                                    {students.into_iter().map(|at_risk| {
+                                        let candidate_number = at_risk.student().candidate_number().to_string();
+                                        let name = at_risk.student().personal_info().name();
+                                        let name = format!("{} {}", name.first_name(), name.last_name());
+                                        let is_selected = {
+                                            let candidate_number = candidate_number.clone();
+                                            move || selected.get().as_deref() == Some(candidate_number.as_str())
+                                        };
+                                        let toggle = {
+                                            let candidate_number = candidate_number.clone();
+                                            move |_| selected.update(|selected| {
+                                                *selected = if selected.as_deref() == Some(candidate_number.as_str()) {
+                                                    None
+                                                } else {
+                                                    Some(candidate_number.clone())
+                                                };
+                                            })
+                                        };
+                                        let drill_down = at_risk.clone();
+                                        view! {
+                                            <tr class="hover:bg-gray-50 cursor-pointer" on:click=toggle>
+                                                <td class="px-4 py-2 text-sm">{candidate_number.clone()}</td>
+                                                <td class="px-4 py-2 text-sm">{name}</td>
+                                                <td class="px-4 py-2 text-sm">
+                                                    {at_risk.average().map(|average| format!("{:.1}", average)).unwrap_or_else(|| "—".to_string())}
+                                                </td>
+                                                <td class="px-4 py-2 text-sm space-x-1">
+                                                    {at_risk.concerns().iter().map(|concern| view! {
+                                                        <span class=format!("inline-block px-2 py-1 rounded text-xs {}", concern_class(concern)) title=concern.to_string()>
+                                                            {concern_label(concern)}
+                                                        </span>
+                                                    }).collect_view()}
+                                                </td>
+                                            </tr>
+                                            <Show when=is_selected.clone()>
+                                                <tr>
+                                                    <td colspan="4" class="px-4 py-4 bg-gray-50">
+                                                        <StudentDrillDown at_risk=drill_down.clone() />
+                                                    </td>
+                                                </tr>
+                                            </Show>
+                                        }
+                                    }).collect_view()}
+                                </tbody>
+                            </table>
+                        </div>
+                    }.into_any(),
+                    Ok(None) => view! {
+                        <p class="text-gray-500">"Choose a class and assessment with marks entered."</p>
+                    }.into_any(),
+                    Err(e) => view! { <ErrorDisplay error=e.to_string() /> }.into_any(),
+                })}
+            </Suspense>
+        </section>
+    }
+}
+
+/// A student's concerns in full, with their results over time.
+#[component]
+fn StudentDrillDown(at_risk: AtRiskStudent) -> impl IntoView {
+    let candidate_number = at_risk.student().candidate_number().to_string();
+    let trends = Resource::new(
+        move || candidate_number.clone(),
+        |candidate_number| async move { get_student_trends(candidate_number, String::new()).await },
+    );
+
+    view! {
+        <div class="space-y-4">
+            <ul class="list-disc pl-6 text-sm">
+                {at_risk.concerns().iter().map(|concern| view! { <li>{concern.to_string()}</li> }).collect_view()}
+            </ul>
+            <Suspense fallback=move || view! { <LoadingSpinner/> }>
+                {move || trends.get().map(|result| match result {
+                    Ok(trends) => view! {
+                        <div class="grid grid-cols-1 md:grid-cols-2 xl:grid-cols-3 gap-4">
+                            <TrendCard series=trends.overall().clone() />
+                            {trends.subjects().iter().map(|series| view! {
+                                <TrendCard series=series.clone() />
+                            }).collect_view()}
+                        </div>
+                    }.into_any(),
+                    Err(e) => view! { <ErrorDisplay error=e.to_string() /> }.into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
+
+pub mod db {
+    #[cfg(feature = "ssr")]
+    pub mod server {
+        use crate::app::db::Error;
+        use crate::marks::db::AssessmentRecord;
+        use shared::analysis::{RankBy, Weighting};
+        use shared::domain::{StudentClass, Subject};
+        use shared::early_warning::AtRiskStudent;
+        use shared::marks::AcademicPeriod;
+        use sqlx::PgPool;
+
+        /// The at-risk students of `class` after `assessment_name` in
+        /// `period`. Earlier results are looked for back to the start of the
+        /// previous academic year, in any class, so the first assessment
+        /// after promotion is compared with the last one before it.
+        pub async fn at_risk_students(
+            pool: &PgPool,
+            class: StudentClass,
+            assessment_name: &str,
+            period: AcademicPeriod,
+            drop_threshold: f32,
+        ) -> Result<Option<Vec<AtRiskStudent>>, Error> {
+            let students = crate::students::db::server::load_class_list(pool, class, None).await?;
+            let results =
+                crate::marks::db::server::load_results(pool, class, assessment_name, period)
+                    .await?;
+            let grading = crate::grading::db::server::load_grading_policy(pool).await?;
+            let Some(analysis) = shared::analysis::analyse_class(
+                class,
+                &students,
+                &results,
+                &Weighting::default(),
+                &grading,
+                RankBy::default(),
+            ) else {
+                return Ok(None);
+            };
+
+            // Records come newest first, so a sitting in the same term is
+            // earlier when it comes after this one.
+            let records = crate::marks::db::server::load_assessments(pool).await?;
+            let is_this = |record: &AssessmentRecord| {
+                record.assessment.class() == class
+                    && record.assessment.name() == assessment_name
+                    && record.assessment.period() == period
+            };
+            let Some(position) = records.iter().position(is_this) else {
+                return Ok(None);
+            };
+            let candidate_numbers: Vec<String> = students
+                .iter()
+                .map(|student| student.candidate_number().to_string())
+                .collect();
+            let sat = crate::marks::db::server::assessments_sat_by(pool, &candidate_numbers).await?;
+            let earlier: Vec<AssessmentRecord> = records
+                .iter()
+                .enumerate()
+                .filter(|(index, record)| {
+                    let sat_in = record.assessment.period();
+                    !is_this(record)
+                        && sat.contains(&record.id)
+                        && sat_in.year() >= period.year() - 1
+                        && (sat_in < period || (sat_in == period && *index > position))
+                })
+                .map(|(_, record)| record.clone())
+                .collect();
+            let sittings = crate::trends::db::server::load_sittings(pool, &earlier, None).await?;
+            let core_subjects: Vec<Subject> = crate::catalogue::db::server::load_catalogue(pool)
+                .await?
+                .core_subjects(class)
+                .collect();
+
+            Ok(Some(shared::early_warning::at_risk_students(
+                &students,
+                &results,
+                &analysis,
+                &sittings,
+                &core_subjects,
+                &grading,
+                drop_threshold,
+            )))
+        }
+    }
+}
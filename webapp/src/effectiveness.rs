@@ -0,0 +1,310 @@
+use leptos::prelude::*;
+use shared::effectiveness::{Effectiveness, EffectivenessReport};
+use shared::marks::AcademicPeriod;
+
+use crate::analysis::{parse_sitting_value, sitting_value};
+use crate::app::{ErrorDisplay, LoadingSpinner};
+
+/// Roles that can compare teachers and subjects.
+pub const EFFECTIVENESS_ROLES: &[&str] = &["admin", "hod"];
+
+#[server(GetEffectivenessAssessments, "/api/effectiveness")]
+pub async fn get_effectiveness_assessments(
+) -> Result<Vec<(String, AcademicPeriod)>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(EFFECTIVENESS_ROLES).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        db::server::assessment_names(&pool)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+#[server(GetEffectivenessReport, "/api/effectiveness")]
+pub async fn get_effectiveness_report(
+    assessment_name: String,
+    academic_year: i32,
+    term: u8,
+) -> Result<EffectivenessReport, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(EFFECTIVENESS_ROLES).await?;
+        let period = crate::marks::requested_period(academic_year, term)?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        db::server::effectiveness_report(&pool, &assessment_name, period)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+fn score(value: f32) -> String {
+    format!("{:.1}", value)
+}
+
+#[component]
+pub fn EffectivenessView() -> impl IntoView {
+    let title = use_context::<RwSignal<String>>()
+        .expect("title context should be provided");
+
+    title.set("Teacher & Subject Effectiveness".to_string());
+
+    let assessment = RwSignal::new(None::<(String, AcademicPeriod)>);
+    let assessments = Resource::new(|| (), |_| async move { get_effectiveness_assessments().await });
+    let report = Resource::new(
+        move || assessment.get(),
+        |assessment| async move {
+            let Some((assessment_name, period)) = assessment else {
+                return Ok(None);
+            };
+            get_effectiveness_report(assessment_name, period.year(), period.term().number())
+                .await
+                .map(Some)
+        },
+    );
+
+    view! {
+        <div class="p-4 space-y-6">
+            <div class="flex gap-2 items-center">
+                <select
+                    class="px-3 py-2 border rounded"
+                    prop:value=move || {
+                        assessment
+                            .get()
+                            .map(|(name, period)| sitting_value(&name, period))
+                            .unwrap_or_default()
+                    }
+                    on:change=move |ev| assessment.set(parse_sitting_value(&event_target_value(&ev)))
+                >
+                    <option value="">"Assessment..."</option>
+                    <Suspense fallback=|| ()>
+                        {move || assessments.get().and_then(Result::ok).map(|names| {
+                            names.into_iter().map(|(name, period)| view! {
+                                <option value=sitting_value(&name, period)>{format!("{} ({})", name, period)}</option>
+                            }).collect_view()
+                        })}
+                    </Suspense>
+                </select>
+                <p class="text-sm text-gray-500">
+                    "Value added is how far students' scores in a subject are above their average in their other subjects."
+                </p>
+            </div>
+            <Suspense fallback=move || view! { <LoadingSpinner/> }>
+                {move || report.get().map(|result| match result {
+                    Ok(Some(report)) if !report.subjects().is_empty() => view! {
+                        <EffectivenessTables report=report />
+                    }.into_any(),
+                    Ok(Some(_)) => view! {
+                        <p class="text-gray-500">"No marks have been entered for this assessment."</p>
+                    }.into_any(),
+                    Ok(None) => view! {
+                        <p class="text-gray-500">"Choose an assessment."</p>
+                    }.into_any(),
+                    Err(e) => view! { <ErrorDisplay error=e.to_string() /> }.into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
+
+/// Mean, pass rate and value added cells for one group.
+#[component]
+fn EffectivenessCells(effectiveness: Effectiveness) -> impl IntoView {
+    let statistics = effectiveness.statistics();
+    let value_added_class = match effectiveness.value_added() {
+        Some(value_added) if value_added > 0.0 => "px-4 py-2 text-sm text-green-600",
+        Some(value_added) if value_added < 0.0 => "px-4 py-2 text-sm text-red-600",
+        _ => "px-4 py-2 text-sm",
+    };
+
+    view! {
+        <td class="px-4 py-2 text-sm">{statistics.count()}</td>
+        <td class="px-4 py-2 text-sm">{score(statistics.mean())}</td>
+        <td class="px-4 py-2 text-sm">{score(statistics.median())}</td>
+        <td class="px-4 py-2 text-sm">
+            {effectiveness.pass_rate().map(|pass_rate| format!("{}%", score(pass_rate))).unwrap_or_else(|| "—".to_string())}
+        </td>
+        <td class=value_added_class>
+            {effectiveness.value_added().map(|value_added| format!("{:+.1}", value_added)).unwrap_or_else(|| "—".to_string())}
+        </td>
+    }
+}
+
+#[component]
+fn EffectivenessHeadings(first: &'static str) -> impl IntoView {
+    view! {
+        <tr>
+            <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">{first}</th>
+            <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Detail"</th>
+            <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Scores"</th>
+            <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Mean"</th>
+            <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Median"</th>
+            <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Pass Rate"</th>
+            <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Value Added"</th>
+        </tr>
+    }
+}
+
+#[component]
+fn EffectivenessTables(report: EffectivenessReport) -> impl IntoView {
+    view! {
+        <section class="bg-white rounded-lg shadow p-4 overflow-auto-x">
+            <h2 class="text-lg font-semibold mb-4">{format!("By subject — {}", report.assessment_name())}</h2>
+            <table class="min-w-full divide-y divide-gray-200">
+                <thead class="bg-gray-50">
+                    <EffectivenessHeadings first="Subject" />
+                </thead>
+                <tbody class="divide-y divide-gray-200">
+                    {report.subjects().iter().map(|subject| view! {
+                        <tr class="bg-gray-50 font-semibold">
+                            <td class="px-4 py-2 text-sm">{subject.subject().to_string()}</td>
+                            <td class="px-4 py-2 text-sm">"All classes"</td>
+                            <EffectivenessCells effectiveness=subject.overall().clone() />
+                        </tr>
+                        {subject.classes().iter().map(|(class, effectiveness)| view! {
+                            <tr class="hover:bg-gray-50">
+                                <td class="px-4 py-2 text-sm"></td>
+                                <td class="px-4 py-2 text-sm">{class.to_string()}</td>
+                                <EffectivenessCells effectiveness=effectiveness.clone() />
+                            </tr>
+                        }).collect_view()}
+                    }).collect_view()}
+                </tbody>
+            </table>
+        </section>
+        <section class="bg-white rounded-lg shadow p-4 overflow-auto-x">
+            <h2 class="text-lg font-semibold mb-4">"By teacher"</h2>
+            {if report.teachers().is_empty() {
+                view! {
+                    <p class="text-gray-500">"No registered teacher teaches a subject sat in this assessment to a class that sat it."</p>
+                }.into_any()
+            } else {
+                view! {
+                    <table class="min-w-full divide-y divide-gray-200">
+                        <thead class="bg-gray-50">
+                            <EffectivenessHeadings first="Teacher" />
+                        </thead>
+                        <tbody class="divide-y divide-gray-200">
+                            {report.teachers().iter().map(|teacher| {
+                                let name = teacher.teacher().personal_info().name();
+                                view! {
+                                    <tr class="bg-gray-50 font-semibold">
+                                        <td class="px-4 py-2 text-sm">{format!("{} {}", name.first_name(), name.last_name())}</td>
+                                        <td class="px-4 py-2 text-sm">"All subjects"</td>
+                                        <EffectivenessCells effectiveness=teacher.overall().clone() />
+                                    </tr>
+                                    {teacher.subjects().iter().map(|subject| view! {
+                                        <tr class="hover:bg-gray-50">
+                                            <td class="px-4 py-2 text-sm"></td>
+                                            <td class="px-4 py-2 text-sm">{subject.subject().to_string()}</td>
+                                            <EffectivenessCells effectiveness=subject.overall().clone() />
+                                        </tr>
+                                    }).collect_view()}
+                                }
+                            }).collect_view()}
+                        </tbody>
+                    </table>
+                }.into_any()
+            }}
+        </section>
+    }
+}
+
+pub mod db {
+    #[cfg(feature = "ssr")]
+    pub mod server {
+        use crate::app::db::Error;
+        use shared::analysis::{RankBy, Weighting};
+        use shared::domain::{StudentClass, Teacher};
+        use shared::effectiveness::EffectivenessReport;
+        use shared::marks::AcademicPeriod;
+        use sqlx::PgPool;
+
+        /// Names of the assessments that have been set, once for each term
+        /// they were sat in, newest first.
+        pub async fn assessment_names(
+            pool: &PgPool,
+        ) -> Result<Vec<(String, AcademicPeriod)>, Error> {
+            let mut names: Vec<(String, AcademicPeriod)> = Vec::new();
+            for record in crate::marks::db::server::load_assessments(pool).await? {
+                let sitting = (
+                    record.assessment.name().to_string(),
+                    record.assessment.period(),
+                );
+                if !names.contains(&sitting) {
+                    names.push(sitting);
+                }
+            }
+
+            Ok(names)
+        }
+
+        /// Every class that sat `assessment_name` in `period`, grouped by
+        /// subject and by teacher, crediting teachers only with the classes
+        /// they teach.
+        pub async fn effectiveness_report(
+            pool: &PgPool,
+            assessment_name: &str,
+            period: AcademicPeriod,
+        ) -> Result<EffectivenessReport, Error> {
+            let mut classes: Vec<StudentClass> = crate::marks::db::server::load_assessments(pool)
+                .await?
+                .into_iter()
+                .filter(|record| {
+                    record.assessment.name() == assessment_name
+                        && record.assessment.period() == period
+                })
+                .map(|record| record.assessment.class())
+                .collect();
+            classes.sort();
+            classes.dedup();
+
+            let mut analyses = Vec::new();
+            for class in classes {
+                analyses.extend(
+                    crate::analysis::db::server::analyse_class(
+                        pool,
+                        class,
+                        assessment_name,
+                        period,
+                        &Weighting::default(),
+                        RankBy::default(),
+                    )
+                    .await?,
+                );
+            }
+            let teachers: Vec<(Teacher, Vec<StudentClass>)> =
+                crate::teachers::db::server::load_teacher_records(pool, false)
+                    .await?
+                    .into_iter()
+                    .map(|record| (record.teacher, record.classes))
+                    .collect();
+            let grading = crate::grading::db::server::load_grading_policy(pool).await?;
+
+            Ok(EffectivenessReport::new(
+                format!("{} ({})", assessment_name, period),
+                &analyses,
+                &teachers,
+                &grading,
+            ))
+        }
+    }
+}
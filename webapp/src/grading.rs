@@ -0,0 +1,607 @@
+use leptos::prelude::*;
+use shared::catalogue::Catalogue;
+use shared::grading::{GradeScheme, GradingPolicy};
+
+use crate::app::{ActionError, ErrorDisplay, LoadingSpinner};
+use crate::catalogue::get_catalogue;
+
+#[server(GetGradingPolicy, "/api/grading")]
+pub async fn get_grading_policy() -> Result<GradingPolicy, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        db::server::load_grading_policy(&pool)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+/// `boundaries` is written as "A1=75, B2=70, ..., F9=0".
+#[server(SaveGradeScheme, "/api/grading")]
+pub async fn save_grade_scheme(
+    code: String,
+    name: String,
+    pass_grade: String,
+    boundaries: String,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(&["admin"]).await?;
+        let boundaries = match parse_boundaries(&boundaries) {
+            Ok(boundaries) => boundaries,
+            Err(e) => return Err(ServerFnError::ServerError(e)),
+        };
+        let scheme = GradeScheme::new(
+            code.trim().to_string(),
+            name.trim().to_string(),
+            boundaries,
+            pass_grade.trim().to_string(),
+        );
+        let issues = scheme.issues();
+        if !issues.is_empty() {
+            return Err(ServerFnError::ServerError(
+                issues
+                    .iter()
+                    .map(|issue| issue.to_string())
+                    .collect::<Vec<_>>()
+                    .join("; "),
+            ));
+        }
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        db::server::save_scheme(&pool, &scheme)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+#[server(DeleteGradeScheme, "/api/grading")]
+pub async fn delete_grade_scheme(code: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(&["admin"]).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        db::server::delete_scheme(&pool, &code)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+/// Attaches a scheme to a programme or subject code. An empty
+/// `scheme_code` removes the attachment.
+#[server(AssignGradeScheme, "/api/grading")]
+pub async fn assign_grade_scheme(
+    target_code: String,
+    scheme_code: String,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(&["admin"]).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        let scheme_code = scheme_code.trim();
+        if scheme_code.is_empty() {
+            db::server::unassign_scheme(&pool, target_code.trim()).await
+        } else {
+            db::server::assign_scheme(&pool, target_code.trim(), scheme_code).await
+        }
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+#[cfg(feature = "ssr")]
+fn parse_boundaries(text: &str) -> Result<Vec<shared::grading::GradeBoundary>, String> {
+    text.split([',', '\n'])
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (grade, min_percentage) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("\"{}\" should look like A1=75", entry))?;
+            let min_percentage = min_percentage
+                .trim()
+                .parse::<f32>()
+                .map_err(|_| format!("\"{}\" is not a percentage", min_percentage.trim()))?;
+            Ok(shared::grading::GradeBoundary::new(
+                grade.trim().to_string(),
+                min_percentage,
+            ))
+        })
+        .collect()
+}
+
+fn format_boundaries(scheme: &GradeScheme) -> String {
+    scheme
+        .boundaries()
+        .iter()
+        .map(|boundary| format!("{}={}", boundary.grade(), boundary.min_percentage()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[component]
+pub fn GradingView() -> impl IntoView {
+    let title = use_context::<RwSignal<String>>()
+        .expect("title context should be provided");
+
+    title.set("Grade Schemes".to_string());
+
+    let save_scheme = ServerAction::<SaveGradeScheme>::new();
+    let delete_scheme = ServerAction::<DeleteGradeScheme>::new();
+    let assign_scheme = ServerAction::<AssignGradeScheme>::new();
+
+    let policy = Resource::new(
+        move || {
+            (
+                save_scheme.version().get(),
+                delete_scheme.version().get(),
+                assign_scheme.version().get(),
+            )
+        },
+        |_| async move { get_grading_policy().await },
+    );
+    let catalogue = Resource::new(|| (), |_| async move { get_catalogue().await });
+
+    view! {
+        <div class="p-4 space-y-8">
+            <Suspense fallback=move || view! { <LoadingSpinner/> }>
+                {move || match (policy.get(), catalogue.get()) {
+                    (Some(Ok(policy)), Some(Ok(catalogue))) => view! {
+                        <SchemeSection
+                            policy=policy.clone()
+                            save_scheme=save_scheme
+                            delete_scheme=delete_scheme
+                        />
+                        <AssignmentSection
+                            policy=policy
+                            catalogue=catalogue
+                            assign_scheme=assign_scheme
+                        />
+                    }.into_any(),
+                    (Some(Err(e)), _) | (_, Some(Err(e))) => {
+                        view! { <ErrorDisplay error=e.to_string() /> }.into_any()
+                    }
+                    _ => view! { <LoadingSpinner/> }.into_any(),
+                }}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn SchemeSection(
+    policy: GradingPolicy,
+    save_scheme: ServerAction<SaveGradeScheme>,
+    delete_scheme: ServerAction<DeleteGradeScheme>,
+) -> impl IntoView {
+    let code = RwSignal::new(String::new());
+    let name = RwSignal::new(String::new());
+    let pass_grade = RwSignal::new(String::new());
+    let boundaries = RwSignal::new(String::new());
+
+    view! {
+        <section class="bg-white rounded-lg shadow p-4">
+            <h2 class="text-lg font-semibold mb-4">"Schemes"</h2>
+            <table class="min-w-full divide-y divide-gray-200 mb-4">
+                <thead class="bg-gray-50">
+                    <tr>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Code"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Name"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Boundaries (%)"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Pass"</th>
+                        <th class="px-4 py-2"></th>
+                    </tr>
+                </thead>
+                <tbody class="divide-y divide-gray-200">
+                    {policy.schemes().iter().map(|scheme| {
+                        let edit_scheme = scheme.clone();
+                        let scheme_code = scheme.code().to_string();
+                        view! {
+                            <tr class="hover:bg-gray-50">
+                                <td class="px-4 py-2 text-sm">{scheme.code().to_string()}</td>
+                                <td class="px-4 py-2 text-sm">{scheme.name().to_string()}</td>
+                                <td class="px-4 py-2 text-sm">{format_boundaries(scheme)}</td>
+                                <td class="px-4 py-2 text-sm">{scheme.pass_grade().to_string()}</td>
+                                <td class="px-4 py-2 text-sm text-right space-x-2">
+                                    <button
+                                        class="text-blue-600"
+                                        on:click=move |_| {
+                                            code.set(edit_scheme.code().to_string());
+                                            name.set(edit_scheme.name().to_string());
+                                            pass_grade.set(edit_scheme.pass_grade().to_string());
+                                            boundaries.set(format_boundaries(&edit_scheme));
+                                        }
+                                    >
+                                        "Edit"
+                                    </button>
+                                    <button
+                                        class="text-red-600"
+                                        on:click=move |_| {
+                                            delete_scheme.dispatch(DeleteGradeScheme { code: scheme_code.clone() });
+                                        }
+                                    >
+                                        "Delete"
+                                    </button>
+                                </td>
+                            </tr>
+                        }
+                    }).collect_view()}
+                </tbody>
+            </table>
+            <ActionForm attr:class="flex gap-2 items-end" action=save_scheme>
+                <input type="text" name="code" placeholder="Code" required class="px-3 py-2 border rounded w-32" bind:value=code />
+                <input type="text" name="name" placeholder="Name" required class="px-3 py-2 border rounded" bind:value=name />
+                <input type="text" name="boundaries" placeholder="A1=75, B2=70, ..., F9=0" required class="px-3 py-2 border rounded flex-1" bind:value=boundaries />
+                <input type="text" name="pass_grade" placeholder="Pass grade" required class="px-3 py-2 border rounded w-28" bind:value=pass_grade />
+                <button type="submit" class="bg-gray-700 text-white px-4 py-2 rounded">"Save Scheme"</button>
+            </ActionForm>
+            <ActionError result=save_scheme.value() />
+            <ActionError result=delete_scheme.value() />
+        </section>
+    }
+}
+
+#[component]
+fn AssignmentSection(
+    policy: GradingPolicy,
+    catalogue: Catalogue,
+    assign_scheme: ServerAction<AssignGradeScheme>,
+) -> impl IntoView {
+    let target_code = RwSignal::new(String::new());
+    let scheme_code = RwSignal::new(String::new());
+
+    let target_name = {
+        let catalogue = catalogue.clone();
+        move |code: &str| {
+            catalogue
+                .programme(code)
+                .map(|programme| format!("{} (all subjects)", programme.name()))
+                .or_else(|| catalogue.subject(code).map(|subject| subject.name().to_string()))
+                .unwrap_or_else(|| code.to_string())
+        }
+    };
+
+    view! {
+        <section class="bg-white rounded-lg shadow p-4">
+            <h2 class="text-lg font-semibold mb-4">"Which scheme grades what"</h2>
+            <p class="text-sm text-gray-500 mb-4">
+                "A scheme attached to a subject overrides the one attached to its programme."
+            </p>
+            <table class="min-w-full divide-y divide-gray-200 mb-4">
+                <thead class="bg-gray-50">
+                    <tr>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Programme / Subject"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Scheme"</th>
+                        <th class="px-4 py-2"></th>
+                    </tr>
+                </thead>
+                <tbody class="divide-y divide-gray-200">
+                    {policy.assignments().iter().map(|assignment| {
+                        let target = assignment.target_code().to_string();
+                        view! {
+                            <tr class="hover:bg-gray-50">
+                                <td class="px-4 py-2 text-sm">{target_name(assignment.target_code())}</td>
+                                <td class="px-4 py-2 text-sm">{assignment.scheme_code().to_string()}</td>
+                                <td class="px-4 py-2 text-sm text-right">
+                                    <button
+                                        class="text-red-600"
+                                        on:click=move |_| {
+                                            assign_scheme.dispatch(AssignGradeScheme {
+                                                target_code: target.clone(),
+                                                scheme_code: String::new(),
+                                            });
+                                        }
+                                    >
+                                        "Remove"
+                                    </button>
+                                </td>
+                            </tr>
+                        }
+                    }).collect_view()}
+                </tbody>
+            </table>
+            <ActionForm attr:class="flex gap-2 items-end" action=assign_scheme>
+                <select
+                    name="target_code"
+                    required
+                    class="px-3 py-2 border rounded"
+                    prop:value=move || target_code.get()
+                    on:change=move |ev| target_code.set(event_target_value(&ev))
+                >
+                    <option value="">"Programme or subject..."</option>
+                    {catalogue.programmes().iter().map(|programme| {
+                        let programme_code = programme.code().to_string();
+                        view! {
+                            <optgroup label=programme.name().to_string()>
+                                <option value=programme_code.clone()>{format!("{} (all subjects)", programme.name())}</option>
+                                {catalogue.subjects().iter().filter(|subject| subject.programme_code() == programme_code).map(|subject| {
+                                    view! {
+                                        <option value=subject.code().to_string()>{subject.name().to_string()}</option>
+                                    }
+                                }).collect_view()}
+                            </optgroup>
+                        }
+                    }).collect_view()}
+                </select>
+                <select
+                    name="scheme_code"
+                    required
+                    class="px-3 py-2 border rounded"
+                    prop:value=move || scheme_code.get()
+                    on:change=move |ev| scheme_code.set(event_target_value(&ev))
+                >
+                    <option value="">"Scheme..."</option>
+                    {policy.schemes().iter().map(|scheme| {
+                        view! {
+                            <option value=scheme.code().to_string()>{scheme.name().to_string()}</option>
+                        }
+                    }).collect_view()}
+                </select>
+                <button type="submit" class="bg-gray-700 text-white px-4 py-2 rounded">"Attach"</button>
+            </ActionForm>
+            <ActionError result=assign_scheme.value() />
+        </section>
+    }
+}
+
+pub mod db {
+    #[cfg(feature = "ssr")]
+    pub mod server {
+        use crate::app::db::Error;
+        use shared::grading::{GradeBoundary, GradeScheme, GradingPolicy, SchemeAssignment};
+        use sqlx::PgPool;
+
+        pub async fn create_grading_tables(pool: &PgPool) -> Result<(), Error> {
+            for query in [
+                "CREATE TABLE IF NOT EXISTS grade_schemes (
+                    id SERIAL PRIMARY KEY,
+                    code VARCHAR(20) NOT NULL UNIQUE,
+                    name VARCHAR(100) NOT NULL,
+                    pass_grade VARCHAR(10) NOT NULL
+                )",
+                "CREATE TABLE IF NOT EXISTS grade_boundaries (
+                    scheme_id INTEGER NOT NULL REFERENCES grade_schemes(id) ON DELETE CASCADE,
+                    grade VARCHAR(10) NOT NULL,
+                    min_percentage REAL NOT NULL,
+                    PRIMARY KEY (scheme_id, grade)
+                )",
+                "CREATE TABLE IF NOT EXISTS grade_scheme_assignments (
+                    target_code VARCHAR(30) PRIMARY KEY,
+                    scheme_id INTEGER NOT NULL REFERENCES grade_schemes(id) ON DELETE CASCADE
+                )",
+            ] {
+                sqlx::query(query).execute(pool).await.map_err(|e| {
+                    log::error!("Failed to create grading table: {}", e);
+                    Error::TableNotCreated
+                })?;
+            }
+
+            Ok(())
+        }
+
+        /// Fills empty grading tables with the board schemes. Schemes that
+        /// already exist are left alone so admin edits survive restarts.
+        pub async fn seed_grading(pool: &PgPool) -> Result<(), Error> {
+            let is_seeded =
+                sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM grade_schemes)")
+                    .fetch_one(pool)
+                    .await
+                    .map_err(|e| {
+                        log::error!("Failed to check grade schemes: {}", e);
+                        Error::DatabaseQueryFailed
+                    })?;
+
+            if is_seeded {
+                log::info!("Grade schemes already seeded, skipping seed");
+                return Ok(());
+            }
+
+            let seed = GradingPolicy::seed();
+            for scheme in seed.schemes() {
+                save_scheme(pool, scheme).await?;
+            }
+            for assignment in seed.assignments() {
+                assign_scheme(pool, assignment.target_code(), assignment.scheme_code()).await?;
+            }
+
+            Ok(())
+        }
+
+        pub async fn load_grading_policy(pool: &PgPool) -> Result<GradingPolicy, Error> {
+            let boundaries = sqlx::query_as::<_, (String, String, f32)>(
+                r#"
+                SELECT s.code, b.grade, b.min_percentage
+                FROM grade_boundaries b
+                JOIN grade_schemes s ON b.scheme_id = s.id
+                "#,
+            )
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to retrieve grade boundaries: {}", e);
+                Error::DatabaseQueryFailed
+            })?;
+
+            let schemes = sqlx::query_as::<_, (String, String, String)>(
+                "SELECT code, name, pass_grade FROM grade_schemes ORDER BY id",
+            )
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to retrieve grade schemes: {}", e);
+                Error::DatabaseQueryFailed
+            })?
+            .into_iter()
+            .map(|(code, name, pass_grade)| {
+                let scheme_boundaries = boundaries
+                    .iter()
+                    .filter(|(scheme_code, _, _)| *scheme_code == code)
+                    .map(|(_, grade, min_percentage)| {
+                        GradeBoundary::new(grade.clone(), *min_percentage)
+                    })
+                    .collect();
+                GradeScheme::new(code, name, scheme_boundaries, pass_grade)
+            })
+            .collect();
+
+            let assignments = sqlx::query_as::<_, (String, String)>(
+                r#"
+                SELECT a.target_code, s.code
+                FROM grade_scheme_assignments a
+                JOIN grade_schemes s ON a.scheme_id = s.id
+                ORDER BY a.target_code
+                "#,
+            )
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to retrieve grade scheme assignments: {}", e);
+                Error::DatabaseQueryFailed
+            })?
+            .into_iter()
+            .map(|(target_code, scheme_code)| SchemeAssignment::new(target_code, scheme_code))
+            .collect();
+
+            Ok(GradingPolicy::new(schemes, assignments))
+        }
+
+        /// Creates or replaces a scheme and all of its boundaries.
+        pub async fn save_scheme(pool: &PgPool, scheme: &GradeScheme) -> Result<(), Error> {
+            let mut tx = pool.begin().await.map_err(|e| {
+                log::error!("Failed to begin transaction: {}", e);
+                Error::TransactionFailed
+            })?;
+
+            let scheme_id = sqlx::query_scalar::<_, i32>(
+                r#"
+                INSERT INTO grade_schemes (code, name, pass_grade)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (code) DO UPDATE SET name = EXCLUDED.name, pass_grade = EXCLUDED.pass_grade
+                RETURNING id
+                "#,
+            )
+            .bind(scheme.code())
+            .bind(scheme.name())
+            .bind(scheme.pass_grade())
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to save grade scheme {}: {}", scheme.code(), e);
+                Error::DatabaseQueryFailed
+            })?;
+
+            sqlx::query("DELETE FROM grade_boundaries WHERE scheme_id = $1")
+                .bind(scheme_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to clear boundaries for {}: {}", scheme.code(), e);
+                    Error::DatabaseQueryFailed
+                })?;
+
+            for boundary in scheme.boundaries() {
+                sqlx::query(
+                    "INSERT INTO grade_boundaries (scheme_id, grade, min_percentage) VALUES ($1, $2, $3)",
+                )
+                .bind(scheme_id)
+                .bind(boundary.grade())
+                .bind(boundary.min_percentage())
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to save boundary {} for {}: {}", boundary.grade(), scheme.code(), e);
+                    Error::DatabaseQueryFailed
+                })?;
+            }
+
+            tx.commit().await.map_err(|e| {
+                log::error!("Failed to commit grade scheme {}: {}", scheme.code(), e);
+                Error::TransactionFailed
+            })
+        }
+
+        pub async fn delete_scheme(pool: &PgPool, code: &str) -> Result<(), Error> {
+            sqlx::query("DELETE FROM grade_schemes WHERE code = $1")
+                .bind(code)
+                .execute(pool)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to delete grade scheme {}: {}", code, e);
+                    Error::DatabaseQueryFailed
+                })?;
+
+            Ok(())
+        }
+
+        pub async fn assign_scheme(
+            pool: &PgPool,
+            target_code: &str,
+            scheme_code: &str,
+        ) -> Result<(), Error> {
+            sqlx::query(
+                r#"
+                INSERT INTO grade_scheme_assignments (target_code, scheme_id)
+                SELECT $1, id FROM grade_schemes WHERE code = $2
+                ON CONFLICT (target_code) DO UPDATE SET scheme_id = EXCLUDED.scheme_id
+                "#,
+            )
+            .bind(target_code)
+            .bind(scheme_code)
+            .execute(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to attach {} to {}: {}", scheme_code, target_code, e);
+                Error::DatabaseQueryFailed
+            })?;
+
+            Ok(())
+        }
+
+        pub async fn unassign_scheme(pool: &PgPool, target_code: &str) -> Result<(), Error> {
+            sqlx::query("DELETE FROM grade_scheme_assignments WHERE target_code = $1")
+                .bind(target_code)
+                .execute(pool)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to detach scheme from {}: {}", target_code, e);
+                    Error::DatabaseQueryFailed
+                })?;
+
+            Ok(())
+        }
+    }
+}
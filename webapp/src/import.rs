@@ -0,0 +1,358 @@
+use leptos::prelude::*;
+use leptos::server_fn::codec::{MultipartData, MultipartFormData};
+use leptos::wasm_bindgen::JsCast;
+use leptos::web_sys::{FormData, HtmlFormElement};
+use shared::import::{MarksColumns, Sheet, read_marks};
+use shared::validation::Severity;
+
+use crate::marks::SaveMarks;
+use crate::marks::db::MarkSheet;
+
+/// Largest file accepted for import.
+pub const MAX_UPLOAD_BYTES: usize = 5 * 1024 * 1024;
+
+/// Reads the `file` field of an uploaded form into a sheet. CSV files and
+/// the first worksheet of .xlsx, .xls and .ods workbooks are understood.
+#[server(name = ReadSheet, prefix = "/api/import", input = MultipartFormData)]
+pub async fn read_sheet(data: MultipartData) -> Result<Sheet, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use leptos::server_fn::error::NoCustomError;
+
+        crate::app::db::server::require_role(crate::marks::MARKS_ROLES).await?;
+        let Some(mut data) = data.into_inner() else {
+            return Err(ServerFnError::ServerError("No file was uploaded".into()));
+        };
+
+        let mut upload = None;
+        while let Some(mut field) = data
+            .next_field()
+            .await
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?
+        {
+            if field.name() != Some("file") {
+                continue;
+            }
+            let file_name = field.file_name().unwrap_or_default().to_string();
+            // Read chunk by chunk so an oversized file is refused as soon
+            // as it passes the limit rather than buffered whole.
+            let mut bytes = Vec::new();
+            loop {
+                match field.chunk().await {
+                    Ok(Some(chunk)) => {
+                        if bytes.len() + chunk.len() > MAX_UPLOAD_BYTES {
+                            return Err(ServerFnError::ServerError(format!(
+                                "{} is larger than {} MB",
+                                file_name,
+                                MAX_UPLOAD_BYTES / 1024 / 1024
+                            )));
+                        }
+                        bytes.extend_from_slice(&chunk);
+                    }
+                    Ok(None) => break,
+                    Err(e) => return Err(ServerFnError::ServerError(e.to_string())),
+                }
+            }
+            upload = Some((file_name, bytes));
+        }
+        let Some((file_name, bytes)) = upload else {
+            return Err(ServerFnError::ServerError("No file was uploaded".into()));
+        };
+
+        server::parse_file(&file_name, bytes).map_err(ServerFnError::ServerError)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        let _ = data;
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+/// Upload, column mapping and preview for importing a mark sheet. Nothing is
+/// saved until the preview is clean and the teacher confirms; the marks then
+/// go through `SaveMarks` like the grid, in one transaction.
+#[component]
+pub fn MarksImportSection(sheet: MarkSheet, save_marks: ServerAction<SaveMarks>) -> impl IntoView {
+    let assessment_id = sheet.id;
+    let assessment = StoredValue::new(sheet.assessment.clone());
+    let candidates = StoredValue::new(sheet.candidates.clone());
+    let candidate_numbers = StoredValue::new(
+        sheet
+            .candidates
+            .iter()
+            .map(|candidate| candidate.candidate_number.clone())
+            .collect::<Vec<_>>(),
+    );
+
+    let upload = Action::new_local(|data: &FormData| read_sheet(data.clone().into()));
+    let uploaded = RwSignal::new(None::<Sheet>);
+    let columns = RwSignal::new(None::<MarksColumns>);
+
+    Effect::new(move |_| {
+        if let Some(Ok(sheet)) = upload.value().get() {
+            columns.set(Some(assessment.with_value(|assessment| {
+                MarksColumns::detect(&sheet, assessment)
+            })));
+            uploaded.set(Some(sheet));
+        }
+    });
+
+    let preview = Memo::new(move |_| {
+        let sheet = uploaded.get()?;
+        let columns = columns.get()?;
+        Some(read_marks(
+            &assessment.get_value(),
+            &candidate_numbers.get_value(),
+            &sheet,
+            &columns,
+        ))
+    });
+
+    let column_select = move |selected: Option<usize>, on_select: Callback<Option<usize>>| {
+        let headers = uploaded.get().map(|sheet| sheet.headers().to_vec()).unwrap_or_default();
+        view! {
+            <select
+                class="px-2 py-1 border rounded text-sm"
+                prop:value=selected.map(|column| column.to_string()).unwrap_or_default()
+                on:change=move |ev| on_select.run(event_target_value(&ev).parse().ok())
+            >
+                <option value="">"(not imported)"</option>
+                {headers.into_iter().enumerate().map(|(column, header)| view! {
+                    <option value=column.to_string()>{header}</option>
+                }).collect_view()}
+            </select>
+        }
+    };
+
+    view! {
+        <section class="bg-white rounded-lg shadow p-4 mt-4">
+            <h2 class="text-lg font-semibold mb-4">"Import From Spreadsheet"</h2>
+            <form
+                class="flex gap-2 items-center"
+                on:submit=move |ev| {
+                    ev.prevent_default();
+                    let Some(form) = ev.target().map(|target| target.unchecked_into::<HtmlFormElement>()) else {
+                        return;
+                    };
+                    if let Ok(data) = FormData::new_with_form(&form) {
+                        uploaded.set(None);
+                        columns.set(None);
+                        upload.dispatch_local(data);
+                    }
+                }
+            >
+                <input type="file" name="file" required accept=".csv,.xlsx,.xls,.ods" class="text-sm" />
+                <button type="submit" class="bg-gray-700 text-white px-4 py-2 rounded">"Preview"</button>
+                {move || upload.pending().get().then(|| view! {
+                    <span class="text-sm text-gray-500">"Reading file..."</span>
+                })}
+            </form>
+            {move || upload.value().get().and_then(Result::err).map(|e| view! {
+                <div class="bg-red-100 border border-red-400 text-red-700 px-4 py-3 rounded mt-4">
+                    {e.to_string()}
+                </div>
+            })}
+            {move || columns.get().map(|current| view! {
+                <div class="flex flex-wrap gap-4 items-center mt-4 text-sm">
+                    <label class="flex gap-2 items-center">
+                        "Candidate number"
+                        {column_select(current.candidate(), Callback::new(move |column| {
+                            columns.update(|columns| {
+                                if let Some(columns) = columns {
+                                    columns.set_candidate(column);
+                                }
+                            });
+                        }))}
+                    </label>
+                    {current.components().iter().cloned().map(|(code, column)| {
+                        let label = code.clone();
+                        view! {
+                            <label class="flex gap-2 items-center">
+                                {label}
+                                {column_select(column, Callback::new(move |column| {
+                                    columns.update(|columns| {
+                                        if let Some(columns) = columns {
+                                            columns.set_component(&code, column);
+                                        }
+                                    });
+                                }))}
+                            </label>
+                        }
+                    }).collect_view()}
+                </div>
+            })}
+            {move || preview.get().map(|preview| {
+                let components: Vec<String> = assessment.with_value(|assessment| {
+                    assessment
+                        .components()
+                        .iter()
+                        .map(|component| component.code().to_string())
+                        .collect()
+                });
+                let general: Vec<String> = preview
+                    .issues()
+                    .iter()
+                    .filter(|issue| issue.line().is_none())
+                    .map(|issue| issue.to_string())
+                    .collect();
+                let entries = preview.entries();
+                let is_importable = preview.is_importable();
+                let errors = preview
+                    .issues()
+                    .iter()
+                    .filter(|issue| issue.severity() == Severity::Error)
+                    .count();
+                view! {
+                    <div class="mt-4 space-y-4">
+                        {(!general.is_empty()).then(|| view! {
+                            <div class="bg-red-100 border border-red-400 text-red-700 px-4 py-3 rounded">
+                                {general.into_iter().map(|issue| view! { <p>{issue}</p> }).collect_view()}
+                            </div>
+                        })}
+                        <table class="min-w-full divide-y divide-gray-200">
+                            <thead class="bg-gray-50">
+                                <tr>
+                                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Line"</th>
+                                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Candidate"</th>
+                                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Name"</th>
+                                    {components.iter().map(|code| view! {
+                                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">{code.clone()}</th>
+                                    }).collect_view()}
+                                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Problems"</th>
+                                </tr>
+                            </thead>
+                            <tbody class="divide-y divide-gray-200">
+                                {preview.rows().iter().map(|row| {
+                                    let issues: Vec<_> = preview.issues_on(row.line()).cloned().collect();
+                                    let has_error = issues.iter().any(|issue| issue.severity() == Severity::Error);
+                                    let name = candidates.with_value(|candidates| {
+                                        candidates
+                                            .iter()
+                                            .find(|candidate| candidate.candidate_number == row.candidate_number())
+                                            .map(|candidate| candidate.name.clone())
+                                    });
+                                    view! {
+                                        <tr class=if has_error { "bg-red-50" } else { "hover:bg-gray-50" }>
+                                            <td class="px-4 py-2 text-sm text-gray-500">{row.line()}</td>
+                                            <td class="px-4 py-2 text-sm">{row.candidate_number().to_string()}</td>
+                                            <td class="px-4 py-2 text-sm">{name.unwrap_or_default()}</td>
+                                            {components.iter().map(|code| view! {
+                                                <td class="px-4 py-2 text-sm">{row.entry(code).unwrap_or_default().to_string()}</td>
+                                            }).collect_view()}
+                                            <td class="px-4 py-2 text-sm">
+                                                {issues.into_iter().map(|issue| {
+                                                    let class = match issue.severity() {
+                                                        Severity::Error => "block text-red-700",
+                                                        Severity::Warning => "block text-yellow-700",
+                                                    };
+                                                    view! { <span class=class>{issue.to_string()}</span> }
+                                                }).collect_view()}
+                                            </td>
+                                        </tr>
+                                    }
+                                }).collect_view()}
+                            </tbody>
+                        </table>
+                        <div class="flex gap-4 items-center">
+                            <button
+                                class="bg-gray-700 text-white px-4 py-2 rounded disabled:opacity-50"
+                                disabled=!is_importable
+                                on:click=move |_| {
+                                    save_marks.dispatch(SaveMarks {
+                                        assessment_id,
+                                        entries: entries.clone(),
+                                    });
+                                    uploaded.set(None);
+                                    columns.set(None);
+                                }
+                            >
+                                {format!("Import {} Rows", preview.rows().len())}
+                            </button>
+                            {(errors > 0).then(|| view! {
+                                <span class="text-sm text-red-700">
+                                    {format!("Fix {} problems in the file or the column choices before importing.", errors)}
+                                </span>
+                            })}
+                        </div>
+                    </div>
+                }
+            })}
+        </section>
+    }
+}
+
+#[cfg(feature = "ssr")]
+pub mod server {
+    use std::io::Cursor;
+
+    use calamine::{Data, Reader};
+    use shared::import::Sheet;
+
+    /// Parses an uploaded file by its extension.
+    pub fn parse_file(file_name: &str, bytes: Vec<u8>) -> Result<Sheet, String> {
+        let extension = file_name
+            .rsplit_once('.')
+            .map(|(_, extension)| extension.to_ascii_lowercase())
+            .unwrap_or_default();
+        let records = match extension.as_str() {
+            "csv" => read_csv(&bytes)?,
+            "xlsx" | "xlsm" | "xls" | "ods" => read_workbook(bytes)?,
+            _ => return Err(format!("{} is not a CSV or Excel file", file_name)),
+        };
+
+        Sheet::from_records(records).ok_or_else(|| format!("{} is empty", file_name))
+    }
+
+    fn read_csv(bytes: &[u8]) -> Result<Vec<Vec<String>>, String> {
+        let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+        csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(bytes)
+            .records()
+            .map(|record| {
+                record
+                    .map(|record| record.iter().map(str::to_string).collect())
+                    .map_err(|e| format!("Could not read CSV: {}", e))
+            })
+            .collect()
+    }
+
+    /// The first worksheet, padded with blank rows so line numbers match
+    /// what Excel shows.
+    fn read_workbook(bytes: Vec<u8>) -> Result<Vec<Vec<String>>, String> {
+        let mut workbook = calamine::open_workbook_auto_from_rs(Cursor::new(bytes))
+            .map_err(|e| format!("Could not open workbook: {}", e))?;
+        let range = workbook
+            .worksheet_range_at(0)
+            .ok_or_else(|| "The workbook has no worksheets".to_string())?
+            .map_err(|e| format!("Could not read worksheet: {}", e))?;
+
+        let first_row = range.start().map_or(0, |(row, _)| row as usize);
+        let first_column = range.start().map_or(0, |(_, column)| column as usize);
+        let mut records = vec![Vec::new(); first_row];
+        records.extend(range.rows().map(|row| {
+            std::iter::repeat_n(String::new(), first_column)
+                .chain(row.iter().map(cell_text))
+                .collect()
+        }));
+
+        Ok(records)
+    }
+
+    /// Whole numbers are written without a decimal point, so a mark typed
+    /// as 45 is not read back as "45.0". Dates are written as YYYY-MM-DD.
+    fn cell_text(cell: &Data) -> String {
+        match cell {
+            Data::Float(value) if value.fract() == 0.0 => format!("{}", *value as i64),
+            Data::DateTime(value) if value.is_datetime() => value
+                .as_datetime()
+                .map(|datetime| datetime.format("%Y-%m-%d").to_string())
+                .unwrap_or_else(|| value.to_string()),
+            Data::Empty => String::new(),
+            cell => cell.to_string(),
+        }
+    }
+}
@@ -0,0 +1,1297 @@
+use leptos::prelude::*;
+use shared::invigilation::{InvigilationReport, SwapStatus};
+
+use crate::app::{ActionError, ErrorDisplay, LoadingSpinner};
+use db::{DutyRecord, InvigilationOverview, SwapRequestRecord};
+
+/// Roles that approve duty changes.
+pub const APPROVER_ROLES: &[&str] = &["admin", "exams_officer"];
+
+#[server(GetInvigilationOverview, "/api/invigilation")]
+pub async fn get_invigilation_overview() -> Result<InvigilationOverview, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let user_session =
+            crate::app::db::server::require_role(&["admin", "exams_officer", "teacher"]).await?;
+        let can_approve = user_session
+            .role_name
+            .as_deref()
+            .is_some_and(|role_name| APPROVER_ROLES.contains(&role_name));
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        db::server::load_overview(&pool, user_session.user_id, can_approve)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+/// Asks `counterpart_id` to cover `duty_id`, or to swap it for
+/// `counterpart_duty_id` when that is not empty.
+#[server(RequestSwap, "/api/invigilation")]
+pub async fn request_swap(
+    duty_id: i32,
+    counterpart_id: i32,
+    counterpart_duty_id: String,
+    reason: String,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let user_session =
+            crate::app::db::server::require_role(&["admin", "exams_officer", "teacher"]).await?;
+        let counterpart_duty_id = match counterpart_duty_id.trim() {
+            "" => None,
+            id => match id.parse::<i32>() {
+                Ok(id) => Some(id),
+                Err(_) => {
+                    return Err(ServerFnError::ServerError(format!("Invalid duty: {}", id)));
+                }
+            },
+        };
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        db::server::create_swap_request(
+            &pool,
+            user_session.user_id,
+            duty_id,
+            counterpart_id,
+            counterpart_duty_id,
+            reason.trim(),
+        )
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+/// The counterpart's answer to a pending request.
+#[server(RespondToSwap, "/api/invigilation")]
+pub async fn respond_to_swap(request_id: i32, accept: bool) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let user_session =
+            crate::app::db::server::require_role(&["admin", "exams_officer", "teacher"]).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        let next = if accept {
+            SwapStatus::Accepted
+        } else {
+            SwapStatus::Declined
+        };
+        db::server::set_swap_status(&pool, request_id, user_session.user_id, next)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+#[server(CancelSwap, "/api/invigilation")]
+pub async fn cancel_swap(request_id: i32) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let user_session =
+            crate::app::db::server::require_role(&["admin", "exams_officer", "teacher"]).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        db::server::set_swap_status(&pool, request_id, user_session.user_id, SwapStatus::Cancelled)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+/// Approving moves the duties over and records the change in one
+/// transaction.
+#[server(DecideSwap, "/api/invigilation")]
+pub async fn decide_swap(request_id: i32, approve: bool) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let user_session = crate::app::db::server::require_role(APPROVER_ROLES).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        if approve {
+            db::server::approve_swap(&pool, request_id, user_session.user_id).await
+        } else {
+            db::server::reject_swap(&pool, request_id, user_session.user_id).await
+        }
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+/// Staffs every room in the stored seat plans from the current teachers
+/// with logins, then publishes the result in place of the current duties.
+#[server(GenerateDuties, "/api/invigilation")]
+pub async fn generate_duties(
+    candidates_per_invigilator: u32,
+    minimum_per_room: u32,
+) -> Result<InvigilationReport, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use leptos::server_fn::error::NoCustomError;
+        use shared::invigilation::InvigilationRules;
+
+        crate::app::db::server::require_role(APPROVER_ROLES).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        let allocations = crate::rooms::db::server::load_allocations(&pool)
+            .await
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+        if allocations.allocations().is_empty() {
+            return Err(ServerFnError::ServerError(
+                "Allocate rooms for the published timetable first".into(),
+            ));
+        }
+        let teachers: Vec<_> = crate::teachers::db::server::load_teacher_records(&pool, false)
+            .await
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?
+            .into_iter()
+            .filter(|record| record.user_id.is_some())
+            .collect();
+
+        let staff: Vec<_> = teachers.iter().map(|record| record.staff_member()).collect();
+        let report = shared::invigilation::generate(
+            &allocations,
+            &staff,
+            &InvigilationRules::new(candidates_per_invigilator, minimum_per_room),
+        );
+        let duties: Vec<_> = report
+            .duties()
+            .iter()
+            .filter_map(|duty| {
+                teachers[duty.staff()]
+                    .user_id
+                    .map(|user_id| (user_id, duty.clone()))
+            })
+            .collect();
+        db::server::publish_duties(&pool, &duties)
+            .await
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+
+        Ok(report)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+fn duty_label(duty: &DutyRecord) -> String {
+    format!(
+        "{} ({}) {}",
+        duty.exam_date.format("%a %-d %b %Y"),
+        duty.session,
+        duty.room_code
+    )
+}
+
+#[component]
+pub fn InvigilationView() -> impl IntoView {
+    let title = use_context::<RwSignal<String>>()
+        .expect("title context should be provided");
+
+    title.set("Invigilation".to_string());
+
+    let request_swap = ServerAction::<RequestSwap>::new();
+    let respond_to_swap = ServerAction::<RespondToSwap>::new();
+    let cancel_swap = ServerAction::<CancelSwap>::new();
+    let decide_swap = ServerAction::<DecideSwap>::new();
+    let generate_duties = ServerAction::<GenerateDuties>::new();
+
+    let overview = Resource::new(
+        move || {
+            (
+                request_swap.version().get(),
+                respond_to_swap.version().get(),
+                cancel_swap.version().get(),
+                decide_swap.version().get(),
+                generate_duties.version().get(),
+            )
+        },
+        |_| async move { get_invigilation_overview().await },
+    );
+
+    view! {
+        <div class="p-4 space-y-6">
+            <Suspense fallback=move || view! { <LoadingSpinner/> }>
+                {move || overview.get().map(|result| match result {
+                    Ok(overview) => view! {
+                        <Show when=move || overview.can_approve>
+                            <RosterSection generate_duties=generate_duties />
+                        </Show>
+                        <MyDutiesSection overview=overview.clone() request_swap=request_swap />
+                        <SwapRequestsSection
+                            overview=overview
+                            respond_to_swap=respond_to_swap
+                            cancel_swap=cancel_swap
+                            decide_swap=decide_swap
+                        />
+                    }.into_any(),
+                    Err(e) => view! { <ErrorDisplay error=e.to_string() /> }.into_any(),
+                })}
+            </Suspense>
+            <ActionError result=request_swap.value() />
+            <ActionError result=respond_to_swap.value() />
+            <ActionError result=cancel_swap.value() />
+            <ActionError result=decide_swap.value() />
+        </div>
+    }
+}
+
+#[component]
+fn RosterSection(generate_duties: ServerAction<GenerateDuties>) -> impl IntoView {
+    view! {
+        <section class="bg-white rounded-lg shadow p-4 space-y-4">
+            <h2 class="text-lg font-semibold">"Duty Roster"</h2>
+            <ActionForm attr:class="flex gap-2 items-center" action=generate_duties>
+                <label class="text-sm">
+                    "Candidates per invigilator"
+                    <input type="number" name="candidates_per_invigilator" value="30" min="1" required class="ml-2 px-2 py-1 border rounded w-20" />
+                </label>
+                <label class="text-sm">
+                    "At least"
+                    <input type="number" name="minimum_per_room" value="1" min="0" required class="mx-2 px-2 py-1 border rounded w-16" />
+                    "per room"
+                </label>
+                <button
+                    type="submit"
+                    class="bg-gray-700 text-white px-4 py-2 rounded"
+                    disabled=move || generate_duties.pending().get()
+                >
+                    "Generate and Publish Duties"
+                </button>
+            </ActionForm>
+            <ActionError result=generate_duties.value() />
+            {move || generate_duties.value().get().and_then(Result::ok).map(|report| view! {
+                <p class="text-sm">{format!("{} duties published.", report.duties().len())}</p>
+                <ul class="text-sm text-red-700 list-disc pl-5">
+                    {report.shortfalls().iter().map(|shortfall| view! {
+                        <li>
+                            {format!(
+                                "{} on {} has {} of the {} invigilators it needs",
+                                shortfall.room_code(),
+                                shortfall.exam_date(),
+                                shortfall.assigned(),
+                                shortfall.needed(),
+                            )}
+                        </li>
+                    }).collect_view()}
+                </ul>
+                <ul class="text-sm text-yellow-700 list-disc pl-5">
+                    {report.relaxations().iter().map(|relaxation| view! {
+                        <li>{relaxation.to_string()}</li>
+                    }).collect_view()}
+                </ul>
+            })}
+        </section>
+    }
+}
+
+#[component]
+fn MyDutiesSection(
+    overview: InvigilationOverview,
+    request_swap: ServerAction<RequestSwap>,
+) -> impl IntoView {
+    let selected_duty = RwSignal::new(None::<DutyRecord>);
+    let counterpart_id = RwSignal::new(String::new());
+    let counterpart_duty_id = RwSignal::new(String::new());
+    let reason = RwSignal::new(String::new());
+
+    let current_user_id = overview.current_user_id;
+    let my_duties: Vec<DutyRecord> = overview
+        .duties
+        .iter()
+        .filter(|duty| duty.user_id == current_user_id)
+        .cloned()
+        .collect();
+    let staff = StoredValue::new(overview.staff.clone());
+    let duties = StoredValue::new(overview.duties.clone());
+
+    view! {
+        <section class="bg-white rounded-lg shadow p-4">
+            <h2 class="text-lg font-semibold mb-4">"My Duties"</h2>
+            <table class="min-w-full divide-y divide-gray-200 mb-4">
+                <thead class="bg-gray-50">
+                    <tr>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Sitting"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Room"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Minutes"</th>
+                        <th class="px-4 py-2"></th>
+                    </tr>
+                </thead>
+                <tbody class="divide-y divide-gray-200">
+                    {my_duties.into_iter().map(|duty| {
+                        let select_duty = duty.clone();
+                        view! {
+                            <tr class="hover:bg-gray-50">
+                                <td class="px-4 py-2 text-sm">
+                                    {format!("{} ({})", duty.exam_date.format("%a %-d %b %Y"), duty.session)}
+                                </td>
+                                <td class="px-4 py-2 text-sm">{duty.room_code.clone()}</td>
+                                <td class="px-4 py-2 text-sm">{duty.minutes}</td>
+                                <td class="px-4 py-2 text-sm text-right">
+                                    <button
+                                        class="text-blue-600"
+                                        on:click=move |_| {
+                                            selected_duty.set(Some(select_duty.clone()));
+                                            counterpart_id.set(String::new());
+                                            counterpart_duty_id.set(String::new());
+                                        }
+                                    >
+                                        "Request swap or cover"
+                                    </button>
+                                </td>
+                            </tr>
+                        }
+                    }).collect_view()}
+                </tbody>
+            </table>
+            <Show when=move || selected_duty.get().is_some()>
+                <ActionForm attr:class="flex gap-2 items-center" action=request_swap>
+                    <input type="hidden" name="duty_id" prop:value=move || selected_duty.get().map(|duty| duty.id.to_string()).unwrap_or_default() />
+                    <span class="text-sm py-2">
+                        {move || selected_duty.get().map(|duty| duty_label(&duty)).unwrap_or_default()}
+                    </span>
+                    <select
+                        name="counterpart_id"
+                        required
+                        class="px-3 py-2 border rounded"
+                        prop:value=move || counterpart_id.get()
+                        on:change=move |ev| {
+                            counterpart_id.set(event_target_value(&ev));
+                            counterpart_duty_id.set(String::new());
+                        }
+                    >
+                        <option value="">"Colleague..."</option>
+                        {staff.get_value().into_iter().filter(|member| member.user_id != current_user_id).map(|member| {
+                            view! {
+                                <option value=member.user_id.to_string()>{member.name}</option>
+                            }
+                        }).collect_view()}
+                    </select>
+                    <select
+                        name="counterpart_duty_id"
+                        class="px-3 py-2 border rounded"
+                        prop:value=move || counterpart_duty_id.get()
+                        on:change=move |ev| counterpart_duty_id.set(event_target_value(&ev))
+                    >
+                        <option value="">"Cover (no swap)"</option>
+                        {move || {
+                            let counterpart = counterpart_id.get();
+                            duties.get_value().into_iter()
+                                .filter(|duty| duty.user_id.to_string() == counterpart)
+                                .map(|duty| view! {
+                                    <option value=duty.id.to_string()>{format!("Swap for {}", duty_label(&duty))}</option>
+                                })
+                                .collect_view()
+                        }}
+                    </select>
+                    <input type="text" name="reason" placeholder="Reason" class="px-3 py-2 border rounded" bind:value=reason />
+                    <button type="submit" class="bg-gray-700 text-white px-4 py-2 rounded">"Send Request"</button>
+                </ActionForm>
+            </Show>
+        </section>
+    }
+}
+
+#[component]
+fn SwapRequestsSection(
+    overview: InvigilationOverview,
+    respond_to_swap: ServerAction<RespondToSwap>,
+    cancel_swap: ServerAction<CancelSwap>,
+    decide_swap: ServerAction<DecideSwap>,
+) -> impl IntoView {
+    let current_user_id = overview.current_user_id;
+    let can_approve = overview.can_approve;
+
+    view! {
+        <section class="bg-white rounded-lg shadow p-4">
+            <h2 class="text-lg font-semibold mb-4">"Swap and Cover Requests"</h2>
+            <table class="min-w-full divide-y divide-gray-200">
+                <thead class="bg-gray-50">
+                    <tr>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Duty"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"From"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"To"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"In Exchange For"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Reason"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Status"</th>
+                        <th class="px-4 py-2"></th>
+                    </tr>
+                </thead>
+                <tbody class="divide-y divide-gray-200">
+                    {overview.requests.into_iter().map(|request: SwapRequestRecord| {
+                        let request_id = request.id;
+                        let status = request.status;
+                        let is_counterpart = request.counterpart_id == current_user_id;
+                        let is_requester = request.requested_by == current_user_id;
+                        view! {
+                            <tr class="hover:bg-gray-50">
+                                <td class="px-4 py-2 text-sm">{duty_label(&request.duty)}</td>
+                                <td class="px-4 py-2 text-sm">{request.requester.clone()}</td>
+                                <td class="px-4 py-2 text-sm">{request.counterpart.clone()}</td>
+                                <td class="px-4 py-2 text-sm">
+                                    {request.counterpart_duty.as_ref().map(duty_label).unwrap_or_else(|| "Cover".to_string())}
+                                </td>
+                                <td class="px-4 py-2 text-sm">{request.reason.clone()}</td>
+                                <td class="px-4 py-2 text-sm">{status.to_string()}</td>
+                                <td class="px-4 py-2 text-sm text-right space-x-2">
+                                    <Show when=move || is_counterpart && status.can_become(SwapStatus::Accepted)>
+                                        <button
+                                            class="text-green-600"
+                                            on:click=move |_| { respond_to_swap.dispatch(RespondToSwap { request_id, accept: true }); }
+                                        >
+                                            "Accept"
+                                        </button>
+                                        <button
+                                            class="text-red-600"
+                                            on:click=move |_| { respond_to_swap.dispatch(RespondToSwap { request_id, accept: false }); }
+                                        >
+                                            "Decline"
+                                        </button>
+                                    </Show>
+                                    <Show when=move || can_approve && status.can_become(SwapStatus::Approved)>
+                                        <button
+                                            class="text-green-600"
+                                            on:click=move |_| { decide_swap.dispatch(DecideSwap { request_id, approve: true }); }
+                                        >
+                                            "Approve"
+                                        </button>
+                                        <button
+                                            class="text-red-600"
+                                            on:click=move |_| { decide_swap.dispatch(DecideSwap { request_id, approve: false }); }
+                                        >
+                                            "Reject"
+                                        </button>
+                                    </Show>
+                                    <Show when=move || is_requester && status.can_become(SwapStatus::Cancelled)>
+                                        <button
+                                            class="text-gray-600"
+                                            on:click=move |_| { cancel_swap.dispatch(CancelSwap { request_id }); }
+                                        >
+                                            "Cancel"
+                                        </button>
+                                    </Show>
+                                </td>
+                            </tr>
+                        }
+                    }).collect_view()}
+                </tbody>
+            </table>
+        </section>
+    }
+}
+
+pub mod db {
+    use chrono::NaiveDate;
+    use serde::{Deserialize, Serialize};
+    use shared::invigilation::SwapStatus;
+
+    /// A published invigilation duty.
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    pub struct DutyRecord {
+        pub id: i32,
+        pub exam_date: NaiveDate,
+        pub session: String,
+        pub room_code: String,
+        pub user_id: i32,
+        pub invigilator: String,
+        pub minutes: i32,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    pub struct SwapRequestRecord {
+        pub id: i32,
+        pub duty: DutyRecord,
+        pub requested_by: i32,
+        pub requester: String,
+        pub counterpart_id: i32,
+        pub counterpart: String,
+        /// `None` for a cover request.
+        pub counterpart_duty: Option<DutyRecord>,
+        pub status: SwapStatus,
+        pub reason: String,
+        pub created_at: chrono::DateTime<chrono::Utc>,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    pub struct StaffOption {
+        pub user_id: i32,
+        pub name: String,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    pub struct InvigilationOverview {
+        pub current_user_id: i32,
+        pub can_approve: bool,
+        pub duties: Vec<DutyRecord>,
+        pub requests: Vec<SwapRequestRecord>,
+        pub staff: Vec<StaffOption>,
+    }
+
+    #[derive(Debug, Clone)]
+    pub enum SwapError {
+        Database(crate::app::db::Error),
+        RequestNotFound,
+        DutyNotFound,
+        NotYourDuty,
+        NotCounterpartsDuty,
+        SameInvigilator,
+        AlreadyRequested,
+        NotAllowed,
+        InvalidTransition(SwapStatus, SwapStatus),
+        /// The duties changed hands after the request was made.
+        Stale,
+        DoubleBooked,
+        OwnSubject,
+        Unavailable,
+    }
+
+    impl From<crate::app::db::Error> for SwapError {
+        fn from(error: crate::app::db::Error) -> Self {
+            Self::Database(error)
+        }
+    }
+
+    impl std::fmt::Display for SwapError {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            match self {
+                Self::Database(error) => write!(f, "{}", error),
+                Self::RequestNotFound => write!(f, "Swap request not found."),
+                Self::DutyNotFound => write!(f, "Duty not found."),
+                Self::NotYourDuty => write!(f, "You can only request changes to your own duties."),
+                Self::NotCounterpartsDuty => {
+                    write!(f, "The duty offered in exchange belongs to someone else.")
+                }
+                Self::SameInvigilator => write!(f, "You cannot swap a duty with yourself."),
+                Self::AlreadyRequested => {
+                    write!(f, "There is already an open request for this duty.")
+                }
+                Self::NotAllowed => write!(f, "You cannot change this request."),
+                Self::InvalidTransition(from, to) => {
+                    write!(f, "A {} request cannot be marked {}.", from, to)
+                }
+                Self::Stale => write!(
+                    f,
+                    "These duties have changed since the request was made. Please request again."
+                ),
+                Self::DoubleBooked => {
+                    write!(f, "This change would put someone on two duties in one sitting.")
+                }
+                Self::OwnSubject => write!(
+                    f,
+                    "This change would have someone invigilate a paper in a subject they teach."
+                ),
+                Self::Unavailable => write!(
+                    f,
+                    "This change would give someone a duty while they are on leave or unavailable."
+                ),
+            }
+        }
+    }
+
+    impl std::error::Error for SwapError {}
+
+    #[cfg(feature = "ssr")]
+    pub mod server {
+        use super::{DutyRecord, InvigilationOverview, StaffOption, SwapError, SwapRequestRecord};
+        use crate::app::db::Error;
+        use chrono::NaiveDate;
+        use shared::domain::{ExamDate, ExamSession};
+        use shared::invigilation::{Duty, SwapStatus};
+        use sqlx::{PgPool, Postgres, Transaction};
+
+        pub async fn create_invigilation_tables(pool: &PgPool) -> Result<(), Error> {
+            for query in [
+                "CREATE TABLE IF NOT EXISTS invigilation_duties (
+                    id SERIAL PRIMARY KEY,
+                    exam_date DATE NOT NULL,
+                    session VARCHAR(10) NOT NULL,
+                    room_code VARCHAR(20) NOT NULL,
+                    user_id INTEGER NOT NULL REFERENCES users(id),
+                    minutes INTEGER NOT NULL
+                )",
+                "CREATE TABLE IF NOT EXISTS duty_swap_requests (
+                    id SERIAL PRIMARY KEY,
+                    duty_id INTEGER NOT NULL REFERENCES invigilation_duties(id) ON DELETE CASCADE,
+                    requested_by INTEGER NOT NULL REFERENCES users(id),
+                    counterpart_id INTEGER NOT NULL REFERENCES users(id),
+                    counterpart_duty_id INTEGER REFERENCES invigilation_duties(id) ON DELETE CASCADE,
+                    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+                    reason TEXT NOT NULL DEFAULT '',
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    responded_at TIMESTAMPTZ,
+                    decided_by INTEGER REFERENCES users(id),
+                    decided_at TIMESTAMPTZ
+                )",
+                "CREATE TABLE IF NOT EXISTS duty_changes (
+                    id SERIAL PRIMARY KEY,
+                    request_id INTEGER REFERENCES duty_swap_requests(id) ON DELETE SET NULL,
+                    duty_id INTEGER REFERENCES invigilation_duties(id) ON DELETE SET NULL,
+                    exam_date DATE NOT NULL,
+                    session VARCHAR(10) NOT NULL,
+                    room_code VARCHAR(20) NOT NULL,
+                    from_user_id INTEGER NOT NULL REFERENCES users(id),
+                    to_user_id INTEGER NOT NULL REFERENCES users(id),
+                    approved_by INTEGER NOT NULL REFERENCES users(id),
+                    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+                )",
+                "ALTER TABLE invigilation_duties
+                    ADD COLUMN IF NOT EXISTS archived_at TIMESTAMPTZ",
+            ] {
+                sqlx::query(query).execute(pool).await.map_err(|e| {
+                    log::error!("Failed to create invigilation tables: {}", e);
+                    Error::TableNotCreated
+                })?;
+            }
+
+            Ok(())
+        }
+
+        /// Publishes `duties`, each paired with the id of the user doing it,
+        /// in place of the current ones. The old duties are archived rather
+        /// than deleted so their swap requests and changes stay on record;
+        /// requests still open on them are cancelled.
+        pub async fn publish_duties(pool: &PgPool, duties: &[(i32, Duty)]) -> Result<(), Error> {
+            let mut tx = pool.begin().await.map_err(|e| {
+                log::error!("Failed to begin transaction: {}", e);
+                Error::TransactionFailed
+            })?;
+
+            sqlx::query("UPDATE duty_swap_requests SET status = $1 WHERE status IN ($2, $3)")
+                .bind(SwapStatus::Cancelled.code())
+                .bind(SwapStatus::Pending.code())
+                .bind(SwapStatus::Accepted.code())
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to cancel open swap requests: {}", e);
+                    Error::DatabaseQueryFailed
+                })?;
+
+            sqlx::query("UPDATE invigilation_duties SET archived_at = NOW() WHERE archived_at IS NULL")
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to archive invigilation duties: {}", e);
+                    Error::DatabaseQueryFailed
+                })?;
+
+            for (user_id, duty) in duties {
+                sqlx::query(
+                    "INSERT INTO invigilation_duties (exam_date, session, room_code, user_id, minutes)
+                    VALUES ($1, $2, $3, $4, $5)",
+                )
+                .bind(duty.exam_date().date())
+                .bind(duty.exam_date().session().as_str())
+                .bind(duty.room_code())
+                .bind(user_id)
+                .bind(duty.minutes() as i32)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to publish invigilation duty: {}", e);
+                    Error::DatabaseQueryFailed
+                })?;
+            }
+
+            tx.commit().await.map_err(|e| {
+                log::error!("Failed to commit transaction: {}", e);
+                Error::TransactionFailed
+            })?;
+
+            Ok(())
+        }
+
+        type DutyRow = (i32, NaiveDate, String, String, i32, String, String, i32);
+
+        const DUTY_SELECT: &str = "
+            SELECT d.id, d.exam_date, d.session, d.room_code, d.user_id, u.first_name, u.last_name, d.minutes
+            FROM invigilation_duties d
+            JOIN users u ON u.id = d.user_id";
+
+        fn duty_from_row(
+            (id, exam_date, session, room_code, user_id, first_name, last_name, minutes): DutyRow,
+        ) -> DutyRecord {
+            DutyRecord {
+                id,
+                exam_date,
+                session,
+                room_code,
+                user_id,
+                invigilator: format!("{} {}", first_name, last_name),
+                minutes,
+            }
+        }
+
+        /// The published duties, with those archived by earlier rosters too
+        /// when `include_archived` is set.
+        pub async fn load_duties(
+            pool: &PgPool,
+            include_archived: bool,
+        ) -> Result<Vec<DutyRecord>, Error> {
+            let rows = sqlx::query_as::<_, DutyRow>(&format!(
+                "{} WHERE $1 OR d.archived_at IS NULL
+                ORDER BY d.exam_date, d.session, d.room_code, u.last_name",
+                DUTY_SELECT
+            ))
+            .bind(include_archived)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to retrieve invigilation duties: {}", e);
+                Error::DatabaseQueryFailed
+            })?;
+
+            Ok(rows.into_iter().map(duty_from_row).collect())
+        }
+
+        /// Every request when `user_id` is `None`, otherwise only those the
+        /// user made or was asked to take on.
+        pub async fn load_swap_requests(
+            pool: &PgPool,
+            user_id: Option<i32>,
+        ) -> Result<Vec<SwapRequestRecord>, Error> {
+            let rows = sqlx::query_as::<
+                _,
+                (
+                    i32,
+                    i32,
+                    i32,
+                    String,
+                    i32,
+                    String,
+                    Option<i32>,
+                    String,
+                    String,
+                    chrono::DateTime<chrono::Utc>,
+                ),
+            >(
+                r#"
+                SELECT r.id, r.duty_id,
+                    r.requested_by, ru.first_name || ' ' || ru.last_name,
+                    r.counterpart_id, cu.first_name || ' ' || cu.last_name,
+                    r.counterpart_duty_id, r.status, r.reason, r.created_at
+                FROM duty_swap_requests r
+                JOIN users ru ON ru.id = r.requested_by
+                JOIN users cu ON cu.id = r.counterpart_id
+                WHERE $1::INTEGER IS NULL OR r.requested_by = $1 OR r.counterpart_id = $1
+                ORDER BY r.created_at DESC
+                "#,
+            )
+            .bind(user_id)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to retrieve swap requests: {}", e);
+                Error::DatabaseQueryFailed
+            })?;
+
+            let duties = load_duties(pool, true).await?;
+            let find_duty = |id: i32| duties.iter().find(|duty| duty.id == id).cloned();
+
+            Ok(rows
+                .into_iter()
+                .filter_map(
+                    |(
+                        id,
+                        duty_id,
+                        requested_by,
+                        requester,
+                        counterpart_id,
+                        counterpart,
+                        counterpart_duty_id,
+                        status,
+                        reason,
+                        created_at,
+                    )| {
+                        Some(SwapRequestRecord {
+                            id,
+                            duty: find_duty(duty_id)?,
+                            requested_by,
+                            requester,
+                            counterpart_id,
+                            counterpart,
+                            counterpart_duty: counterpart_duty_id.and_then(find_duty),
+                            status: SwapStatus::from_code(&status)?,
+                            reason,
+                            created_at,
+                        })
+                    },
+                )
+                .collect())
+        }
+
+        /// Active staff who can take on a duty.
+        pub async fn load_staff(pool: &PgPool) -> Result<Vec<StaffOption>, Error> {
+            let rows = sqlx::query_as::<_, (i32, String, String)>(
+                r#"
+                SELECT u.id, u.first_name, u.last_name
+                FROM users u
+                JOIN roles r ON r.id = u.role_id
+                WHERE u.is_active AND r.name IN ('teacher', 'exams_officer')
+                ORDER BY u.last_name, u.first_name
+                "#,
+            )
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to retrieve staff: {}", e);
+                Error::DatabaseQueryFailed
+            })?;
+
+            Ok(rows
+                .into_iter()
+                .map(|(user_id, first_name, last_name)| StaffOption {
+                    user_id,
+                    name: format!("{} {}", first_name, last_name),
+                })
+                .collect())
+        }
+
+        /// Everything the invigilation page shows. Approvers see every
+        /// request, everyone else only their own.
+        pub async fn load_overview(
+            pool: &PgPool,
+            current_user_id: i32,
+            can_approve: bool,
+        ) -> Result<InvigilationOverview, Error> {
+            Ok(InvigilationOverview {
+                current_user_id,
+                can_approve,
+                duties: load_duties(pool, false).await?,
+                requests: load_swap_requests(pool, (!can_approve).then_some(current_user_id))
+                    .await?,
+                staff: load_staff(pool).await?,
+            })
+        }
+
+        fn query_failed(e: sqlx::Error) -> SwapError {
+            log::error!("Failed to update swap request: {}", e);
+            SwapError::Database(Error::DatabaseQueryFailed)
+        }
+
+        async fn duty_owner(
+            tx: &mut Transaction<'_, Postgres>,
+            duty_id: i32,
+        ) -> Result<(i32, NaiveDate, String, String), SwapError> {
+            sqlx::query_as::<_, (i32, NaiveDate, String, String)>(
+                "SELECT user_id, exam_date, session, room_code FROM invigilation_duties
+                WHERE id = $1 AND archived_at IS NULL FOR UPDATE",
+            )
+            .bind(duty_id)
+            .fetch_optional(&mut **tx)
+            .await
+            .map_err(query_failed)?
+            .ok_or(SwapError::DutyNotFound)
+        }
+
+        pub async fn create_swap_request(
+            pool: &PgPool,
+            requested_by: i32,
+            duty_id: i32,
+            counterpart_id: i32,
+            counterpart_duty_id: Option<i32>,
+            reason: &str,
+        ) -> Result<(), SwapError> {
+            if requested_by == counterpart_id {
+                return Err(SwapError::SameInvigilator);
+            }
+
+            let mut tx = pool.begin().await.map_err(|e| {
+                log::error!("Failed to begin transaction: {}", e);
+                SwapError::Database(Error::TransactionFailed)
+            })?;
+
+            if duty_owner(&mut tx, duty_id).await?.0 != requested_by {
+                return Err(SwapError::NotYourDuty);
+            }
+            if let Some(counterpart_duty_id) = counterpart_duty_id
+                && duty_owner(&mut tx, counterpart_duty_id).await?.0 != counterpart_id
+            {
+                return Err(SwapError::NotCounterpartsDuty);
+            }
+
+            let already_requested = sqlx::query_scalar::<_, bool>(
+                "SELECT EXISTS(SELECT 1 FROM duty_swap_requests WHERE duty_id = $1 AND status IN ($2, $3))",
+            )
+            .bind(duty_id)
+            .bind(SwapStatus::Pending.code())
+            .bind(SwapStatus::Accepted.code())
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(query_failed)?;
+            if already_requested {
+                return Err(SwapError::AlreadyRequested);
+            }
+
+            sqlx::query(
+                "INSERT INTO duty_swap_requests (duty_id, requested_by, counterpart_id, counterpart_duty_id, status, reason)
+                VALUES ($1, $2, $3, $4, $5, $6)",
+            )
+            .bind(duty_id)
+            .bind(requested_by)
+            .bind(counterpart_id)
+            .bind(counterpart_duty_id)
+            .bind(SwapStatus::Pending.code())
+            .bind(reason)
+            .execute(&mut *tx)
+            .await
+            .map_err(query_failed)?;
+
+            tx.commit().await.map_err(|e| {
+                log::error!("Failed to commit transaction: {}", e);
+                SwapError::Database(Error::TransactionFailed)
+            })?;
+
+            Ok(())
+        }
+
+        async fn load_request(
+            tx: &mut Transaction<'_, Postgres>,
+            request_id: i32,
+        ) -> Result<(i32, i32, i32, Option<i32>, SwapStatus), SwapError> {
+            let (duty_id, requested_by, counterpart_id, counterpart_duty_id, status) =
+                sqlx::query_as::<_, (i32, i32, i32, Option<i32>, String)>(
+                    "SELECT duty_id, requested_by, counterpart_id, counterpart_duty_id, status
+                    FROM duty_swap_requests WHERE id = $1 FOR UPDATE",
+                )
+                .bind(request_id)
+                .fetch_optional(&mut **tx)
+                .await
+                .map_err(query_failed)?
+                .ok_or(SwapError::RequestNotFound)?;
+            let status = SwapStatus::from_code(&status).ok_or(SwapError::RequestNotFound)?;
+
+            Ok((duty_id, requested_by, counterpart_id, counterpart_duty_id, status))
+        }
+
+        /// Accepting, declining or cancelling, by the counterpart or the
+        /// requester as appropriate.
+        pub async fn set_swap_status(
+            pool: &PgPool,
+            request_id: i32,
+            user_id: i32,
+            next: SwapStatus,
+        ) -> Result<(), SwapError> {
+            let mut tx = pool.begin().await.map_err(|e| {
+                log::error!("Failed to begin transaction: {}", e);
+                SwapError::Database(Error::TransactionFailed)
+            })?;
+
+            let (_, requested_by, counterpart_id, _, status) =
+                load_request(&mut tx, request_id).await?;
+            let allowed = match next {
+                SwapStatus::Accepted | SwapStatus::Declined => user_id == counterpart_id,
+                SwapStatus::Cancelled => user_id == requested_by,
+                _ => false,
+            };
+            if !allowed {
+                return Err(SwapError::NotAllowed);
+            }
+            if !status.can_become(next) {
+                return Err(SwapError::InvalidTransition(status, next));
+            }
+
+            sqlx::query("UPDATE duty_swap_requests SET status = $1, responded_at = NOW() WHERE id = $2")
+                .bind(next.code())
+                .bind(request_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(query_failed)?;
+
+            tx.commit().await.map_err(|e| {
+                log::error!("Failed to commit transaction: {}", e);
+                SwapError::Database(Error::TransactionFailed)
+            })?;
+
+            Ok(())
+        }
+
+        pub async fn reject_swap(
+            pool: &PgPool,
+            request_id: i32,
+            decided_by: i32,
+        ) -> Result<(), SwapError> {
+            let mut tx = pool.begin().await.map_err(|e| {
+                log::error!("Failed to begin transaction: {}", e);
+                SwapError::Database(Error::TransactionFailed)
+            })?;
+
+            let (.., status) = load_request(&mut tx, request_id).await?;
+            if !status.can_become(SwapStatus::Rejected) {
+                return Err(SwapError::InvalidTransition(status, SwapStatus::Rejected));
+            }
+
+            sqlx::query(
+                "UPDATE duty_swap_requests SET status = $1, decided_by = $2, decided_at = NOW() WHERE id = $3",
+            )
+            .bind(SwapStatus::Rejected.code())
+            .bind(decided_by)
+            .bind(request_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(query_failed)?;
+
+            tx.commit().await.map_err(|e| {
+                log::error!("Failed to commit transaction: {}", e);
+                SwapError::Database(Error::TransactionFailed)
+            })?;
+
+            Ok(())
+        }
+
+        /// Whether `user_id` has a duty in the sitting other than `except`.
+        async fn is_on_duty(
+            tx: &mut Transaction<'_, Postgres>,
+            user_id: i32,
+            exam_date: NaiveDate,
+            session: &str,
+            except: Option<i32>,
+        ) -> Result<bool, SwapError> {
+            sqlx::query_scalar::<_, bool>(
+                "SELECT EXISTS(
+                    SELECT 1 FROM invigilation_duties
+                    WHERE user_id = $1 AND exam_date = $2 AND session = $3
+                        AND archived_at IS NULL AND ($4::INTEGER IS NULL OR id <> $4)
+                )",
+            )
+            .bind(user_id)
+            .bind(exam_date)
+            .bind(session)
+            .bind(except)
+            .fetch_one(&mut **tx)
+            .await
+            .map_err(query_failed)
+        }
+
+        /// Whether `user_id` teaches a subject being sat in the room, going by
+        /// the stored seat plans.
+        async fn teaches_subject_in_room(
+            tx: &mut Transaction<'_, Postgres>,
+            user_id: i32,
+            (_, exam_date, session, room_code): &(i32, NaiveDate, String, String),
+        ) -> Result<bool, SwapError> {
+            sqlx::query_scalar::<_, bool>(
+                "SELECT EXISTS(
+                    SELECT 1 FROM room_allocations ra
+                    JOIN teacher_subjects ts ON ts.subject_code = ra.subject_code
+                    JOIN teachers t ON t.id = ts.teacher_id
+                    WHERE t.user_id = $1 AND ra.exam_date = $2 AND ra.session = $3 AND ra.room_code = $4
+                )",
+            )
+            .bind(user_id)
+            .bind(exam_date)
+            .bind(session)
+            .bind(room_code)
+            .fetch_one(&mut **tx)
+            .await
+            .map_err(query_failed)
+        }
+
+        /// Whether the teacher linked to `user_id` is on leave or otherwise
+        /// unavailable for the duty's sitting. Staff without a teacher record
+        /// have no constraints.
+        async fn is_unavailable(
+            pool: &PgPool,
+            user_id: i32,
+            (_, exam_date, session, _): &(i32, NaiveDate, String, String),
+        ) -> Result<bool, SwapError> {
+            let Some(session) = ExamSession::from_code(session) else {
+                log::error!("Invigilation duty has unknown session {}", session);
+                return Err(SwapError::Database(Error::DatabaseQueryFailed));
+            };
+            let teacher = crate::teachers::db::server::load_teacher_for_user(pool, user_id).await?;
+
+            Ok(teacher.is_some_and(|teacher| {
+                !teacher
+                    .staff_member()
+                    .constraints()
+                    .is_available(&ExamDate::new(*exam_date, session))
+            }))
+        }
+
+        async fn reassign(
+            tx: &mut Transaction<'_, Postgres>,
+            request_id: i32,
+            duty_id: i32,
+            (from_user_id, exam_date, session, room_code): (i32, NaiveDate, String, String),
+            to_user_id: i32,
+            approved_by: i32,
+        ) -> Result<(), SwapError> {
+            sqlx::query("UPDATE invigilation_duties SET user_id = $1 WHERE id = $2")
+                .bind(to_user_id)
+                .bind(duty_id)
+                .execute(&mut **tx)
+                .await
+                .map_err(query_failed)?;
+
+            sqlx::query(
+                "INSERT INTO duty_changes (request_id, duty_id, exam_date, session, room_code, from_user_id, to_user_id, approved_by)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+            )
+            .bind(request_id)
+            .bind(duty_id)
+            .bind(exam_date)
+            .bind(session)
+            .bind(room_code)
+            .bind(from_user_id)
+            .bind(to_user_id)
+            .bind(approved_by)
+            .execute(&mut **tx)
+            .await
+            .map_err(query_failed)?;
+
+            Ok(())
+        }
+
+        /// Moves the duty, and the one offered in exchange, then records the
+        /// change and closes other open requests on either duty. The rules
+        /// the roster was generated under are checked again first: nobody
+        /// ends up on two duties in a sitting, invigilating their own
+        /// subject, or on duty while on leave or unavailable. Nothing is
+        /// written unless every step succeeds.
+        pub async fn approve_swap(
+            pool: &PgPool,
+            request_id: i32,
+            approved_by: i32,
+        ) -> Result<(), SwapError> {
+            let mut tx = pool.begin().await.map_err(|e| {
+                log::error!("Failed to begin transaction: {}", e);
+                SwapError::Database(Error::TransactionFailed)
+            })?;
+
+            let (duty_id, requested_by, counterpart_id, counterpart_duty_id, status) =
+                load_request(&mut tx, request_id).await?;
+            if !status.can_become(SwapStatus::Approved) {
+                return Err(SwapError::InvalidTransition(status, SwapStatus::Approved));
+            }
+
+            let duty = duty_owner(&mut tx, duty_id).await?;
+            if duty.0 != requested_by {
+                return Err(SwapError::Stale);
+            }
+            if is_on_duty(&mut tx, counterpart_id, duty.1, &duty.2, counterpart_duty_id).await? {
+                return Err(SwapError::DoubleBooked);
+            }
+            if teaches_subject_in_room(&mut tx, counterpart_id, &duty).await? {
+                return Err(SwapError::OwnSubject);
+            }
+            if is_unavailable(pool, counterpart_id, &duty).await? {
+                return Err(SwapError::Unavailable);
+            }
+
+            let counterpart_duty = match counterpart_duty_id {
+                Some(counterpart_duty_id) => {
+                    let counterpart_duty = duty_owner(&mut tx, counterpart_duty_id).await?;
+                    if counterpart_duty.0 != counterpart_id {
+                        return Err(SwapError::Stale);
+                    }
+                    if is_on_duty(
+                        &mut tx,
+                        requested_by,
+                        counterpart_duty.1,
+                        &counterpart_duty.2,
+                        Some(duty_id),
+                    )
+                    .await?
+                    {
+                        return Err(SwapError::DoubleBooked);
+                    }
+                    if teaches_subject_in_room(&mut tx, requested_by, &counterpart_duty).await? {
+                        return Err(SwapError::OwnSubject);
+                    }
+                    if is_unavailable(pool, requested_by, &counterpart_duty).await? {
+                        return Err(SwapError::Unavailable);
+                    }
+                    Some((counterpart_duty_id, counterpart_duty))
+                }
+                None => None,
+            };
+
+            reassign(&mut tx, request_id, duty_id, duty, counterpart_id, approved_by).await?;
+            if let Some((counterpart_duty_id, counterpart_duty)) = counterpart_duty {
+                reassign(
+                    &mut tx,
+                    request_id,
+                    counterpart_duty_id,
+                    counterpart_duty,
+                    requested_by,
+                    approved_by,
+                )
+                .await?;
+            }
+
+            sqlx::query(
+                "UPDATE duty_swap_requests SET status = $1, decided_by = $2, decided_at = NOW() WHERE id = $3",
+            )
+            .bind(SwapStatus::Approved.code())
+            .bind(approved_by)
+            .bind(request_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(query_failed)?;
+
+            sqlx::query(
+                "UPDATE duty_swap_requests SET status = $1
+                WHERE id <> $2 AND status IN ($3, $4)
+                    AND (duty_id IN ($5, $6) OR counterpart_duty_id IN ($5, $6))",
+            )
+            .bind(SwapStatus::Cancelled.code())
+            .bind(request_id)
+            .bind(SwapStatus::Pending.code())
+            .bind(SwapStatus::Accepted.code())
+            .bind(duty_id)
+            .bind(counterpart_duty_id.unwrap_or(duty_id))
+            .execute(&mut *tx)
+            .await
+            .map_err(query_failed)?;
+
+            tx.commit().await.map_err(|e| {
+                log::error!("Failed to commit transaction: {}", e);
+                SwapError::Database(Error::TransactionFailed)
+            })?;
+
+            Ok(())
+        }
+    }
+}
@@ -1,4 +1,19 @@
+pub mod analysis;
 pub mod app;
+pub mod catalogue;
+pub mod early_warning;
+pub mod effectiveness;
+pub mod grading;
+pub mod import;
+pub mod invigilation;
+pub mod marks;
+pub mod reports;
+pub mod rooms;
+pub mod rosters;
+pub mod students;
+pub mod teachers;
+pub mod timetables;
+pub mod trends;
 
 #[cfg(feature = "hydrate")]
 #[wasm_bindgen::prelude::wasm_bindgen]
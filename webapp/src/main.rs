@@ -35,6 +35,58 @@ async fn main() -> std::io::Result<()> {
         .await
         .expect("Failed to create sessions table");
 
+    use webapp::catalogue::db::server as catalogue;
+
+    catalogue::create_catalogue_tables(&pool)
+        .await
+        .expect("Failed to create catalogue tables");
+
+    catalogue::seed_catalogue(&pool)
+        .await
+        .expect("Failed to seed catalogue");
+
+    webapp::rooms::db::server::create_rooms_table(&pool)
+        .await
+        .expect("Failed to create rooms table");
+
+    webapp::invigilation::db::server::create_invigilation_tables(&pool)
+        .await
+        .expect("Failed to create invigilation tables");
+
+    webapp::students::db::server::create_students_tables(&pool)
+        .await
+        .expect("Failed to create students tables");
+
+    webapp::teachers::db::server::create_teachers_tables(&pool)
+        .await
+        .expect("Failed to create teachers tables");
+
+    webapp::rosters::db::server::create_rosters_tables(&pool)
+        .await
+        .expect("Failed to create rosters tables");
+
+    webapp::marks::db::server::create_marks_tables(&pool)
+        .await
+        .expect("Failed to create marks tables");
+
+    use webapp::grading::db::server as grading;
+
+    grading::create_grading_tables(&pool)
+        .await
+        .expect("Failed to create grading tables");
+
+    grading::seed_grading(&pool)
+        .await
+        .expect("Failed to seed grade schemes");
+
+    webapp::reports::db::server::create_reports_tables(&pool)
+        .await
+        .expect("Failed to create reports tables");
+
+    webapp::timetables::db::server::create_timetable_tables(&pool)
+        .await
+        .expect("Failed to create timetable tables");
+
     let secret_key = server::get_secret_session_key();
 
     let config = get_configuration(None).unwrap();
@@ -56,6 +108,30 @@ async fn main() -> std::io::Result<()> {
             .app_data(leptos_options_data.clone())
             .service(Files::new("/pkg", format!("{site_root}/pkg")))
             .service(Files::new("/assets", &site_root))
+            .route(
+                "/api/reports/card.pdf",
+                web::get().to(webapp::reports::server::report_card_pdf),
+            )
+            .route(
+                "/api/reports/class.pdf",
+                web::get().to(webapp::reports::server::class_report_cards_pdf),
+            )
+            .route(
+                "/api/reports/class.zip",
+                web::get().to(webapp::reports::server::class_report_cards_zip),
+            )
+            .route(
+                "/api/students/registration.csv",
+                web::get().to(webapp::students::server::registration_csv),
+            )
+            .route(
+                "/api/timetables/exams.{format}",
+                web::get().to(webapp::timetables::server::exam_timetable_file),
+            )
+            .route(
+                "/api/timetables/invigilation.{format}",
+                web::get().to(webapp::timetables::server::invigilation_timetable_file),
+            )
             .leptos_routes(routes, {
                 let leptos_options = leptos_options.clone();
                 let pool = pool.clone();
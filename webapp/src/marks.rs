@@ -0,0 +1,1344 @@
+use leptos::prelude::*;
+use leptos::server_fn::codec::Json;
+use shared::marks::{AcademicPeriod, MarkEntry, MarkIssue, Term};
+
+use crate::app::{ActionError, ErrorDisplay, LoadingSpinner};
+use crate::catalogue::{ClassOptions, get_catalogue};
+use crate::import::MarksImportSection;
+use db::{AssessmentRecord, MarkSheet};
+
+/// Roles that can set up assessments and enter marks.
+pub const MARKS_ROLES: &[&str] = &["admin", "exams_officer", "teacher"];
+
+/// Roles that can write marks for any class and subject. Teachers only
+/// write marks for their own classes in the subjects they teach.
+pub const MARKS_OFFICER_ROLES: &[&str] = &["admin", "exams_officer"];
+
+#[server(GetAssessments, "/api/marks")]
+pub async fn get_assessments() -> Result<Vec<AssessmentRecord>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(MARKS_ROLES).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        db::server::load_assessments(&pool)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+/// The term a request names.
+#[cfg(feature = "ssr")]
+pub fn requested_period(academic_year: i32, term: u8) -> Result<AcademicPeriod, ServerFnError> {
+    Term::from_number(term)
+        .map(|term| AcademicPeriod::new(academic_year, term))
+        .ok_or_else(|| ServerFnError::ServerError(format!("Unknown term: {}", term)))
+}
+
+/// Creates an assessment with one component per catalogue paper for the
+/// subject, each out of 100 and equally weighted.
+#[server(CreateAssessment, "/api/marks")]
+pub async fn create_assessment(
+    name: String,
+    class_code: String,
+    subject_code: String,
+    academic_year: i32,
+    term: u8,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use leptos::server_fn::error::NoCustomError;
+
+        let user_session = crate::app::db::server::require_role(MARKS_ROLES).await?;
+        let period = requested_period(academic_year, term)?;
+        if name.trim().is_empty() {
+            return Err(ServerFnError::ServerError("Assessment name is required".into()));
+        }
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+        let catalogue = crate::catalogue::db::server::load_catalogue(&pool)
+            .await
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+        let class = catalogue
+            .class_for(&class_code)
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+        let subject = catalogue
+            .subject_for(&subject_code)
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+        db::server::require_teaching(&pool, &user_session, class, subject)
+            .await
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+
+        db::server::create_assessment(&pool, name.trim(), class, subject, period)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+#[server(DeleteAssessment, "/api/marks")]
+pub async fn delete_assessment(assessment_id: i32) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(&["admin", "exams_officer"]).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        db::server::delete_assessment(&pool, assessment_id)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+#[server(SaveAssessmentComponent, "/api/marks")]
+pub async fn save_assessment_component(
+    assessment_id: i32,
+    code: String,
+    name: String,
+    max_mark: i32,
+    weight: f32,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use leptos::server_fn::error::NoCustomError;
+
+        let user_session = crate::app::db::server::require_role(MARKS_ROLES).await?;
+        if max_mark <= 0 {
+            return Err(ServerFnError::ServerError(
+                "Maximum mark must be greater than zero".into(),
+            ));
+        }
+        if !(weight.is_finite() && weight > 0.0) {
+            return Err(ServerFnError::ServerError(
+                "Weight must be greater than zero".into(),
+            ));
+        }
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+        db::server::require_teaching_assessment(&pool, &user_session, assessment_id)
+            .await
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+
+        let highest = db::server::highest_mark(&pool, assessment_id, code.trim())
+            .await
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+        if highest.is_some_and(|highest| highest > max_mark as f32) {
+            return Err(ServerFnError::ServerError(format!(
+                "{} already has a mark of {}, above the new maximum",
+                code.trim(),
+                highest.unwrap_or_default()
+            )));
+        }
+
+        db::server::upsert_assessment_component(
+            &pool,
+            assessment_id,
+            code.trim(),
+            name.trim(),
+            max_mark,
+            weight,
+        )
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+#[server(DeleteAssessmentComponent, "/api/marks")]
+pub async fn delete_assessment_component(
+    assessment_id: i32,
+    code: String,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use leptos::server_fn::error::NoCustomError;
+
+        let user_session = crate::app::db::server::require_role(MARKS_ROLES).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+        db::server::require_teaching_assessment(&pool, &user_session, assessment_id)
+            .await
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+
+        db::server::delete_assessment_component(&pool, assessment_id, &code)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+#[server(GetMarkSheet, "/api/marks")]
+pub async fn get_mark_sheet(assessment_id: i32) -> Result<MarkSheet, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use leptos::server_fn::error::NoCustomError;
+
+        let user_session = crate::app::db::server::require_role(MARKS_ROLES).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        let sheet = db::server::load_mark_sheet(&pool, assessment_id)
+            .await
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+        db::server::require_teaching(
+            &pool,
+            &user_session,
+            sheet.assessment.class(),
+            sheet.assessment.subject(),
+        )
+        .await
+        .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+
+        Ok(sheet)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+/// Saves a mark sheet. Nothing is stored if any entry is invalid; missing
+/// marks are returned as warnings.
+#[server(name = SaveMarks, prefix = "/api/marks", input = Json)]
+pub async fn save_marks(
+    assessment_id: i32,
+    entries: Vec<MarkEntry>,
+) -> Result<Vec<MarkIssue>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use leptos::server_fn::error::NoCustomError;
+        use shared::validation::Severity;
+
+        let user_session = crate::app::db::server::require_role(MARKS_ROLES).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        let sheet = db::server::load_mark_sheet(&pool, assessment_id)
+            .await
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+        db::server::require_teaching(
+            &pool,
+            &user_session,
+            sheet.assessment.class(),
+            sheet.assessment.subject(),
+        )
+        .await
+        .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+        let candidate_numbers: Vec<String> = sheet
+            .candidates
+            .iter()
+            .map(|candidate| candidate.candidate_number.clone())
+            .collect();
+        let (marks, issues) =
+            shared::marks::validate_entries(&sheet.assessment, &candidate_numbers, &entries);
+
+        let errors: Vec<String> = issues
+            .iter()
+            .filter(|issue| issue.severity() == Severity::Error)
+            .map(|issue| issue.to_string())
+            .collect();
+        if !errors.is_empty() {
+            return Err(ServerFnError::ServerError(errors.join("; ")));
+        }
+
+        let cleared: Vec<&MarkEntry> = entries
+            .iter()
+            .filter(|entry| entry.entry().trim().is_empty())
+            .collect();
+        db::server::save_marks(&pool, assessment_id, &marks, &cleared, user_session.user_id)
+            .await
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+
+        Ok(issues)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+#[component]
+pub fn MarksView() -> impl IntoView {
+    let title = use_context::<RwSignal<String>>()
+        .expect("title context should be provided");
+
+    title.set("Marks Entry".to_string());
+
+    let create_assessment = ServerAction::<CreateAssessment>::new();
+    let delete_assessment = ServerAction::<DeleteAssessment>::new();
+    let save_component = ServerAction::<SaveAssessmentComponent>::new();
+    let delete_component = ServerAction::<DeleteAssessmentComponent>::new();
+
+    let assessments = Resource::new(
+        move || {
+            (
+                create_assessment.version().get(),
+                delete_assessment.version().get(),
+                save_component.version().get(),
+                delete_component.version().get(),
+            )
+        },
+        |_| async move { get_assessments().await },
+    );
+
+    let selected = RwSignal::new(None::<i32>);
+
+    view! {
+        <div class="p-4 space-y-6">
+            <Suspense fallback=move || view! { <LoadingSpinner/> }>
+                {move || assessments.get().map(|result| match result {
+                    Ok(assessments) => view! {
+                        <AssessmentSection
+                            assessments=assessments
+                            selected=selected
+                            create_assessment=create_assessment
+                            delete_assessment=delete_assessment
+                        />
+                    }.into_any(),
+                    Err(e) => view! { <ErrorDisplay error=e.to_string() /> }.into_any(),
+                })}
+            </Suspense>
+            <ActionError result=create_assessment.value() />
+            <ActionError result=delete_assessment.value() />
+            {move || selected.get().map(|assessment_id| view! {
+                <MarkSheetSection
+                    assessment_id=assessment_id
+                    save_component=save_component
+                    delete_component=delete_component
+                />
+            })}
+        </div>
+    }
+}
+
+#[component]
+fn AssessmentSection(
+    assessments: Vec<AssessmentRecord>,
+    selected: RwSignal<Option<i32>>,
+    create_assessment: ServerAction<CreateAssessment>,
+    delete_assessment: ServerAction<DeleteAssessment>,
+) -> impl IntoView {
+    let name = RwSignal::new(String::new());
+    let class_code = RwSignal::new(String::new());
+    let subject_code = RwSignal::new(String::new());
+    let catalogue = Resource::new(|| (), |_| async move { get_catalogue().await });
+    let current = AcademicPeriod::containing(chrono::Local::now().date_naive());
+
+    view! {
+        <section class="bg-white rounded-lg shadow p-4">
+            <h2 class="text-lg font-semibold mb-4">"Assessments"</h2>
+            <table class="min-w-full divide-y divide-gray-200 mb-4">
+                <thead class="bg-gray-50">
+                    <tr>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Name"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Class"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Subject"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Term"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Components"</th>
+                        <th class="px-4 py-2"></th>
+                    </tr>
+                </thead>
+                <tbody class="divide-y divide-gray-200">
+                    {assessments.into_iter().map(|record| {
+                        let assessment_id = record.id;
+                        let assessment = record.assessment;
+                        view! {
+                            <tr
+                                class="hover:bg-gray-50"
+                                class:bg-blue-50=move || selected.get() == Some(assessment_id)
+                            >
+                                <td class="px-4 py-2 text-sm">{assessment.name().to_string()}</td>
+                                <td class="px-4 py-2 text-sm">{assessment.class().to_string()}</td>
+                                <td class="px-4 py-2 text-sm">{assessment.subject().to_string()}</td>
+                                <td class="px-4 py-2 text-sm">{assessment.period().to_string()}</td>
+                                <td class="px-4 py-2 text-sm">
+                                    {assessment.components().iter().map(|component| component.code().to_string()).collect::<Vec<_>>().join(", ")}
+                                </td>
+                                <td class="px-4 py-2 text-sm text-right space-x-2">
+                                    <button class="text-blue-600" on:click=move |_| selected.set(Some(assessment_id))>
+                                        "Enter Marks"
+                                    </button>
+                                    <button
+                                        class="text-red-600"
+                                        on:click=move |_| {
+                                            if selected.get_untracked() == Some(assessment_id) {
+                                                selected.set(None);
+                                            }
+                                            delete_assessment.dispatch(DeleteAssessment { assessment_id });
+                                        }
+                                    >
+                                        "Delete"
+                                    </button>
+                                </td>
+                            </tr>
+                        }
+                    }).collect_view()}
+                </tbody>
+            </table>
+            <ActionForm attr:class="flex gap-2 items-center" action=create_assessment>
+                <input type="text" name="name" placeholder="Name (e.g. Term 1 Exam 2024)" required class="px-3 py-2 border rounded" bind:value=name />
+                <select
+                    name="class_code"
+                    required
+                    class="px-3 py-2 border rounded"
+                    prop:value=move || class_code.get()
+                    on:change=move |ev| {
+                        class_code.set(event_target_value(&ev));
+                        subject_code.set(String::new());
+                    }
+                >
+                    <option value="">"Class..."</option>
+                    <ClassOptions catalogue=catalogue />
+                </select>
+                <select
+                    name="subject_code"
+                    required
+                    class="px-3 py-2 border rounded"
+                    prop:value=move || subject_code.get()
+                    on:change=move |ev| subject_code.set(event_target_value(&ev))
+                >
+                    <option value="">"Subject..."</option>
+                    <Suspense fallback=|| ()>
+                        {move || catalogue.get().and_then(Result::ok).and_then(|catalogue| {
+                            let class = catalogue.class_for(&class_code.get()).ok()?;
+                            let options = catalogue
+                                .subjects_offered(class)
+                                .map(|subject| view! { <option value=subject.code()>{subject.to_string()}</option> })
+                                .collect_view();
+                            Some(options)
+                        })}
+                    </Suspense>
+                </select>
+                <select name="academic_year" required class="px-3 py-2 border rounded">
+                    {(current.year() - 1..=current.year() + 1).map(|year| {
+                        let label = AcademicPeriod::new(year, Term::First).year_label();
+                        view! { <option value=year selected=year == current.year()>{label}</option> }
+                    }).collect_view()}
+                </select>
+                <select name="term" required class="px-3 py-2 border rounded">
+                    {Term::ALL.map(|term| view! {
+                        <option value=term.number() selected=term == current.term()>{term.to_string()}</option>
+                    }).collect_view()}
+                </select>
+                <button type="submit" class="bg-gray-700 text-white px-4 py-2 rounded">"Create Assessment"</button>
+            </ActionForm>
+        </section>
+    }
+}
+
+#[component]
+fn MarkSheetSection(
+    assessment_id: i32,
+    save_component: ServerAction<SaveAssessmentComponent>,
+    delete_component: ServerAction<DeleteAssessmentComponent>,
+) -> impl IntoView {
+    let save_marks = ServerAction::<SaveMarks>::new();
+
+    let sheet = Resource::new(
+        move || {
+            (
+                save_marks.version().get(),
+                save_component.version().get(),
+                delete_component.version().get(),
+            )
+        },
+        move |_| async move { get_mark_sheet(assessment_id).await },
+    );
+
+    view! {
+        <Suspense fallback=move || view! { <LoadingSpinner/> }>
+            {move || sheet.get().map(|result| match result {
+                Ok(sheet) => view! {
+                    <ComponentSection
+                        sheet=sheet.clone()
+                        save_component=save_component
+                        delete_component=delete_component
+                    />
+                    <MarkGrid sheet=sheet.clone() save_marks=save_marks />
+                    <MarksImportSection sheet=sheet save_marks=save_marks />
+                }.into_any(),
+                Err(e) => view! { <ErrorDisplay error=e.to_string() /> }.into_any(),
+            })}
+        </Suspense>
+        <ActionError result=save_component.value() />
+        <ActionError result=delete_component.value() />
+        <ActionError result=save_marks.value() />
+        {move || save_marks.value().get().and_then(Result::ok).filter(|issues| !issues.is_empty()).map(|issues| view! {
+            <div class="bg-yellow-50 border border-yellow-300 text-yellow-800 px-4 py-3 rounded mt-4">
+                <p class="font-semibold">{format!("Saved. {} marks still missing:", issues.len())}</p>
+                <ul class="list-disc ml-6 text-sm">
+                    {issues.into_iter().map(|issue| view! { <li>{issue.to_string()}</li> }).collect_view()}
+                </ul>
+            </div>
+        })}
+    }
+}
+
+#[component]
+fn ComponentSection(
+    sheet: MarkSheet,
+    save_component: ServerAction<SaveAssessmentComponent>,
+    delete_component: ServerAction<DeleteAssessmentComponent>,
+) -> impl IntoView {
+    let assessment_id = sheet.id;
+    let code = RwSignal::new(String::new());
+    let name = RwSignal::new(String::new());
+    let max_mark = RwSignal::new(String::new());
+    let weight = RwSignal::new(String::new());
+
+    view! {
+        <section class="bg-white rounded-lg shadow p-4">
+            <h2 class="text-lg font-semibold mb-4">
+                {format!("{} — {} {}", sheet.assessment.name(), sheet.assessment.class(), sheet.assessment.subject())}
+            </h2>
+            <table class="min-w-full divide-y divide-gray-200 mb-4">
+                <thead class="bg-gray-50">
+                    <tr>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Code"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Component"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Out Of"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Weight"</th>
+                        <th class="px-4 py-2"></th>
+                    </tr>
+                </thead>
+                <tbody class="divide-y divide-gray-200">
+                    {sheet.assessment.components().iter().map(|component| {
+                        let edit_component = component.clone();
+                        let component_code = component.code().to_string();
+                        view! {
+                            <tr class="hover:bg-gray-50">
+                                <td class="px-4 py-2 text-sm">{component.code().to_string()}</td>
+                                <td class="px-4 py-2 text-sm">{component.name().to_string()}</td>
+                                <td class="px-4 py-2 text-sm">{component.max_mark()}</td>
+                                <td class="px-4 py-2 text-sm">{component.weight()}</td>
+                                <td class="px-4 py-2 text-sm text-right space-x-2">
+                                    <button
+                                        class="text-blue-600"
+                                        on:click=move |_| {
+                                            code.set(edit_component.code().to_string());
+                                            name.set(edit_component.name().to_string());
+                                            max_mark.set(edit_component.max_mark().to_string());
+                                            weight.set(edit_component.weight().to_string());
+                                        }
+                                    >
+                                        "Edit"
+                                    </button>
+                                    <button
+                                        class="text-red-600"
+                                        on:click=move |_| {
+                                            delete_component.dispatch(DeleteAssessmentComponent {
+                                                assessment_id,
+                                                code: component_code.clone(),
+                                            });
+                                        }
+                                    >
+                                        "Delete"
+                                    </button>
+                                </td>
+                            </tr>
+                        }
+                    }).collect_view()}
+                </tbody>
+            </table>
+            <ActionForm attr:class="flex gap-2 items-center" action=save_component>
+                <input type="hidden" name="assessment_id" value=assessment_id />
+                <input type="text" name="code" placeholder="Code (e.g. CW)" required class="px-3 py-2 border rounded w-32" bind:value=code />
+                <input type="text" name="name" placeholder="Name (e.g. Coursework)" required class="px-3 py-2 border rounded" bind:value=name />
+                <input type="number" name="max_mark" placeholder="Out of" required min="1" class="px-3 py-2 border rounded w-28" bind:value=max_mark />
+                <input type="number" name="weight" placeholder="Weight" required min="0" step="any" class="px-3 py-2 border rounded w-28" bind:value=weight />
+                <button type="submit" class="bg-gray-700 text-white px-4 py-2 rounded">"Save Component"</button>
+            </ActionForm>
+        </section>
+    }
+}
+
+#[component]
+fn MarkGrid(sheet: MarkSheet, save_marks: ServerAction<SaveMarks>) -> impl IntoView {
+    let assessment_id = sheet.id;
+    let components = sheet.assessment.components().to_vec();
+
+    let cells: Vec<(String, String, RwSignal<String>)> = sheet
+        .candidates
+        .iter()
+        .flat_map(|candidate| {
+            components.iter().map(|component| {
+                let entry = sheet
+                    .marks
+                    .iter()
+                    .find(|mark| {
+                        mark.candidate_number() == candidate.candidate_number
+                            && mark.component_code() == component.code()
+                    })
+                    .map(|mark| match mark.raw_score() {
+                        Some(score) => score.to_string(),
+                        None => shared::marks::ABSENT.to_string(),
+                    })
+                    .unwrap_or_default();
+                (
+                    candidate.candidate_number.clone(),
+                    component.code().to_string(),
+                    RwSignal::new(entry),
+                )
+            })
+        })
+        .collect();
+    let cells = StoredValue::new(cells);
+
+    let cell = move |candidate_number: &str, component_code: &str| {
+        cells.with_value(|cells| {
+            cells
+                .iter()
+                .find(|(candidate, component, _)| {
+                    candidate == candidate_number && component == component_code
+                })
+                .map(|(_, _, entry)| *entry)
+        })
+    };
+
+    let save = move |_| {
+        let entries = cells.with_value(|cells| {
+            cells
+                .iter()
+                .map(|(candidate_number, component_code, entry)| {
+                    MarkEntry::new(
+                        candidate_number.clone(),
+                        component_code.clone(),
+                        entry.get_untracked(),
+                    )
+                })
+                .collect()
+        });
+        save_marks.dispatch(SaveMarks {
+            assessment_id,
+            entries,
+        });
+    };
+
+    view! {
+        <section class="bg-white rounded-lg shadow p-4 overflow-auto-x">
+            <h2 class="text-lg font-semibold mb-2">"Marks"</h2>
+            <p class="text-sm text-gray-500 mb-4">
+                {format!("Enter raw marks. Type {} for an absent candidate; leave blank if not yet marked.", shared::marks::ABSENT)}
+            </p>
+            <table class="min-w-full divide-y divide-gray-200 mb-4">
+                <thead class="bg-gray-50">
+                    <tr>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Candidate"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Name"</th>
+                        {components.iter().map(|component| view! {
+                            <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">
+                                {format!("{} (/{})", component.code(), component.max_mark())}
+                            </th>
+                        }).collect_view()}
+                    </tr>
+                </thead>
+                <tbody class="divide-y divide-gray-200">
+                    {sheet.candidates.iter().map(|candidate| view! {
+                        <tr class="hover:bg-gray-50">
+                            <td class="px-4 py-2 text-sm">{candidate.candidate_number.clone()}</td>
+                            <td class="px-4 py-2 text-sm">{candidate.name.clone()}</td>
+                            {components.iter().map(|component| {
+                                let max_mark = component.max_mark();
+                                cell(&candidate.candidate_number, component.code()).map(|entry| view! {
+                                    <td class="px-2 py-1">
+                                        <input
+                                            type="text"
+                                            inputmode="decimal"
+                                            class="px-2 py-1 border rounded w-20"
+                                            class:border-red-500=move || {
+                                                let text = entry.get();
+                                                let text = text.trim();
+                                                !text.is_empty()
+                                                    && !text.eq_ignore_ascii_case(shared::marks::ABSENT)
+                                                    && !text.parse::<f32>().is_ok_and(|score| (0.0..=max_mark as f32).contains(&score))
+                                            }
+                                            bind:value=entry
+                                        />
+                                    </td>
+                                })
+                            }).collect_view()}
+                        </tr>
+                    }).collect_view()}
+                </tbody>
+            </table>
+            <button class="bg-gray-700 text-white px-4 py-2 rounded" on:click=save>"Save Marks"</button>
+        </section>
+    }
+}
+
+pub mod db {
+    use serde::{Deserialize, Serialize};
+    use shared::marks::{Assessment, Mark};
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    pub struct AssessmentRecord {
+        pub id: i32,
+        pub assessment: Assessment,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    pub struct Candidate {
+        pub candidate_number: String,
+        pub name: String,
+    }
+
+    /// An assessment with its class list and the marks entered so far.
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    pub struct MarkSheet {
+        pub id: i32,
+        pub assessment: Assessment,
+        pub candidates: Vec<Candidate>,
+        pub marks: Vec<Mark>,
+    }
+
+    #[cfg(feature = "ssr")]
+    pub mod server {
+        use super::{AssessmentRecord, Candidate, MarkSheet};
+        use crate::app::db::{Error, UserSession};
+        use crate::catalogue::db::server::{stored_class, stored_subject};
+        use crate::teachers::db::TeacherRecord;
+        use shared::domain::{StudentClass, Subject};
+        use shared::analysis::AssessmentResults;
+        use shared::marks::{AcademicPeriod, Assessment, AssessmentComponent, Mark, MarkEntry, Term};
+        use sqlx::PgPool;
+
+        /// Max mark given to components created from catalogue papers.
+        pub const DEFAULT_MAX_MARK: i32 = 100;
+
+        pub async fn create_marks_tables(pool: &PgPool) -> Result<(), Error> {
+            for query in [
+                "CREATE TABLE IF NOT EXISTS assessments (
+                    id SERIAL PRIMARY KEY,
+                    name VARCHAR(100) NOT NULL,
+                    class_code VARCHAR(10) NOT NULL,
+                    subject_code VARCHAR(30) NOT NULL,
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+                )",
+                "CREATE TABLE IF NOT EXISTS assessment_components (
+                    id SERIAL PRIMARY KEY,
+                    assessment_id INTEGER NOT NULL REFERENCES assessments(id) ON DELETE CASCADE,
+                    code VARCHAR(20) NOT NULL,
+                    name VARCHAR(100) NOT NULL,
+                    max_mark INTEGER NOT NULL CHECK (max_mark > 0),
+                    weight REAL NOT NULL CHECK (weight > 0),
+                    UNIQUE (assessment_id, code)
+                )",
+                // Assessments made before terms were recorded are placed in
+                // the term they were created in.
+                "ALTER TABLE assessments
+                    ADD COLUMN IF NOT EXISTS academic_year INTEGER,
+                    ADD COLUMN IF NOT EXISTS term SMALLINT CHECK (term BETWEEN 1 AND 3)",
+                "UPDATE assessments SET
+                    academic_year = EXTRACT(YEAR FROM created_at)::INTEGER
+                        - CASE WHEN EXTRACT(MONTH FROM created_at) >= 9 THEN 0 ELSE 1 END,
+                    term = CASE
+                        WHEN EXTRACT(MONTH FROM created_at) >= 9 THEN 1
+                        WHEN EXTRACT(MONTH FROM created_at) <= 4 THEN 2
+                        ELSE 3
+                    END
+                WHERE academic_year IS NULL OR term IS NULL",
+                // A name may be reused in another term, so the period is part
+                // of what identifies an assessment.
+                "ALTER TABLE assessments
+                    DROP CONSTRAINT IF EXISTS assessments_name_class_code_subject_code_key",
+                "CREATE UNIQUE INDEX IF NOT EXISTS assessments_name_class_subject_period_key
+                    ON assessments (name, class_code, subject_code, academic_year, term)",
+                "CREATE TABLE IF NOT EXISTS marks (
+                    component_id INTEGER NOT NULL REFERENCES assessment_components(id) ON DELETE CASCADE,
+                    candidate_number VARCHAR(20) NOT NULL,
+                    raw_score REAL,
+                    entered_by INTEGER REFERENCES users(id),
+                    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    PRIMARY KEY (component_id, candidate_number)
+                )",
+            ] {
+                sqlx::query(query).execute(pool).await.map_err(|e| {
+                    log::error!("Failed to create marks tables: {}", e);
+                    Error::TableNotCreated
+                })?;
+            }
+
+            Ok(())
+        }
+
+        pub async fn load_assessments(pool: &PgPool) -> Result<Vec<AssessmentRecord>, Error> {
+            load_assessment_records(pool, None).await
+        }
+
+        /// Every assessment when `assessment_id` is `None`, otherwise only
+        /// that one.
+        async fn load_assessment_records(
+            pool: &PgPool,
+            assessment_id: Option<i32>,
+        ) -> Result<Vec<AssessmentRecord>, Error> {
+            let assessments = sqlx::query_as::<_, (i32, String, String, String, i32, i16)>(
+                "SELECT id, name, class_code, subject_code, academic_year, term
+                FROM assessments
+                WHERE $1::INTEGER IS NULL OR id = $1
+                ORDER BY created_at DESC, id DESC",
+            )
+            .bind(assessment_id)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to retrieve assessments: {}", e);
+                Error::DatabaseQueryFailed
+            })?;
+
+            let components = sqlx::query_as::<_, (i32, String, String, i32, f32)>(
+                "SELECT assessment_id, code, name, max_mark, weight FROM assessment_components
+                WHERE $1::INTEGER IS NULL OR assessment_id = $1
+                ORDER BY id",
+            )
+            .bind(assessment_id)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to retrieve assessment components: {}", e);
+                Error::DatabaseQueryFailed
+            })?;
+
+            let catalogue = crate::catalogue::db::server::load_catalogue(pool).await?;
+            assessments
+                .into_iter()
+                .map(|(id, name, class_code, subject_code, academic_year, term)| {
+                    let components = components
+                        .iter()
+                        .filter(|(assessment_id, ..)| *assessment_id == id)
+                        .map(|(_, code, name, max_mark, weight)| {
+                            AssessmentComponent::new(
+                                code.clone(),
+                                name.clone(),
+                                *max_mark as u32,
+                                *weight,
+                            )
+                        })
+                        .collect();
+                    let Some(term) = Term::from_number(term as u8) else {
+                        log::error!("Assessment {} has unknown term {}", id, term);
+                        return Err(Error::DatabaseQueryFailed);
+                    };
+                    Ok(AssessmentRecord {
+                        id,
+                        assessment: Assessment::new(
+                            name,
+                            stored_class(&catalogue, &class_code)?,
+                            stored_subject(&catalogue, &subject_code)?,
+                            AcademicPeriod::new(academic_year, term),
+                            components,
+                        ),
+                    })
+                })
+                .collect()
+        }
+
+        pub async fn load_assessment(pool: &PgPool, assessment_id: i32) -> Result<Assessment, Error> {
+            load_assessment_records(pool, Some(assessment_id))
+                .await?
+                .pop()
+                .map(|record| record.assessment)
+                .ok_or_else(|| {
+                    log::error!("Assessment {} not found", assessment_id);
+                    Error::DatabaseQueryFailed
+                })
+        }
+
+        fn is_marks_officer(role_name: Option<&str>) -> bool {
+            role_name.is_some_and(|role_name| crate::marks::MARKS_OFFICER_ROLES.contains(&role_name))
+        }
+
+        /// Whether a user with `role_name`, linked to `teacher` if they have
+        /// a teacher profile, may write marks for `subject` in `class`.
+        pub fn may_write_marks(
+            role_name: Option<&str>,
+            teacher: Option<&TeacherRecord>,
+            class: StudentClass,
+            subject: Subject,
+        ) -> bool {
+            if is_marks_officer(role_name) {
+                return true;
+            }
+            teacher.is_some_and(|record| {
+                record.classes.contains(&class)
+                    && record.teacher.subjects_taught().contains(&subject)
+            })
+        }
+
+        /// Refuses unless the user may write marks for `subject` in `class`.
+        /// Reading them needs the same.
+        pub async fn require_teaching(
+            pool: &PgPool,
+            user_session: &UserSession,
+            class: StudentClass,
+            subject: Subject,
+        ) -> Result<(), Error> {
+            let role_name = user_session.role_name.as_deref();
+            let teacher = if is_marks_officer(role_name) {
+                None
+            } else {
+                crate::teachers::db::server::load_teacher_for_user(pool, user_session.user_id).await?
+            };
+            if may_write_marks(role_name, teacher.as_ref(), class, subject) {
+                Ok(())
+            } else {
+                Err(Error::NotAuthorized)
+            }
+        }
+
+        /// Refuses unless the user may write marks for the assessment.
+        pub async fn require_teaching_assessment(
+            pool: &PgPool,
+            user_session: &UserSession,
+            assessment_id: i32,
+        ) -> Result<(), Error> {
+            let assessment = load_assessment(pool, assessment_id).await?;
+            require_teaching(pool, user_session, assessment.class(), assessment.subject()).await
+        }
+
+        pub async fn create_assessment(
+            pool: &PgPool,
+            name: &str,
+            class: StudentClass,
+            subject: Subject,
+            period: AcademicPeriod,
+        ) -> Result<(), Error> {
+            let catalogue = crate::catalogue::db::server::load_catalogue(pool).await?;
+            let papers = shared::timetable::paper_components(&catalogue, subject);
+
+            let mut tx = pool.begin().await.map_err(|e| {
+                log::error!("Failed to begin transaction: {}", e);
+                Error::TransactionFailed
+            })?;
+
+            let assessment_id: i32 = sqlx::query_scalar(
+                "INSERT INTO assessments (name, class_code, subject_code, academic_year, term)
+                VALUES ($1, $2, $3, $4, $5) RETURNING id",
+            )
+            .bind(name)
+            .bind(class.code())
+            .bind(subject.code())
+            .bind(period.year())
+            .bind(i16::from(period.term().number()))
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to create assessment {}: {}", name, e);
+                Error::DatabaseQueryFailed
+            })?;
+
+            for paper in papers {
+                sqlx::query(
+                    "INSERT INTO assessment_components (assessment_id, code, name, max_mark, weight)
+                    VALUES ($1, $2, $3, $4, 1.0)",
+                )
+                .bind(assessment_id)
+                .bind(paper.code())
+                .bind(paper.name())
+                .bind(DEFAULT_MAX_MARK)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to create assessment component {}: {}", paper.code(), e);
+                    Error::DatabaseQueryFailed
+                })?;
+            }
+
+            tx.commit().await.map_err(|e| {
+                log::error!("Failed to commit transaction: {}", e);
+                Error::TransactionFailed
+            })?;
+
+            Ok(())
+        }
+
+        pub async fn delete_assessment(pool: &PgPool, assessment_id: i32) -> Result<(), Error> {
+            sqlx::query("DELETE FROM assessments WHERE id = $1")
+                .bind(assessment_id)
+                .execute(pool)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to delete assessment {}: {}", assessment_id, e);
+                    Error::DatabaseQueryFailed
+                })?;
+
+            Ok(())
+        }
+
+        pub async fn upsert_assessment_component(
+            pool: &PgPool,
+            assessment_id: i32,
+            code: &str,
+            name: &str,
+            max_mark: i32,
+            weight: f32,
+        ) -> Result<(), Error> {
+            sqlx::query(
+                r#"
+                INSERT INTO assessment_components (assessment_id, code, name, max_mark, weight)
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT (assessment_id, code) DO UPDATE
+                SET name = EXCLUDED.name,
+                    max_mark = EXCLUDED.max_mark,
+                    weight = EXCLUDED.weight
+                "#,
+            )
+            .bind(assessment_id)
+            .bind(code)
+            .bind(name)
+            .bind(max_mark)
+            .bind(weight)
+            .execute(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to save assessment component {}: {}", code, e);
+                Error::DatabaseQueryFailed
+            })?;
+
+            Ok(())
+        }
+
+        pub async fn delete_assessment_component(
+            pool: &PgPool,
+            assessment_id: i32,
+            code: &str,
+        ) -> Result<(), Error> {
+            sqlx::query("DELETE FROM assessment_components WHERE assessment_id = $1 AND code = $2")
+                .bind(assessment_id)
+                .bind(code)
+                .execute(pool)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to delete assessment component {}: {}", code, e);
+                    Error::DatabaseQueryFailed
+                })?;
+
+            Ok(())
+        }
+
+        /// The highest mark entered for a component, if any.
+        pub async fn highest_mark(
+            pool: &PgPool,
+            assessment_id: i32,
+            code: &str,
+        ) -> Result<Option<f32>, Error> {
+            sqlx::query_scalar::<_, Option<f32>>(
+                "SELECT MAX(m.raw_score)
+                FROM marks m
+                JOIN assessment_components c ON c.id = m.component_id
+                WHERE c.assessment_id = $1 AND c.code = $2",
+            )
+            .bind(assessment_id)
+            .bind(code)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to retrieve highest mark for {}: {}", code, e);
+                Error::DatabaseQueryFailed
+            })
+        }
+
+        pub async fn load_marks(pool: &PgPool, assessment_id: i32) -> Result<Vec<Mark>, Error> {
+            let rows = sqlx::query_as::<_, (String, String, Option<f32>)>(
+                "SELECT m.candidate_number, c.code, m.raw_score
+                FROM marks m
+                JOIN assessment_components c ON c.id = m.component_id
+                WHERE c.assessment_id = $1",
+            )
+            .bind(assessment_id)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to retrieve marks for assessment {}: {}", assessment_id, e);
+                Error::DatabaseQueryFailed
+            })?;
+
+            Ok(rows
+                .into_iter()
+                .map(|(candidate_number, code, raw_score)| Mark::new(candidate_number, code, raw_score))
+                .collect())
+        }
+
+        /// The marks in each of `assessment_ids`, with the assessment they
+        /// belong to, in one query.
+        pub async fn load_marks_for(
+            pool: &PgPool,
+            assessment_ids: &[i32],
+        ) -> Result<Vec<(i32, Mark)>, Error> {
+            let rows = sqlx::query_as::<_, (i32, String, String, Option<f32>)>(
+                "SELECT c.assessment_id, m.candidate_number, c.code, m.raw_score
+                FROM marks m
+                JOIN assessment_components c ON c.id = m.component_id
+                WHERE c.assessment_id = ANY($1)",
+            )
+            .bind(assessment_ids)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to retrieve marks: {}", e);
+                Error::DatabaseQueryFailed
+            })?;
+
+            Ok(rows
+                .into_iter()
+                .map(|(assessment_id, candidate_number, code, raw_score)| {
+                    (assessment_id, Mark::new(candidate_number, code, raw_score))
+                })
+                .collect())
+        }
+
+        pub async fn load_mark_sheet(pool: &PgPool, assessment_id: i32) -> Result<MarkSheet, Error> {
+            let assessment = load_assessment(pool, assessment_id).await?;
+            let candidates = crate::students::db::server::load_class_list(
+                pool,
+                assessment.class(),
+                Some(assessment.subject()),
+            )
+            .await?
+            .into_iter()
+            .map(|student| {
+                let name = student.personal_info().name();
+                Candidate {
+                    candidate_number: student.candidate_number().to_string(),
+                    name: format!("{} {}", name.first_name(), name.last_name()),
+                }
+            })
+            .collect();
+            let marks = load_marks(pool, assessment_id).await?;
+
+            Ok(MarkSheet {
+                id: assessment_id,
+                assessment,
+                candidates,
+                marks,
+            })
+        }
+
+        /// Every assessment called `name` for `class` in `period`, one per
+        /// subject, with its marks.
+        pub async fn load_results(
+            pool: &PgPool,
+            class: StudentClass,
+            name: &str,
+            period: AcademicPeriod,
+        ) -> Result<Vec<AssessmentResults>, Error> {
+            let mut results = Vec::new();
+            for record in load_assessments(pool).await? {
+                if record.assessment.class() == class
+                    && record.assessment.name() == name
+                    && record.assessment.period() == period
+                {
+                    let marks = load_marks(pool, record.id).await?;
+                    results.push(AssessmentResults::new(record.assessment, marks));
+                }
+            }
+
+            Ok(results)
+        }
+
+        /// Ids of the assessments any of `candidate_numbers` has a mark in.
+        pub async fn assessments_sat_by(
+            pool: &PgPool,
+            candidate_numbers: &[String],
+        ) -> Result<Vec<i32>, Error> {
+            sqlx::query_scalar::<_, i32>(
+                "SELECT DISTINCT c.assessment_id
+                FROM marks m
+                JOIN assessment_components c ON c.id = m.component_id
+                WHERE m.candidate_number = ANY($1)",
+            )
+            .bind(candidate_numbers)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to retrieve assessments sat: {}", e);
+                Error::DatabaseQueryFailed
+            })
+        }
+
+        /// Stores `marks` and removes any mark for the `cleared` cells, all or
+        /// nothing.
+        pub async fn save_marks(
+            pool: &PgPool,
+            assessment_id: i32,
+            marks: &[Mark],
+            cleared: &[&MarkEntry],
+            entered_by: i32,
+        ) -> Result<(), Error> {
+            let mut tx = pool.begin().await.map_err(|e| {
+                log::error!("Failed to begin transaction: {}", e);
+                Error::TransactionFailed
+            })?;
+
+            for mark in marks {
+                sqlx::query(
+                    r#"
+                    INSERT INTO marks (component_id, candidate_number, raw_score, entered_by, updated_at)
+                    SELECT id, $3, $4, $5, NOW() FROM assessment_components
+                    WHERE assessment_id = $1 AND code = $2
+                    ON CONFLICT (component_id, candidate_number) DO UPDATE
+                    SET raw_score = EXCLUDED.raw_score,
+                        entered_by = EXCLUDED.entered_by,
+                        updated_at = NOW()
+                    WHERE marks.raw_score IS DISTINCT FROM EXCLUDED.raw_score
+                    "#,
+                )
+                .bind(assessment_id)
+                .bind(mark.component_code())
+                .bind(mark.candidate_number())
+                .bind(mark.raw_score())
+                .bind(entered_by)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to save mark for {}: {}", mark.candidate_number(), e);
+                    Error::DatabaseQueryFailed
+                })?;
+            }
+
+            for entry in cleared {
+                sqlx::query(
+                    "DELETE FROM marks
+                    WHERE candidate_number = $3
+                        AND component_id = (SELECT id FROM assessment_components WHERE assessment_id = $1 AND code = $2)",
+                )
+                .bind(assessment_id)
+                .bind(entry.component_code())
+                .bind(entry.candidate_number())
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to clear mark for {}: {}", entry.candidate_number(), e);
+                    Error::DatabaseQueryFailed
+                })?;
+            }
+
+            tx.commit().await.map_err(|e| {
+                log::error!("Failed to commit transaction: {}", e);
+                Error::TransactionFailed
+            })?;
+
+            Ok(())
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use shared::catalogue::Catalogue;
+            use shared::domain::{
+                Gender, PersonalName, StudentClass, Subject, Teacher, TeacherPersonalInfo,
+            };
+
+            use super::may_write_marks;
+            use crate::teachers::db::TeacherRecord;
+
+            fn two_classes_and_subjects() -> ([StudentClass; 2], [Subject; 2]) {
+                let catalogue = Catalogue::seed();
+                let classes = catalogue.classes();
+                let (class, subjects): (_, Vec<Subject>) = classes
+                    .iter()
+                    .map(|&class| (class, catalogue.subjects_offered(class).take(2).collect()))
+                    .find(|(_, subjects): &(_, Vec<_>)| subjects.len() == 2)
+                    .expect("a class with two subjects");
+                let other = classes
+                    .into_iter()
+                    .find(|&other| other != class)
+                    .expect("a second class");
+                ([class, other], [subjects[0], subjects[1]])
+            }
+
+            fn teacher(classes: Vec<StudentClass>, subjects: Vec<Subject>) -> TeacherRecord {
+                TeacherRecord {
+                    id: 1,
+                    teacher: Teacher::new(
+                        TeacherPersonalInfo::new(
+                            PersonalName::new("Ama".to_string(), String::new(), "Mensah".to_string()),
+                            Gender::Female,
+                        ),
+                        subjects,
+                    ),
+                    classes,
+                    unavailable_days: Vec::new(),
+                    unavailable_sessions: Vec::new(),
+                    leave: Vec::new(),
+                    max_duties_per_day: None,
+                    no_back_to_back: None,
+                    preferred_sessions: Vec::new(),
+                    user_id: Some(7),
+                    username: Some("amensah".to_string()),
+                    is_archived: false,
+                }
+            }
+
+            #[test]
+            fn teacher_cannot_write_marks_for_another_class() {
+                let ([own, other], [subject, _]) = two_classes_and_subjects();
+                let record = teacher(vec![own], vec![subject]);
+
+                assert!(!may_write_marks(Some("teacher"), Some(&record), other, subject));
+            }
+
+            #[test]
+            fn teacher_writes_marks_only_for_subjects_they_teach() {
+                let ([class, _], [taught, not_taught]) = two_classes_and_subjects();
+                let record = teacher(vec![class], vec![taught]);
+
+                assert!(may_write_marks(Some("teacher"), Some(&record), class, taught));
+                assert!(!may_write_marks(Some("teacher"), Some(&record), class, not_taught));
+            }
+
+            #[test]
+            fn teacher_without_a_profile_cannot_write_marks() {
+                let ([class, _], [subject, _]) = two_classes_and_subjects();
+
+                assert!(!may_write_marks(Some("teacher"), None, class, subject));
+                assert!(!may_write_marks(None, None, class, subject));
+            }
+
+            #[test]
+            fn officers_write_marks_for_any_class() {
+                let ([_, other], [_, subject]) = two_classes_and_subjects();
+
+                assert!(may_write_marks(Some("admin"), None, other, subject));
+                assert!(may_write_marks(Some("exams_officer"), None, other, subject));
+            }
+        }
+    }
+}
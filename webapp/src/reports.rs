@@ -0,0 +1,783 @@
+use leptos::prelude::*;
+use shared::analysis::RankBy;
+use shared::marks::AcademicPeriod;
+use shared::report_card::ReportCard;
+
+use crate::analysis::ClassAssessmentPicker;
+use crate::app::{ActionError, ErrorDisplay, LoadingSpinner};
+
+/// Printed at the top of every report card.
+pub const SCHOOL_NAME: &str = "Ridge School, Kumasi";
+
+#[server(GetReportCards, "/api/reports")]
+pub async fn get_report_cards(
+    class_code: String,
+    assessment_name: String,
+    academic_year: i32,
+    term: u8,
+    rank_by: String,
+) -> Result<Vec<ReportCard>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(crate::marks::MARKS_ROLES).await?;
+        let period = crate::marks::requested_period(academic_year, term)?;
+        let Some(rank_by) = RankBy::from_name(&rank_by) else {
+            return Err(ServerFnError::ServerError(format!("Unknown ranking: {}", rank_by)));
+        };
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+        let class = crate::catalogue::requested_class(&pool, &class_code).await?;
+
+        db::server::load_report_cards(&pool, class, &assessment_name, period, rank_by)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+#[server(SaveReportRemarks, "/api/reports")]
+pub async fn save_report_remarks(
+    class_code: String,
+    assessment_name: String,
+    academic_year: i32,
+    term: u8,
+    candidate_number: String,
+    remarks: String,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let user_session = crate::app::db::server::require_role(crate::marks::MARKS_ROLES).await?;
+        let period = crate::marks::requested_period(academic_year, term)?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+        let class = crate::catalogue::requested_class(&pool, &class_code).await?;
+
+        db::server::save_remarks(
+            &pool,
+            class,
+            &assessment_name,
+            period,
+            &candidate_number,
+            remarks.trim(),
+            user_session.user_id,
+        )
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+/// Link to one of the download routes registered in `main`.
+fn download_url(
+    file: &str,
+    class_code: &str,
+    assessment_name: &str,
+    period: AcademicPeriod,
+    rank_by: &str,
+    candidate: Option<&str>,
+) -> String {
+    let encode = |value: &str| {
+        value
+            .bytes()
+            .map(|byte| match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                    (byte as char).to_string()
+                }
+                _ => format!("%{:02X}", byte),
+            })
+            .collect::<String>()
+    };
+    let mut url = format!(
+        "/api/reports/{}?class={}&assessment={}&academic_year={}&term={}&rank_by={}",
+        file,
+        encode(class_code),
+        encode(assessment_name),
+        period.year(),
+        period.term().number(),
+        encode(rank_by)
+    );
+    if let Some(candidate) = candidate {
+        url.push_str(&format!("&candidate={}", encode(candidate)));
+    }
+    url
+}
+
+#[component]
+pub fn ReportCardsView() -> impl IntoView {
+    let title = use_context::<RwSignal<String>>()
+        .expect("title context should be provided");
+
+    title.set("Report Cards".to_string());
+
+    let class_code = RwSignal::new(String::new());
+    let assessment = RwSignal::new(None::<(String, AcademicPeriod)>);
+    let rank_by = RwSignal::new(RankBy::default().as_str().to_string());
+    let save_remarks = ServerAction::<SaveReportRemarks>::new();
+
+    let cards = Resource::new(
+        move || {
+            (
+                class_code.get(),
+                assessment.get(),
+                rank_by.get(),
+                save_remarks.version().get(),
+            )
+        },
+        |(class_code, assessment, rank_by, _)| async move {
+            let Some((assessment_name, period)) = assessment.filter(|_| !class_code.is_empty())
+            else {
+                return Ok(Vec::new());
+            };
+            get_report_cards(
+                class_code,
+                assessment_name,
+                period.year(),
+                period.term().number(),
+                rank_by,
+            )
+            .await
+        },
+    );
+
+    view! {
+        <div class="p-4 space-y-6">
+            <div class="flex gap-2 items-center">
+                <ClassAssessmentPicker class_code=class_code assessment=assessment />
+                <label class="text-sm">"Rank by"</label>
+                <select
+                    class="px-3 py-2 border rounded"
+                    prop:value=move || rank_by.get()
+                    on:change=move |ev| rank_by.set(event_target_value(&ev))
+                >
+                    {RankBy::NAMES.iter().map(|name| view! {
+                        <option value=*name>{*name}</option>
+                    }).collect_view()}
+                </select>
+                {move || assessment.get().filter(|_| !class_code.get().is_empty()).map(|(assessment_name, period)| {
+                    let class_code = class_code.get();
+                    let rank_by = rank_by.get();
+                    view! {
+                        <a
+                            class="bg-gray-700 text-white px-4 py-2 rounded"
+                            href=download_url("class.pdf", &class_code, &assessment_name, period, &rank_by, None)
+                        >
+                            "Download All (PDF)"
+                        </a>
+                        <a
+                            class="bg-gray-700 text-white px-4 py-2 rounded"
+                            href=download_url("class.zip", &class_code, &assessment_name, period, &rank_by, None)
+                        >
+                            "Download All (Zip)"
+                        </a>
+                    }
+                })}
+            </div>
+            <ActionError result=save_remarks.value() />
+            <Suspense fallback=move || view! { <LoadingSpinner/> }>
+                {move || cards.get().map(|result| match result {
+                    Ok(cards) if cards.is_empty() => view! {
+                        <p class="text-gray-500">"Choose a class and assessment with marks entered."</p>
+                    }.into_any(),
+                    Ok(cards) => match assessment.get_untracked() {
+                        Some((assessment_name, period)) => view! {
+                            <ReportCardList
+                                cards=cards
+                                class_code=class_code.get_untracked()
+                                assessment_name=assessment_name
+                                period=period
+                                rank_by=rank_by.get_untracked()
+                                save_remarks=save_remarks
+                            />
+                        }.into_any(),
+                        None => ().into_any(),
+                    },
+                    Err(e) => view! { <ErrorDisplay error=e.to_string() /> }.into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn ReportCardList(
+    cards: Vec<ReportCard>,
+    class_code: String,
+    assessment_name: String,
+    period: AcademicPeriod,
+    rank_by: String,
+    save_remarks: ServerAction<SaveReportRemarks>,
+) -> impl IntoView {
+    view! {
+        <section class="bg-white rounded-lg shadow p-4">
+            <table class="min-w-full divide-y divide-gray-200">
+                <thead class="bg-gray-50">
+                    <tr>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Candidate"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Name"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Average %"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Position"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Teacher's Remarks"</th>
+                        <th class="px-4 py-2"></th>
+                    </tr>
+                </thead>
+                <tbody class="divide-y divide-gray-200">
+                    {cards.into_iter().map(|card| {
+                        let candidate_number = card.student().candidate_number().to_string();
+                        let name = card.student().personal_info().name();
+                        let remarks = RwSignal::new(card.remarks().to_string());
+                        let pdf_url = download_url("card.pdf", &class_code, &assessment_name, period, &rank_by, Some(&candidate_number));
+                        let class_code = class_code.clone();
+                        let assessment_name = assessment_name.clone();
+                        view! {
+                            <tr class="hover:bg-gray-50 align-top">
+                                <td class="px-4 py-2 text-sm">{card.student().candidate_number().to_string()}</td>
+                                <td class="px-4 py-2 text-sm">{format!("{} {}", name.first_name(), name.last_name())}</td>
+                                <td class="px-4 py-2 text-sm">{format!("{:.1}", card.overall_average())}</td>
+                                <td class="px-4 py-2 text-sm">
+                                    {card.position().map(|position| format!("{} of {}", position, position.out_of()))}
+                                </td>
+                                <td class="px-4 py-2 text-sm">
+                                    <ActionForm attr:class="flex gap-2 items-start" action=save_remarks>
+                                        <input type="hidden" name="class_code" value=class_code />
+                                        <input type="hidden" name="assessment_name" value=assessment_name />
+                                        <input type="hidden" name="academic_year" value=period.year() />
+                                        <input type="hidden" name="term" value=period.term().number() />
+                                        <input type="hidden" name="candidate_number" value=candidate_number />
+                                        <textarea name="remarks" rows="2" class="px-2 py-1 border rounded w-80" bind:value=remarks></textarea>
+                                        <button type="submit" class="text-blue-600">"Save"</button>
+                                    </ActionForm>
+                                </td>
+                                <td class="px-4 py-2 text-sm text-right">
+                                    <a class="text-blue-600" href=pdf_url>
+                                        "PDF"
+                                    </a>
+                                </td>
+                            </tr>
+                        }
+                    }).collect_view()}
+                </tbody>
+            </table>
+        </section>
+    }
+}
+
+/// Download handlers, registered as plain actix routes because they return
+/// files rather than data.
+#[cfg(feature = "ssr")]
+pub mod server {
+    use std::io::{Cursor, Write};
+    use std::sync::Arc;
+
+    use actix_session::Session;
+    use actix_web::{HttpResponse, web};
+    use leptos::config::LeptosOptions;
+    use printpdf::image_crate::{DynamicImage, Rgb, RgbImage};
+    use printpdf::{
+        BuiltinFont, Image, ImageTransform, IndirectFontRef, Line, Mm, PdfDocument,
+        PdfLayerReference, Point,
+    };
+    use serde::Deserialize;
+    use shared::analysis::RankBy;
+    use shared::marks::{AcademicPeriod, Term};
+    use shared::report_card::ReportCard;
+    use sqlx::PgPool;
+
+    use super::SCHOOL_NAME;
+    use crate::app::db::Error;
+
+    const LOGO_FILE: &str = "Ridge_School_Kumasi_Logo.png";
+    const PAGE_WIDTH: f32 = 210.0;
+    const PAGE_HEIGHT: f32 = 297.0;
+    const MARGIN: f32 = 15.0;
+
+    #[derive(Deserialize)]
+    pub struct ReportQuery {
+        class: String,
+        assessment: String,
+        academic_year: i32,
+        term: u8,
+        rank_by: Option<String>,
+        candidate: Option<String>,
+    }
+
+    /// One student's report card.
+    pub async fn report_card_pdf(
+        pool: web::Data<Arc<PgPool>>,
+        options: web::Data<LeptosOptions>,
+        session: Session,
+        query: web::Query<ReportQuery>,
+    ) -> HttpResponse {
+        if query.candidate.is_none() {
+            return HttpResponse::BadRequest().body("No candidate given");
+        }
+        let cards = match load_cards(&pool, &session, &query).await {
+            Ok(cards) => cards,
+            Err(response) => return response,
+        };
+        match render(&cards, load_logo(&options).as_ref()) {
+            Ok(pdf) => file_response("application/pdf", &cards[0].file_name(), pdf),
+            Err(e) => render_failed(e),
+        }
+    }
+
+    /// Every report card in the class, one per page.
+    pub async fn class_report_cards_pdf(
+        pool: web::Data<Arc<PgPool>>,
+        options: web::Data<LeptosOptions>,
+        session: Session,
+        query: web::Query<ReportQuery>,
+    ) -> HttpResponse {
+        let cards = match load_cards(&pool, &session, &query).await {
+            Ok(cards) => cards,
+            Err(response) => return response,
+        };
+        match render(&cards, load_logo(&options).as_ref()) {
+            Ok(pdf) => file_response("application/pdf", &batch_name(&query, "pdf"), pdf),
+            Err(e) => render_failed(e),
+        }
+    }
+
+    /// Every report card in the class, one PDF each, zipped.
+    pub async fn class_report_cards_zip(
+        pool: web::Data<Arc<PgPool>>,
+        options: web::Data<LeptosOptions>,
+        session: Session,
+        query: web::Query<ReportQuery>,
+    ) -> HttpResponse {
+        let cards = match load_cards(&pool, &session, &query).await {
+            Ok(cards) => cards,
+            Err(response) => return response,
+        };
+        let logo = load_logo(&options);
+        let zipped = (|| {
+            let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+            for card in &cards {
+                let pdf = render(std::slice::from_ref(card), logo.as_ref())?;
+                zip.start_file(card.file_name(), zip::write::SimpleFileOptions::default())
+                    .map_err(|e| e.to_string())?;
+                zip.write_all(&pdf).map_err(|e| e.to_string())?;
+            }
+            zip.finish()
+                .map(Cursor::into_inner)
+                .map_err(|e| e.to_string())
+        })();
+        match zipped {
+            Ok(zip) => file_response("application/zip", &batch_name(&query, "zip"), zip),
+            Err(e) => render_failed(e),
+        }
+    }
+
+    async fn load_cards(
+        pool: &PgPool,
+        session: &Session,
+        query: &ReportQuery,
+    ) -> Result<Vec<ReportCard>, HttpResponse> {
+        crate::app::db::server::require_session_role(pool, session, crate::marks::MARKS_ROLES)
+            .await
+            .map_err(|e| match e {
+                Error::NotAuthorized => HttpResponse::Forbidden().body(e.to_string()),
+                _ => HttpResponse::Unauthorized().body(e.to_string()),
+            })?;
+        let class = crate::catalogue::db::server::load_catalogue(pool)
+            .await
+            .map_err(|e| HttpResponse::InternalServerError().body(e.to_string()))?
+            .class_for(&query.class)
+            .map_err(|e| HttpResponse::BadRequest().body(e.to_string()))?;
+
+        let period = Term::from_number(query.term)
+            .map(|term| AcademicPeriod::new(query.academic_year, term))
+            .ok_or_else(|| HttpResponse::BadRequest().body(format!("Unknown term: {}", query.term)))?;
+        let rank_by = match query.rank_by.as_deref() {
+            Some(rank_by) => RankBy::from_name(rank_by).ok_or_else(|| {
+                HttpResponse::BadRequest().body(format!("Unknown ranking: {}", rank_by))
+            })?,
+            None => RankBy::default(),
+        };
+
+        let mut cards =
+            super::db::server::load_report_cards(pool, class, &query.assessment, period, rank_by)
+                .await
+                .map_err(|e| HttpResponse::InternalServerError().body(e.to_string()))?;
+        if let Some(candidate) = &query.candidate {
+            cards.retain(|card| card.student().candidate_number() == candidate);
+        }
+        if cards.is_empty() {
+            return Err(HttpResponse::NotFound().body("No report cards to print"));
+        }
+
+        Ok(cards)
+    }
+
+    fn batch_name(query: &ReportQuery, extension: &str) -> String {
+        let stem: String = format!(
+            "{}-{}-{}T{}",
+            query.class, query.assessment, query.academic_year, query.term
+        )
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+            .collect();
+        format!("{}.{}", stem, extension)
+    }
+
+    pub(crate) fn file_response(content_type: &str, file_name: &str, body: Vec<u8>) -> HttpResponse {
+        HttpResponse::Ok()
+            .content_type(content_type)
+            .insert_header((
+                "Content-Disposition",
+                format!("attachment; filename=\"{}\"", file_name),
+            ))
+            .body(body)
+    }
+
+    fn render_failed(error: String) -> HttpResponse {
+        log::error!("Failed to render report cards: {}", error);
+        HttpResponse::InternalServerError().body("Failed to render report cards")
+    }
+
+    /// The logo from the site assets, flattened onto white because PDF
+    /// images here have no transparency.
+    pub(crate) fn load_logo(options: &LeptosOptions) -> Option<DynamicImage> {
+        let path = format!("{}/{}", options.site_root, LOGO_FILE);
+        let bytes = std::fs::read(&path)
+            .map_err(|e| log::warn!("Report card logo {} not read: {}", path, e))
+            .ok()?;
+        let logo = printpdf::image_crate::load_from_memory(&bytes)
+            .map_err(|e| log::warn!("Report card logo {} not decoded: {}", path, e))
+            .ok()?
+            .to_rgba8();
+
+        let flattened = RgbImage::from_fn(logo.width(), logo.height(), |x, y| {
+            let [r, g, b, a] = logo.get_pixel(x, y).0;
+            let over_white =
+                |channel: u8| ((channel as u32 * a as u32 + 255 * (255 - a as u32)) / 255) as u8;
+            Rgb([over_white(r), over_white(g), over_white(b)])
+        });
+        Some(DynamicImage::ImageRgb8(flattened))
+    }
+
+    /// Renders `cards` into one A4 document, a page each.
+    pub fn render(cards: &[ReportCard], logo: Option<&DynamicImage>) -> Result<Vec<u8>, String> {
+        let (document, first_page, first_layer) =
+            PdfDocument::new(SCHOOL_NAME, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Report card");
+        let regular = document
+            .add_builtin_font(BuiltinFont::Helvetica)
+            .map_err(|e| e.to_string())?;
+        let bold = document
+            .add_builtin_font(BuiltinFont::HelveticaBold)
+            .map_err(|e| e.to_string())?;
+
+        for (index, card) in cards.iter().enumerate() {
+            let layer = if index == 0 {
+                document.get_page(first_page).get_layer(first_layer)
+            } else {
+                let (page, layer) =
+                    document.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Report card");
+                document.get_page(page).get_layer(layer)
+            };
+            draw_card(&layer, card, logo, &regular, &bold);
+        }
+
+        document.save_to_bytes().map_err(|e| e.to_string())
+    }
+
+    fn draw_card(
+        layer: &PdfLayerReference,
+        card: &ReportCard,
+        logo: Option<&DynamicImage>,
+        regular: &IndirectFontRef,
+        bold: &IndirectFontRef,
+    ) {
+        let logo_size = 25.0;
+        let top = PAGE_HEIGHT - MARGIN;
+        if let Some(logo) = logo {
+            let dpi = 300.0;
+            let width = logo.width() as f32 / dpi * 25.4;
+            let height = logo.height() as f32 / dpi * 25.4;
+            let scale = (logo_size / width).min(logo_size / height);
+            Image::from_dynamic_image(logo).add_to_layer(
+                layer.clone(),
+                ImageTransform {
+                    translate_x: Some(Mm(MARGIN)),
+                    translate_y: Some(Mm(top - height * scale)),
+                    scale_x: Some(scale),
+                    scale_y: Some(scale),
+                    dpi: Some(dpi),
+                    ..Default::default()
+                },
+            );
+        }
+
+        let text_left = MARGIN + logo_size + 5.0;
+        layer.use_text(SCHOOL_NAME, 18.0, Mm(text_left), Mm(top - 8.0), bold);
+        layer.use_text("Student Report Card", 12.0, Mm(text_left), Mm(top - 15.0), regular);
+        layer.use_text(card.assessment_name(), 12.0, Mm(text_left), Mm(top - 21.0), regular);
+        rule(layer, top - logo_size - 4.0);
+
+        let student = card.student();
+        let name = student.personal_info().name();
+        let full_name = [name.first_name(), name.other_names(), name.last_name()]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut y = top - logo_size - 12.0;
+        let right = PAGE_WIDTH / 2.0 + 10.0;
+        let position_label = match card.rank_by() {
+            RankBy::Average => "Position",
+            RankBy::Total => "Position (total)",
+        };
+        for (left_field, right_field) in [
+            (("Name", full_name), ("Average", format!("{:.1}%", card.overall_average()))),
+            (
+                ("Candidate number", student.candidate_number().to_string()),
+                ("Total", format!("{:.1}", card.total_score())),
+            ),
+            (
+                ("Class", student.class().to_string()),
+                ("Class average", format!("{:.1}%", card.class_average())),
+            ),
+            (
+                ("", String::new()),
+                (
+                    position_label,
+                    card.position()
+                        .map(|position| format!("{} of {}", position, position.out_of()))
+                        .unwrap_or_default(),
+                ),
+            ),
+        ] {
+            for (x, (label, value)) in [(MARGIN, left_field), (right, right_field)] {
+                if label.is_empty() {
+                    continue;
+                }
+                layer.use_text(format!("{}:", label), 10.0, Mm(x), Mm(y), bold);
+                layer.use_text(value, 10.0, Mm(x + 38.0), Mm(y), regular);
+            }
+            y -= 7.0;
+        }
+
+        y -= 6.0;
+        let columns = [MARGIN, 100.0, 125.0, 145.0, 170.0];
+        for (x, heading) in columns
+            .iter()
+            .zip(["Subject", "Score (%)", "Grade", "Position", "Class avg (%)"])
+        {
+            layer.use_text(heading, 10.0, Mm(*x), Mm(y), bold);
+        }
+        rule(layer, y - 2.5);
+        y -= 8.0;
+        for line in card.lines() {
+            let cells = [
+                line.subject().to_string(),
+                format!("{:.1}", line.score()),
+                line.grade().unwrap_or("-").to_string(),
+                line.position()
+                    .map(|position| position.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+                line.class_average()
+                    .map(|average| format!("{:.1}", average))
+                    .unwrap_or_else(|| "-".to_string()),
+            ];
+            for (x, cell) in columns.iter().zip(cells) {
+                layer.use_text(cell, 10.0, Mm(*x), Mm(y), regular);
+            }
+            y -= 7.0;
+        }
+        rule(layer, y + 4.5);
+
+        y -= 8.0;
+        layer.use_text("Teacher's remarks", 10.0, Mm(MARGIN), Mm(y), bold);
+        y -= 6.0;
+        for text_line in wrap(card.remarks(), 95) {
+            layer.use_text(text_line, 10.0, Mm(MARGIN), Mm(y), regular);
+            y -= 5.0;
+        }
+
+        layer.use_text(
+            format!("Printed {}", chrono::Local::now().format("%d %B %Y")),
+            8.0,
+            Mm(MARGIN),
+            Mm(MARGIN),
+            regular,
+        );
+    }
+
+    fn rule(layer: &PdfLayerReference, y: f32) {
+        layer.add_line(Line {
+            points: vec![
+                (Point::new(Mm(MARGIN), Mm(y)), false),
+                (Point::new(Mm(PAGE_WIDTH - MARGIN), Mm(y)), false),
+            ],
+            is_closed: false,
+        });
+    }
+
+    /// Splits text into lines of at most `width` characters at spaces.
+    pub(crate) fn wrap(text: &str, width: usize) -> Vec<String> {
+        let mut lines = Vec::new();
+        for paragraph in text.lines() {
+            let mut line = String::new();
+            for word in paragraph.split_whitespace() {
+                if !line.is_empty() && line.len() + 1 + word.len() > width {
+                    lines.push(std::mem::take(&mut line));
+                }
+                if !line.is_empty() {
+                    line.push(' ');
+                }
+                line.push_str(word);
+            }
+            lines.push(line);
+        }
+        lines
+    }
+}
+
+pub mod db {
+    #[cfg(feature = "ssr")]
+    pub mod server {
+        use crate::app::db::Error;
+        use shared::analysis::{RankBy, Weighting};
+        use shared::domain::StudentClass;
+        use shared::marks::AcademicPeriod;
+        use shared::report_card::ReportCard;
+        use sqlx::PgPool;
+
+        pub async fn create_reports_tables(pool: &PgPool) -> Result<(), Error> {
+            for query in [
+                "CREATE TABLE IF NOT EXISTS report_remarks (
+                    class_code VARCHAR(10) NOT NULL,
+                    assessment_name VARCHAR(100) NOT NULL,
+                    academic_year INTEGER,
+                    term SMALLINT CHECK (term BETWEEN 1 AND 3),
+                    candidate_number VARCHAR(20) NOT NULL,
+                    remarks TEXT NOT NULL,
+                    written_by INTEGER REFERENCES users(id),
+                    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+                )",
+                // Remarks written before assessments were told apart by term
+                // belong to the latest assessment of that name.
+                "ALTER TABLE report_remarks
+                    ADD COLUMN IF NOT EXISTS academic_year INTEGER,
+                    ADD COLUMN IF NOT EXISTS term SMALLINT CHECK (term BETWEEN 1 AND 3)",
+                "UPDATE report_remarks r SET academic_year = a.academic_year, term = a.term
+                FROM (
+                    SELECT DISTINCT ON (class_code, name) class_code, name, academic_year, term
+                    FROM assessments
+                    ORDER BY class_code, name, academic_year DESC, term DESC
+                ) a
+                WHERE r.academic_year IS NULL AND a.class_code = r.class_code AND a.name = r.assessment_name",
+                "ALTER TABLE report_remarks DROP CONSTRAINT IF EXISTS report_remarks_pkey",
+                "CREATE UNIQUE INDEX IF NOT EXISTS report_remarks_sitting_candidate_key
+                    ON report_remarks (class_code, assessment_name, academic_year, term, candidate_number)",
+            ] {
+                sqlx::query(query).execute(pool).await.map_err(|e| {
+                    log::error!("Failed to create report remarks table: {}", e);
+                    Error::TableNotCreated
+                })?;
+            }
+
+            Ok(())
+        }
+
+        /// (candidate number, remarks) for everyone in `class` with remarks
+        /// for the assessment in `period`.
+        pub async fn load_remarks(
+            pool: &PgPool,
+            class: StudentClass,
+            assessment_name: &str,
+            period: AcademicPeriod,
+        ) -> Result<Vec<(String, String)>, Error> {
+            sqlx::query_as::<_, (String, String)>(
+                "SELECT candidate_number, remarks FROM report_remarks
+                WHERE class_code = $1 AND assessment_name = $2 AND academic_year = $3 AND term = $4",
+            )
+            .bind(class.code())
+            .bind(assessment_name)
+            .bind(period.year())
+            .bind(i16::from(period.term().number()))
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to retrieve remarks for {} {}: {}", class, assessment_name, e);
+                Error::DatabaseQueryFailed
+            })
+        }
+
+        pub async fn save_remarks(
+            pool: &PgPool,
+            class: StudentClass,
+            assessment_name: &str,
+            period: AcademicPeriod,
+            candidate_number: &str,
+            remarks: &str,
+            written_by: i32,
+        ) -> Result<(), Error> {
+            sqlx::query(
+                r#"
+                INSERT INTO report_remarks (class_code, assessment_name, academic_year, term, candidate_number, remarks, written_by, updated_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, NOW())
+                ON CONFLICT (class_code, assessment_name, academic_year, term, candidate_number)
+                DO UPDATE SET remarks = EXCLUDED.remarks, written_by = EXCLUDED.written_by, updated_at = NOW()
+                "#,
+            )
+            .bind(class.code())
+            .bind(assessment_name)
+            .bind(period.year())
+            .bind(i16::from(period.term().number()))
+            .bind(candidate_number)
+            .bind(remarks)
+            .bind(written_by)
+            .execute(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to save remarks for {}: {}", candidate_number, e);
+                Error::DatabaseQueryFailed
+            })?;
+
+            Ok(())
+        }
+
+        /// Report cards for everyone in `class` with marks for the
+        /// assessment in `period`, using the assessment's own component
+        /// weights.
+        pub async fn load_report_cards(
+            pool: &PgPool,
+            class: StudentClass,
+            assessment_name: &str,
+            period: AcademicPeriod,
+            rank_by: RankBy,
+        ) -> Result<Vec<ReportCard>, Error> {
+            let Some(analysis) = crate::analysis::db::server::analyse_class(
+                pool,
+                class,
+                assessment_name,
+                period,
+                &Weighting::default(),
+                rank_by,
+            )
+            .await?
+            else {
+                return Ok(Vec::new());
+            };
+            let remarks = load_remarks(pool, class, assessment_name, period).await?;
+
+            Ok(ReportCard::for_class(
+                &analysis,
+                &format!("{} ({})", assessment_name, period),
+                &remarks,
+            ))
+        }
+    }
+}
@@ -0,0 +1,670 @@
+use leptos::prelude::*;
+use shared::rooms::{AllocationReport, Room};
+
+use crate::app::{ActionError, ErrorDisplay, LoadingSpinner};
+
+#[server(GetRooms, "/api/rooms")]
+pub async fn get_rooms() -> Result<Vec<Room>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        db::server::load_rooms(&pool)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+#[server(SaveRoom, "/api/rooms")]
+pub async fn save_room(
+    code: String,
+    name: String,
+    capacity: i32,
+    is_accessible: Option<String>,
+    is_exam_suitable: Option<String>,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(&["admin"]).await?;
+        if capacity < 0 {
+            return Err(ServerFnError::ServerError(
+                "Room capacity cannot be negative".into(),
+            ));
+        }
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        let room = Room::new(
+            code.trim().to_string(),
+            name.trim().to_string(),
+            capacity as u32,
+            is_accessible.is_some(),
+            is_exam_suitable.is_some(),
+        );
+        db::server::upsert_room(&pool, &room)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+#[server(DeleteRoom, "/api/rooms")]
+pub async fn delete_room(code: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(&["admin"]).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        if db::server::room_in_use(&pool, &code)
+            .await
+            .map_err(|e| ServerFnError::<leptos::server_fn::error::NoCustomError>::ServerError(e.to_string()))?
+        {
+            return Err(ServerFnError::ServerError(format!(
+                "Room {} is in the current seat plans. Allocate rooms again without it first.",
+                code
+            )));
+        }
+        db::server::delete_room(&pool, &code)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+/// Seats every current student for each paper in the published timetable,
+/// replacing the stored seat plans. `accessible_candidates` lists the
+/// candidate numbers that must sit in an accessible room.
+#[server(AllocateRooms, "/api/rooms")]
+pub async fn allocate_rooms(accessible_candidates: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use leptos::server_fn::error::NoCustomError;
+
+        crate::app::db::server::require_role(crate::timetables::SCHEDULER_ROLES).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        let timetables = crate::timetables::db::server::load_timetable(&pool)
+            .await
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+        if timetables.is_empty() {
+            return Err(ServerFnError::ServerError(
+                "Publish the exam timetable before allocating rooms".into(),
+            ));
+        }
+        let students: Vec<_> =
+            crate::students::db::server::load_student_records(&pool, None, false)
+                .await
+                .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?
+                .into_iter()
+                .map(|record| record.student)
+                .collect();
+        let rooms = db::server::load_rooms(&pool)
+            .await
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+        let accessible_candidates = accessible_candidates
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|candidate| !candidate.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        let report = shared::rooms::allocate(&timetables, &students, &rooms, &accessible_candidates);
+        db::server::save_allocations(&pool, &report)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+/// The stored seat plans and the candidates left without a desk.
+#[server(GetRoomAllocations, "/api/rooms")]
+pub async fn get_room_allocations() -> Result<AllocationReport, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(crate::timetables::SCHEDULER_ROLES).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        db::server::load_allocations(&pool)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+#[component]
+pub fn RoomsView() -> impl IntoView {
+    let title = use_context::<RwSignal<String>>()
+        .expect("title context should be provided");
+
+    title.set("Exam Rooms".to_string());
+
+    let save_room = ServerAction::<SaveRoom>::new();
+    let delete_room = ServerAction::<DeleteRoom>::new();
+
+    let rooms = Resource::new(
+        move || (save_room.version().get(), delete_room.version().get()),
+        |_| async move { get_rooms().await },
+    );
+
+    let code = RwSignal::new(String::new());
+    let name = RwSignal::new(String::new());
+    let capacity = RwSignal::new(String::new());
+    let is_accessible = RwSignal::new(false);
+    let is_exam_suitable = RwSignal::new(true);
+
+    view! {
+        <div class="p-4">
+            <Suspense fallback=move || view! { <LoadingSpinner/> }>
+                {move || rooms.get().map(|result| match result {
+                    Ok(rooms) => view! {
+                        <div class="bg-white rounded-lg shadow overflow-auto-x mb-4">
+                            <table class="min-w-full divide-y divide-gray-200">
+                                <thead class="bg-gray-50">
+                                    <tr>
+                                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Code"</th>
+                                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Name"</th>
+                                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Capacity"</th>
+                                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Accessible"</th>
+                                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Exam Suitable"</th>
+                                        <th class="px-4 py-2"></th>
+                                    </tr>
+                                </thead>
+                                <tbody class="divide-y divide-gray-200">
+                                    {rooms.into_iter().map(|room| {
+                                        let edit_room = room.clone();
+                                        let room_code = room.code().to_string();
+                                        view! {
+                                            <tr class="hover:bg-gray-50">
+                                                <td class="px-4 py-2 text-sm">{room.code().to_string()}</td>
+                                                <td class="px-4 py-2 text-sm">{room.name().to_string()}</td>
+                                                <td class="px-4 py-2 text-sm">{room.capacity()}</td>
+                                                <td class="px-4 py-2 text-sm">{if room.is_accessible() { "Yes" } else { "No" }}</td>
+                                                <td class="px-4 py-2 text-sm">{if room.is_exam_suitable() { "Yes" } else { "No" }}</td>
+                                                <td class="px-4 py-2 text-sm text-right space-x-2">
+                                                    <button
+                                                        class="text-blue-600"
+                                                        on:click=move |_| {
+                                                            code.set(edit_room.code().to_string());
+                                                            name.set(edit_room.name().to_string());
+                                                            capacity.set(edit_room.capacity().to_string());
+                                                            is_accessible.set(edit_room.is_accessible());
+                                                            is_exam_suitable.set(edit_room.is_exam_suitable());
+                                                        }
+                                                    >
+                                                        "Edit"
+                                                    </button>
+                                                    <button
+                                                        class="text-red-600"
+                                                        on:click=move |_| {
+                                                            delete_room.dispatch(DeleteRoom { code: room_code.clone() });
+                                                        }
+                                                    >
+                                                        "Delete"
+                                                    </button>
+                                                </td>
+                                            </tr>
+                                        }
+                                    }).collect_view()}
+                                </tbody>
+                            </table>
+                        </div>
+                    }.into_any(),
+                    Err(e) => view! { <ErrorDisplay error=e.to_string() /> }.into_any(),
+                })}
+            </Suspense>
+            <ActionForm attr:class="flex gap-2 items-center" action=save_room>
+                <input type="text" name="code" placeholder="Code" required class="px-3 py-2 border rounded w-32" bind:value=code />
+                <input type="text" name="name" placeholder="Name" required class="px-3 py-2 border rounded" bind:value=name />
+                <input type="number" name="capacity" placeholder="Desks" required min="0" class="px-3 py-2 border rounded w-28" bind:value=capacity />
+                <label class="text-sm">
+                    <input type="checkbox" name="is_accessible" class="h-4 w-4 mr-1" bind:checked=is_accessible />
+                    "Accessible"
+                </label>
+                <label class="text-sm">
+                    <input type="checkbox" name="is_exam_suitable" class="h-4 w-4 mr-1" bind:checked=is_exam_suitable />
+                    "Exam suitable"
+                </label>
+                <button type="submit" class="bg-gray-700 text-white px-4 py-2 rounded">"Save Room"</button>
+            </ActionForm>
+            <ActionError result=save_room.value() />
+            <ActionError result=delete_room.value() />
+            <SeatPlanSection />
+        </div>
+    }
+}
+
+#[component]
+fn SeatPlanSection() -> impl IntoView {
+    let allocate = ServerAction::<AllocateRooms>::new();
+    let allocations = Resource::new(
+        move || allocate.version().get(),
+        |_| async move { get_room_allocations().await },
+    );
+
+    view! {
+        <section class="bg-white rounded-lg shadow p-4 mt-6 space-y-4">
+            <h2 class="text-lg font-semibold">"Seat Plans"</h2>
+            <ActionForm attr:class="space-y-2" action=allocate>
+                <textarea
+                    name="accessible_candidates"
+                    rows="2"
+                    placeholder="Candidate numbers needing an accessible room"
+                    class="w-full px-3 py-2 border rounded"
+                ></textarea>
+                <button
+                    type="submit"
+                    class="bg-gray-700 text-white px-4 py-2 rounded"
+                    disabled=move || allocate.pending().get()
+                >
+                    "Allocate Rooms"
+                </button>
+            </ActionForm>
+            <ActionError result=allocate.value() />
+            <Suspense fallback=move || view! { <LoadingSpinner/> }>
+                {move || allocations.get().map(|result| match result {
+                    Ok(report) => view! { <AllocationTable report=report /> }.into_any(),
+                    Err(e) => view! { <ErrorDisplay error=e.to_string() /> }.into_any(),
+                })}
+            </Suspense>
+        </section>
+    }
+}
+
+#[component]
+fn AllocationTable(report: AllocationReport) -> impl IntoView {
+    if report.allocations().is_empty() && report.unseated().is_empty() {
+        return view! {
+            <p class="text-gray-500">"Rooms have not been allocated for the published timetable yet."</p>
+        }
+        .into_any();
+    }
+
+    view! {
+        {(!report.unseated().is_empty()).then(|| view! {
+            <ul class="text-sm text-red-700 list-disc pl-5">
+                {report.unseated().iter().map(|unseated| view! {
+                    <li>
+                        {format!(
+                            "{} {} {} has no desk for {} {} on {}{}",
+                            unseated.candidate_number(),
+                            unseated.name().first_name(),
+                            unseated.name().last_name(),
+                            unseated.paper().subject(),
+                            unseated.paper().component().name(),
+                            unseated.exam_date(),
+                            if unseated.needs_accessible_room() { " (needs an accessible room)" } else { "" },
+                        )}
+                    </li>
+                }).collect_view()}
+            </ul>
+        })}
+        <table class="min-w-full divide-y divide-gray-200">
+            <thead class="bg-gray-50">
+                <tr>
+                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Sitting"</th>
+                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Room"</th>
+                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Candidates"</th>
+                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Papers"</th>
+                </tr>
+            </thead>
+            <tbody class="divide-y divide-gray-200">
+                {report.allocations().iter().map(|allocation| view! {
+                    <tr class="hover:bg-gray-50">
+                        <td class="px-4 py-2 text-sm">{allocation.exam_date().to_string()}</td>
+                        <td class="px-4 py-2 text-sm">{allocation.room().name().to_string()}</td>
+                        <td class="px-4 py-2 text-sm">
+                            {format!("{} of {}", allocation.seats().len(), allocation.room().capacity())}
+                        </td>
+                        <td class="px-4 py-2 text-sm">
+                            {allocation
+                                .papers()
+                                .iter()
+                                .map(|paper| format!("{} {}", paper.subject(), paper.component().name()))
+                                .collect::<Vec<_>>()
+                                .join(", ")}
+                        </td>
+                    </tr>
+                }).collect_view()}
+            </tbody>
+        </table>
+    }
+    .into_any()
+}
+
+pub mod db {
+    #[cfg(feature = "ssr")]
+    pub mod server {
+        use std::collections::BTreeMap;
+
+        use crate::app::db::Error;
+        use crate::catalogue::db::server::{load_catalogue, stored_subject};
+        use chrono::{NaiveDate, NaiveTime};
+        use shared::domain::{ExamDate, ExamPaper, ExamSession, PaperComponent, PersonalName};
+        use shared::rooms::{AllocationReport, Room, RoomAllocation, Seat, Unseated};
+        use sqlx::PgPool;
+
+        pub async fn create_rooms_table(pool: &PgPool) -> Result<(), Error> {
+            // A seat plan row with no room is a candidate left without a desk.
+            let queries = [
+                "CREATE TABLE IF NOT EXISTS rooms (
+                    id SERIAL PRIMARY KEY,
+                    code VARCHAR(20) NOT NULL UNIQUE,
+                    name VARCHAR(100) NOT NULL,
+                    capacity INTEGER NOT NULL CHECK (capacity >= 0),
+                    is_accessible BOOLEAN NOT NULL DEFAULT FALSE,
+                    is_exam_suitable BOOLEAN NOT NULL DEFAULT TRUE
+                )",
+                "CREATE TABLE IF NOT EXISTS room_allocations (
+                    id SERIAL PRIMARY KEY,
+                    exam_date DATE NOT NULL,
+                    session VARCHAR(10) NOT NULL,
+                    room_code VARCHAR(20) REFERENCES rooms(code),
+                    desk INTEGER,
+                    candidate_number VARCHAR(20) NOT NULL,
+                    first_name VARCHAR(100) NOT NULL,
+                    other_names VARCHAR(100) NOT NULL,
+                    last_name VARCHAR(100) NOT NULL,
+                    needs_accessible_room BOOLEAN NOT NULL DEFAULT FALSE,
+                    subject_code VARCHAR(30) NOT NULL,
+                    component_code VARCHAR(20) NOT NULL,
+                    component_name VARCHAR(100) NOT NULL,
+                    start_time TIME NOT NULL,
+                    duration_minutes INTEGER NOT NULL
+                )",
+            ];
+
+            for query in queries {
+                sqlx::query(query).execute(pool).await.map_err(|e| {
+                    log::error!("Failed to create rooms tables: {}", e);
+                    Error::TableNotCreated
+                })?;
+            }
+
+            Ok(())
+        }
+
+        pub async fn load_rooms(pool: &PgPool) -> Result<Vec<Room>, Error> {
+            let rooms = sqlx::query_as::<_, (String, String, i32, bool, bool)>(
+                "SELECT code, name, capacity, is_accessible, is_exam_suitable FROM rooms ORDER BY code",
+            )
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to retrieve rooms: {}", e);
+                Error::DatabaseQueryFailed
+            })?
+            .into_iter()
+            .map(|(code, name, capacity, is_accessible, is_exam_suitable)| {
+                Room::new(code, name, capacity as u32, is_accessible, is_exam_suitable)
+            })
+            .collect();
+
+            Ok(rooms)
+        }
+
+        pub async fn upsert_room(pool: &PgPool, room: &Room) -> Result<(), Error> {
+            sqlx::query(
+                r#"
+                INSERT INTO rooms (code, name, capacity, is_accessible, is_exam_suitable)
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT (code) DO UPDATE
+                SET name = EXCLUDED.name,
+                    capacity = EXCLUDED.capacity,
+                    is_accessible = EXCLUDED.is_accessible,
+                    is_exam_suitable = EXCLUDED.is_exam_suitable
+                "#,
+            )
+            .bind(room.code())
+            .bind(room.name())
+            .bind(room.capacity() as i32)
+            .bind(room.is_accessible())
+            .bind(room.is_exam_suitable())
+            .execute(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to save room {}: {}", room.code(), e);
+                Error::DatabaseQueryFailed
+            })?;
+
+            Ok(())
+        }
+
+        /// Whether any stored seat plan uses the room.
+        pub async fn room_in_use(pool: &PgPool, code: &str) -> Result<bool, Error> {
+            sqlx::query_scalar::<_, bool>(
+                "SELECT EXISTS (SELECT 1 FROM room_allocations WHERE room_code = $1)",
+            )
+            .bind(code)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to check room {}: {}", code, e);
+                Error::DatabaseQueryFailed
+            })
+        }
+
+        /// Replaces the stored seat plans with `report`.
+        pub async fn save_allocations(pool: &PgPool, report: &AllocationReport) -> Result<(), Error> {
+            let mut tx = pool.begin().await.map_err(|e| {
+                log::error!("Failed to begin transaction: {}", e);
+                Error::TransactionFailed
+            })?;
+
+            sqlx::query("DELETE FROM room_allocations")
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to clear seat plans: {}", e);
+                    Error::DatabaseQueryFailed
+                })?;
+
+            let seated = report.allocations().iter().flat_map(|allocation| {
+                allocation.seats().iter().map(move |seat| {
+                    (
+                        allocation.exam_date(),
+                        Some((allocation.room().code(), seat.desk() as i32)),
+                        seat.candidate_number(),
+                        seat.name(),
+                        false,
+                        seat.paper(),
+                    )
+                })
+            });
+            let unseated = report.unseated().iter().map(|unseated| {
+                (
+                    unseated.exam_date(),
+                    None,
+                    unseated.candidate_number(),
+                    unseated.name(),
+                    unseated.needs_accessible_room(),
+                    unseated.paper(),
+                )
+            });
+            for (exam_date, desk, candidate_number, name, needs_accessible_room, paper) in
+                seated.chain(unseated)
+            {
+                sqlx::query(
+                    r#"
+                    INSERT INTO room_allocations (exam_date, session, room_code, desk, candidate_number, first_name, other_names, last_name,
+                        needs_accessible_room, subject_code, component_code, component_name, start_time, duration_minutes)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+                    "#,
+                )
+                .bind(exam_date.date())
+                .bind(exam_date.session().as_str())
+                .bind(desk.map(|(room_code, _)| room_code))
+                .bind(desk.map(|(_, desk)| desk))
+                .bind(candidate_number)
+                .bind(name.first_name())
+                .bind(name.other_names())
+                .bind(name.last_name())
+                .bind(needs_accessible_room)
+                .bind(paper.subject().code())
+                .bind(paper.component().code())
+                .bind(paper.component().name())
+                .bind(paper.start_time())
+                .bind(paper.component().duration_minutes() as i32)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to save seat plan: {}", e);
+                    Error::DatabaseQueryFailed
+                })?;
+            }
+
+            tx.commit().await.map_err(|e| {
+                log::error!("Failed to commit transaction: {}", e);
+                Error::TransactionFailed
+            })?;
+
+            Ok(())
+        }
+
+        /// The stored seat plans, with the rooms as they are now.
+        pub async fn load_allocations(pool: &PgPool) -> Result<AllocationReport, Error> {
+            let rows = sqlx::query_as::<
+                _,
+                (
+                    NaiveDate,
+                    String,
+                    Option<String>,
+                    Option<i32>,
+                    String,
+                    String,
+                    String,
+                    String,
+                    bool,
+                    String,
+                    String,
+                    String,
+                    NaiveTime,
+                    i32,
+                ),
+            >(
+                r#"
+                SELECT exam_date, session, room_code, desk, candidate_number, first_name, other_names, last_name,
+                    needs_accessible_room, subject_code, component_code, component_name, start_time, duration_minutes
+                FROM room_allocations
+                ORDER BY exam_date, session, room_code, desk
+                "#,
+            )
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to retrieve seat plans: {}", e);
+                Error::DatabaseQueryFailed
+            })?;
+
+            let catalogue = load_catalogue(pool).await?;
+            let rooms: BTreeMap<String, Room> = load_rooms(pool)
+                .await?
+                .into_iter()
+                .map(|room| (room.code().to_string(), room))
+                .collect();
+            let mut seats: BTreeMap<(ExamDate, String), Vec<Seat>> = BTreeMap::new();
+            let mut unseated = Vec::new();
+            for (
+                exam_date,
+                session,
+                room_code,
+                desk,
+                candidate_number,
+                first_name,
+                other_names,
+                last_name,
+                needs_accessible_room,
+                subject_code,
+                component_code,
+                component_name,
+                start_time,
+                duration_minutes,
+            ) in rows
+            {
+                let Some(session) = ExamSession::from_code(&session) else {
+                    log::error!("Seat plan row for {} has unknown session {}", candidate_number, session);
+                    return Err(Error::DatabaseQueryFailed);
+                };
+                let exam_date = ExamDate::new(exam_date, session);
+                let name = PersonalName::new(first_name, other_names, last_name);
+                let paper = ExamPaper::new(
+                    stored_subject(&catalogue, &subject_code)?,
+                    PaperComponent::new(component_code, component_name, duration_minutes.max(0) as u32),
+                    start_time,
+                );
+                match (room_code, desk) {
+                    (Some(room_code), Some(desk)) => seats
+                        .entry((exam_date, room_code))
+                        .or_default()
+                        .push(Seat::new(desk.max(0) as u32, candidate_number, name, paper)),
+                    _ => unseated.push(Unseated::new(
+                        exam_date,
+                        paper,
+                        candidate_number,
+                        name,
+                        needs_accessible_room,
+                    )),
+                }
+            }
+
+            let allocations = seats
+                .into_iter()
+                .map(|((exam_date, room_code), seats)| {
+                    let room = rooms.get(&room_code).cloned().ok_or_else(|| {
+                        log::error!("Seat plan refers to unknown room {}", room_code);
+                        Error::DatabaseQueryFailed
+                    })?;
+                    Ok(RoomAllocation::new(exam_date, room, seats))
+                })
+                .collect::<Result<_, Error>>()?;
+
+            Ok(AllocationReport::new(allocations, unseated))
+        }
+
+        pub async fn delete_room(pool: &PgPool, code: &str) -> Result<(), Error> {
+            sqlx::query("DELETE FROM rooms WHERE code = $1")
+                .bind(code)
+                .execute(pool)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to delete room {}: {}", code, e);
+                    Error::DatabaseQueryFailed
+                })?;
+
+            Ok(())
+        }
+    }
+}
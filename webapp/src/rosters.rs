@@ -0,0 +1,774 @@
+use leptos::prelude::*;
+use leptos::server_fn::codec::Json;
+use leptos::wasm_bindgen::JsCast;
+use leptos::web_sys::{FormData, HtmlFormElement};
+use shared::import::Sheet;
+use shared::roster::{RosterColumns, RosterEntry, RosterImport, RosterKind};
+use shared::validation::Severity;
+
+use crate::app::{ActionError, ErrorDisplay, LoadingSpinner};
+use crate::import::read_sheet;
+use db::ImportBatch;
+
+/// Checks a roster against the people already registered without saving
+/// anything.
+#[server(name = PreviewRoster, prefix = "/api/rosters", input = Json)]
+pub async fn preview_roster(
+    sheet: Sheet,
+    columns: RosterColumns,
+) -> Result<RosterImport, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(crate::students::STUDENT_ROLES).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        db::server::read_roster(&pool, &sheet, &columns)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+/// Checks the roster again and, if it is clean, saves every row as one
+/// batch that can be rolled back.
+#[server(name = ImportRoster, prefix = "/api/rosters", input = Json)]
+pub async fn import_roster(
+    file_name: String,
+    sheet: Sheet,
+    columns: RosterColumns,
+) -> Result<usize, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use leptos::server_fn::error::NoCustomError;
+
+        let user_session = crate::app::db::server::require_role(crate::students::STUDENT_ROLES).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        let import = db::server::read_roster(&pool, &sheet, &columns)
+            .await
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+        if !import.is_importable() {
+            return Err(ServerFnError::ServerError(
+                "The roster has problems; preview it again and fix them first".into(),
+            ));
+        }
+
+        db::server::save_batch(&pool, &file_name, user_session.user_id, &import)
+            .await
+            .map(|_| import.rows().len())
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+#[server(GetImportBatches, "/api/rosters")]
+pub async fn get_import_batches() -> Result<Vec<ImportBatch>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(crate::students::STUDENT_ROLES).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        db::server::load_batches(&pool)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+/// Removes everyone a batch added. Refused once marks have been entered for
+/// any of its students, or any of its teachers has a login or classes;
+/// archive them instead.
+#[server(RollBackImport, "/api/rosters")]
+pub async fn roll_back_import(batch_id: i32) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use leptos::server_fn::error::NoCustomError;
+
+        crate::app::db::server::require_role(crate::students::STUDENT_ROLES).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        let with_marks = db::server::students_with_marks(&pool, batch_id)
+            .await
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+        let in_use = db::server::teachers_in_use(&pool, batch_id)
+            .await
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+        shared::roster::check_rollback(with_marks, in_use)
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+
+        db::server::roll_back_batch(&pool, batch_id)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+#[component]
+pub fn RostersView() -> impl IntoView {
+    let title = use_context::<RwSignal<String>>()
+        .expect("title context should be provided");
+
+    title.set("Roster Import".to_string());
+
+    let import_roster = ServerAction::<ImportRoster>::new();
+    let roll_back = ServerAction::<RollBackImport>::new();
+    let batches = Resource::new(
+        move || (import_roster.version().get(), roll_back.version().get()),
+        |_| async move { get_import_batches().await },
+    );
+
+    view! {
+        <div class="p-4 space-y-6">
+            <RosterUpload import_roster=import_roster />
+            <section class="bg-white rounded-lg shadow p-4 overflow-auto-x">
+                <h2 class="text-lg font-semibold mb-4">"Import History"</h2>
+                <Suspense fallback=move || view! { <LoadingSpinner/> }>
+                    {move || batches.get().map(|result| match result {
+                        Ok(batches) if batches.is_empty() => view! {
+                            <p class="text-gray-500">"No rosters have been imported yet."</p>
+                        }.into_any(),
+                        Ok(batches) => view! { <BatchTable batches=batches roll_back=roll_back /> }.into_any(),
+                        Err(e) => view! { <ErrorDisplay error=e.to_string() /> }.into_any(),
+                    })}
+                </Suspense>
+                <ActionError result=roll_back.value() />
+            </section>
+        </div>
+    }
+}
+
+/// Upload, column mapping and dry-run preview. Nothing is saved until the
+/// preview is clean and the import is confirmed.
+#[component]
+fn RosterUpload(import_roster: ServerAction<ImportRoster>) -> impl IntoView {
+    let kind = RwSignal::new(RosterKind::Students);
+    let file_name = RwSignal::new(String::new());
+    let upload = Action::new_local(|data: &FormData| read_sheet(data.clone().into()));
+    let uploaded = RwSignal::new(None::<Sheet>);
+    let columns = RwSignal::new(None::<RosterColumns>);
+
+    Effect::new(move |_| {
+        if let Some(Ok(sheet)) = upload.value().get() {
+            columns.set(Some(RosterColumns::detect(&sheet, kind.get_untracked())));
+            uploaded.set(Some(sheet));
+        }
+    });
+
+    Effect::new(move |_| {
+        if let Some(Ok(_)) = import_roster.value().get() {
+            uploaded.set(None);
+            columns.set(None);
+        }
+    });
+
+    let preview = Resource::new(
+        move || (uploaded.get(), columns.get()),
+        |(sheet, columns)| async move {
+            match (sheet, columns) {
+                (Some(sheet), Some(columns)) => preview_roster(sheet, columns).await.map(Some),
+                _ => Ok(None),
+            }
+        },
+    );
+
+    let column_select = move |field, selected: Option<usize>| {
+        let headers = uploaded.get().map(|sheet| sheet.headers().to_vec()).unwrap_or_default();
+        view! {
+            <select
+                class="px-2 py-1 border rounded text-sm"
+                prop:value=selected.map(|column| column.to_string()).unwrap_or_default()
+                on:change=move |ev| {
+                    let column = event_target_value(&ev).parse().ok();
+                    columns.update(|columns| {
+                        if let Some(columns) = columns {
+                            columns.set_column(field, column);
+                        }
+                    });
+                }
+            >
+                <option value="">"(not imported)"</option>
+                {headers.into_iter().enumerate().map(|(column, header)| view! {
+                    <option value=column.to_string()>{header}</option>
+                }).collect_view()}
+            </select>
+        }
+    };
+
+    view! {
+        <section class="bg-white rounded-lg shadow p-4 overflow-auto-x">
+            <h2 class="text-lg font-semibold mb-4">"Import Roster"</h2>
+            <form
+                class="flex gap-2 items-center"
+                on:submit=move |ev| {
+                    ev.prevent_default();
+                    let Some(form) = ev.target().map(|target| target.unchecked_into::<HtmlFormElement>()) else {
+                        return;
+                    };
+                    if let Ok(data) = FormData::new_with_form(&form) {
+                        uploaded.set(None);
+                        columns.set(None);
+                        upload.dispatch_local(data);
+                    }
+                }
+            >
+                <select
+                    class="px-3 py-2 border rounded"
+                    prop:value=move || kind.get().as_str()
+                    on:change=move |ev| {
+                        if let Some(selected) = RosterKind::from_name(&event_target_value(&ev)) {
+                            kind.set(selected);
+                            if let Some(sheet) = uploaded.get_untracked() {
+                                columns.set(Some(RosterColumns::detect(&sheet, selected)));
+                            }
+                        }
+                    }
+                >
+                    {RosterKind::ALL.iter().map(|kind| view! {
+                        <option value=kind.as_str()>{kind.as_str()}</option>
+                    }).collect_view()}
+                </select>
+                <input
+                    type="file"
+                    name="file"
+                    required
+                    accept=".csv,.xlsx,.xls,.ods"
+                    class="text-sm"
+                    on:change=move |ev| {
+                        // Browsers report the path as C:\fakepath\<name>.
+                        let path = event_target_value(&ev);
+                        let name = path.rsplit(['\\', '/']).next().unwrap_or_default();
+                        file_name.set(name.to_string());
+                    }
+                />
+                <button type="submit" class="bg-gray-700 text-white px-4 py-2 rounded">"Preview"</button>
+                {move || upload.pending().get().then(|| view! {
+                    <span class="text-sm text-gray-500">"Reading file..."</span>
+                })}
+            </form>
+            <p class="text-sm text-gray-500 mt-2">
+                "Students need a candidate number, names, gender, date of birth and class; teachers need names and gender. Subjects may be codes or names, separated by commas."
+            </p>
+            {move || upload.value().get().and_then(Result::err).map(|e| view! {
+                <div class="bg-red-100 border border-red-400 text-red-700 px-4 py-3 rounded mt-4">
+                    {e.to_string()}
+                </div>
+            })}
+            {move || columns.get().map(|current| view! {
+                <div class="flex flex-wrap gap-4 items-center mt-4 text-sm">
+                    {current.columns().iter().map(|(field, column)| view! {
+                        <label class="flex gap-2 items-center">
+                            {field.as_str()}
+                            {column_select(*field, *column)}
+                        </label>
+                    }).collect_view()}
+                </div>
+            })}
+            <Suspense fallback=move || view! { <LoadingSpinner/> }>
+                {move || preview.get().map(|result| match result {
+                    Ok(Some(preview)) => {
+                        let count = preview.rows().len();
+                        let is_importable = preview.is_importable();
+                        let errors = preview
+                            .issues()
+                            .iter()
+                            .filter(|issue| issue.severity() == Severity::Error)
+                            .count();
+                        let confirm = move |_| {
+                            let (Some(sheet), Some(columns)) = (uploaded.get_untracked(), columns.get_untracked()) else {
+                                return;
+                            };
+                            import_roster.dispatch(ImportRoster {
+                                file_name: file_name.get_untracked(),
+                                sheet,
+                                columns,
+                            });
+                        };
+                        view! {
+                            <div class="mt-4 space-y-4">
+                                {(!preview.issues().is_empty()).then(|| view! {
+                                    <div class="border rounded px-4 py-3 text-sm">
+                                        {preview.issues().iter().map(|issue| {
+                                            let class = match issue.severity() {
+                                                Severity::Error => "text-red-700",
+                                                Severity::Warning => "text-yellow-700",
+                                            };
+                                            view! { <p class=class>{issue.to_string()}</p> }
+                                        }).collect_view()}
+                                    </div>
+                                })}
+                                <RosterPreviewTable preview=preview />
+                                <div class="flex gap-4 items-center">
+                                    <button
+                                        class="bg-gray-700 text-white px-4 py-2 rounded disabled:opacity-50"
+                                        disabled=!is_importable
+                                        on:click=confirm
+                                    >
+                                        {format!("Import {} Rows", count)}
+                                    </button>
+                                    {(errors > 0).then(|| view! {
+                                        <span class="text-sm text-red-700">
+                                            {format!("Fix {} problems in the file or the column choices before importing.", errors)}
+                                        </span>
+                                    })}
+                                </div>
+                            </div>
+                        }.into_any()
+                    }
+                    Ok(None) => ().into_any(),
+                    Err(e) => view! { <ErrorDisplay error=e.to_string() /> }.into_any(),
+                })}
+            </Suspense>
+            <ActionError result=import_roster.value() />
+            {move || import_roster.value().get().and_then(Result::ok).map(|count| view! {
+                <p class="mt-2 text-sm text-green-700">{format!("{} rows imported.", count)}</p>
+            })}
+        </section>
+    }
+}
+
+fn subject_names(subjects: &[shared::domain::Subject]) -> String {
+    subjects
+        .iter()
+        .map(|subject| subject.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[component]
+fn RosterPreviewTable(preview: RosterImport) -> impl IntoView {
+    let is_students = preview.kind() == RosterKind::Students;
+
+    view! {
+        <table class="min-w-full divide-y divide-gray-200">
+            <thead class="bg-gray-50">
+                <tr>
+                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Line"</th>
+                    {is_students.then(|| view! {
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Candidate"</th>
+                    })}
+                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Name"</th>
+                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Gender"</th>
+                    {is_students.then(|| view! {
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Date of Birth"</th>
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Class"</th>
+                    })}
+                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Subjects"</th>
+                </tr>
+            </thead>
+            <tbody class="divide-y divide-gray-200">
+                {preview.rows().iter().map(|row| match row.entry() {
+                    RosterEntry::Student(student) => {
+                        let info = student.personal_info();
+                        let name = info.name();
+                        view! {
+                            <tr class="hover:bg-gray-50">
+                                <td class="px-4 py-2 text-sm text-gray-500">{row.line()}</td>
+                                <td class="px-4 py-2 text-sm">{student.candidate_number().to_string()}</td>
+                                <td class="px-4 py-2 text-sm">
+                                    {format!("{} {} {}", name.first_name(), name.other_names(), name.last_name()).split_whitespace().collect::<Vec<_>>().join(" ")}
+                                </td>
+                                <td class="px-4 py-2 text-sm">{info.gender().to_string()}</td>
+                                <td class="px-4 py-2 text-sm">{info.date_of_birth().format("%-d %b %Y").to_string()}</td>
+                                <td class="px-4 py-2 text-sm">{student.class().to_string()}</td>
+                                <td class="px-4 py-2 text-sm">{subject_names(student.subjects())}</td>
+                            </tr>
+                        }.into_any()
+                    }
+                    RosterEntry::Teacher(teacher) => {
+                        let info = teacher.personal_info();
+                        let name = info.name();
+                        view! {
+                            <tr class="hover:bg-gray-50">
+                                <td class="px-4 py-2 text-sm text-gray-500">{row.line()}</td>
+                                <td class="px-4 py-2 text-sm">
+                                    {format!("{} {} {}", name.first_name(), name.other_names(), name.last_name()).split_whitespace().collect::<Vec<_>>().join(" ")}
+                                </td>
+                                <td class="px-4 py-2 text-sm">{info.gender().to_string()}</td>
+                                <td class="px-4 py-2 text-sm">{subject_names(teacher.subjects_taught())}</td>
+                            </tr>
+                        }.into_any()
+                    }
+                }).collect_view()}
+            </tbody>
+        </table>
+    }
+}
+
+#[component]
+fn BatchTable(batches: Vec<ImportBatch>, roll_back: ServerAction<RollBackImport>) -> impl IntoView {
+    view! {
+        <table class="min-w-full divide-y divide-gray-200">
+            <thead class="bg-gray-50">
+                <tr>
+                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Imported"</th>
+                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Roster"</th>
+                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"File"</th>
+                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Rows"</th>
+                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"By"</th>
+                    <th class="px-4 py-2"></th>
+                </tr>
+            </thead>
+            <tbody class="divide-y divide-gray-200">
+                {batches.into_iter().map(|batch| {
+                    let batch_id = batch.id;
+                    view! {
+                        <tr class="hover:bg-gray-50" class:text-gray-400=batch.rolled_back_at.is_some()>
+                            <td class="px-4 py-2 text-sm">{batch.imported_at.format("%-d %b %Y %H:%M").to_string()}</td>
+                            <td class="px-4 py-2 text-sm">{batch.kind.to_string()}</td>
+                            <td class="px-4 py-2 text-sm">{batch.file_name}</td>
+                            <td class="px-4 py-2 text-sm">{batch.row_count}</td>
+                            <td class="px-4 py-2 text-sm">{batch.imported_by}</td>
+                            <td class="px-4 py-2 text-sm text-right">
+                                {match batch.rolled_back_at {
+                                    Some(rolled_back_at) => view! {
+                                        <span>{format!("Rolled back {}", rolled_back_at.format("%-d %b %Y"))}</span>
+                                    }.into_any(),
+                                    None => view! {
+                                        <button
+                                            class="text-red-600"
+                                            on:click=move |_| {
+                                                roll_back.dispatch(RollBackImport { batch_id });
+                                            }
+                                        >
+                                            "Roll Back"
+                                        </button>
+                                    }.into_any(),
+                                }}
+                            </td>
+                        </tr>
+                    }
+                }).collect_view()}
+            </tbody>
+        </table>
+    }
+}
+
+pub mod db {
+    use serde::{Deserialize, Serialize};
+    use shared::roster::RosterKind;
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    pub struct ImportBatch {
+        pub id: i32,
+        pub kind: RosterKind,
+        pub file_name: String,
+        pub row_count: i32,
+        pub imported_by: String,
+        pub imported_at: chrono::DateTime<chrono::Utc>,
+        pub rolled_back_at: Option<chrono::DateTime<chrono::Utc>>,
+    }
+
+    #[cfg(feature = "ssr")]
+    pub mod server {
+        use super::ImportBatch;
+        use crate::app::db::Error;
+        use shared::import::Sheet;
+        use shared::roster::{RosterColumns, RosterImport, RosterKind};
+        use sqlx::PgPool;
+
+        /// Must run after the students and teachers tables are created.
+        pub async fn create_rosters_tables(pool: &PgPool) -> Result<(), Error> {
+            for query in [
+                "CREATE TABLE IF NOT EXISTS import_batches (
+                    id SERIAL PRIMARY KEY,
+                    kind VARCHAR(20) NOT NULL,
+                    file_name VARCHAR(255) NOT NULL,
+                    row_count INTEGER NOT NULL,
+                    imported_by INTEGER REFERENCES users(id),
+                    imported_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    rolled_back_at TIMESTAMPTZ
+                )",
+                "ALTER TABLE students
+                    ADD COLUMN IF NOT EXISTS import_batch_id INTEGER REFERENCES import_batches(id)",
+                "ALTER TABLE teachers
+                    ADD COLUMN IF NOT EXISTS import_batch_id INTEGER REFERENCES import_batches(id)",
+            ] {
+                sqlx::query(query).execute(pool).await.map_err(|e| {
+                    log::error!("Failed to create rosters tables: {}", e);
+                    Error::TableNotCreated
+                })?;
+            }
+
+            Ok(())
+        }
+
+        /// Reads a roster and checks it against everyone registered,
+        /// including archived students, whose candidate numbers stay taken.
+        /// Ages are checked on this academic year's cut-off.
+        pub async fn read_roster(
+            pool: &PgPool,
+            sheet: &Sheet,
+            columns: &RosterColumns,
+        ) -> Result<RosterImport, Error> {
+            let catalogue = crate::catalogue::db::server::load_catalogue(pool).await?;
+            match columns.kind() {
+                RosterKind::Students => {
+                    let registered: Vec<_> =
+                        crate::students::db::server::load_student_records(pool, None, true)
+                            .await?
+                            .into_iter()
+                            .map(|record| record.student)
+                            .collect();
+                    let cut_off = shared::marks::AcademicPeriod::containing(
+                        chrono::Local::now().date_naive(),
+                    )
+                    .age_cut_off();
+                    Ok(shared::roster::read_students(
+                        sheet, columns, &registered, cut_off, &catalogue,
+                    ))
+                }
+                RosterKind::Teachers => {
+                    let registered = crate::teachers::db::server::load_teachers(pool).await?;
+                    Ok(shared::roster::read_teachers(sheet, columns, &registered, &catalogue))
+                }
+            }
+        }
+
+        /// Saves every row of `import` in one transaction, tagged with a new
+        /// batch.
+        pub async fn save_batch(
+            pool: &PgPool,
+            file_name: &str,
+            user_id: i32,
+            import: &RosterImport,
+        ) -> Result<i32, Error> {
+            let mut tx = pool.begin().await.map_err(|e| {
+                log::error!("Failed to begin transaction: {}", e);
+                Error::TransactionFailed
+            })?;
+
+            let batch_id: i32 = sqlx::query_scalar(
+                "INSERT INTO import_batches (kind, file_name, row_count, imported_by)
+                VALUES ($1, $2, $3, $4)
+                RETURNING id",
+            )
+            .bind(import.kind().as_str())
+            .bind(file_name)
+            .bind(import.rows().len() as i32)
+            .bind(user_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to create import batch: {}", e);
+                Error::DatabaseQueryFailed
+            })?;
+
+            for student in import.students() {
+                let info = student.personal_info();
+                let name = info.name();
+                let student_id: i32 = sqlx::query_scalar(
+                    "INSERT INTO students (candidate_number, first_name, other_names, last_name,
+                        gender, date_of_birth, class_code, import_batch_id)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                    RETURNING id",
+                )
+                .bind(student.candidate_number())
+                .bind(name.first_name())
+                .bind(name.other_names())
+                .bind(name.last_name())
+                .bind(info.gender().as_str())
+                .bind(info.date_of_birth())
+                .bind(student.class().code())
+                .bind(batch_id)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to import student {}: {}", student.candidate_number(), e);
+                    Error::DatabaseQueryFailed
+                })?;
+
+                for subject in student.subjects() {
+                    sqlx::query("INSERT INTO student_subjects (student_id, subject_code) VALUES ($1, $2)")
+                        .bind(student_id)
+                        .bind(subject.code())
+                        .execute(&mut *tx)
+                        .await
+                        .map_err(|e| {
+                            log::error!("Failed to import subject {} of student {}: {}", subject, student_id, e);
+                            Error::DatabaseQueryFailed
+                        })?;
+                }
+            }
+
+            for teacher in import.teachers() {
+                let info = teacher.personal_info();
+                let teacher_id: i32 = sqlx::query_scalar(
+                    "INSERT INTO teachers (first_name, other_names, last_name, gender, import_batch_id)
+                    VALUES ($1, $2, $3, $4, $5)
+                    RETURNING id",
+                )
+                .bind(info.name().first_name())
+                .bind(info.name().other_names())
+                .bind(info.name().last_name())
+                .bind(info.gender().as_str())
+                .bind(batch_id)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to import teacher {}: {}", info.name().last_name(), e);
+                    Error::DatabaseQueryFailed
+                })?;
+
+                for subject in teacher.subjects_taught() {
+                    sqlx::query("INSERT INTO teacher_subjects (teacher_id, subject_code) VALUES ($1, $2)")
+                        .bind(teacher_id)
+                        .bind(subject.code())
+                        .execute(&mut *tx)
+                        .await
+                        .map_err(|e| {
+                            log::error!("Failed to import subject {} of teacher {}: {}", subject, teacher_id, e);
+                            Error::DatabaseQueryFailed
+                        })?;
+                }
+            }
+
+            tx.commit().await.map_err(|e| {
+                log::error!("Failed to commit transaction: {}", e);
+                Error::TransactionFailed
+            })?;
+
+            Ok(batch_id)
+        }
+
+        /// Newest first.
+        pub async fn load_batches(pool: &PgPool) -> Result<Vec<ImportBatch>, Error> {
+            let rows = sqlx::query_as::<
+                _,
+                (
+                    i32,
+                    String,
+                    String,
+                    i32,
+                    String,
+                    chrono::DateTime<chrono::Utc>,
+                    Option<chrono::DateTime<chrono::Utc>>,
+                ),
+            >(
+                "SELECT b.id, b.kind, b.file_name, b.row_count,
+                    COALESCE(u.first_name || ' ' || u.last_name, ''), b.imported_at, b.rolled_back_at
+                FROM import_batches b
+                LEFT JOIN users u ON u.id = b.imported_by
+                ORDER BY b.imported_at DESC, b.id DESC",
+            )
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to retrieve import batches: {}", e);
+                Error::DatabaseQueryFailed
+            })?;
+
+            Ok(rows
+                .into_iter()
+                .filter_map(
+                    |(id, kind, file_name, row_count, imported_by, imported_at, rolled_back_at)| {
+                        Some(ImportBatch {
+                            id,
+                            kind: RosterKind::from_name(&kind)?,
+                            file_name,
+                            row_count,
+                            imported_by,
+                            imported_at,
+                            rolled_back_at,
+                        })
+                    },
+                )
+                .collect())
+        }
+
+        /// How many students added by the batch have marks entered.
+        pub async fn students_with_marks(pool: &PgPool, batch_id: i32) -> Result<i64, Error> {
+            sqlx::query_scalar::<_, i64>(
+                "SELECT COUNT(DISTINCT s.id)
+                FROM students s
+                JOIN marks m ON m.candidate_number = s.candidate_number
+                WHERE s.import_batch_id = $1",
+            )
+            .bind(batch_id)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to check marks for import batch {}: {}", batch_id, e);
+                Error::DatabaseQueryFailed
+            })
+        }
+
+        /// How many teachers added by the batch are linked to a login or
+        /// assigned classes.
+        pub async fn teachers_in_use(pool: &PgPool, batch_id: i32) -> Result<i64, Error> {
+            sqlx::query_scalar::<_, i64>(
+                "SELECT COUNT(*)
+                FROM teachers t
+                WHERE t.import_batch_id = $1
+                    AND (t.user_id IS NOT NULL
+                        OR EXISTS (SELECT 1 FROM teacher_classes tc WHERE tc.teacher_id = t.id))",
+            )
+            .bind(batch_id)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to check teachers for import batch {}: {}", batch_id, e);
+                Error::DatabaseQueryFailed
+            })
+        }
+
+        /// Deletes the students and teachers the batch added, with their
+        /// subjects, and marks it rolled back.
+        pub async fn roll_back_batch(pool: &PgPool, batch_id: i32) -> Result<(), Error> {
+            let mut tx = pool.begin().await.map_err(|e| {
+                log::error!("Failed to begin transaction: {}", e);
+                Error::TransactionFailed
+            })?;
+
+            for query in [
+                "DELETE FROM students WHERE import_batch_id = $1",
+                "DELETE FROM teachers WHERE import_batch_id = $1",
+                "UPDATE import_batches SET rolled_back_at = NOW()
+                WHERE id = $1 AND rolled_back_at IS NULL",
+            ] {
+                sqlx::query(query)
+                    .bind(batch_id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| {
+                        log::error!("Failed to roll back import batch {}: {}", batch_id, e);
+                        Error::DatabaseQueryFailed
+                    })?;
+            }
+
+            tx.commit().await.map_err(|e| {
+                log::error!("Failed to commit transaction: {}", e);
+                Error::TransactionFailed
+            })?;
+
+            Ok(())
+        }
+    }
+}
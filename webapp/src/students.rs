@@ -0,0 +1,1277 @@
+use chrono::NaiveDate;
+use leptos::prelude::*;
+use leptos::server_fn::codec::Json;
+use shared::catalogue::Catalogue;
+use shared::domain::Gender;
+use shared::registration::Registration;
+
+use crate::app::{ActionError, ErrorDisplay, LoadingSpinner};
+use crate::catalogue::{ClassOptions, get_catalogue};
+use db::StudentRecord;
+
+/// Roles that can enrol, edit, archive and promote students.
+pub const STUDENT_ROLES: &[&str] = &["admin", "exams_officer"];
+
+/// Students in `class_code`, or in every class when it is empty, by
+/// surname.
+#[server(GetStudents, "/api/students")]
+pub async fn get_students(
+    class_code: String,
+    include_archived: bool,
+) -> Result<Vec<StudentRecord>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use leptos::server_fn::error::NoCustomError;
+
+        crate::app::db::server::require_role(crate::marks::MARKS_ROLES).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+        let catalogue = crate::catalogue::db::server::load_catalogue(&pool)
+            .await
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+        let class = match class_code.trim() {
+            "" => None,
+            code => Some(
+                catalogue
+                    .class_for(code)
+                    .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?,
+            ),
+        };
+
+        db::server::load_student_records(&pool, class, include_archived)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+/// Enrols a student when `id` is `None`, otherwise updates them. Subjects
+/// must belong to the class's programme, and the student's age on this
+/// academic year's cut-off must be plausible for their class.
+#[allow(clippy::too_many_arguments)]
+#[server(name = SaveStudent, prefix = "/api/students", input = Json)]
+pub async fn save_student(
+    id: Option<i32>,
+    candidate_number: String,
+    first_name: String,
+    other_names: String,
+    last_name: String,
+    gender: String,
+    date_of_birth: String,
+    class_code: String,
+    subject_codes: Vec<String>,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use leptos::server_fn::error::NoCustomError;
+        use shared::domain::{PersonalName, Student, StudentPersonalInfo};
+
+        crate::app::db::server::require_role(STUDENT_ROLES).await?;
+        let candidate_number = candidate_number.trim();
+        if candidate_number.is_empty() {
+            return Err(ServerFnError::ServerError("Candidate number is required".into()));
+        }
+        if first_name.trim().is_empty() || last_name.trim().is_empty() {
+            return Err(ServerFnError::ServerError(
+                "First name and last name are required".into(),
+            ));
+        }
+        let Some(gender) = Gender::from_code(&gender) else {
+            return Err(ServerFnError::ServerError(format!("Unknown gender: {}", gender)));
+        };
+        let Ok(date_of_birth) = NaiveDate::parse_from_str(date_of_birth.trim(), "%Y-%m-%d") else {
+            return Err(ServerFnError::ServerError(format!(
+                "Invalid date of birth: {}",
+                date_of_birth
+            )));
+        };
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+        let catalogue = crate::catalogue::db::server::load_catalogue(&pool)
+            .await
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+        let class = catalogue
+            .class_for(&class_code)
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+        let personal_info = StudentPersonalInfo::new(
+            PersonalName::new(
+                first_name.trim().to_string(),
+                other_names.trim().to_string(),
+                last_name.trim().to_string(),
+            ),
+            date_of_birth,
+            gender,
+        );
+        let cut_off = current_cut_off();
+        let age = personal_info.age_on(cut_off);
+        let ages = class.plausible_ages();
+        if !ages.contains(&age) {
+            return Err(ServerFnError::ServerError(format!(
+                "A student born on {} would be {} on {}, outside {} to {} for {}",
+                date_of_birth.format("%-d %b %Y"),
+                age,
+                cut_off.format("%-d %b %Y"),
+                ages.start(),
+                ages.end(),
+                class
+            )));
+        }
+        let mut subjects = Vec::new();
+        for code in &subject_codes {
+            let subject = catalogue
+                .subject_for(code)
+                .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+            if subject.programme_code() != class.programme_code() {
+                return Err(ServerFnError::ServerError(format!(
+                    "{} is not taught in {}",
+                    subject, class
+                )));
+            }
+            if !subjects.contains(&subject) {
+                subjects.push(subject);
+            }
+        }
+
+        let is_taken = db::server::candidate_number_in_use(&pool, candidate_number, id)
+            .await
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+        if is_taken {
+            return Err(ServerFnError::ServerError(format!(
+                "Candidate number {} belongs to another student",
+                candidate_number
+            )));
+        }
+
+        let student = Student::new(candidate_number.to_string(), personal_info, class, subjects);
+        db::server::save_student(&pool, id, &student)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+/// Archives a student who has left, or restores one. Archived students keep
+/// their marks but drop off class lists.
+#[server(SetStudentArchived, "/api/students")]
+pub async fn set_student_archived(id: i32, is_archived: bool) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(STUDENT_ROLES).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        db::server::set_archived(&pool, id, is_archived)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+/// Moves every current student in `class_code` to `to_class_code`, or
+/// archives them all when it is empty. The class moved into must be empty,
+/// so classes are promoted from the top year down. Returns how many
+/// students moved.
+#[server(PromoteClass, "/api/students")]
+pub async fn promote_class(class_code: String, to_class_code: String) -> Result<u64, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use leptos::server_fn::error::NoCustomError;
+
+        crate::app::db::server::require_role(STUDENT_ROLES).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+        let catalogue = crate::catalogue::db::server::load_catalogue(&pool)
+            .await
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+        let class = catalogue
+            .class_for(&class_code)
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+        let to_class = match to_class_code.trim() {
+            "" => None,
+            code => {
+                let to_class = catalogue
+                    .class_for(code)
+                    .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+                if to_class == class {
+                    return Err(ServerFnError::ServerError(
+                        "Choose a different class to promote into".into(),
+                    ));
+                }
+                Some(to_class)
+            }
+        };
+
+        if let Some(to_class) = to_class {
+            let occupants = load_class_list(&pool, to_class, None)
+                .await
+                .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+            if !occupants.is_empty() {
+                return Err(ServerFnError::ServerError(format!(
+                    "{} still has {} students; promote or archive them first",
+                    to_class,
+                    occupants.len()
+                )));
+            }
+        }
+
+        db::server::promote_class(&pool, class, to_class)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+/// Candidates of `class_code` to register with the exam board, with ages
+/// on `cut_off` (YYYY-MM-DD).
+#[server(GetRegistration, "/api/students")]
+pub async fn get_registration(
+    class_code: String,
+    cut_off: String,
+) -> Result<Registration, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(STUDENT_ROLES).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        server::registration(&pool, &class_code, &cut_off)
+            .await
+            .map_err(ServerFnError::ServerError)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+#[cfg(feature = "ssr")]
+use db::server::load_class_list;
+
+/// The date ages are checked on for the academic year under way, by the
+/// server's clock so every browser sees the same date.
+#[server(GetAgeCutOff, "/api/students")]
+pub async fn get_age_cut_off() -> Result<NaiveDate, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(crate::marks::MARKS_ROLES).await?;
+        Ok(current_cut_off())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+/// The date ages are checked on for the academic year under way.
+#[cfg(feature = "ssr")]
+fn current_cut_off() -> NaiveDate {
+    shared::marks::AcademicPeriod::containing(chrono::Local::now().date_naive()).age_cut_off()
+}
+
+#[component]
+pub fn StudentsView() -> impl IntoView {
+    let title = use_context::<RwSignal<String>>()
+        .expect("title context should be provided");
+
+    title.set("Students".to_string());
+
+    let save_student = ServerAction::<SaveStudent>::new();
+    let set_archived = ServerAction::<SetStudentArchived>::new();
+    let promote_class = ServerAction::<PromoteClass>::new();
+
+    let class_filter = RwSignal::new(String::new());
+    let include_archived = RwSignal::new(false);
+    let editing = RwSignal::new(None::<StudentRecord>);
+    let catalogue = Resource::new(|| (), |_| async move { get_catalogue().await });
+    let cut_off = Resource::new(|| (), |_| async move { get_age_cut_off().await });
+
+    let students = Resource::new(
+        move || {
+            (
+                class_filter.get(),
+                include_archived.get(),
+                save_student.version().get(),
+                set_archived.version().get(),
+                promote_class.version().get(),
+            )
+        },
+        |(class_code, include_archived, ..)| async move {
+            get_students(class_code, include_archived).await
+        },
+    );
+
+    view! {
+        <div class="p-4 space-y-6">
+            <section class="bg-white rounded-lg shadow p-4 overflow-auto-x">
+                <div class="flex gap-2 items-center mb-4">
+                    <h2 class="text-lg font-semibold mr-4">"Students"</h2>
+                    <select
+                        class="px-3 py-2 border rounded"
+                        prop:value=move || class_filter.get()
+                        on:change=move |ev| class_filter.set(event_target_value(&ev))
+                    >
+                        <option value="">"All classes"</option>
+                        <ClassOptions catalogue=catalogue />
+                    </select>
+                    <label class="text-sm">
+                        <input
+                            type="checkbox"
+                            class="mr-2"
+                            prop:checked=move || include_archived.get()
+                            on:change=move |ev| include_archived.set(event_target_checked(&ev))
+                        />
+                        "Show archived"
+                    </label>
+                </div>
+                <Suspense fallback=move || view! { <LoadingSpinner/> }>
+                    {move || students.get().zip(cut_off.get()).map(|results| match results {
+                        (Ok(students), Ok(cut_off)) => view! {
+                            <StudentTable students=students cut_off=cut_off editing=editing set_archived=set_archived />
+                        }.into_any(),
+                        (Err(e), _) | (_, Err(e)) => view! { <ErrorDisplay error=e.to_string() /> }.into_any(),
+                    })}
+                </Suspense>
+                <ActionError result=set_archived.value() />
+            </section>
+            <StudentForm editing=editing save_student=save_student catalogue=catalogue />
+            <PromotionSection promote_class=promote_class catalogue=catalogue />
+            <RegistrationSection catalogue=catalogue cut_off=cut_off />
+        </div>
+    }
+}
+
+#[component]
+fn StudentTable(
+    students: Vec<StudentRecord>,
+    cut_off: NaiveDate,
+    editing: RwSignal<Option<StudentRecord>>,
+    set_archived: ServerAction<SetStudentArchived>,
+) -> impl IntoView {
+    if students.is_empty() {
+        return view! { <p class="text-gray-500">"No students found."</p> }.into_any();
+    }
+    let estimated = students.iter().filter(|record| record.date_of_birth_estimated).count();
+
+    view! {
+        {(estimated > 0).then(|| view! {
+            <p class="mb-4 text-sm text-orange-600">
+                {format!(
+                    "{} students have a date of birth estimated from their age. Edit them to enter the real date.",
+                    estimated
+                )}
+            </p>
+        })}
+        <table class="min-w-full divide-y divide-gray-200">
+            <thead class="bg-gray-50">
+                <tr>
+                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Candidate"</th>
+                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Name"</th>
+                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Gender"</th>
+                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Date of Birth"</th>
+                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase" title=format!("On {}", cut_off.format("%-d %b %Y"))>"Age"</th>
+                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Class"</th>
+                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Subjects"</th>
+                    <th class="px-4 py-2"></th>
+                </tr>
+            </thead>
+            <tbody class="divide-y divide-gray-200">
+                {students.into_iter().map(|record| {
+                    let id = record.id;
+                    let is_archived = record.is_archived;
+                    let is_estimated = record.date_of_birth_estimated;
+                    let student = record.student.clone();
+                    let info = student.personal_info();
+                    let name = info.name();
+                    let age = info.age_on(cut_off);
+                    let is_plausible = student.class().plausible_ages().contains(&age);
+                    view! {
+                        <tr class="hover:bg-gray-50" class:text-gray-400=is_archived>
+                            <td class="px-4 py-2 text-sm">{student.candidate_number().to_string()}</td>
+                            <td class="px-4 py-2 text-sm">
+                                {format!("{} {} {}", name.first_name(), name.other_names(), name.last_name()).split_whitespace().collect::<Vec<_>>().join(" ")}
+                            </td>
+                            <td class="px-4 py-2 text-sm">{info.gender().to_string()}</td>
+                            <td class="px-4 py-2 text-sm">
+                                {info.date_of_birth().format("%-d %b %Y").to_string()}
+                                {is_estimated.then(|| view! {
+                                    <span class="text-orange-600" title="Estimated from an age; enter the real date">" (estimated)"</span>
+                                })}
+                            </td>
+                            <td class="px-4 py-2 text-sm" class:text-red-600=!is_plausible>{age}</td>
+                            <td class="px-4 py-2 text-sm">
+                                {student.class().to_string()}
+                                {is_archived.then_some(" (archived)")}
+                            </td>
+                            <td class="px-4 py-2 text-sm">
+                                {student.subjects_read().iter().map(|subject| subject.name()).collect::<Vec<_>>().join(", ")}
+                            </td>
+                            <td class="px-4 py-2 text-sm text-right space-x-2">
+                                <button class="text-blue-600" on:click=move |_| editing.set(Some(record.clone()))>
+                                    "Edit"
+                                </button>
+                                <button
+                                    class=if is_archived { "text-green-600" } else { "text-red-600" }
+                                    on:click=move |_| {
+                                        set_archived.dispatch(SetStudentArchived { id, is_archived: !is_archived });
+                                    }
+                                >
+                                    {if is_archived { "Restore" } else { "Archive" }}
+                                </button>
+                            </td>
+                        </tr>
+                    }
+                }).collect_view()}
+            </tbody>
+        </table>
+    }.into_any()
+}
+
+/// Enrolment form, which also edits the student chosen in the table.
+#[component]
+fn StudentForm(
+    editing: RwSignal<Option<StudentRecord>>,
+    save_student: ServerAction<SaveStudent>,
+    catalogue: Resource<Result<Catalogue, ServerFnError>>,
+) -> impl IntoView {
+    let candidate_number = RwSignal::new(String::new());
+    let first_name = RwSignal::new(String::new());
+    let other_names = RwSignal::new(String::new());
+    let last_name = RwSignal::new(String::new());
+    let gender = RwSignal::new(Gender::ALL[0].as_str().to_string());
+    let date_of_birth = RwSignal::new(String::new());
+    let class_code = RwSignal::new(String::new());
+    let subject_codes = RwSignal::new(Vec::<String>::new());
+
+    let clear = move || {
+        editing.set(None);
+        candidate_number.set(String::new());
+        first_name.set(String::new());
+        other_names.set(String::new());
+        last_name.set(String::new());
+        gender.set(Gender::ALL[0].as_str().to_string());
+        date_of_birth.set(String::new());
+        class_code.set(String::new());
+        subject_codes.set(Vec::new());
+    };
+
+    Effect::new(move |_| {
+        if let Some(record) = editing.get() {
+            let is_estimated = record.date_of_birth_estimated;
+            let student = record.student;
+            let info = student.personal_info();
+            candidate_number.set(student.candidate_number().to_string());
+            first_name.set(info.name().first_name().to_string());
+            other_names.set(info.name().other_names().to_string());
+            last_name.set(info.name().last_name().to_string());
+            gender.set(info.gender().as_str().to_string());
+            // An estimated date is left blank so it is not saved as real.
+            date_of_birth.set(if is_estimated {
+                String::new()
+            } else {
+                info.date_of_birth().format("%Y-%m-%d").to_string()
+            });
+            class_code.set(student.class().code().to_string());
+            subject_codes.set(student.subjects_read().iter().map(|subject| subject.code()).collect());
+        }
+    });
+
+    Effect::new(move |_| {
+        if let Some(Ok(())) = save_student.value().get() {
+            clear();
+        }
+    });
+
+    let submit = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        save_student.dispatch(SaveStudent {
+            id: editing.get_untracked().map(|record| record.id),
+            candidate_number: candidate_number.get_untracked(),
+            first_name: first_name.get_untracked(),
+            other_names: other_names.get_untracked(),
+            last_name: last_name.get_untracked(),
+            gender: gender.get_untracked(),
+            date_of_birth: date_of_birth.get_untracked(),
+            class_code: class_code.get_untracked(),
+            subject_codes: subject_codes.get_untracked(),
+        });
+    };
+
+    view! {
+        <section class="bg-white rounded-lg shadow p-4">
+            <h2 class="text-lg font-semibold mb-4">
+                {move || if editing.get().is_some() { "Edit Student" } else { "Enrol Student" }}
+            </h2>
+            <form class="space-y-4" on:submit=submit>
+                <div class="flex gap-2 items-center flex-wrap">
+                    <input type="text" placeholder="Candidate number" required class="px-3 py-2 border rounded w-40" bind:value=candidate_number />
+                    <input type="text" placeholder="First name" required class="px-3 py-2 border rounded" bind:value=first_name />
+                    <input type="text" placeholder="Other names" class="px-3 py-2 border rounded" bind:value=other_names />
+                    <input type="text" placeholder="Last name" required class="px-3 py-2 border rounded" bind:value=last_name />
+                    <select
+                        class="px-3 py-2 border rounded"
+                        prop:value=move || gender.get()
+                        on:change=move |ev| gender.set(event_target_value(&ev))
+                    >
+                        {Gender::ALL.iter().map(|gender| view! {
+                            <option value=gender.as_str()>{gender.as_str()}</option>
+                        }).collect_view()}
+                    </select>
+                    <label class="text-sm">"Born"</label>
+                    <input type="date" required class="px-3 py-2 border rounded" bind:value=date_of_birth />
+                    <select
+                        required
+                        class="px-3 py-2 border rounded"
+                        prop:value=move || class_code.get()
+                        on:change=move |ev| {
+                            let code = event_target_value(&ev);
+                            if let Some(Ok(catalogue)) = catalogue.get_untracked() {
+                                let programme_code = catalogue.class_for(&code).ok().map(|class| class.programme_code());
+                                subject_codes.update(|codes| codes.retain(|code| {
+                                    catalogue.subject_for(code).ok().map(|subject| subject.programme_code()) == programme_code
+                                }));
+                            }
+                            class_code.set(code);
+                        }
+                    >
+                        <option value="">"Class..."</option>
+                        <ClassOptions catalogue=catalogue />
+                    </select>
+                </div>
+                <div class="flex gap-4 flex-wrap text-sm">
+                    <Suspense fallback=|| ()>
+                        {move || catalogue.get().and_then(Result::ok).and_then(|catalogue| {
+                            let class = catalogue.class_for(&class_code.get()).ok()?;
+                            let subjects = catalogue
+                                .subjects_offered(class)
+                                .map(|subject| {
+                                    let code = subject.code();
+                                    let is_read = {
+                                        let code = code.clone();
+                                        move || subject_codes.get().contains(&code)
+                                    };
+                                    view! {
+                                        <label>
+                                            <input
+                                                type="checkbox"
+                                                class="mr-1"
+                                                prop:checked=is_read
+                                                on:change=move |ev| {
+                                                    let is_checked = event_target_checked(&ev);
+                                                    subject_codes.update(|codes| {
+                                                        codes.retain(|read| *read != code);
+                                                        if is_checked {
+                                                            codes.push(code.clone());
+                                                        }
+                                                    });
+                                                }
+                                            />
+                                            {subject.name()}
+                                        </label>
+                                    }
+                                })
+                                .collect_view();
+                            Some(subjects)
+                        })}
+                    </Suspense>
+                </div>
+                <div class="space-x-2">
+                    <button type="submit" class="bg-gray-700 text-white px-4 py-2 rounded">"Save Student"</button>
+                    <button type="button" class="px-4 py-2 rounded border" on:click=move |_| clear()>"Clear"</button>
+                </div>
+            </form>
+            <ActionError result=save_student.value() />
+        </section>
+    }
+}
+
+/// Year-end promotion of a whole class.
+#[component]
+fn PromotionSection(
+    promote_class: ServerAction<PromoteClass>,
+    catalogue: Resource<Result<Catalogue, ServerFnError>>,
+) -> impl IntoView {
+    let class_code = RwSignal::new(String::new());
+    let to_class_code = RwSignal::new(String::new());
+
+    view! {
+        <section class="bg-white rounded-lg shadow p-4">
+            <h2 class="text-lg font-semibold mb-2">"Year-End Promotion"</h2>
+            <p class="text-sm text-gray-500 mb-4">
+                "Promote the top year first: a class can only move into an empty class. Subjects are kept when the programme stays the same."
+            </p>
+            <ActionForm attr:class="flex gap-2 items-center" action=promote_class>
+                <select
+                    name="class_code"
+                    required
+                    class="px-3 py-2 border rounded"
+                    prop:value=move || class_code.get()
+                    on:change=move |ev| {
+                        let code = event_target_value(&ev);
+                        to_class_code.set(
+                            catalogue
+                                .get_untracked()
+                                .and_then(Result::ok)
+                                .and_then(|catalogue| {
+                                    let class = catalogue.class_for(&code).ok()?;
+                                    catalogue.next_year(class)
+                                })
+                                .map(|class| class.code().to_string())
+                                .unwrap_or_default(),
+                        );
+                        class_code.set(code);
+                    }
+                >
+                    <option value="">"Class..."</option>
+                    <ClassOptions catalogue=catalogue />
+                </select>
+                <span class="text-sm">"to"</span>
+                <select
+                    name="to_class_code"
+                    class="px-3 py-2 border rounded"
+                    prop:value=move || to_class_code.get()
+                    on:change=move |ev| to_class_code.set(event_target_value(&ev))
+                >
+                    <option value="">"Leaving school (archive)"</option>
+                    <ClassOptions catalogue=catalogue />
+                </select>
+                <button type="submit" class="bg-gray-700 text-white px-4 py-2 rounded">"Promote"</button>
+            </ActionForm>
+            {move || match promote_class.value().get() {
+                Some(Ok(moved)) => Some(view! { <p class="mt-2 text-sm text-green-700">{format!("{} students moved.", moved)}</p> }),
+                _ => None,
+            }}
+            <ActionError result=promote_class.value() />
+        </section>
+    }
+}
+
+/// Exam board registration list for a class, previewed with any ages that
+/// look wrong, and downloaded as CSV.
+#[component]
+fn RegistrationSection(
+    catalogue: Resource<Result<Catalogue, ServerFnError>>,
+    cut_off: Resource<Result<NaiveDate, ServerFnError>>,
+) -> impl IntoView {
+    let class_code = RwSignal::new(String::new());
+    let ages_on = RwSignal::new(String::new());
+
+    Effect::new(move |_| {
+        if let Some(Ok(date)) = cut_off.get()
+            && ages_on.get_untracked().is_empty()
+        {
+            ages_on.set(date.format("%Y-%m-%d").to_string());
+        }
+    });
+
+    let registration = Resource::new(
+        move || (class_code.get(), ages_on.get()),
+        |(class_code, cut_off)| async move {
+            if class_code.is_empty() || cut_off.is_empty() {
+                return Ok(None);
+            }
+            get_registration(class_code, cut_off).await.map(Some)
+        },
+    );
+
+    view! {
+        <section class="bg-white rounded-lg shadow p-4 space-y-4 overflow-auto-x">
+            <h2 class="text-lg font-semibold">"Exam Board Registration"</h2>
+            <div class="flex gap-2 items-center">
+                <select
+                    class="px-3 py-2 border rounded"
+                    prop:value=move || class_code.get()
+                    on:change=move |ev| class_code.set(event_target_value(&ev))
+                >
+                    <option value="">"Class..."</option>
+                    <ClassOptions catalogue=catalogue />
+                </select>
+                <label class="text-sm">"Ages on"</label>
+                <input
+                    type="date"
+                    class="px-3 py-2 border rounded"
+                    prop:value=move || ages_on.get()
+                    on:change=move |ev| ages_on.set(event_target_value(&ev))
+                />
+                <Show when=move || !class_code.get().is_empty()>
+                    <a
+                        class="text-blue-600 text-sm"
+                        href=move || format!("/api/students/registration.csv?class={}&cut_off={}", class_code.get(), ages_on.get())
+                    >
+                        "Download CSV"
+                    </a>
+                </Show>
+            </div>
+            <Suspense fallback=move || view! { <LoadingSpinner/> }>
+                {move || registration.get().map(|result| match result {
+                    Ok(Some(registration)) => view! { <RegistrationTable registration=registration /> }.into_any(),
+                    Ok(None) => ().into_any(),
+                    Err(e) => view! { <ErrorDisplay error=e.to_string() /> }.into_any(),
+                })}
+            </Suspense>
+        </section>
+    }
+}
+
+#[component]
+fn RegistrationTable(registration: Registration) -> impl IntoView {
+    if registration.entries().is_empty() {
+        return view! { <p class="text-gray-500">"No students in this class."</p> }.into_any();
+    }
+    let ages = registration.class().plausible_ages();
+    let implausible = registration.implausible_ages().count();
+
+    view! {
+        {(implausible > 0).then(|| view! {
+            <p class="text-sm text-red-600">
+                {format!(
+                    "{} students are outside {} to {} on {}; check their dates of birth before registering.",
+                    implausible,
+                    ages.start(),
+                    ages.end(),
+                    registration.cut_off().format("%-d %b %Y"),
+                )}
+            </p>
+        })}
+        <table class="min-w-full divide-y divide-gray-200">
+            <thead class="bg-gray-50">
+                <tr>
+                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Candidate"</th>
+                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Name"</th>
+                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Gender"</th>
+                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Date of Birth"</th>
+                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Age"</th>
+                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Syllabuses"</th>
+                </tr>
+            </thead>
+            <tbody class="divide-y divide-gray-200">
+                {registration.entries().iter().map(|entry| {
+                    let is_plausible = ages.contains(&entry.age());
+                    view! {
+                        <tr class="hover:bg-gray-50">
+                            <td class="px-4 py-2 text-sm">{entry.candidate_number().to_string()}</td>
+                            <td class="px-4 py-2 text-sm">
+                                {format!("{}, {} {}", entry.last_name(), entry.first_name(), entry.other_names()).trim().to_string()}
+                            </td>
+                            <td class="px-4 py-2 text-sm">{entry.gender().to_string()}</td>
+                            <td class="px-4 py-2 text-sm">{entry.date_of_birth().format("%-d %b %Y").to_string()}</td>
+                            <td class="px-4 py-2 text-sm" class:text-red-600=!is_plausible>{entry.age()}</td>
+                            <td class="px-4 py-2 text-sm">{entry.syllabus_codes().join(", ")}</td>
+                        </tr>
+                    }
+                }).collect_view()}
+            </tbody>
+        </table>
+    }.into_any()
+}
+
+#[cfg(feature = "ssr")]
+pub mod server {
+    use std::sync::Arc;
+
+    use actix_session::Session;
+    use actix_web::{HttpResponse, web};
+    use chrono::NaiveDate;
+    use serde::Deserialize;
+    use shared::registration::Registration;
+    use sqlx::PgPool;
+
+    use crate::app::db::Error;
+    use crate::reports::server::file_response;
+
+    #[derive(Deserialize)]
+    pub struct RegistrationQuery {
+        class: String,
+        cut_off: String,
+    }
+
+    /// The current students of `class_code` with ages on `cut_off`, given
+    /// as YYYY-MM-DD.
+    pub async fn registration(
+        pool: &PgPool,
+        class_code: &str,
+        cut_off: &str,
+    ) -> Result<Registration, String> {
+        let catalogue = crate::catalogue::db::server::load_catalogue(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        let class = catalogue.class_for(class_code).map_err(|e| e.to_string())?;
+        let Ok(cut_off) = NaiveDate::parse_from_str(cut_off.trim(), "%Y-%m-%d") else {
+            return Err(format!("Invalid cut-off date: {}", cut_off));
+        };
+        let students = super::db::server::load_class_list(pool, class, None)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(Registration::new(class, cut_off, &students, &catalogue))
+    }
+
+    /// `/api/students/registration.csv`
+    pub async fn registration_csv(
+        pool: web::Data<Arc<PgPool>>,
+        session: Session,
+        query: web::Query<RegistrationQuery>,
+    ) -> HttpResponse {
+        if let Err(e) =
+            crate::app::db::server::require_session_role(&pool, &session, super::STUDENT_ROLES).await
+        {
+            return match e {
+                Error::NotAuthorized => HttpResponse::Forbidden().body(e.to_string()),
+                _ => HttpResponse::Unauthorized().body(e.to_string()),
+            };
+        }
+        match registration(&pool, &query.class, &query.cut_off).await {
+            Ok(registration) => file_response(
+                "text/csv; charset=utf-8",
+                &registration.file_name(),
+                registration.to_csv().into_bytes(),
+            ),
+            Err(e) => HttpResponse::BadRequest().body(e),
+        }
+    }
+}
+
+pub mod db {
+    use serde::{Deserialize, Serialize};
+    use shared::domain::Student;
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    pub struct StudentRecord {
+        pub id: i32,
+        pub student: Student,
+        pub is_archived: bool,
+        /// Whether the date of birth was estimated from an age and still
+        /// needs the real date entering.
+        pub date_of_birth_estimated: bool,
+    }
+
+    #[cfg(feature = "ssr")]
+    pub mod server {
+        use super::StudentRecord;
+        use crate::app::db::Error;
+        use crate::catalogue::db::server::{load_catalogue, stored_class, stored_subjects};
+        use chrono::NaiveDate;
+        use shared::catalogue::Catalogue;
+        use shared::domain::{
+            Gender, PersonalName, Student, StudentClass, StudentPersonalInfo, Subject,
+        };
+        use sqlx::PgPool;
+
+        pub async fn create_students_tables(pool: &PgPool) -> Result<(), Error> {
+            for query in [
+                "CREATE TABLE IF NOT EXISTS students (
+                    id SERIAL PRIMARY KEY,
+                    candidate_number VARCHAR(20) NOT NULL UNIQUE,
+                    first_name VARCHAR(100) NOT NULL,
+                    other_names VARCHAR(100) NOT NULL DEFAULT '',
+                    last_name VARCHAR(100) NOT NULL,
+                    gender VARCHAR(10) NOT NULL,
+                    date_of_birth DATE NOT NULL,
+                    date_of_birth_estimated BOOLEAN NOT NULL DEFAULT FALSE,
+                    class_code VARCHAR(10) NOT NULL,
+                    is_archived BOOLEAN NOT NULL DEFAULT FALSE
+                )",
+                "CREATE TABLE IF NOT EXISTS student_subjects (
+                    student_id INTEGER NOT NULL REFERENCES students(id) ON DELETE CASCADE,
+                    subject_code VARCHAR(30) NOT NULL,
+                    PRIMARY KEY (student_id, subject_code)
+                )",
+                "ALTER TABLE students ADD COLUMN IF NOT EXISTS date_of_birth DATE",
+                "ALTER TABLE students ADD COLUMN IF NOT EXISTS date_of_birth_estimated BOOLEAN NOT NULL DEFAULT FALSE",
+                // Students saved with only an age get 1 January of the year
+                // that age implies, flagged as estimated so the students page
+                // can list them until their real date of birth is entered.
+                "DO $$
+                BEGIN
+                    IF EXISTS (
+                        SELECT 1 FROM information_schema.columns
+                        WHERE table_name = 'students' AND column_name = 'age'
+                    ) THEN
+                        UPDATE students
+                        SET date_of_birth = MAKE_DATE(EXTRACT(YEAR FROM CURRENT_DATE)::INTEGER - age, 1, 1),
+                            date_of_birth_estimated = TRUE
+                        WHERE date_of_birth IS NULL;
+                        ALTER TABLE students DROP COLUMN age;
+                    END IF;
+                END $$",
+                "ALTER TABLE students ALTER COLUMN date_of_birth SET NOT NULL",
+            ] {
+                sqlx::query(query).execute(pool).await.map_err(|e| {
+                    log::error!("Failed to create students tables: {}", e);
+                    Error::TableNotCreated
+                })?;
+            }
+
+            Ok(())
+        }
+
+        type StudentRow = (
+            String,
+            String,
+            String,
+            String,
+            String,
+            NaiveDate,
+            String,
+            Vec<String>,
+        );
+
+        const STUDENT_SELECT: &str = r#"
+            SELECT s.candidate_number, s.first_name, s.other_names, s.last_name, s.gender, s.date_of_birth, s.class_code,
+                COALESCE(ARRAY_AGG(ss.subject_code) FILTER (WHERE ss.subject_code IS NOT NULL), '{}')
+            FROM students s
+            LEFT JOIN student_subjects ss ON ss.student_id = s.id"#;
+
+        fn student_from_row(
+            catalogue: &Catalogue,
+            (candidate_number, first_name, other_names, last_name, gender, date_of_birth, class_code, subjects): StudentRow,
+        ) -> Result<Student, Error> {
+            let Some(gender) = Gender::from_code(&gender) else {
+                log::error!("Student {} has unknown gender {}", candidate_number, gender);
+                return Err(Error::DatabaseQueryFailed);
+            };
+            Ok(Student::new(
+                candidate_number,
+                StudentPersonalInfo::new(
+                    PersonalName::new(first_name, other_names, last_name),
+                    date_of_birth,
+                    gender,
+                ),
+                stored_class(catalogue, &class_code)?,
+                stored_subjects(catalogue, &subjects)?,
+            ))
+        }
+
+        /// Current students in `class`, or only those reading `subject` when
+        /// given, ordered by surname.
+        pub async fn load_class_list(
+            pool: &PgPool,
+            class: StudentClass,
+            subject: Option<Subject>,
+        ) -> Result<Vec<Student>, Error> {
+            let rows = sqlx::query_as::<_, StudentRow>(&format!(
+                "{}
+                WHERE s.class_code = $1 AND NOT s.is_archived
+                GROUP BY s.id
+                ORDER BY s.last_name, s.first_name",
+                STUDENT_SELECT
+            ))
+            .bind(class.code())
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to retrieve class list for {}: {}", class, e);
+                Error::DatabaseQueryFailed
+            })?;
+
+            let catalogue = load_catalogue(pool).await?;
+            let mut students = Vec::new();
+            for row in rows {
+                let student = student_from_row(&catalogue, row)?;
+                if subject.is_none_or(|subject| student.subjects_read().contains(&subject)) {
+                    students.push(student);
+                }
+            }
+            Ok(students)
+        }
+
+        /// The current student with `candidate_number`, if any.
+        pub async fn load_student(
+            pool: &PgPool,
+            candidate_number: &str,
+        ) -> Result<Option<Student>, Error> {
+            let row = sqlx::query_as::<_, StudentRow>(&format!(
+                "{}
+                WHERE s.candidate_number = $1 AND NOT s.is_archived
+                GROUP BY s.id",
+                STUDENT_SELECT
+            ))
+            .bind(candidate_number.trim())
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to retrieve student {}: {}", candidate_number, e);
+                Error::DatabaseQueryFailed
+            })?;
+
+            let Some(row) = row else {
+                return Ok(None);
+            };
+            let catalogue = load_catalogue(pool).await?;
+            student_from_row(&catalogue, row).map(Some)
+        }
+
+        /// The students with any of `candidate_numbers`, archived or not, in
+        /// the class they are in now.
+        pub async fn load_students(
+            pool: &PgPool,
+            candidate_numbers: &[String],
+        ) -> Result<Vec<Student>, Error> {
+            let rows = sqlx::query_as::<_, StudentRow>(&format!(
+                "{}
+                WHERE s.candidate_number = ANY($1)
+                GROUP BY s.id
+                ORDER BY s.last_name, s.first_name",
+                STUDENT_SELECT
+            ))
+            .bind(candidate_numbers)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to retrieve students: {}", e);
+                Error::DatabaseQueryFailed
+            })?;
+
+            let catalogue = load_catalogue(pool).await?;
+            rows.into_iter()
+                .map(|row| student_from_row(&catalogue, row))
+                .collect()
+        }
+
+        /// Students in `class`, or in every class, by class then surname.
+        pub async fn load_student_records(
+            pool: &PgPool,
+            class: Option<StudentClass>,
+            include_archived: bool,
+        ) -> Result<Vec<StudentRecord>, Error> {
+            let rows = sqlx::query_as::<_, (i32, bool, bool, String, String, String, String, String, NaiveDate, String, Vec<String>)>(
+                r#"
+                SELECT s.id, s.is_archived, s.date_of_birth_estimated, s.candidate_number, s.first_name, s.other_names, s.last_name, s.gender, s.date_of_birth, s.class_code,
+                    COALESCE(ARRAY_AGG(ss.subject_code) FILTER (WHERE ss.subject_code IS NOT NULL), '{}')
+                FROM students s
+                LEFT JOIN student_subjects ss ON ss.student_id = s.id
+                WHERE ($1::VARCHAR IS NULL OR s.class_code = $1) AND ($2 OR NOT s.is_archived)
+                GROUP BY s.id
+                ORDER BY s.class_code, s.last_name, s.first_name
+                "#,
+            )
+            .bind(class.map(|class| class.code()))
+            .bind(include_archived)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to retrieve students: {}", e);
+                Error::DatabaseQueryFailed
+            })?;
+
+            let catalogue = load_catalogue(pool).await?;
+            rows.into_iter()
+                .map(
+                    |(id, is_archived, date_of_birth_estimated, candidate_number, first_name, other_names, last_name, gender, date_of_birth, class_code, subjects)| {
+                        Ok(StudentRecord {
+                            id,
+                            student: student_from_row(
+                                &catalogue,
+                                (
+                                    candidate_number,
+                                    first_name,
+                                    other_names,
+                                    last_name,
+                                    gender,
+                                    date_of_birth,
+                                    class_code,
+                                    subjects,
+                                ),
+                            )?,
+                            is_archived,
+                            date_of_birth_estimated,
+                        })
+                    },
+                )
+                .collect()
+        }
+
+        /// Whether a student other than `except_id` has `candidate_number`.
+        pub async fn candidate_number_in_use(
+            pool: &PgPool,
+            candidate_number: &str,
+            except_id: Option<i32>,
+        ) -> Result<bool, Error> {
+            sqlx::query_scalar::<_, bool>(
+                "SELECT EXISTS(
+                    SELECT 1 FROM students
+                    WHERE candidate_number = $1 AND ($2::INTEGER IS NULL OR id <> $2)
+                )",
+            )
+            .bind(candidate_number)
+            .bind(except_id)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to check candidate number {}: {}", candidate_number, e);
+                Error::DatabaseQueryFailed
+            })
+        }
+
+        /// Inserts `student`, or updates the student with `id`, along with
+        /// the subjects they read.
+        pub async fn save_student(
+            pool: &PgPool,
+            id: Option<i32>,
+            student: &Student,
+        ) -> Result<(), Error> {
+            let mut tx = pool.begin().await.map_err(|e| {
+                log::error!("Failed to begin transaction: {}", e);
+                Error::TransactionFailed
+            })?;
+
+            let info = student.personal_info();
+            let query = match id {
+                Some(_) => {
+                    "UPDATE students
+                    SET candidate_number = $2, first_name = $3, other_names = $4, last_name = $5,
+                        gender = $6, date_of_birth = $7, date_of_birth_estimated = FALSE, class_code = $8
+                    WHERE id = $1
+                    RETURNING id"
+                }
+                None => {
+                    "INSERT INTO students (candidate_number, first_name, other_names, last_name, gender, date_of_birth, class_code)
+                    VALUES ($2, $3, $4, $5, $6, $7, $8)
+                    RETURNING id"
+                }
+            };
+            let student_id: i32 = sqlx::query_scalar(query)
+                .bind(id)
+                .bind(student.candidate_number())
+                .bind(info.name().first_name())
+                .bind(info.name().other_names())
+                .bind(info.name().last_name())
+                .bind(info.gender().as_str())
+                .bind(info.date_of_birth())
+                .bind(student.class().code())
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to save student {}: {}", student.candidate_number(), e);
+                    Error::DatabaseQueryFailed
+                })?;
+
+            sqlx::query("DELETE FROM student_subjects WHERE student_id = $1")
+                .bind(student_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to clear subjects of student {}: {}", student_id, e);
+                    Error::DatabaseQueryFailed
+                })?;
+
+            for subject in student.subjects_read() {
+                sqlx::query("INSERT INTO student_subjects (student_id, subject_code) VALUES ($1, $2)")
+                    .bind(student_id)
+                    .bind(subject.code())
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| {
+                        log::error!("Failed to save subject {} of student {}: {}", subject, student_id, e);
+                        Error::DatabaseQueryFailed
+                    })?;
+            }
+
+            tx.commit().await.map_err(|e| {
+                log::error!("Failed to commit transaction: {}", e);
+                Error::TransactionFailed
+            })?;
+
+            Ok(())
+        }
+
+        pub async fn set_archived(pool: &PgPool, id: i32, is_archived: bool) -> Result<(), Error> {
+            sqlx::query("UPDATE students SET is_archived = $2 WHERE id = $1")
+                .bind(id)
+                .bind(is_archived)
+                .execute(pool)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to archive student {}: {}", id, e);
+                    Error::DatabaseQueryFailed
+                })?;
+
+            Ok(())
+        }
+
+        /// Moves the current students of `class` to `to_class`, or archives
+        /// them when there is none. Subjects are dropped when the programme
+        /// changes, since they belong to the old one. Returns how many
+        /// students moved.
+        pub async fn promote_class(
+            pool: &PgPool,
+            class: StudentClass,
+            to_class: Option<StudentClass>,
+        ) -> Result<u64, Error> {
+            let mut tx = pool.begin().await.map_err(|e| {
+                log::error!("Failed to begin transaction: {}", e);
+                Error::TransactionFailed
+            })?;
+
+            let moved = match to_class {
+                Some(to_class) => {
+                    if to_class.programme_code() != class.programme_code() {
+                        sqlx::query(
+                            "DELETE FROM student_subjects WHERE student_id IN (
+                                SELECT id FROM students WHERE class_code = $1 AND NOT is_archived
+                            )",
+                        )
+                        .bind(class.code())
+                        .execute(&mut *tx)
+                        .await
+                        .map_err(|e| {
+                            log::error!("Failed to clear subjects of {}: {}", class, e);
+                            Error::DatabaseQueryFailed
+                        })?;
+                    }
+                    sqlx::query("UPDATE students SET class_code = $2 WHERE class_code = $1 AND NOT is_archived")
+                        .bind(class.code())
+                        .bind(to_class.code())
+                        .execute(&mut *tx)
+                        .await
+                }
+                None => {
+                    sqlx::query("UPDATE students SET is_archived = TRUE WHERE class_code = $1 AND NOT is_archived")
+                        .bind(class.code())
+                        .execute(&mut *tx)
+                        .await
+                }
+            }
+            .map_err(|e| {
+                log::error!("Failed to promote {}: {}", class, e);
+                Error::DatabaseQueryFailed
+            })?
+            .rows_affected();
+
+            tx.commit().await.map_err(|e| {
+                log::error!("Failed to commit transaction: {}", e);
+                Error::TransactionFailed
+            })?;
+
+            Ok(moved)
+        }
+    }
+}
@@ -0,0 +1,1437 @@
+use chrono::NaiveDate;
+use leptos::prelude::*;
+use leptos::server_fn::codec::Json;
+use shared::domain::{ExamDay, ExamSession, Gender};
+use shared::invigilation::Strength;
+
+use crate::app::{ActionError, ErrorDisplay, LoadingSpinner};
+use crate::catalogue::get_catalogue;
+use crate::invigilation::db::StaffOption;
+use db::{MyTeaching, TeacherRecord};
+
+/// Roles that can add, edit and archive teachers.
+pub const TEACHER_ROLES: &[&str] = &["admin", "exams_officer"];
+
+/// Every role a member of staff can log in with.
+pub const STAFF_ROLES: &[&str] = &["admin", "exams_officer", "teacher", "hod"];
+
+#[server(GetTeachers, "/api/teachers")]
+pub async fn get_teachers(include_archived: bool) -> Result<Vec<TeacherRecord>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(TEACHER_ROLES).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        db::server::load_teacher_records(&pool, include_archived)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+/// Active users a teacher profile can be linked to.
+#[server(GetLoginUsers, "/api/teachers")]
+pub async fn get_login_users() -> Result<Vec<StaffOption>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(TEACHER_ROLES).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        db::server::load_login_users(&pool)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+/// Adds a teacher when `id` is `None`, otherwise updates them. A login can
+/// belong to one teacher only.
+#[allow(clippy::too_many_arguments)]
+#[server(name = SaveTeacher, prefix = "/api/teachers", input = Json)]
+pub async fn save_teacher(
+    id: Option<i32>,
+    first_name: String,
+    other_names: String,
+    last_name: String,
+    gender: String,
+    subject_codes: Vec<String>,
+    class_codes: Vec<String>,
+    unavailable_days: Vec<String>,
+    unavailable_sessions: Vec<String>,
+    leave: Vec<(NaiveDate, NaiveDate)>,
+    max_duties_per_day: Option<u32>,
+    max_duties_strength: String,
+    no_back_to_back: String,
+    preferred_sessions: Vec<String>,
+    user_id: Option<i32>,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use leptos::server_fn::error::NoCustomError;
+        use shared::domain::{PersonalName, Teacher, TeacherPersonalInfo};
+
+        crate::app::db::server::require_role(TEACHER_ROLES).await?;
+        if first_name.trim().is_empty() || last_name.trim().is_empty() {
+            return Err(ServerFnError::ServerError(
+                "First name and last name are required".into(),
+            ));
+        }
+        let Some(gender) = Gender::from_code(&gender) else {
+            return Err(ServerFnError::ServerError(format!("Unknown gender: {}", gender)));
+        };
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+        let catalogue = crate::catalogue::db::server::load_catalogue(&pool)
+            .await
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+        let mut subjects = Vec::new();
+        for code in &subject_codes {
+            let subject = catalogue
+                .subject_for(code)
+                .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+            if !subjects.contains(&subject) {
+                subjects.push(subject);
+            }
+        }
+        let mut classes = Vec::new();
+        for code in &class_codes {
+            let class = catalogue
+                .class_for(code)
+                .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+            if !classes.contains(&class) {
+                classes.push(class);
+            }
+        }
+        let mut days = Vec::new();
+        for code in &unavailable_days {
+            let Some(day) = ExamDay::from_code(code) else {
+                return Err(ServerFnError::ServerError(format!("Unknown day: {}", code)));
+            };
+            days.push(day);
+        }
+        let mut sessions = Vec::new();
+        for code in &unavailable_sessions {
+            let Some(session) = ExamSession::from_code(code) else {
+                return Err(ServerFnError::ServerError(format!("Unknown session: {}", code)));
+            };
+            sessions.push(session);
+        }
+        if let Some((from, to)) = leave.iter().find(|(from, to)| to < from) {
+            return Err(ServerFnError::ServerError(format!(
+                "Leave from {} ends before it starts on {}",
+                from, to
+            )));
+        }
+        let max_duties_per_day = match max_duties_per_day {
+            Some(0) => {
+                return Err(ServerFnError::ServerError(
+                    "Allow at least one duty a day, or leave the limit empty".into(),
+                ));
+            }
+            Some(limit) => {
+                let Some(strength) = Strength::from_code(&max_duties_strength) else {
+                    return Err(ServerFnError::ServerError(format!(
+                        "Unknown strength: {}",
+                        max_duties_strength
+                    )));
+                };
+                Some((limit, strength))
+            }
+            None => None,
+        };
+        let no_back_to_back = match no_back_to_back.as_str() {
+            "" => None,
+            code => match Strength::from_code(code) {
+                Some(strength) => Some(strength),
+                None => {
+                    return Err(ServerFnError::ServerError(format!("Unknown strength: {}", code)));
+                }
+            },
+        };
+        let mut preferred = Vec::new();
+        for code in &preferred_sessions {
+            let Some(session) = ExamSession::from_code(code) else {
+                return Err(ServerFnError::ServerError(format!("Unknown session: {}", code)));
+            };
+            preferred.push(session);
+        }
+
+        if let Some(user_id) = user_id {
+            let linked = db::server::teacher_linked_to(&pool, user_id)
+                .await
+                .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+            if linked.is_some_and(|linked| Some(linked) != id) {
+                return Err(ServerFnError::ServerError(
+                    "That login already belongs to another teacher".into(),
+                ));
+            }
+        }
+
+        let record = TeacherRecord {
+            id: id.unwrap_or_default(),
+            teacher: Teacher::new(
+                TeacherPersonalInfo::new(
+                    PersonalName::new(
+                        first_name.trim().to_string(),
+                        other_names.trim().to_string(),
+                        last_name.trim().to_string(),
+                    ),
+                    gender,
+                ),
+                subjects,
+            ),
+            classes,
+            unavailable_days: days,
+            unavailable_sessions: sessions,
+            leave,
+            max_duties_per_day,
+            no_back_to_back,
+            preferred_sessions: preferred,
+            user_id,
+            username: None,
+            is_archived: false,
+        };
+        db::server::save_teacher(&pool, id, &record)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+#[server(SetTeacherArchived, "/api/teachers")]
+pub async fn set_teacher_archived(id: i32, is_archived: bool) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(TEACHER_ROLES).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        db::server::set_archived(&pool, id, is_archived)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+/// The logged-in user's classes, assessments and invigilation duties, or
+/// `None` when their login is not linked to a teacher.
+#[server(GetMyTeaching, "/api/teachers")]
+pub async fn get_my_teaching() -> Result<Option<MyTeaching>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let user_session = crate::app::db::server::require_role(STAFF_ROLES).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        db::server::load_my_teaching(&pool, user_session.user_id)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+fn full_name(teacher: &shared::domain::Teacher) -> String {
+    let name = teacher.personal_info().name();
+    format!("{} {} {}", name.first_name(), name.other_names(), name.last_name())
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn availability(record: &TeacherRecord) -> String {
+    let unavailable: Vec<&str> = record
+        .unavailable_days
+        .iter()
+        .map(|day| day.as_str())
+        .chain(record.unavailable_sessions.iter().map(|session| session.as_str()))
+        .collect();
+    let mut notes = Vec::new();
+    if !unavailable.is_empty() {
+        notes.push(format!("Not {}", unavailable.join(", ")));
+    }
+    notes.extend(record.leave.iter().map(|(from, to)| {
+        format!("Leave {} to {}", from.format("%-d %b"), to.format("%-d %b"))
+    }));
+    if let Some((limit, strength)) = record.max_duties_per_day {
+        notes.push(format!("At most {} a day ({})", limit, strength.as_str().to_lowercase()));
+    }
+    if let Some(strength) = record.no_back_to_back {
+        notes.push(format!("No back-to-back ({})", strength.as_str().to_lowercase()));
+    }
+    if !record.preferred_sessions.is_empty() {
+        notes.push(format!(
+            "Prefers {}",
+            record
+                .preferred_sessions
+                .iter()
+                .map(|session| session.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    if notes.is_empty() {
+        "Any time".to_string()
+    } else {
+        notes.join("; ")
+    }
+}
+
+#[component]
+pub fn TeachersView() -> impl IntoView {
+    let title = use_context::<RwSignal<String>>()
+        .expect("title context should be provided");
+
+    title.set("Teachers".to_string());
+
+    let save_teacher = ServerAction::<SaveTeacher>::new();
+    let set_archived = ServerAction::<SetTeacherArchived>::new();
+    let include_archived = RwSignal::new(false);
+    let editing = RwSignal::new(None::<TeacherRecord>);
+
+    let teachers = Resource::new(
+        move || {
+            (
+                include_archived.get(),
+                save_teacher.version().get(),
+                set_archived.version().get(),
+            )
+        },
+        |(include_archived, ..)| async move { get_teachers(include_archived).await },
+    );
+
+    view! {
+        <div class="p-4 space-y-6">
+            <section class="bg-white rounded-lg shadow p-4 overflow-auto-x">
+                <div class="flex gap-2 items-center mb-4">
+                    <h2 class="text-lg font-semibold mr-4">"Teachers"</h2>
+                    <label class="text-sm">
+                        <input
+                            type="checkbox"
+                            class="mr-2"
+                            prop:checked=move || include_archived.get()
+                            on:change=move |ev| include_archived.set(event_target_checked(&ev))
+                        />
+                        "Show archived"
+                    </label>
+                </div>
+                <Suspense fallback=move || view! { <LoadingSpinner/> }>
+                    {move || teachers.get().map(|result| match result {
+                        Ok(teachers) => view! {
+                            <TeacherTable teachers=teachers editing=editing set_archived=set_archived />
+                        }.into_any(),
+                        Err(e) => view! { <ErrorDisplay error=e.to_string() /> }.into_any(),
+                    })}
+                </Suspense>
+                <ActionError result=set_archived.value() />
+            </section>
+            <TeacherForm editing=editing save_teacher=save_teacher />
+        </div>
+    }
+}
+
+#[component]
+fn TeacherTable(
+    teachers: Vec<TeacherRecord>,
+    editing: RwSignal<Option<TeacherRecord>>,
+    set_archived: ServerAction<SetTeacherArchived>,
+) -> impl IntoView {
+    if teachers.is_empty() {
+        return view! { <p class="text-gray-500">"No teachers found."</p> }.into_any();
+    }
+
+    view! {
+        <table class="min-w-full divide-y divide-gray-200">
+            <thead class="bg-gray-50">
+                <tr>
+                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Name"</th>
+                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Gender"</th>
+                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Subjects"</th>
+                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Classes"</th>
+                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Availability"</th>
+                    <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Login"</th>
+                    <th class="px-4 py-2"></th>
+                </tr>
+            </thead>
+            <tbody class="divide-y divide-gray-200">
+                {teachers.into_iter().map(|record| {
+                    let id = record.id;
+                    let is_archived = record.is_archived;
+                    view! {
+                        <tr class="hover:bg-gray-50" class:text-gray-400=is_archived>
+                            <td class="px-4 py-2 text-sm">
+                                {full_name(&record.teacher)}
+                                {is_archived.then_some(" (archived)")}
+                            </td>
+                            <td class="px-4 py-2 text-sm">{record.teacher.personal_info().gender().to_string()}</td>
+                            <td class="px-4 py-2 text-sm">
+                                {record.teacher.subjects_taught().iter().map(|subject| subject.to_string()).collect::<Vec<_>>().join(", ")}
+                            </td>
+                            <td class="px-4 py-2 text-sm">
+                                {record.classes.iter().map(|class| class.code()).collect::<Vec<_>>().join(", ")}
+                            </td>
+                            <td class="px-4 py-2 text-sm">{availability(&record)}</td>
+                            <td class="px-4 py-2 text-sm">{record.username.clone().unwrap_or_else(|| "—".to_string())}</td>
+                            <td class="px-4 py-2 text-sm text-right space-x-2">
+                                <button class="text-blue-600" on:click=move |_| editing.set(Some(record.clone()))>
+                                    "Edit"
+                                </button>
+                                <button
+                                    class=if is_archived { "text-green-600" } else { "text-red-600" }
+                                    on:click=move |_| {
+                                        set_archived.dispatch(SetTeacherArchived { id, is_archived: !is_archived });
+                                    }
+                                >
+                                    {if is_archived { "Restore" } else { "Archive" }}
+                                </button>
+                            </td>
+                        </tr>
+                    }
+                }).collect_view()}
+            </tbody>
+        </table>
+    }.into_any()
+}
+
+/// Checkboxes that add or remove a code from `selected`.
+pub(crate) fn code_checkboxes(options: Vec<(String, String)>, selected: RwSignal<Vec<String>>) -> impl IntoView {
+    options
+        .into_iter()
+        .map(|(code, label)| {
+            let is_checked = {
+                let code = code.clone();
+                move || selected.get().contains(&code)
+            };
+            view! {
+                <label>
+                    <input
+                        type="checkbox"
+                        class="mr-1"
+                        prop:checked=is_checked
+                        on:change=move |ev| {
+                            let is_checked = event_target_checked(&ev);
+                            selected.update(|codes| {
+                                codes.retain(|selected| *selected != code);
+                                if is_checked {
+                                    codes.push(code.clone());
+                                }
+                            });
+                        }
+                    />
+                    {label}
+                </label>
+            }
+        })
+        .collect_view()
+}
+
+#[component]
+fn TeacherForm(
+    editing: RwSignal<Option<TeacherRecord>>,
+    save_teacher: ServerAction<SaveTeacher>,
+) -> impl IntoView {
+    let first_name = RwSignal::new(String::new());
+    let other_names = RwSignal::new(String::new());
+    let last_name = RwSignal::new(String::new());
+    let gender = RwSignal::new(Gender::ALL[0].as_str().to_string());
+    let subject_codes = RwSignal::new(Vec::<String>::new());
+    let class_codes = RwSignal::new(Vec::<String>::new());
+    let unavailable_days = RwSignal::new(Vec::<String>::new());
+    let unavailable_sessions = RwSignal::new(Vec::<String>::new());
+    let leave = RwSignal::new(Vec::<(NaiveDate, NaiveDate)>::new());
+    let leave_from = RwSignal::new(String::new());
+    let leave_to = RwSignal::new(String::new());
+    let max_duties_per_day = RwSignal::new(String::new());
+    let max_duties_strength = RwSignal::new(Strength::Hard.code().to_string());
+    let no_back_to_back = RwSignal::new(String::new());
+    let preferred_sessions = RwSignal::new(Vec::<String>::new());
+    let user_id = RwSignal::new(String::new());
+    let logins = Resource::new(|| (), |_| async move { get_login_users().await });
+    let catalogue = Resource::new(|| (), |_| async move { get_catalogue().await });
+
+    let clear = move || {
+        editing.set(None);
+        first_name.set(String::new());
+        other_names.set(String::new());
+        last_name.set(String::new());
+        gender.set(Gender::ALL[0].as_str().to_string());
+        subject_codes.set(Vec::new());
+        class_codes.set(Vec::new());
+        unavailable_days.set(Vec::new());
+        unavailable_sessions.set(Vec::new());
+        leave.set(Vec::new());
+        leave_from.set(String::new());
+        leave_to.set(String::new());
+        max_duties_per_day.set(String::new());
+        max_duties_strength.set(Strength::Hard.code().to_string());
+        no_back_to_back.set(String::new());
+        preferred_sessions.set(Vec::new());
+        user_id.set(String::new());
+    };
+
+    Effect::new(move |_| {
+        if let Some(record) = editing.get() {
+            let info = record.teacher.personal_info();
+            first_name.set(info.name().first_name().to_string());
+            other_names.set(info.name().other_names().to_string());
+            last_name.set(info.name().last_name().to_string());
+            gender.set(info.gender().as_str().to_string());
+            subject_codes.set(record.teacher.subjects_taught().iter().map(|subject| subject.code()).collect());
+            class_codes.set(record.classes.iter().map(|class| class.code().to_string()).collect());
+            unavailable_days.set(record.unavailable_days.iter().map(|day| day.as_str().to_string()).collect());
+            unavailable_sessions.set(record.unavailable_sessions.iter().map(|session| session.as_str().to_string()).collect());
+            leave.set(record.leave.clone());
+            max_duties_per_day.set(record.max_duties_per_day.map(|(limit, _)| limit.to_string()).unwrap_or_default());
+            max_duties_strength.set(record.max_duties_per_day.map_or(Strength::Hard, |(_, strength)| strength).code().to_string());
+            no_back_to_back.set(record.no_back_to_back.map(|strength| strength.code().to_string()).unwrap_or_default());
+            preferred_sessions.set(record.preferred_sessions.iter().map(|session| session.as_str().to_string()).collect());
+            user_id.set(record.user_id.map(|id| id.to_string()).unwrap_or_default());
+        }
+    });
+
+    Effect::new(move |_| {
+        if let Some(Ok(())) = save_teacher.value().get() {
+            clear();
+        }
+    });
+
+    let submit = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        save_teacher.dispatch(SaveTeacher {
+            id: editing.get_untracked().map(|record| record.id),
+            first_name: first_name.get_untracked(),
+            other_names: other_names.get_untracked(),
+            last_name: last_name.get_untracked(),
+            gender: gender.get_untracked(),
+            subject_codes: subject_codes.get_untracked(),
+            class_codes: class_codes.get_untracked(),
+            unavailable_days: unavailable_days.get_untracked(),
+            unavailable_sessions: unavailable_sessions.get_untracked(),
+            leave: leave.get_untracked(),
+            max_duties_per_day: max_duties_per_day.get_untracked().trim().parse().ok(),
+            max_duties_strength: max_duties_strength.get_untracked(),
+            no_back_to_back: no_back_to_back.get_untracked(),
+            preferred_sessions: preferred_sessions.get_untracked(),
+            user_id: user_id.get_untracked().parse().ok(),
+        });
+    };
+
+    view! {
+        <section class="bg-white rounded-lg shadow p-4">
+            <h2 class="text-lg font-semibold mb-4">
+                {move || if editing.get().is_some() { "Edit Teacher" } else { "Add Teacher" }}
+            </h2>
+            <form class="space-y-4" on:submit=submit>
+                <div class="flex gap-2 items-center flex-wrap">
+                    <input type="text" placeholder="First name" required class="px-3 py-2 border rounded" bind:value=first_name />
+                    <input type="text" placeholder="Other names" class="px-3 py-2 border rounded" bind:value=other_names />
+                    <input type="text" placeholder="Last name" required class="px-3 py-2 border rounded" bind:value=last_name />
+                    <select
+                        class="px-3 py-2 border rounded"
+                        prop:value=move || gender.get()
+                        on:change=move |ev| gender.set(event_target_value(&ev))
+                    >
+                        {Gender::ALL.iter().map(|gender| view! {
+                            <option value=gender.as_str()>{gender.as_str()}</option>
+                        }).collect_view()}
+                    </select>
+                    <select
+                        class="px-3 py-2 border rounded"
+                        prop:value=move || user_id.get()
+                        on:change=move |ev| user_id.set(event_target_value(&ev))
+                    >
+                        <option value="">"No login"</option>
+                        <Suspense fallback=|| ()>
+                            {move || logins.get().and_then(Result::ok).map(|logins| {
+                                logins.into_iter().map(|login| view! {
+                                    <option value=login.user_id.to_string()>{login.name}</option>
+                                }).collect_view()
+                            })}
+                        </Suspense>
+                    </select>
+                </div>
+                <fieldset>
+                    <legend class="text-sm font-medium mb-1">"Subjects taught"</legend>
+                    <div class="flex gap-4 flex-wrap text-sm">
+                        <Suspense fallback=|| ()>
+                            {move || catalogue.get().and_then(Result::ok).map(|catalogue| code_checkboxes(
+                                catalogue.subjects().iter().filter(|entry| entry.is_active()).map(|entry| (entry.code().to_string(), entry.subject().to_string())).collect(),
+                                subject_codes,
+                            ))}
+                        </Suspense>
+                    </div>
+                </fieldset>
+                <fieldset>
+                    <legend class="text-sm font-medium mb-1">"Classes taught"</legend>
+                    <div class="flex gap-4 flex-wrap text-sm">
+                        <Suspense fallback=|| ()>
+                            {move || catalogue.get().and_then(Result::ok).map(|catalogue| code_checkboxes(
+                                catalogue.classes().into_iter().map(|class| (class.code().to_string(), class.to_string())).collect(),
+                                class_codes,
+                            ))}
+                        </Suspense>
+                    </div>
+                </fieldset>
+                <fieldset>
+                    <legend class="text-sm font-medium mb-1">"Not available to invigilate"</legend>
+                    <div class="flex gap-4 flex-wrap text-sm">
+                        {code_checkboxes(
+                            ExamDay::ALL.iter().map(|day| (day.as_str().to_string(), day.as_str().to_string())).collect(),
+                            unavailable_days,
+                        )}
+                        {code_checkboxes(
+                            ExamSession::ALL.iter().map(|session| (session.as_str().to_string(), format!("{} sessions", session))).collect(),
+                            unavailable_sessions,
+                        )}
+                    </div>
+                    <div class="flex gap-2 items-center flex-wrap text-sm mt-2">
+                        <span>"Leave"</span>
+                        {move || leave.get().into_iter().enumerate().map(|(index, (from, to))| view! {
+                            <span class="px-2 py-1 bg-gray-100 rounded">
+                                {format!("{} to {}", from.format("%-d %b %Y"), to.format("%-d %b %Y"))}
+                                <button
+                                    type="button"
+                                    class="ml-1 text-red-600"
+                                    on:click=move |_| leave.update(|leave| { leave.remove(index); })
+                                >
+                                    "x"
+                                </button>
+                            </span>
+                        }).collect_view()}
+                        <input type="date" class="px-2 py-1 border rounded" bind:value=leave_from />
+                        <input type="date" class="px-2 py-1 border rounded" bind:value=leave_to />
+                        <button
+                            type="button"
+                            class="px-2 py-1 rounded border"
+                            on:click=move |_| {
+                                if let (Ok(from), Ok(to)) = (
+                                    leave_from.get_untracked().parse::<NaiveDate>(),
+                                    leave_to.get_untracked().parse::<NaiveDate>(),
+                                ) {
+                                    leave.update(|leave| leave.push((from.min(to), from.max(to))));
+                                    leave_from.set(String::new());
+                                    leave_to.set(String::new());
+                                }
+                            }
+                        >
+                            "Add leave"
+                        </button>
+                    </div>
+                </fieldset>
+                <fieldset>
+                    <legend class="text-sm font-medium mb-1">"Invigilation limits"</legend>
+                    <div class="flex gap-4 items-center flex-wrap text-sm">
+                        <label>
+                            "At most "
+                            <input type="number" min="1" placeholder="No limit" class="px-2 py-1 border rounded w-24" bind:value=max_duties_per_day />
+                            " duties a day"
+                        </label>
+                        <select
+                            class="px-2 py-1 border rounded"
+                            prop:value=move || max_duties_strength.get()
+                            on:change=move |ev| max_duties_strength.set(event_target_value(&ev))
+                        >
+                            {Strength::ALL.iter().map(|strength| view! {
+                                <option value=strength.code()>{strength.as_str()}</option>
+                            }).collect_view()}
+                        </select>
+                        <label>
+                            "Back-to-back duties "
+                            <select
+                                class="px-2 py-1 border rounded"
+                                prop:value=move || no_back_to_back.get()
+                                on:change=move |ev| no_back_to_back.set(event_target_value(&ev))
+                            >
+                                <option value="">"Allowed"</option>
+                                {Strength::ALL.iter().map(|strength| view! {
+                                    <option value=strength.code()>{format!("Avoid: {}", strength.as_str().to_lowercase())}</option>
+                                }).collect_view()}
+                            </select>
+                        </label>
+                    </div>
+                    <div class="flex gap-4 flex-wrap text-sm mt-2">
+                        <span>"Prefers"</span>
+                        {code_checkboxes(
+                            ExamSession::ALL.iter().map(|session| (session.as_str().to_string(), format!("{} sessions", session))).collect(),
+                            preferred_sessions,
+                        )}
+                    </div>
+                </fieldset>
+                <div class="space-x-2">
+                    <button type="submit" class="bg-gray-700 text-white px-4 py-2 rounded">"Save Teacher"</button>
+                    <button type="button" class="px-4 py-2 rounded border" on:click=move |_| clear()>"Clear"</button>
+                </div>
+            </form>
+            <ActionError result=save_teacher.value() />
+        </section>
+    }
+}
+
+/// A teacher's own page: their classes, the assessments they mark and their
+/// invigilation duties.
+#[component]
+pub fn MyClassesView() -> impl IntoView {
+    let title = use_context::<RwSignal<String>>()
+        .expect("title context should be provided");
+
+    title.set("My Classes".to_string());
+
+    let teaching = Resource::new(|| (), |_| async move { get_my_teaching().await });
+
+    view! {
+        <div class="p-4 space-y-6">
+            <Suspense fallback=move || view! { <LoadingSpinner/> }>
+                {move || teaching.get().map(|result| match result {
+                    Ok(Some(teaching)) => view! { <MyTeachingSections teaching=teaching /> }.into_any(),
+                    Ok(None) => view! {
+                        <p class="text-gray-500">"Your login is not linked to a teacher profile. Ask an administrator to link it on the Teachers page."</p>
+                    }.into_any(),
+                    Err(e) => view! { <ErrorDisplay error=e.to_string() /> }.into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn MyTeachingSections(teaching: MyTeaching) -> impl IntoView {
+    view! {
+        <section class="bg-white rounded-lg shadow p-4">
+            <h2 class="text-lg font-semibold mb-2">{full_name(&teaching.teacher.teacher)}</h2>
+            <p class="text-sm">
+                {teaching.teacher.teacher.subjects_taught().iter().map(|subject| subject.to_string()).collect::<Vec<_>>().join(", ")}
+            </p>
+            <p class="text-sm text-gray-500">{format!("Invigilation: {}", availability(&teaching.teacher))}</p>
+        </section>
+        <section class="bg-white rounded-lg shadow p-4 overflow-auto-x">
+            <h2 class="text-lg font-semibold mb-4">"Classes"</h2>
+            {if teaching.classes.is_empty() {
+                view! { <p class="text-gray-500">"No classes are assigned to you."</p> }.into_any()
+            } else {
+                view! {
+                    <table class="min-w-full divide-y divide-gray-200">
+                        <thead class="bg-gray-50">
+                            <tr>
+                                <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Class"</th>
+                                <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Subject"</th>
+                                <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Students"</th>
+                            </tr>
+                        </thead>
+                        <tbody class="divide-y divide-gray-200">
+                            {teaching.classes.iter().map(|class| view! {
+                                <tr class="hover:bg-gray-50">
+                                    <td class="px-4 py-2 text-sm">{class.class.to_string()}</td>
+                                    <td class="px-4 py-2 text-sm">{class.subject.to_string()}</td>
+                                    <td class="px-4 py-2 text-sm">{class.students}</td>
+                                </tr>
+                            }).collect_view()}
+                        </tbody>
+                    </table>
+                }.into_any()
+            }}
+        </section>
+        <section class="bg-white rounded-lg shadow p-4 overflow-auto-x">
+            <h2 class="text-lg font-semibold mb-4">"Marks"</h2>
+            {if teaching.assessments.is_empty() {
+                view! { <p class="text-gray-500">"No assessments have been set for your classes."</p> }.into_any()
+            } else {
+                view! {
+                    <table class="min-w-full divide-y divide-gray-200">
+                        <thead class="bg-gray-50">
+                            <tr>
+                                <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Assessment"</th>
+                                <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Term"</th>
+                                <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Class"</th>
+                                <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Subject"</th>
+                                <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Entered"</th>
+                            </tr>
+                        </thead>
+                        <tbody class="divide-y divide-gray-200">
+                            {teaching.assessments.iter().map(|progress| {
+                                let assessment = &progress.assessment;
+                                let is_complete = progress.entered >= progress.expected;
+                                view! {
+                                    <tr class="hover:bg-gray-50">
+                                        <td class="px-4 py-2 text-sm">{assessment.name().to_string()}</td>
+                                        <td class="px-4 py-2 text-sm">{assessment.period().to_string()}</td>
+                                        <td class="px-4 py-2 text-sm">{assessment.class().to_string()}</td>
+                                        <td class="px-4 py-2 text-sm">{assessment.subject().to_string()}</td>
+                                        <td class="px-4 py-2 text-sm" class:text-red-600=!is_complete>
+                                            {format!("{} of {}", progress.entered, progress.expected)}
+                                        </td>
+                                    </tr>
+                                }
+                            }).collect_view()}
+                        </tbody>
+                    </table>
+                }.into_any()
+            }}
+        </section>
+        <section class="bg-white rounded-lg shadow p-4 overflow-auto-x">
+            <h2 class="text-lg font-semibold mb-4">"Invigilation Duties"</h2>
+            {if teaching.duties.is_empty() {
+                view! { <p class="text-gray-500">"You have no invigilation duties."</p> }.into_any()
+            } else {
+                view! {
+                    <table class="min-w-full divide-y divide-gray-200">
+                        <thead class="bg-gray-50">
+                            <tr>
+                                <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Date"</th>
+                                <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Session"</th>
+                                <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Room"</th>
+                                <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">"Minutes"</th>
+                            </tr>
+                        </thead>
+                        <tbody class="divide-y divide-gray-200">
+                            {teaching.duties.iter().map(|duty| view! {
+                                <tr class="hover:bg-gray-50">
+                                    <td class="px-4 py-2 text-sm">{duty.exam_date.format("%a %-d %b %Y").to_string()}</td>
+                                    <td class="px-4 py-2 text-sm">{duty.session.clone()}</td>
+                                    <td class="px-4 py-2 text-sm">{duty.room_code.clone()}</td>
+                                    <td class="px-4 py-2 text-sm">{duty.minutes}</td>
+                                </tr>
+                            }).collect_view()}
+                        </tbody>
+                    </table>
+                }.into_any()
+            }}
+        </section>
+    }
+}
+
+pub mod db {
+    use chrono::NaiveDate;
+    use serde::{Deserialize, Serialize};
+    use shared::domain::{ExamDay, ExamSession, StudentClass, Subject, Teacher};
+    use shared::invigilation::{StaffConstraints, StaffMember, Strength, Unavailability};
+    use shared::marks::Assessment;
+
+    use crate::invigilation::db::DutyRecord;
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    pub struct TeacherRecord {
+        pub id: i32,
+        pub teacher: Teacher,
+        pub classes: Vec<StudentClass>,
+        pub unavailable_days: Vec<ExamDay>,
+        pub unavailable_sessions: Vec<ExamSession>,
+        /// Inclusive date ranges.
+        pub leave: Vec<(NaiveDate, NaiveDate)>,
+        pub max_duties_per_day: Option<(u32, Strength)>,
+        pub no_back_to_back: Option<Strength>,
+        pub preferred_sessions: Vec<ExamSession>,
+        /// The login linked to this teacher, if any.
+        pub user_id: Option<i32>,
+        pub username: Option<String>,
+        pub is_archived: bool,
+    }
+
+    impl TeacherRecord {
+        /// The teacher as invigilation planning sees them. Leave and the
+        /// days and sessions they are unavailable are hard constraints.
+        pub fn staff_member(&self) -> StaffMember {
+            let unavailable = self
+                .unavailable_days
+                .iter()
+                .copied()
+                .map(Unavailability::Day)
+                .chain(self.unavailable_sessions.iter().copied().map(Unavailability::Session))
+                .chain(
+                    self.leave
+                        .iter()
+                        .map(|(from, to)| Unavailability::Leave { from: *from, to: *to }),
+                )
+                .collect();
+            StaffMember::new(
+                self.teacher.clone(),
+                StaffConstraints::new(
+                    unavailable,
+                    self.max_duties_per_day,
+                    self.no_back_to_back,
+                    self.preferred_sessions.clone(),
+                ),
+            )
+        }
+    }
+
+    /// One subject a teacher teaches in one of their classes.
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    pub struct TaughtClass {
+        pub class: StudentClass,
+        pub subject: Subject,
+        /// Current students reading the subject.
+        pub students: usize,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    pub struct AssessmentProgress {
+        pub id: i32,
+        pub assessment: Assessment,
+        pub entered: usize,
+        pub expected: usize,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    pub struct MyTeaching {
+        pub teacher: TeacherRecord,
+        pub classes: Vec<TaughtClass>,
+        /// Newest first.
+        pub assessments: Vec<AssessmentProgress>,
+        pub duties: Vec<DutyRecord>,
+    }
+
+    #[cfg(feature = "ssr")]
+    pub mod server {
+        use super::{AssessmentProgress, MyTeaching, TaughtClass, TeacherRecord};
+        use crate::app::db::Error;
+        use crate::catalogue::db::server::{load_catalogue, stored_class, stored_subjects};
+        use crate::invigilation::db::StaffOption;
+        use shared::catalogue::Catalogue;
+        use chrono::NaiveDate;
+        use shared::domain::{
+            ExamDay, ExamSession, Gender, PersonalName, StudentClass, Subject, Teacher,
+            TeacherPersonalInfo,
+        };
+        use shared::invigilation::Strength;
+        use sqlx::PgPool;
+
+        pub async fn create_teachers_tables(pool: &PgPool) -> Result<(), Error> {
+            for query in [
+                "CREATE TABLE IF NOT EXISTS teachers (
+                    id SERIAL PRIMARY KEY,
+                    first_name VARCHAR(100) NOT NULL,
+                    other_names VARCHAR(100) NOT NULL DEFAULT '',
+                    last_name VARCHAR(100) NOT NULL,
+                    gender VARCHAR(10) NOT NULL,
+                    is_archived BOOLEAN NOT NULL DEFAULT FALSE
+                )",
+                "CREATE TABLE IF NOT EXISTS teacher_subjects (
+                    teacher_id INTEGER NOT NULL REFERENCES teachers(id) ON DELETE CASCADE,
+                    subject_code VARCHAR(30) NOT NULL,
+                    PRIMARY KEY (teacher_id, subject_code)
+                )",
+                "CREATE TABLE IF NOT EXISTS teacher_classes (
+                    teacher_id INTEGER NOT NULL REFERENCES teachers(id) ON DELETE CASCADE,
+                    class_code VARCHAR(10) NOT NULL,
+                    PRIMARY KEY (teacher_id, class_code)
+                )",
+                "ALTER TABLE teachers
+                    ADD COLUMN IF NOT EXISTS user_id INTEGER UNIQUE REFERENCES users(id) ON DELETE SET NULL,
+                    ADD COLUMN IF NOT EXISTS unavailable_days VARCHAR(10)[] NOT NULL DEFAULT '{}',
+                    ADD COLUMN IF NOT EXISTS unavailable_sessions VARCHAR(10)[] NOT NULL DEFAULT '{}'",
+                "ALTER TABLE teachers
+                    ADD COLUMN IF NOT EXISTS max_duties_per_day INTEGER CHECK (max_duties_per_day > 0),
+                    ADD COLUMN IF NOT EXISTS max_duties_strength VARCHAR(10),
+                    ADD COLUMN IF NOT EXISTS no_back_to_back VARCHAR(10),
+                    ADD COLUMN IF NOT EXISTS preferred_sessions VARCHAR(10)[] NOT NULL DEFAULT '{}'",
+                "CREATE TABLE IF NOT EXISTS teacher_leave (
+                    id SERIAL PRIMARY KEY,
+                    teacher_id INTEGER NOT NULL REFERENCES teachers(id) ON DELETE CASCADE,
+                    from_date DATE NOT NULL,
+                    to_date DATE NOT NULL CHECK (to_date >= from_date)
+                )",
+            ] {
+                sqlx::query(query).execute(pool).await.map_err(|e| {
+                    log::error!("Failed to create teachers tables: {}", e);
+                    Error::TableNotCreated
+                })?;
+            }
+
+            Ok(())
+        }
+
+        /// Current teachers with the subjects they teach, ordered by surname.
+        pub async fn load_teachers(pool: &PgPool) -> Result<Vec<Teacher>, Error> {
+            let rows = sqlx::query_as::<_, (String, String, String, String, Vec<String>)>(
+                r#"
+                SELECT t.first_name, t.other_names, t.last_name, t.gender,
+                    COALESCE(ARRAY_AGG(ts.subject_code) FILTER (WHERE ts.subject_code IS NOT NULL), '{}')
+                FROM teachers t
+                LEFT JOIN teacher_subjects ts ON ts.teacher_id = t.id
+                WHERE NOT t.is_archived
+                GROUP BY t.id
+                ORDER BY t.last_name, t.first_name
+                "#,
+            )
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to retrieve teachers: {}", e);
+                Error::DatabaseQueryFailed
+            })?;
+
+            let catalogue = load_catalogue(pool).await?;
+            rows.into_iter()
+                .map(|(first_name, other_names, last_name, gender, subjects)| {
+                    let name = PersonalName::new(first_name, other_names, last_name);
+                    let gender = stored_gender(&name, &gender)?;
+                    Ok(Teacher::new(
+                        TeacherPersonalInfo::new(name, gender),
+                        stored_subjects(&catalogue, &subjects)?,
+                    ))
+                })
+                .collect()
+        }
+
+        #[derive(sqlx::FromRow)]
+        struct TeacherRow {
+            id: i32,
+            first_name: String,
+            other_names: String,
+            last_name: String,
+            gender: String,
+            subjects: Vec<String>,
+            classes: Vec<String>,
+            unavailable_days: Vec<String>,
+            unavailable_sessions: Vec<String>,
+            leave_from: Vec<NaiveDate>,
+            leave_to: Vec<NaiveDate>,
+            max_duties_per_day: Option<i32>,
+            max_duties_strength: Option<String>,
+            no_back_to_back: Option<String>,
+            preferred_sessions: Vec<String>,
+            user_id: Option<i32>,
+            username: Option<String>,
+            is_archived: bool,
+        }
+
+        const TEACHER_SELECT: &str = r#"
+            SELECT t.id, t.first_name, t.other_names, t.last_name, t.gender,
+                COALESCE((SELECT ARRAY_AGG(ts.subject_code ORDER BY ts.subject_code)
+                    FROM teacher_subjects ts WHERE ts.teacher_id = t.id), '{}') AS subjects,
+                COALESCE((SELECT ARRAY_AGG(tc.class_code ORDER BY tc.class_code)
+                    FROM teacher_classes tc WHERE tc.teacher_id = t.id), '{}') AS classes,
+                t.unavailable_days, t.unavailable_sessions,
+                COALESCE((SELECT ARRAY_AGG(tl.from_date ORDER BY tl.from_date, tl.id)
+                    FROM teacher_leave tl WHERE tl.teacher_id = t.id), '{}') AS leave_from,
+                COALESCE((SELECT ARRAY_AGG(tl.to_date ORDER BY tl.from_date, tl.id)
+                    FROM teacher_leave tl WHERE tl.teacher_id = t.id), '{}') AS leave_to,
+                t.max_duties_per_day, t.max_duties_strength, t.no_back_to_back, t.preferred_sessions,
+                t.user_id, u.username, t.is_archived
+            FROM teachers t
+            LEFT JOIN users u ON u.id = t.user_id"#;
+
+        fn stored_strength(id: i32, code: &str) -> Result<Strength, Error> {
+            Strength::from_code(code).ok_or_else(|| {
+                log::error!("Teacher {} has unknown constraint strength {}", id, code);
+                Error::DatabaseQueryFailed
+            })
+        }
+
+        fn stored_gender(name: &PersonalName, code: &str) -> Result<Gender, Error> {
+            Gender::from_code(code).ok_or_else(|| {
+                log::error!(
+                    "Teacher {} {} has unknown gender {}",
+                    name.first_name(),
+                    name.last_name(),
+                    code
+                );
+                Error::DatabaseQueryFailed
+            })
+        }
+
+        fn record_from_row(
+            catalogue: &Catalogue,
+            TeacherRow {
+                id,
+                first_name,
+                other_names,
+                last_name,
+                gender,
+                subjects,
+                classes,
+                unavailable_days,
+                unavailable_sessions,
+                leave_from,
+                leave_to,
+                max_duties_per_day,
+                max_duties_strength,
+                no_back_to_back,
+                preferred_sessions,
+                user_id,
+                username,
+                is_archived,
+            }: TeacherRow,
+        ) -> Result<TeacherRecord, Error> {
+            let mut classes = classes
+                .iter()
+                .map(|code| stored_class(catalogue, code))
+                .collect::<Result<Vec<StudentClass>, Error>>()?;
+            classes.sort();
+            let name = PersonalName::new(first_name, other_names, last_name);
+            let gender = stored_gender(&name, &gender)?;
+            let unavailable_days = unavailable_days
+                .iter()
+                .map(|code| {
+                    ExamDay::from_code(code).ok_or_else(|| {
+                        log::error!("Teacher {} has unknown unavailable day {}", id, code);
+                        Error::DatabaseQueryFailed
+                    })
+                })
+                .collect::<Result<_, Error>>()?;
+            let unavailable_sessions = unavailable_sessions
+                .iter()
+                .map(|code| {
+                    ExamSession::from_code(code).ok_or_else(|| {
+                        log::error!("Teacher {} has unknown unavailable session {}", id, code);
+                        Error::DatabaseQueryFailed
+                    })
+                })
+                .collect::<Result<_, Error>>()?;
+            let max_duties_per_day = match max_duties_per_day {
+                Some(limit) => Some((
+                    limit.max(1) as u32,
+                    stored_strength(id, max_duties_strength.as_deref().unwrap_or_default())?,
+                )),
+                None => None,
+            };
+            let no_back_to_back = no_back_to_back
+                .as_deref()
+                .map(|code| stored_strength(id, code))
+                .transpose()?;
+            let preferred_sessions = preferred_sessions
+                .iter()
+                .map(|code| {
+                    ExamSession::from_code(code).ok_or_else(|| {
+                        log::error!("Teacher {} has unknown preferred session {}", id, code);
+                        Error::DatabaseQueryFailed
+                    })
+                })
+                .collect::<Result<_, Error>>()?;
+            Ok(TeacherRecord {
+                id,
+                teacher: Teacher::new(
+                    TeacherPersonalInfo::new(name, gender),
+                    stored_subjects(catalogue, &subjects)?,
+                ),
+                classes,
+                unavailable_days,
+                unavailable_sessions,
+                leave: leave_from.into_iter().zip(leave_to).collect(),
+                max_duties_per_day,
+                no_back_to_back,
+                preferred_sessions,
+                user_id,
+                username,
+                is_archived,
+            })
+        }
+
+        /// Ordered by surname.
+        pub async fn load_teacher_records(
+            pool: &PgPool,
+            include_archived: bool,
+        ) -> Result<Vec<TeacherRecord>, Error> {
+            let rows = sqlx::query_as::<_, TeacherRow>(&format!(
+                "{} WHERE $1 OR NOT t.is_archived ORDER BY t.last_name, t.first_name",
+                TEACHER_SELECT
+            ))
+            .bind(include_archived)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to retrieve teachers: {}", e);
+                Error::DatabaseQueryFailed
+            })?;
+
+            let catalogue = load_catalogue(pool).await?;
+            rows.into_iter()
+                .map(|row| record_from_row(&catalogue, row))
+                .collect()
+        }
+
+        /// The current teacher linked to `user_id`.
+        pub async fn load_teacher_for_user(
+            pool: &PgPool,
+            user_id: i32,
+        ) -> Result<Option<TeacherRecord>, Error> {
+            let row = sqlx::query_as::<_, TeacherRow>(&format!(
+                "{} WHERE t.user_id = $1 AND NOT t.is_archived",
+                TEACHER_SELECT
+            ))
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to retrieve teacher for user {}: {}", user_id, e);
+                Error::DatabaseQueryFailed
+            })?;
+
+            let Some(row) = row else {
+                return Ok(None);
+            };
+            let catalogue = load_catalogue(pool).await?;
+            record_from_row(&catalogue, row).map(Some)
+        }
+
+        /// The teacher, archived or not, linked to `user_id`.
+        pub async fn teacher_linked_to(pool: &PgPool, user_id: i32) -> Result<Option<i32>, Error> {
+            sqlx::query_scalar::<_, i32>("SELECT id FROM teachers WHERE user_id = $1")
+                .bind(user_id)
+                .fetch_optional(pool)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to check teacher for user {}: {}", user_id, e);
+                    Error::DatabaseQueryFailed
+                })
+        }
+
+        pub async fn load_login_users(pool: &PgPool) -> Result<Vec<StaffOption>, Error> {
+            let rows = sqlx::query_as::<_, (i32, String, String, String)>(
+                "SELECT id, first_name, last_name, username
+                FROM users
+                WHERE is_active
+                ORDER BY last_name, first_name",
+            )
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to retrieve users: {}", e);
+                Error::DatabaseQueryFailed
+            })?;
+
+            Ok(rows
+                .into_iter()
+                .map(|(user_id, first_name, last_name, username)| StaffOption {
+                    user_id,
+                    name: format!("{} {} ({})", first_name, last_name, username),
+                })
+                .collect())
+        }
+
+        /// Inserts the teacher in `record`, or updates the teacher with `id`,
+        /// along with their subjects and classes.
+        pub async fn save_teacher(
+            pool: &PgPool,
+            id: Option<i32>,
+            record: &TeacherRecord,
+        ) -> Result<(), Error> {
+            let mut tx = pool.begin().await.map_err(|e| {
+                log::error!("Failed to begin transaction: {}", e);
+                Error::TransactionFailed
+            })?;
+
+            let info = record.teacher.personal_info();
+            let query = match id {
+                Some(_) => {
+                    "UPDATE teachers
+                    SET first_name = $2, other_names = $3, last_name = $4, gender = $5,
+                        unavailable_days = $6, unavailable_sessions = $7, user_id = $8,
+                        max_duties_per_day = $9, max_duties_strength = $10, no_back_to_back = $11,
+                        preferred_sessions = $12
+                    WHERE id = $1
+                    RETURNING id"
+                }
+                None => {
+                    "INSERT INTO teachers (first_name, other_names, last_name, gender,
+                        unavailable_days, unavailable_sessions, user_id,
+                        max_duties_per_day, max_duties_strength, no_back_to_back, preferred_sessions)
+                    VALUES ($2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+                    RETURNING id"
+                }
+            };
+            let teacher_id: i32 = sqlx::query_scalar(query)
+                .bind(id)
+                .bind(info.name().first_name())
+                .bind(info.name().other_names())
+                .bind(info.name().last_name())
+                .bind(info.gender().as_str())
+                .bind(record.unavailable_days.iter().map(|day| day.as_str()).collect::<Vec<_>>())
+                .bind(
+                    record
+                        .unavailable_sessions
+                        .iter()
+                        .map(|session| session.as_str())
+                        .collect::<Vec<_>>(),
+                )
+                .bind(record.user_id)
+                .bind(record.max_duties_per_day.map(|(limit, _)| limit as i32))
+                .bind(record.max_duties_per_day.map(|(_, strength)| strength.code()))
+                .bind(record.no_back_to_back.map(|strength| strength.code()))
+                .bind(
+                    record
+                        .preferred_sessions
+                        .iter()
+                        .map(|session| session.as_str())
+                        .collect::<Vec<_>>(),
+                )
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to save teacher {}: {}", info.name().last_name(), e);
+                    Error::DatabaseQueryFailed
+                })?;
+
+            for query in [
+                "DELETE FROM teacher_subjects WHERE teacher_id = $1",
+                "DELETE FROM teacher_classes WHERE teacher_id = $1",
+                "DELETE FROM teacher_leave WHERE teacher_id = $1",
+            ] {
+                sqlx::query(query)
+                    .bind(teacher_id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| {
+                        log::error!("Failed to clear teaching of teacher {}: {}", teacher_id, e);
+                        Error::DatabaseQueryFailed
+                    })?;
+            }
+            for subject in record.teacher.subjects_taught() {
+                sqlx::query("INSERT INTO teacher_subjects (teacher_id, subject_code) VALUES ($1, $2)")
+                    .bind(teacher_id)
+                    .bind(subject.code())
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| {
+                        log::error!("Failed to save subject {} of teacher {}: {}", subject, teacher_id, e);
+                        Error::DatabaseQueryFailed
+                    })?;
+            }
+            for (from, to) in &record.leave {
+                sqlx::query("INSERT INTO teacher_leave (teacher_id, from_date, to_date) VALUES ($1, $2, $3)")
+                    .bind(teacher_id)
+                    .bind(from)
+                    .bind(to)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| {
+                        log::error!("Failed to save leave of teacher {}: {}", teacher_id, e);
+                        Error::DatabaseQueryFailed
+                    })?;
+            }
+            for class in &record.classes {
+                sqlx::query("INSERT INTO teacher_classes (teacher_id, class_code) VALUES ($1, $2)")
+                    .bind(teacher_id)
+                    .bind(class.code())
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| {
+                        log::error!("Failed to save class {} of teacher {}: {}", class, teacher_id, e);
+                        Error::DatabaseQueryFailed
+                    })?;
+            }
+
+            tx.commit().await.map_err(|e| {
+                log::error!("Failed to commit transaction: {}", e);
+                Error::TransactionFailed
+            })?;
+
+            Ok(())
+        }
+
+        pub async fn set_archived(pool: &PgPool, id: i32, is_archived: bool) -> Result<(), Error> {
+            sqlx::query("UPDATE teachers SET is_archived = $2 WHERE id = $1")
+                .bind(id)
+                .bind(is_archived)
+                .execute(pool)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to archive teacher {}: {}", id, e);
+                    Error::DatabaseQueryFailed
+                })?;
+
+            Ok(())
+        }
+
+        /// What the teacher linked to `user_id` teaches: each subject they
+        /// teach that is offered in each of their classes, the assessments
+        /// set for those with how many marks are in, and their duties.
+        pub async fn load_my_teaching(
+            pool: &PgPool,
+            user_id: i32,
+        ) -> Result<Option<MyTeaching>, Error> {
+            let Some(teacher) = load_teacher_for_user(pool, user_id).await? else {
+                return Ok(None);
+            };
+            let teaches = |class: StudentClass, subject: Subject| {
+                teacher.classes.contains(&class) && teacher.teacher.subjects_taught().contains(&subject)
+            };
+
+            let mut classes = Vec::new();
+            for class in &teacher.classes {
+                for subject in teacher.teacher.subjects_taught() {
+                    if subject.programme_code() != class.programme_code() {
+                        continue;
+                    }
+                    let students =
+                        crate::students::db::server::load_class_list(pool, *class, Some(*subject))
+                            .await?;
+                    classes.push(TaughtClass {
+                        class: *class,
+                        subject: *subject,
+                        students: students.len(),
+                    });
+                }
+            }
+
+            let mut assessments = Vec::new();
+            for record in crate::marks::db::server::load_assessments(pool).await? {
+                if !teaches(record.assessment.class(), record.assessment.subject()) {
+                    continue;
+                }
+                let sheet = crate::marks::db::server::load_mark_sheet(pool, record.id).await?;
+                let entered = sheet
+                    .marks
+                    .iter()
+                    .filter(|mark| {
+                        mark.raw_score().is_some()
+                            && sheet
+                                .candidates
+                                .iter()
+                                .any(|candidate| candidate.candidate_number == mark.candidate_number())
+                    })
+                    .count();
+                assessments.push(AssessmentProgress {
+                    id: record.id,
+                    expected: sheet.candidates.len() * sheet.assessment.components().len(),
+                    assessment: sheet.assessment,
+                    entered,
+                });
+            }
+
+            let duties = crate::invigilation::db::server::load_duties(pool, false)
+                .await?
+                .into_iter()
+                .filter(|duty| duty.user_id == user_id)
+                .collect();
+
+            Ok(Some(MyTeaching {
+                teacher,
+                classes,
+                assessments,
+                duties,
+            }))
+        }
+    }
+}
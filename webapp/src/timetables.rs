@@ -0,0 +1,1319 @@
+use chrono::NaiveDate;
+use leptos::prelude::*;
+use leptos::server_fn::codec::Json;
+use shared::domain::{ExamDay, ExamSession};
+use shared::export::{CSV_HEADERS, ExamScope, Timetable};
+use shared::timetable::Clash;
+use shared::validation::{Diagnostic, Severity};
+
+use crate::app::{ActionError, ErrorDisplay, LoadingSpinner};
+use crate::catalogue::{ClassOptions, get_catalogue};
+use crate::invigilation::db::StaffOption;
+use crate::teachers::code_checkboxes;
+use crate::timetables::db::DraftReview;
+
+/// Roles that can generate and publish the exam timetable.
+pub const SCHEDULER_ROLES: &[&str] = &["admin", "exams_officer"];
+
+/// The published exam timetable for the whole school, one class, or one
+/// student by candidate number.
+#[server(GetExamTimetable, "/api/timetables")]
+pub async fn get_exam_timetable(
+    class_code: String,
+    candidate_number: String,
+) -> Result<Timetable, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(crate::marks::MARKS_ROLES).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        server::exam_timetable(&pool, &class_code, &candidate_number)
+            .await
+            .map_err(ServerFnError::ServerError)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+/// Published invigilation duties for all staff, or for one user when
+/// `user_id` is given.
+#[server(GetInvigilationTimetable, "/api/timetables")]
+pub async fn get_invigilation_timetable(
+    user_id: Option<i32>,
+) -> Result<Timetable, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(crate::marks::MARKS_ROLES).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        server::invigilation_timetable(&pool, user_id)
+            .await
+            .map_err(ServerFnError::ServerError)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+#[server(GetTimetableStaff, "/api/timetables")]
+pub async fn get_timetable_staff() -> Result<Vec<StaffOption>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(crate::marks::MARKS_ROLES).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        crate::invigilation::db::server::load_staff(&pool)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+/// Schedules every current student's papers into the sessions on `days`
+/// from `start_date` to `end_date`, replacing the draft timetable. Returns
+/// the papers that could not be placed free of clashes.
+#[server(name = GenerateTimetable, prefix = "/api/timetables", input = Json)]
+pub async fn generate_timetable(
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    days: Vec<String>,
+    sessions: Vec<String>,
+) -> Result<Vec<Clash>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use leptos::server_fn::error::NoCustomError;
+        use shared::timetable::ExamWindow;
+
+        crate::app::db::server::require_role(SCHEDULER_ROLES).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        if end_date < start_date {
+            return Err(ServerFnError::<NoCustomError>::ServerError(
+                "The exam window ends before it starts".into(),
+            ));
+        }
+        let days = days
+            .iter()
+            .map(|day| {
+                ExamDay::from_code(day).ok_or_else(|| {
+                    ServerFnError::<NoCustomError>::ServerError(format!("Unknown exam day: {}", day))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let sessions = sessions
+            .iter()
+            .map(|session| {
+                ExamSession::from_code(session).ok_or_else(|| {
+                    ServerFnError::<NoCustomError>::ServerError(format!("Unknown session: {}", session))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let window = ExamWindow::from_range(start_date, end_date, &days, sessions);
+
+        let students: Vec<_> =
+            crate::students::db::server::load_student_records(&pool, None, false)
+                .await
+                .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?
+                .into_iter()
+                .map(|record| record.student)
+                .collect();
+        let catalogue = crate::catalogue::db::server::load_catalogue(&pool)
+            .await
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+
+        let report = shared::timetable::generate(&students, &window, &catalogue)
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+        db::server::save_draft(&pool, &window, report.timetables())
+            .await
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?;
+
+        Ok(report.clashes().to_vec())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+/// The draft timetable, if one has been generated, checked against the
+/// current students and the window it was generated over.
+#[server(GetDraftTimetable, "/api/timetables")]
+pub async fn get_draft_timetable(
+    max_papers_per_day: u32,
+) -> Result<Option<DraftReview>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use leptos::server_fn::error::NoCustomError;
+
+        crate::app::db::server::require_role(SCHEDULER_ROLES).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        let Some(draft) = db::server::load_draft(&pool)
+            .await
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?
+        else {
+            return Ok(None);
+        };
+        let students: Vec<_> =
+            crate::students::db::server::load_student_records(&pool, None, false)
+                .await
+                .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?
+                .into_iter()
+                .map(|record| record.student)
+                .collect();
+        let diagnostics = shared::validation::validate(
+            &draft.timetables,
+            &students,
+            &draft.window,
+            max_papers_per_day as usize,
+        );
+
+        Ok(Some(DraftReview { draft, diagnostics }))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+/// Replaces the published timetable with the draft. Refused while the
+/// draft has errors against the current students.
+#[server(PublishTimetable, "/api/timetables")]
+pub async fn publish_timetable() -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use leptos::server_fn::error::NoCustomError;
+        use shared::validation::validate;
+
+        crate::app::db::server::require_role(SCHEDULER_ROLES).await?;
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        let draft = db::server::load_draft(&pool)
+            .await
+            .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?
+            .ok_or_else(|| {
+                ServerFnError::<NoCustomError>::ServerError("Generate a draft timetable first".into())
+            })?;
+        let students: Vec<_> =
+            crate::students::db::server::load_student_records(&pool, None, false)
+                .await
+                .map_err(|e| ServerFnError::<NoCustomError>::ServerError(e.to_string()))?
+                .into_iter()
+                .map(|record| record.student)
+                .collect();
+        let errors = validate(&draft.timetables, &students, &draft.window, usize::MAX)
+            .iter()
+            .filter(|diagnostic| diagnostic.severity() == Severity::Error)
+            .count();
+        if errors > 0 {
+            return Err(ServerFnError::ServerError(format!(
+                "The draft has {} errors. Regenerate it before publishing.",
+                errors
+            )));
+        }
+
+        db::server::publish_timetable(&pool, &draft.timetables)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+/// Link to one of the download routes registered in `main`.
+fn download_url(file: &str, params: &[(&str, String)]) -> String {
+    let encode = |value: &str| {
+        value
+            .bytes()
+            .map(|byte| match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                    (byte as char).to_string()
+                }
+                _ => format!("%{:02X}", byte),
+            })
+            .collect::<String>()
+    };
+    let query = params
+        .iter()
+        .filter(|(_, value)| !value.is_empty())
+        .map(|(name, value)| format!("{}={}", name, encode(value)))
+        .collect::<Vec<_>>()
+        .join("&");
+    format!("/api/timetables/{}?{}", file, query)
+}
+
+#[component]
+pub fn TimetablesView() -> impl IntoView {
+    let title = use_context::<RwSignal<String>>()
+        .expect("title context should be provided");
+
+    title.set("Timetables".to_string());
+
+    let publish = ServerAction::<PublishTimetable>::new();
+
+    view! {
+        <div class="p-4 space-y-6">
+            <DraftTimetableSection publish=publish />
+            <ExamTimetableSection publish=publish />
+            <InvigilationTimetableSection />
+        </div>
+    }
+}
+
+#[component]
+fn DraftTimetableSection(publish: ServerAction<PublishTimetable>) -> impl IntoView {
+    let start_date = RwSignal::new(String::new());
+    let end_date = RwSignal::new(String::new());
+    let days = RwSignal::new(
+        ExamDay::ALL
+            .iter()
+            .map(|day| day.as_str().to_string())
+            .collect::<Vec<_>>(),
+    );
+    let sessions = RwSignal::new(vec![
+        ExamSession::Morning.as_str().to_string(),
+        ExamSession::Afternoon.as_str().to_string(),
+    ]);
+    let max_papers_per_day = RwSignal::new(2u32);
+    let generate = ServerAction::<GenerateTimetable>::new();
+    let draft = Resource::new(
+        move || (generate.version().get(), max_papers_per_day.get()),
+        |(_, max_papers_per_day)| async move { get_draft_timetable(max_papers_per_day).await },
+    );
+
+    let submit = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        let (Ok(start_date), Ok(end_date)) = (
+            start_date.get_untracked().parse(),
+            end_date.get_untracked().parse(),
+        ) else {
+            return;
+        };
+        generate.dispatch(GenerateTimetable {
+            start_date,
+            end_date,
+            days: days.get_untracked(),
+            sessions: sessions.get_untracked(),
+        });
+    };
+
+    view! {
+        <section class="bg-white rounded-lg shadow p-4 space-y-4">
+            <h2 class="text-lg font-semibold">"Draft Timetable"</h2>
+            <form class="space-y-2" on:submit=submit>
+                <div class="flex gap-2 items-center">
+                    <input
+                        type="date"
+                        required
+                        class="px-3 py-2 border rounded"
+                        prop:value=move || start_date.get()
+                        on:change=move |ev| start_date.set(event_target_value(&ev))
+                    />
+                    <span>"to"</span>
+                    <input
+                        type="date"
+                        required
+                        class="px-3 py-2 border rounded"
+                        prop:value=move || end_date.get()
+                        on:change=move |ev| end_date.set(event_target_value(&ev))
+                    />
+                    <button
+                        type="submit"
+                        class="bg-gray-700 text-white px-4 py-2 rounded"
+                        disabled=move || generate.pending().get()
+                    >
+                        "Generate Draft"
+                    </button>
+                </div>
+                <div class="flex gap-4 flex-wrap text-sm">
+                    {code_checkboxes(
+                        ExamDay::ALL.iter().map(|day| (day.as_str().to_string(), day.as_str().to_string())).collect(),
+                        days,
+                    )}
+                    {code_checkboxes(
+                        ExamSession::ALL.iter().map(|session| (session.as_str().to_string(), format!("{} sessions", session))).collect(),
+                        sessions,
+                    )}
+                </div>
+            </form>
+            <ActionError result=generate.value() />
+            {move || generate.value().get().and_then(Result::ok).map(|clashes| view! { <ClashList clashes=clashes /> })}
+            <Suspense fallback=move || view! { <LoadingSpinner/> }>
+                {move || draft.get().map(|result| match result {
+                    Ok(Some(DraftReview { draft, diagnostics })) => view! {
+                        <label class="text-sm block">
+                            "Most papers a student should sit in one day "
+                            <input
+                                type="number"
+                                min="1"
+                                class="ml-2 px-2 py-1 border rounded w-16"
+                                prop:value=move || max_papers_per_day.get().to_string()
+                                on:change=move |ev| {
+                                    if let Ok(limit) = event_target_value(&ev).parse::<u32>() {
+                                        max_papers_per_day.set(limit.max(1));
+                                    }
+                                }
+                            />
+                        </label>
+                        <DiagnosticList diagnostics=diagnostics />
+                        <button
+                            class="bg-green-700 text-white px-4 py-2 rounded"
+                            disabled=move || publish.pending().get()
+                            on:click=move |_| {
+                                publish.dispatch(PublishTimetable {});
+                            }
+                        >
+                            "Publish Timetable"
+                        </button>
+                        <ActionError result=publish.value() />
+                        <p class="text-sm text-gray-500">
+                            {format!(
+                                "Generated {} over {} days",
+                                draft.generated_at.format("%d %B %Y %H:%M"),
+                                draft.window.dates().len(),
+                            )}
+                        </p>
+                        <TimetableTable timetable=Timetable::exams(
+                            "Draft exam timetable".to_string(),
+                            &draft.timetables,
+                            &ExamScope::School,
+                        ) />
+                    }.into_any(),
+                    Ok(None) => view! {
+                        <p class="text-gray-500">"No draft has been generated yet."</p>
+                    }.into_any(),
+                    Err(e) => view! { <ErrorDisplay error=e.to_string() /> }.into_any(),
+                })}
+            </Suspense>
+        </section>
+    }
+}
+
+#[component]
+fn DiagnosticList(diagnostics: Vec<Diagnostic>) -> impl IntoView {
+    if diagnostics.is_empty() {
+        return view! {
+            <p class="text-sm text-green-700">"The draft passes every check."</p>
+        }
+        .into_any();
+    }
+
+    let errors = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.severity() == Severity::Error)
+        .count();
+    view! {
+        <p class="text-sm font-medium">
+            {format!("{} errors, {} warnings", errors, diagnostics.len() - errors)}
+        </p>
+        <ul class="text-sm list-disc pl-5 max-h-64 overflow-y-auto">
+            {diagnostics.into_iter().map(|diagnostic| {
+                let class = match diagnostic.severity() {
+                    Severity::Error => "text-red-700",
+                    Severity::Warning => "text-yellow-700",
+                };
+                view! { <li class=class>{diagnostic.to_string()}</li> }
+            }).collect_view()}
+        </ul>
+    }
+    .into_any()
+}
+
+#[component]
+fn ClashList(clashes: Vec<Clash>) -> impl IntoView {
+    if clashes.is_empty() {
+        return view! {
+            <p class="text-sm text-green-700">"Every paper was placed without a clash."</p>
+        }
+        .into_any();
+    }
+
+    view! {
+        <ul class="text-sm text-red-700 list-disc pl-5">
+            {clashes.into_iter().map(|clash| view! {
+                <li>
+                    {format!(
+                        "{} {} on {} clashes with {} for {} students",
+                        clash.paper().subject(),
+                        clash.paper().component().name(),
+                        clash.exam_date(),
+                        clash
+                            .clashes_with()
+                            .iter()
+                            .map(|paper| format!("{} {}", paper.subject(), paper.component().name()))
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                        clash.students().len(),
+                    )}
+                </li>
+            }).collect_view()}
+        </ul>
+    }
+    .into_any()
+}
+
+#[component]
+fn ExamTimetableSection(publish: ServerAction<PublishTimetable>) -> impl IntoView {
+    let class_code = RwSignal::new(String::new());
+    let candidate_number = RwSignal::new(String::new());
+    let catalogue = Resource::new(|| (), |_| async move { get_catalogue().await });
+
+    let timetable = Resource::new(
+        move || (class_code.get(), candidate_number.get(), publish.version().get()),
+        |(class_code, candidate_number, _)| async move {
+            get_exam_timetable(class_code, candidate_number).await
+        },
+    );
+    let params = move || {
+        vec![
+            ("class", class_code.get()),
+            ("candidate", candidate_number.get()),
+        ]
+    };
+
+    view! {
+        <section class="bg-white rounded-lg shadow p-4 space-y-4">
+            <h2 class="text-lg font-semibold">"Exam Timetable"</h2>
+            <div class="flex gap-2 items-center">
+                <select
+                    class="px-3 py-2 border rounded"
+                    prop:value=move || class_code.get()
+                    on:change=move |ev| {
+                        class_code.set(event_target_value(&ev));
+                        candidate_number.set(String::new());
+                    }
+                >
+                    <option value="">"Whole school"</option>
+                    <ClassOptions catalogue=catalogue />
+                </select>
+                <input
+                    type="text"
+                    placeholder="Candidate number"
+                    class="px-3 py-2 border rounded"
+                    prop:value=move || candidate_number.get()
+                    on:change=move |ev| candidate_number.set(event_target_value(&ev).trim().to_string())
+                />
+                <DownloadLinks file="exams" params=Signal::derive(params) />
+            </div>
+            <Suspense fallback=move || view! { <LoadingSpinner/> }>
+                {move || timetable.get().map(|result| match result {
+                    Ok(timetable) => view! { <TimetableTable timetable=timetable /> }.into_any(),
+                    Err(e) => view! { <ErrorDisplay error=e.to_string() /> }.into_any(),
+                })}
+            </Suspense>
+        </section>
+    }
+}
+
+#[component]
+fn InvigilationTimetableSection() -> impl IntoView {
+    let user_id = RwSignal::new(String::new());
+
+    let staff = Resource::new(|| (), |_| async move { get_timetable_staff().await });
+    let timetable = Resource::new(
+        move || user_id.get(),
+        |user_id| async move { get_invigilation_timetable(user_id.parse().ok()).await },
+    );
+    let params = move || vec![("user_id", user_id.get())];
+
+    view! {
+        <section class="bg-white rounded-lg shadow p-4 space-y-4">
+            <h2 class="text-lg font-semibold">"Invigilation Timetable"</h2>
+            <div class="flex gap-2 items-center">
+                <select
+                    class="px-3 py-2 border rounded"
+                    prop:value=move || user_id.get()
+                    on:change=move |ev| user_id.set(event_target_value(&ev))
+                >
+                    <option value="">"All staff"</option>
+                    <Suspense fallback=|| ()>
+                        {move || staff.get().and_then(Result::ok).map(|staff| {
+                            staff.into_iter().map(|member| view! {
+                                <option value=member.user_id.to_string()>{member.name}</option>
+                            }).collect_view()
+                        })}
+                    </Suspense>
+                </select>
+                <DownloadLinks file="invigilation" params=Signal::derive(params) />
+            </div>
+            <Suspense fallback=move || view! { <LoadingSpinner/> }>
+                {move || timetable.get().map(|result| match result {
+                    Ok(timetable) => view! { <TimetableTable timetable=timetable /> }.into_any(),
+                    Err(e) => view! { <ErrorDisplay error=e.to_string() /> }.into_any(),
+                })}
+            </Suspense>
+        </section>
+    }
+}
+
+#[component]
+fn DownloadLinks(file: &'static str, params: Signal<Vec<(&'static str, String)>>) -> impl IntoView {
+    view! {
+        {[("pdf", "PDF"), ("csv", "CSV"), ("ics", "Calendar (.ics)")].into_iter().map(|(extension, label)| {
+            view! {
+                <a
+                    class="bg-gray-700 text-white px-4 py-2 rounded"
+                    href=move || download_url(&format!("{}.{}", file, extension), &params.get())
+                >
+                    {label}
+                </a>
+            }
+        }).collect_view()}
+    }
+}
+
+#[component]
+fn TimetableTable(timetable: Timetable) -> impl IntoView {
+    if timetable.entries().is_empty() {
+        return view! {
+            <p class="text-gray-500">"Nothing has been published for this timetable yet."</p>
+        }
+        .into_any();
+    }
+
+    view! {
+        <table class="min-w-full divide-y divide-gray-200">
+            <thead class="bg-gray-50">
+                <tr>
+                    {CSV_HEADERS.iter().map(|header| view! {
+                        <th class="px-4 py-2 text-left text-xs font-medium text-gray-500 uppercase">{*header}</th>
+                    }).collect_view()}
+                </tr>
+            </thead>
+            <tbody class="divide-y divide-gray-200">
+                {timetable.entries().iter().map(|entry| view! {
+                    <tr class="hover:bg-gray-50">
+                        {entry.cells().into_iter().map(|cell| view! {
+                            <td class="px-4 py-2 text-sm">{cell}</td>
+                        }).collect_view()}
+                    </tr>
+                }).collect_view()}
+            </tbody>
+        </table>
+    }
+    .into_any()
+}
+
+/// Timetable lookups shared by the server functions and the download
+/// handlers, which are registered as plain actix routes because they
+/// return files.
+#[cfg(feature = "ssr")]
+pub mod server {
+    use std::sync::Arc;
+
+    use actix_session::Session;
+    use actix_web::{HttpResponse, web};
+    use leptos::config::LeptosOptions;
+    use printpdf::image_crate::DynamicImage;
+    use printpdf::{
+        BuiltinFont, Image, ImageTransform, IndirectFontRef, Line, Mm, PdfDocument,
+        PdfLayerReference, Point,
+    };
+    use serde::Deserialize;
+    use shared::domain::{ExamDate, ExamSession};
+    use shared::export::{ExamScope, Timetable, TimetableEntry};
+    use sqlx::PgPool;
+
+    use crate::app::db::Error;
+    use crate::reports::SCHOOL_NAME;
+    use crate::reports::server::{file_response, load_logo, wrap};
+
+    const PAGE_WIDTH: f32 = 297.0;
+    const PAGE_HEIGHT: f32 = 210.0;
+    const MARGIN: f32 = 12.0;
+
+    #[derive(Deserialize)]
+    pub struct TimetableQuery {
+        class: Option<String>,
+        candidate: Option<String>,
+        user_id: Option<i32>,
+    }
+
+    /// Empty `class_code` and `candidate_number` mean the whole school. A
+    /// candidate number takes precedence over a class.
+    pub async fn exam_timetable(
+        pool: &PgPool,
+        class_code: &str,
+        candidate_number: &str,
+    ) -> Result<Timetable, String> {
+        let (name, scope) = if !candidate_number.trim().is_empty() {
+            let student = crate::students::db::server::load_student(pool, candidate_number)
+                .await
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("No current student has candidate number {}", candidate_number))?;
+            let name = student.personal_info().name();
+            (
+                format!(
+                    "Exam timetable {} {} {}",
+                    student.candidate_number(),
+                    name.first_name(),
+                    name.last_name()
+                ),
+                ExamScope::Student(student),
+            )
+        } else if !class_code.is_empty() {
+            let catalogue = crate::catalogue::db::server::load_catalogue(pool)
+                .await
+                .map_err(|e| e.to_string())?;
+            let class = catalogue.class_for(class_code).map_err(|e| e.to_string())?;
+            (format!("Exam timetable {}", class.code()), ExamScope::Class(class))
+        } else {
+            ("Exam timetable".to_string(), ExamScope::School)
+        };
+
+        let timetables = super::db::server::load_timetable(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(Timetable::exams(name, &timetables, &scope))
+    }
+
+    pub async fn invigilation_timetable(
+        pool: &PgPool,
+        user_id: Option<i32>,
+    ) -> Result<Timetable, String> {
+        let duties: Vec<_> = crate::invigilation::db::server::load_duties(pool, false)
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .filter(|duty| user_id.is_none_or(|user_id| duty.user_id == user_id))
+            .collect();
+
+        let name = match (user_id, duties.first()) {
+            (Some(_), Some(duty)) => format!("Invigilation timetable {}", duty.invigilator),
+            _ => "Invigilation timetable".to_string(),
+        };
+        let entries = duties
+            .iter()
+            .map(|duty| {
+                let session = ExamSession::from_code(&duty.session)
+                    .ok_or_else(|| format!("Unknown session: {}", duty.session))?;
+                Ok(TimetableEntry::duty(
+                    ExamDate::new(duty.exam_date, session),
+                    &duty.room_code,
+                    &duty.invigilator,
+                    duty.minutes.max(0) as u32,
+                ))
+            })
+            .collect::<Result<_, String>>()?;
+
+        Ok(Timetable::new(name, entries))
+    }
+
+    /// `/api/timetables/exams.{format}`
+    pub async fn exam_timetable_file(
+        pool: web::Data<Arc<PgPool>>,
+        options: web::Data<LeptosOptions>,
+        session: Session,
+        format: web::Path<String>,
+        query: web::Query<TimetableQuery>,
+    ) -> HttpResponse {
+        if let Err(response) = authorise(&pool, &session).await {
+            return response;
+        }
+        let timetable = exam_timetable(
+            &pool,
+            query.class.as_deref().unwrap_or_default(),
+            query.candidate.as_deref().unwrap_or_default(),
+        )
+        .await;
+        respond(timetable, &format, &options)
+    }
+
+    /// `/api/timetables/invigilation.{format}`
+    pub async fn invigilation_timetable_file(
+        pool: web::Data<Arc<PgPool>>,
+        options: web::Data<LeptosOptions>,
+        session: Session,
+        format: web::Path<String>,
+        query: web::Query<TimetableQuery>,
+    ) -> HttpResponse {
+        if let Err(response) = authorise(&pool, &session).await {
+            return response;
+        }
+        respond(invigilation_timetable(&pool, query.user_id).await, &format, &options)
+    }
+
+    async fn authorise(pool: &PgPool, session: &Session) -> Result<(), HttpResponse> {
+        crate::app::db::server::require_session_role(pool, session, crate::marks::MARKS_ROLES)
+            .await
+            .map(|_| ())
+            .map_err(|e| match e {
+                Error::NotAuthorized => HttpResponse::Forbidden().body(e.to_string()),
+                _ => HttpResponse::Unauthorized().body(e.to_string()),
+            })
+    }
+
+    fn respond(
+        timetable: Result<Timetable, String>,
+        format: &str,
+        options: &LeptosOptions,
+    ) -> HttpResponse {
+        let timetable = match timetable {
+            Ok(timetable) => timetable,
+            Err(e) => return HttpResponse::BadRequest().body(e),
+        };
+        match format {
+            "csv" => file_response(
+                "text/csv; charset=utf-8",
+                &timetable.file_name("csv"),
+                timetable.to_csv().into_bytes(),
+            ),
+            "ics" => file_response(
+                "text/calendar; charset=utf-8",
+                &timetable.file_name("ics"),
+                timetable.to_ics(chrono::Utc::now().naive_utc()).into_bytes(),
+            ),
+            "pdf" => match render(&timetable, load_logo(options).as_ref()) {
+                Ok(pdf) => file_response("application/pdf", &timetable.file_name("pdf"), pdf),
+                Err(e) => {
+                    log::error!("Failed to render timetable: {}", e);
+                    HttpResponse::InternalServerError().body("Failed to render timetable")
+                }
+            },
+            _ => HttpResponse::NotFound().body(format!("Unknown format: {}", format)),
+        }
+    }
+
+    /// Column left edges and the characters that fit in each, in landscape.
+    const COLUMNS: [(f32, usize); 6] = [
+        (MARGIN, 24),
+        (57.0, 10),
+        (75.0, 14),
+        (100.0, 48),
+        (185.0, 40),
+        (260.0, 12),
+    ];
+    const HEADINGS: [&str; 6] = ["Date", "Session", "Time", "Paper or duty", "Details", "Room"];
+    const LINE_HEIGHT: f32 = 4.5;
+
+    /// Renders the timetable as a landscape A4 table, repeating the
+    /// heading on every page.
+    pub fn render(timetable: &Timetable, logo: Option<&DynamicImage>) -> Result<Vec<u8>, String> {
+        let (document, first_page, first_layer) =
+            PdfDocument::new(timetable.name(), Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Timetable");
+        let regular = document
+            .add_builtin_font(BuiltinFont::Helvetica)
+            .map_err(|e| e.to_string())?;
+        let bold = document
+            .add_builtin_font(BuiltinFont::HelveticaBold)
+            .map_err(|e| e.to_string())?;
+
+        let mut layer = document.get_page(first_page).get_layer(first_layer);
+        let mut y = draw_heading(&layer, timetable, logo, &regular, &bold);
+        if timetable.entries().is_empty() {
+            layer.use_text("Nothing has been published yet.", 10.0, Mm(MARGIN), Mm(y), &regular);
+        }
+
+        for entry in timetable.entries() {
+            let cells = [
+                entry.exam_date().date().format("%a %-d %b %Y").to_string(),
+                entry.exam_date().session().to_string(),
+                format!("{}-{}", entry.start().format("%H:%M"), entry.end().format("%H:%M")),
+                entry.title().to_string(),
+                entry.details().to_string(),
+                entry.room().to_string(),
+            ];
+            let wrapped: Vec<Vec<String>> = cells
+                .iter()
+                .zip(COLUMNS)
+                .map(|(cell, (_, width))| wrap(cell, width))
+                .collect();
+            let lines = wrapped.iter().map(Vec::len).max().unwrap_or(1);
+            let height = lines as f32 * LINE_HEIGHT + 2.0;
+
+            if y - height < MARGIN {
+                let (page, page_layer) =
+                    document.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Timetable");
+                layer = document.get_page(page).get_layer(page_layer);
+                y = draw_heading(&layer, timetable, logo, &regular, &bold);
+            }
+
+            for (cell_lines, (x, _)) in wrapped.iter().zip(COLUMNS) {
+                for (index, line) in cell_lines.iter().enumerate() {
+                    layer.use_text(
+                        line.as_str(),
+                        9.0,
+                        Mm(x),
+                        Mm(y - index as f32 * LINE_HEIGHT),
+                        &regular,
+                    );
+                }
+            }
+            y -= height;
+            rule(&layer, y + LINE_HEIGHT - 0.5);
+        }
+
+        document.save_to_bytes().map_err(|e| e.to_string())
+    }
+
+    /// Draws the page heading and returns where the first row goes.
+    fn draw_heading(
+        layer: &PdfLayerReference,
+        timetable: &Timetable,
+        logo: Option<&DynamicImage>,
+        regular: &IndirectFontRef,
+        bold: &IndirectFontRef,
+    ) -> f32 {
+        let logo_size = 16.0;
+        let top = PAGE_HEIGHT - MARGIN;
+        if let Some(logo) = logo {
+            let dpi = 300.0;
+            let width = logo.width() as f32 / dpi * 25.4;
+            let height = logo.height() as f32 / dpi * 25.4;
+            let scale = (logo_size / width).min(logo_size / height);
+            Image::from_dynamic_image(logo).add_to_layer(
+                layer.clone(),
+                ImageTransform {
+                    translate_x: Some(Mm(MARGIN)),
+                    translate_y: Some(Mm(top - height * scale)),
+                    scale_x: Some(scale),
+                    scale_y: Some(scale),
+                    dpi: Some(dpi),
+                    ..Default::default()
+                },
+            );
+        }
+
+        let text_left = MARGIN + logo_size + 4.0;
+        layer.use_text(SCHOOL_NAME, 14.0, Mm(text_left), Mm(top - 6.0), bold);
+        layer.use_text(timetable.name(), 11.0, Mm(text_left), Mm(top - 12.0), regular);
+        layer.use_text(
+            format!("Printed {}", chrono::Local::now().format("%d %B %Y")),
+            8.0,
+            Mm(PAGE_WIDTH - MARGIN - 35.0),
+            Mm(top - 6.0),
+            regular,
+        );
+
+        let y = top - logo_size - 6.0;
+        for ((x, _), heading) in COLUMNS.iter().zip(HEADINGS) {
+            layer.use_text(heading, 9.0, Mm(*x), Mm(y), bold);
+        }
+        rule(layer, y - 2.0);
+        y - 7.0
+    }
+
+    fn rule(layer: &PdfLayerReference, y: f32) {
+        layer.add_line(Line {
+            points: vec![
+                (Point::new(Mm(MARGIN), Mm(y)), false),
+                (Point::new(Mm(PAGE_WIDTH - MARGIN), Mm(y)), false),
+            ],
+            is_closed: false,
+        });
+    }
+}
+
+pub mod db {
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Serialize};
+    use shared::domain::ExamTTObject;
+    use shared::timetable::ExamWindow;
+    use shared::validation::Diagnostic;
+
+    /// A generated timetable waiting to be checked and published.
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    pub struct DraftTimetable {
+        pub window: ExamWindow,
+        pub generated_at: DateTime<Utc>,
+        pub timetables: Vec<ExamTTObject>,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    pub struct DraftReview {
+        pub draft: DraftTimetable,
+        pub diagnostics: Vec<Diagnostic>,
+    }
+
+    #[cfg(feature = "ssr")]
+    pub mod server {
+        use std::collections::BTreeMap;
+
+        use super::DraftTimetable;
+        use crate::app::db::Error;
+        use crate::catalogue::db::server::{load_catalogue, stored_class, stored_subject};
+        use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+        use shared::domain::{
+            ExamDate, ExamPair, ExamPaper, ExamSession, ExamTTObject, PaperComponent,
+            StudentClass,
+        };
+        use shared::catalogue::Catalogue;
+        use shared::timetable::ExamWindow;
+        use sqlx::{PgConnection, PgPool};
+
+        pub async fn create_timetable_tables(pool: &PgPool) -> Result<(), Error> {
+            // The draft has the same columns as the published timetable so
+            // publishing is a straight copy.
+            let queries = [
+                "CREATE TABLE IF NOT EXISTS exam_timetable (
+                    id SERIAL PRIMARY KEY,
+                    class_code VARCHAR(10) NOT NULL,
+                    exam_date DATE NOT NULL,
+                    session VARCHAR(10) NOT NULL,
+                    subject_code VARCHAR(30) NOT NULL,
+                    component_code VARCHAR(20) NOT NULL,
+                    component_name VARCHAR(100) NOT NULL,
+                    start_time TIME NOT NULL,
+                    duration_minutes INTEGER NOT NULL
+                )",
+                "CREATE TABLE IF NOT EXISTS exam_timetable_draft (
+                    id SERIAL PRIMARY KEY,
+                    class_code VARCHAR(10) NOT NULL,
+                    exam_date DATE NOT NULL,
+                    session VARCHAR(10) NOT NULL,
+                    subject_code VARCHAR(30) NOT NULL,
+                    component_code VARCHAR(20) NOT NULL,
+                    component_name VARCHAR(100) NOT NULL,
+                    start_time TIME NOT NULL,
+                    duration_minutes INTEGER NOT NULL
+                )",
+                "CREATE TABLE IF NOT EXISTS exam_timetable_draft_window (
+                    id INTEGER PRIMARY KEY CHECK (id = 1),
+                    dates DATE[] NOT NULL,
+                    sessions VARCHAR(10)[] NOT NULL,
+                    generated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+                )",
+            ];
+
+            for query in queries {
+                sqlx::query(query).execute(pool).await.map_err(|e| {
+                    log::error!("Failed to create exam timetable tables: {}", e);
+                    Error::TableNotCreated
+                })?;
+            }
+
+            Ok(())
+        }
+
+        /// Replaces the published exam timetable with `timetables`.
+        pub async fn publish_timetable(
+            pool: &PgPool,
+            timetables: &[ExamTTObject],
+        ) -> Result<(), Error> {
+            let mut tx = pool.begin().await.map_err(|e| {
+                log::error!("Failed to begin transaction: {}", e);
+                Error::TransactionFailed
+            })?;
+
+            sqlx::query("DELETE FROM exam_timetable")
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to clear exam timetable: {}", e);
+                    Error::DatabaseQueryFailed
+                })?;
+            insert_papers(&mut tx, "exam_timetable", timetables).await?;
+
+            tx.commit().await.map_err(|e| {
+                log::error!("Failed to commit transaction: {}", e);
+                Error::TransactionFailed
+            })?;
+
+            Ok(())
+        }
+
+        /// Replaces the draft timetable with `timetables`, generated over
+        /// `window`.
+        pub async fn save_draft(
+            pool: &PgPool,
+            window: &ExamWindow,
+            timetables: &[ExamTTObject],
+        ) -> Result<(), Error> {
+            let mut tx = pool.begin().await.map_err(|e| {
+                log::error!("Failed to begin transaction: {}", e);
+                Error::TransactionFailed
+            })?;
+
+            for query in [
+                "DELETE FROM exam_timetable_draft",
+                "DELETE FROM exam_timetable_draft_window",
+            ] {
+                sqlx::query(query).execute(&mut *tx).await.map_err(|e| {
+                    log::error!("Failed to clear draft timetable: {}", e);
+                    Error::DatabaseQueryFailed
+                })?;
+            }
+
+            sqlx::query(
+                "INSERT INTO exam_timetable_draft_window (id, dates, sessions) VALUES (1, $1, $2)",
+            )
+            .bind(window.dates())
+            .bind(
+                window
+                    .sessions()
+                    .iter()
+                    .map(|session| session.as_str())
+                    .collect::<Vec<_>>(),
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to save draft exam window: {}", e);
+                Error::DatabaseQueryFailed
+            })?;
+            insert_papers(&mut tx, "exam_timetable_draft", timetables).await?;
+
+            tx.commit().await.map_err(|e| {
+                log::error!("Failed to commit transaction: {}", e);
+                Error::TransactionFailed
+            })?;
+
+            Ok(())
+        }
+
+        /// The draft timetable, if one has been generated.
+        pub async fn load_draft(pool: &PgPool) -> Result<Option<DraftTimetable>, Error> {
+            let window = sqlx::query_as::<_, (Vec<NaiveDate>, Vec<String>, DateTime<Utc>)>(
+                "SELECT dates, sessions, generated_at FROM exam_timetable_draft_window WHERE id = 1",
+            )
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to retrieve draft exam window: {}", e);
+                Error::DatabaseQueryFailed
+            })?;
+            let Some((dates, sessions, generated_at)) = window else {
+                return Ok(None);
+            };
+
+            let sessions = sessions
+                .iter()
+                .map(|session| {
+                    ExamSession::from_code(session).ok_or_else(|| {
+                        log::error!("Draft exam window has unknown session {}", session);
+                        Error::DatabaseQueryFailed
+                    })
+                })
+                .collect::<Result<_, _>>()?;
+
+            Ok(Some(DraftTimetable {
+                window: ExamWindow::new(dates, sessions),
+                generated_at,
+                timetables: load_papers(pool, "exam_timetable_draft").await?,
+            }))
+        }
+
+        /// One row of `exam_timetable` or its draft: class, date, session,
+        /// subject, component code and name, start time and duration.
+        type PaperRow = (String, NaiveDate, String, String, String, String, NaiveTime, i32);
+
+        /// Writes every paper in `timetables` to `table`, which has the
+        /// columns of `exam_timetable`.
+        async fn insert_papers(
+            conn: &mut PgConnection,
+            table: &str,
+            timetables: &[ExamTTObject],
+        ) -> Result<(), Error> {
+            let query = format!(
+                "INSERT INTO {} (class_code, exam_date, session, subject_code, component_code, component_name, start_time, duration_minutes)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+                table
+            );
+            for row in paper_rows(timetables) {
+                sqlx::query(&query)
+                    .bind(row.0)
+                    .bind(row.1)
+                    .bind(row.2)
+                    .bind(row.3)
+                    .bind(row.4)
+                    .bind(row.5)
+                    .bind(row.6)
+                    .bind(row.7)
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| {
+                        log::error!("Failed to write {}: {}", table, e);
+                        Error::DatabaseQueryFailed
+                    })?;
+            }
+
+            Ok(())
+        }
+
+        /// The published exam timetable, one per class.
+        pub async fn load_timetable(pool: &PgPool) -> Result<Vec<ExamTTObject>, Error> {
+            load_papers(pool, "exam_timetable").await
+        }
+
+        /// The papers in `table`, grouped into one timetable per class.
+        async fn load_papers(pool: &PgPool, table: &str) -> Result<Vec<ExamTTObject>, Error> {
+            let rows = sqlx::query_as::<_, PaperRow>(&format!(
+                "SELECT class_code, exam_date, session, subject_code, component_code, component_name, start_time, duration_minutes
+                FROM {}
+                ORDER BY class_code, exam_date, session, start_time, subject_code, component_code",
+                table
+            ))
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to retrieve {}: {}", table, e);
+                Error::DatabaseQueryFailed
+            })?;
+
+            let catalogue = load_catalogue(pool).await?;
+            timetables_from_rows(&catalogue, rows)
+        }
+
+        fn paper_rows(timetables: &[ExamTTObject]) -> Vec<PaperRow> {
+            timetables
+                .iter()
+                .flat_map(|timetable| {
+                    timetable.exam_pairs().iter().flat_map(move |pair| {
+                        pair.papers().iter().map(move |paper| {
+                            (
+                                timetable.student_class().code().to_string(),
+                                pair.exam_date().date(),
+                                pair.exam_date().session().as_str().to_string(),
+                                paper.subject().code().to_string(),
+                                paper.component().code().to_string(),
+                                paper.component().name().to_string(),
+                                paper.start_time(),
+                                paper.component().duration_minutes() as i32,
+                            )
+                        })
+                    })
+                })
+                .collect()
+        }
+
+        fn timetables_from_rows(
+            catalogue: &Catalogue,
+            rows: Vec<PaperRow>,
+        ) -> Result<Vec<ExamTTObject>, Error> {
+            let mut classes: BTreeMap<StudentClass, BTreeMap<ExamDate, Vec<ExamPaper>>> =
+                BTreeMap::new();
+            for (
+                class_code,
+                exam_date,
+                session,
+                subject_code,
+                component_code,
+                component_name,
+                start_time,
+                duration_minutes,
+            ) in rows
+            {
+                let Some(session) = ExamSession::from_code(&session) else {
+                    log::error!("Exam timetable row for {} has unknown session {}", subject_code, session);
+                    return Err(Error::DatabaseQueryFailed);
+                };
+                let class = stored_class(catalogue, &class_code)?;
+                let subject = stored_subject(catalogue, &subject_code)?;
+                classes
+                    .entry(class)
+                    .or_default()
+                    .entry(ExamDate::new(exam_date, session))
+                    .or_default()
+                    .push(ExamPaper::new(
+                        subject,
+                        PaperComponent::new(
+                            component_code,
+                            component_name,
+                            duration_minutes.max(0) as u32,
+                        ),
+                        start_time,
+                    ));
+            }
+
+            Ok(classes
+                .into_iter()
+                .map(|(class, sittings)| {
+                    ExamTTObject::new(
+                        class,
+                        sittings
+                            .into_iter()
+                            .map(|(exam_date, papers)| ExamPair::new(exam_date, papers))
+                            .collect(),
+                    )
+                })
+                .collect())
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use chrono::NaiveDate;
+            use shared::catalogue::Catalogue;
+            use shared::domain::{ExamDay, ExamSession, Student, Subject};
+            use shared::export::{ExamScope, Timetable};
+            use shared::fixtures::student;
+            use shared::timetable::{ExamWindow, generate};
+
+            use super::{paper_rows, timetables_from_rows};
+
+            #[test]
+            fn published_timetable_exports_every_session() {
+                let catalogue = Catalogue::seed();
+                let (class, subjects): (_, Vec<Subject>) = catalogue
+                    .classes()
+                    .into_iter()
+                    .map(|class| (class, catalogue.subjects_offered(class).take(4).collect()))
+                    .find(|(_, subjects): &(_, Vec<_>)| subjects.len() > 1)
+                    .expect("a class with subjects");
+                let students: Vec<Student> = (0..3)
+                    .map(|index| student(&format!("C{}", index), class, &subjects))
+                    .collect();
+                let window = ExamWindow::from_range(
+                    NaiveDate::from_ymd_opt(2026, 5, 4).expect("valid date"),
+                    NaiveDate::from_ymd_opt(2026, 5, 29).expect("valid date"),
+                    ExamDay::ALL,
+                    vec![ExamSession::Morning, ExamSession::Afternoon],
+                );
+                let report = generate(&students, &window, &catalogue).expect("window has sessions");
+
+                let published =
+                    timetables_from_rows(&catalogue, paper_rows(report.timetables()))
+                        .expect("published rows load back");
+                assert_eq!(published, report.timetables());
+
+                let export = Timetable::exams("Exam timetable".to_string(), &published, &ExamScope::School);
+                let papers: usize = report
+                    .timetables()
+                    .iter()
+                    .flat_map(|timetable| timetable.exam_pairs())
+                    .map(|pair| pair.papers().len())
+                    .sum();
+                assert!(papers > 0);
+                assert_eq!(export.entries().len(), papers);
+                for pair in report.timetables().iter().flat_map(|timetable| timetable.exam_pairs()) {
+                    assert!(
+                        export.entries().iter().any(|entry| entry.exam_date() == pair.exam_date()),
+                        "{} is missing from the export",
+                        pair.exam_date()
+                    );
+                }
+            }
+        }
+    }
+}
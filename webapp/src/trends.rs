@@ -0,0 +1,413 @@
+use leptos::prelude::*;
+use shared::marks::Term;
+use shared::trends::{Direction, TrendSeries, Trends};
+
+use crate::app::{ErrorDisplay, LoadingSpinner};
+use crate::catalogue::{ClassOptions, get_catalogue};
+
+/// Chart size in SVG units. Charts scale to their card's width.
+const CHART_WIDTH: f32 = 300.0;
+const CHART_HEIGHT: f32 = 120.0;
+const CHART_PADDING: f32 = 12.0;
+
+#[server(GetClassTrends, "/api/trends")]
+pub async fn get_class_trends(class_code: String, term: String) -> Result<Trends, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(crate::marks::MARKS_ROLES).await?;
+        let term = match term.trim() {
+            "" => None,
+            number => {
+                let Some(term) = number.parse().ok().and_then(Term::from_number) else {
+                    return Err(ServerFnError::ServerError(format!("Unknown term: {}", number)));
+                };
+                Some(term)
+            }
+        };
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+        let class = crate::catalogue::requested_class(&pool, &class_code).await?;
+
+        db::server::class_trends(&pool, class, term)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+#[server(GetStudentTrends, "/api/trends")]
+pub async fn get_student_trends(
+    candidate_number: String,
+    term: String,
+) -> Result<Trends, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::app::db::server::require_role(crate::marks::MARKS_ROLES).await?;
+        let term = match term.trim() {
+            "" => None,
+            number => {
+                let Some(term) = number.parse().ok().and_then(Term::from_number) else {
+                    return Err(ServerFnError::ServerError(format!("Unknown term: {}", number)));
+                };
+                Some(term)
+            }
+        };
+        let pool = crate::app::db::server::connect()
+            .await
+            .expect("Failed to create database pool");
+
+        db::server::student_trends(&pool, candidate_number.trim(), term)
+            .await
+            .map_err(|e| ServerFnError::ServerError(e.to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::ServerError(
+            "Server function called on client".into(),
+        ))
+    }
+}
+
+fn score(value: f32) -> String {
+    format!("{:.1}", value)
+}
+
+/// Term-on-term and year-on-year results for a class or a student, for the
+/// dashboard. Picking one term compares that term across years.
+#[component]
+pub fn PerformanceTrends() -> impl IntoView {
+    let for_student = RwSignal::new(false);
+    let class_code = RwSignal::new(String::new());
+    let catalogue = Resource::new(|| (), |_| async move { get_catalogue().await });
+    let candidate_number = RwSignal::new(String::new());
+    let term = RwSignal::new(String::new());
+
+    let trends = Resource::new(
+        move || (for_student.get(), class_code.get(), candidate_number.get(), term.get()),
+        |(for_student, class_code, candidate_number, term)| async move {
+            if for_student {
+                if candidate_number.trim().is_empty() {
+                    return Ok(None);
+                }
+                get_student_trends(candidate_number, term).await.map(Some)
+            } else {
+                if class_code.is_empty() {
+                    return Ok(None);
+                }
+                get_class_trends(class_code, term).await.map(Some)
+            }
+        },
+    );
+
+    view! {
+        <section class="space-y-4">
+            <div class="flex gap-2 items-center">
+                <h2 class="text-lg font-semibold mr-4">"Trends"</h2>
+                <select
+                    class="px-3 py-2 border rounded"
+                    prop:value=move || if for_student.get() { "student" } else { "class" }
+                    on:change=move |ev| for_student.set(event_target_value(&ev) == "student")
+                >
+                    <option value="class">"Class"</option>
+                    <option value="student">"Student"</option>
+                </select>
+                <Show
+                    when=move || for_student.get()
+                    fallback=move || view! {
+                        <select
+                            class="px-3 py-2 border rounded"
+                            prop:value=move || class_code.get()
+                            on:change=move |ev| class_code.set(event_target_value(&ev))
+                        >
+                            <option value="">"Class..."</option>
+                            <ClassOptions catalogue=catalogue />
+                        </select>
+                    }
+                >
+                    <input
+                        type="text"
+                        placeholder="Candidate number"
+                        class="px-3 py-2 border rounded"
+                        prop:value=move || candidate_number.get()
+                        on:change=move |ev| candidate_number.set(event_target_value(&ev))
+                    />
+                </Show>
+                <select
+                    class="px-3 py-2 border rounded"
+                    prop:value=move || term.get()
+                    on:change=move |ev| term.set(event_target_value(&ev))
+                >
+                    <option value="">"All terms"</option>
+                    {Term::ALL.map(|term| view! {
+                        <option value=term.number()>{format!("{} each year", term)}</option>
+                    }).collect_view()}
+                </select>
+            </div>
+            <Suspense fallback=move || view! { <LoadingSpinner/> }>
+                {move || trends.get().map(|result| match result {
+                    Ok(Some(trends)) if !trends.is_empty() => view! {
+                        <div class="grid grid-cols-1 md:grid-cols-2 xl:grid-cols-3 gap-4">
+                            <TrendCard series=trends.overall().clone() />
+                            {trends.subjects().iter().map(|series| view! {
+                                <TrendCard series=series.clone() />
+                            }).collect_view()}
+                        </div>
+                    }.into_any(),
+                    Ok(Some(_)) => view! {
+                        <p class="text-gray-500">"No marks have been entered yet."</p>
+                    }.into_any(),
+                    Ok(None) => view! {
+                        <p class="text-gray-500">"Choose a class or enter a candidate number."</p>
+                    }.into_any(),
+                    Err(e) => view! { <ErrorDisplay error=e.to_string() /> }.into_any(),
+                })}
+            </Suspense>
+        </section>
+    }
+}
+
+#[component]
+pub fn TrendCard(series: TrendSeries) -> impl IntoView {
+    let points = series.points();
+    let step = if points.len() > 1 {
+        (CHART_WIDTH - 2.0 * CHART_PADDING) / (points.len() - 1) as f32
+    } else {
+        0.0
+    };
+    let coordinates: Vec<(f32, f32)> = points
+        .iter()
+        .enumerate()
+        .map(|(index, point)| {
+            let x = if points.len() > 1 {
+                CHART_PADDING + index as f32 * step
+            } else {
+                CHART_WIDTH / 2.0
+            };
+            let y = CHART_PADDING
+                + (100.0 - point.score().clamp(0.0, 100.0)) / 100.0
+                    * (CHART_HEIGHT - 2.0 * CHART_PADDING);
+            (x, y)
+        })
+        .collect();
+    let line = coordinates
+        .iter()
+        .map(|(x, y)| format!("{:.1},{:.1}", x, y))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let (change_class, change) = match (series.direction(), series.change()) {
+        (Some(direction), Some(change)) => {
+            let class = match direction {
+                Direction::Improving => "text-green-600",
+                Direction::Steady => "text-gray-500",
+                Direction::Declining => "text-red-600",
+            };
+            (class, format!("{} ({:+.1})", direction, change))
+        }
+        _ => ("text-gray-500", "—".to_string()),
+    };
+
+    view! {
+        <div class="bg-white rounded-lg shadow p-4">
+            <div class="flex justify-between items-baseline mb-2">
+                <h3 class="font-semibold">{series.label()}</h3>
+                <span class=format!("text-sm {}", change_class)>{change}</span>
+            </div>
+            <p class="text-sm text-gray-500 mb-2">
+                {series.latest().map(|latest| format!("Latest {}%", score(latest.score())))}
+                {series.overall_change().map(|change| format!(", {:+.1} since first", change))}
+            </p>
+            <svg viewBox=format!("0 0 {} {}", CHART_WIDTH, CHART_HEIGHT) class="w-full h-32">
+                {[0.0, 50.0, 100.0].map(|level: f32| {
+                    let y = CHART_PADDING + (100.0 - level) / 100.0 * (CHART_HEIGHT - 2.0 * CHART_PADDING);
+                    view! {
+                        <line x1="0" x2=CHART_WIDTH y1=y y2=y stroke="#e5e7eb" stroke-width="1" />
+                    }
+                }).collect_view()}
+                <polyline points=line fill="none" stroke="#3b82f6" stroke-width="2" />
+                {points.iter().zip(&coordinates).map(|(point, (x, y))| {
+                    let title = match point.position() {
+                        Some(position) => format!("{} ({}): {}%, {}", point.assessment_name(), point.period(), score(point.score()), position),
+                        None => format!("{} ({}): {}%", point.assessment_name(), point.period(), score(point.score())),
+                    };
+                    view! {
+                        <circle cx=*x cy=*y r="4" fill="#3b82f6">
+                            <title>{title}</title>
+                        </circle>
+                    }
+                }).collect_view()}
+            </svg>
+            <div class="flex justify-between text-xs text-gray-500 mt-1">
+                {points.iter().map(|point| view! {
+                    <span title=point.assessment_name().to_string()>
+                        {format!("{} T{}", point.period().year_label(), point.period().term().number())}
+                    </span>
+                }).collect_view()}
+            </div>
+        </div>
+    }
+}
+
+pub mod db {
+    #[cfg(feature = "ssr")]
+    pub mod server {
+        use crate::app::db::Error;
+        use crate::marks::db::AssessmentRecord;
+        use shared::analysis::{AssessmentResults, RankBy, Weighting};
+        use shared::domain::{Student, StudentClass};
+        use shared::marks::{AcademicPeriod, Mark, Term};
+        use std::collections::{HashMap, HashSet};
+        use shared::trends::{Sitting, Trends};
+        use sqlx::PgPool;
+
+        /// The average of `class` at each assessment it has sat, in `term`
+        /// of each year when given.
+        pub async fn class_trends(
+            pool: &PgPool,
+            class: StudentClass,
+            term: Option<Term>,
+        ) -> Result<Trends, Error> {
+            let records: Vec<AssessmentRecord> = crate::marks::db::server::load_assessments(pool)
+                .await?
+                .into_iter()
+                .filter(|record| record.assessment.class() == class)
+                .collect();
+            let sittings = load_sittings(pool, &records, term).await?;
+
+            Ok(Trends::class(&sittings))
+        }
+
+        /// A student's results at every assessment they have marks in,
+        /// whichever class they were in at the time.
+        pub async fn student_trends(
+            pool: &PgPool,
+            candidate_number: &str,
+            term: Option<Term>,
+        ) -> Result<Trends, Error> {
+            let sat = crate::marks::db::server::assessments_sat_by(
+                pool,
+                &[candidate_number.trim().to_string()],
+            )
+            .await?;
+            let records: Vec<AssessmentRecord> = crate::marks::db::server::load_assessments(pool)
+                .await?
+                .into_iter()
+                .filter(|record| sat.contains(&record.id))
+                .collect();
+            let sittings = load_sittings(pool, &records, term).await?;
+
+            Ok(Trends::student(candidate_number, &sittings))
+        }
+
+        /// Analyses each class, assessment name and term among `records`,
+        /// with every subject sat in it. The class is made up of the
+        /// students with marks in the assessment, placed back in the class
+        /// they sat it in, so sittings from past years survive promotion and
+        /// archiving. Marks and students are each loaded in one query.
+        pub async fn load_sittings(
+            pool: &PgPool,
+            records: &[AssessmentRecord],
+            term: Option<Term>,
+        ) -> Result<Vec<Sitting>, Error> {
+            let grading = crate::grading::db::server::load_grading_policy(pool).await?;
+
+            // Records come newest first; keep the oldest first within a term.
+            let mut sittings: Vec<(StudentClass, String, AcademicPeriod)> = Vec::new();
+            for record in records.iter().rev() {
+                let assessment = &record.assessment;
+                if term.is_some_and(|term| assessment.period().term() != term) {
+                    continue;
+                }
+                if !sittings.iter().any(|(class, name, period)| {
+                    *class == assessment.class()
+                        && name == assessment.name()
+                        && *period == assessment.period()
+                }) {
+                    sittings.push((
+                        assessment.class(),
+                        assessment.name().to_string(),
+                        assessment.period(),
+                    ));
+                }
+            }
+
+            let in_sitting = |record: &AssessmentRecord,
+                              (class, name, period): &(StudentClass, String, AcademicPeriod)| {
+                record.assessment.class() == *class
+                    && record.assessment.name() == name
+                    && record.assessment.period() == *period
+            };
+            let sat: Vec<AssessmentRecord> = crate::marks::db::server::load_assessments(pool)
+                .await?
+                .into_iter()
+                .filter(|record| sittings.iter().any(|sitting| in_sitting(record, sitting)))
+                .collect();
+            let ids: Vec<i32> = sat.iter().map(|record| record.id).collect();
+            let mut marks: HashMap<i32, Vec<Mark>> = HashMap::new();
+            for (assessment_id, mark) in
+                crate::marks::db::server::load_marks_for(pool, &ids).await?
+            {
+                marks.entry(assessment_id).or_default().push(mark);
+            }
+            let mut candidate_numbers: Vec<String> = marks
+                .values()
+                .flatten()
+                .map(|mark| mark.candidate_number().to_string())
+                .collect();
+            candidate_numbers.sort();
+            candidate_numbers.dedup();
+            let all_students =
+                crate::students::db::server::load_students(pool, &candidate_numbers).await?;
+
+            let mut analysed = Vec::new();
+            for sitting in sittings {
+                let results: Vec<AssessmentResults> = sat
+                    .iter()
+                    .filter(|record| in_sitting(record, &sitting))
+                    .map(|record| {
+                        AssessmentResults::new(
+                            record.assessment.clone(),
+                            marks.remove(&record.id).unwrap_or_default(),
+                        )
+                    })
+                    .collect();
+                let (class, name, period) = sitting;
+                let sitting_candidates: HashSet<&str> = results
+                    .iter()
+                    .flat_map(|results| results.marks())
+                    .map(|mark| mark.candidate_number())
+                    .collect();
+                let students: Vec<Student> = all_students
+                    .iter()
+                    .filter(|student| sitting_candidates.contains(student.candidate_number()))
+                    .map(|student| {
+                        Student::new(
+                            student.candidate_number().to_string(),
+                            student.personal_info().clone(),
+                            class,
+                            student.subjects_read().to_vec(),
+                        )
+                    })
+                    .collect();
+
+                if let Some(analysis) = shared::analysis::analyse_class(
+                    class,
+                    &students,
+                    &results,
+                    &Weighting::default(),
+                    &grading,
+                    RankBy::default(),
+                ) {
+                    analysed.push(Sitting::new(name, period, analysis));
+                }
+            }
+
+            Ok(analysed)
+        }
+    }
+}